@@ -0,0 +1,98 @@
+//! Build script helpers that derive the vkey hash of an SP1 program from its ELF.
+//!
+//! A Solana program verifying proofs of an SP1 program needs that program's vkey hash,
+//! `vk.bytes32()`. Hard-coded, it silently drifts from the ELF when the guest program changes. Called
+//! from a `build.rs`, [`vkey_hash_from_elf`] computes it from the ELF with `sp1-sdk` instead, checks
+//! it against a pinned value, and writes it as a `[u8; 32]` constant to include:
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     sp1_solana_build::vkey_hash_from_elf(
+//!         "FIBONACCI_VKEY_HASH",
+//!         "../sp1-program/elf/riscv32im-succinct-zkvm-elf",
+//!         "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83",
+//!     );
+//! }
+//!
+//! // lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/fibonacci_vkey_hash.rs"));
+//! ```
+//!
+//! The pinned value makes a change of the ELF fail the build, rather than change the program vkey
+//! hash the contract accepts: update it along with the ELF.
+
+use std::path::{Path, PathBuf};
+
+use sp1_sdk::{HashableKey, ProverClient};
+
+/// Computes the vkey hash of the SP1 program `name` from the ELF at `elf_path`, relative to the
+/// crate's manifest directory, and writes it to `$OUT_DIR/<name in lowercase>.rs` as
+/// `pub const <name>: [u8; 32]`.
+///
+/// This fails the build if the ELF is missing, or if its vkey hash isn't `pinned`, `0x` followed
+/// by 64 hex digits. The build script is rerun when the ELF changes.
+pub fn vkey_hash_from_elf(name: &str, elf_path: impl AsRef<Path>, pinned: &str) {
+    let elf_path = elf_path.as_ref();
+    let manifest_dir = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let elf_file = manifest_dir.join(elf_path);
+    println!("cargo:rerun-if-changed={}", elf_file.display());
+
+    let vkey_hash = try_vkey_hash_from_elf(&elf_file)
+        .and_then(|vkey_hash| check_pinned(name, &vkey_hash, pinned).map(|()| vkey_hash))
+        .unwrap_or_else(|error| panic!("{error}"));
+
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+    let path = out_dir.join(format!("{}.rs", name.to_lowercase()));
+    std::fs::write(&path, vkey_hash_const(name, &vkey_hash, elf_path))
+        .unwrap_or_else(|error| panic!("failed to write {}: {error}", path.display()));
+}
+
+/// Computes `vk.bytes32()` of the SP1 program whose ELF is at `elf_path`.
+pub fn try_vkey_hash_from_elf(elf_path: &Path) -> Result<[u8; 32], String> {
+    let elf = std::fs::read(elf_path).map_err(|error| {
+        format!(
+            "the SP1 program ELF {} can't be read ({error}): build it with `cargo prove build`",
+            elf_path.display()
+        )
+    })?;
+    let (_, vk) = ProverClient::new().setup(&elf);
+    decode(&vk.bytes32()).ok_or_else(|| {
+        format!(
+            "sp1-sdk returned an invalid vkey hash for {}",
+            elf_path.display()
+        )
+    })
+}
+
+/// Checks the vkey hash computed for the program `name` against the `pinned` one.
+pub fn check_pinned(name: &str, vkey_hash: &[u8; 32], pinned: &str) -> Result<(), String> {
+    let pinned_hash = decode(pinned).ok_or_else(|| {
+        format!("the pinned vkey hash of {name}, {pinned}, isn't 0x followed by 64 hex digits")
+    })?;
+    if pinned_hash != *vkey_hash {
+        return Err(format!(
+            "the vkey hash of {name} changed: the ELF hashes to 0x{}, but 0x{} is pinned. If the SP1 \
+             program was changed on purpose, pin the new hash",
+            hex::encode(vkey_hash),
+            hex::encode(pinned_hash)
+        ));
+    }
+    Ok(())
+}
+
+/// The Rust source of the constant `name`, holding `vkey_hash`.
+pub fn vkey_hash_const(name: &str, vkey_hash: &[u8; 32], elf_path: &Path) -> String {
+    format!(
+        "/// The SP1 program vkey hash, `vk.bytes32()`, of `{}`, computed at build time.\n\
+         pub const {name}: [u8; 32] = {vkey_hash:?};\n",
+        elf_path.display()
+    )
+}
+
+/// Decodes a `0x`-prefixed, 32-byte hex string.
+fn decode(vkey_hash: &str) -> Option<[u8; 32]> {
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(vkey_hash.strip_prefix("0x")?, &mut bytes).ok()?;
+    Some(bytes)
+}
@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use sp1_solana_build::{check_pinned, try_vkey_hash_from_elf, vkey_hash_const};
+
+const FIBONACCI_ELF: &str = "../example/sp1-program/elf/riscv32im-succinct-zkvm-elf";
+const FIBONACCI_VKEY_HASH: &str =
+    "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83";
+
+fn fibonacci_vkey_hash() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(&FIBONACCI_VKEY_HASH[2..], &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn test_check_pinned() {
+    let vkey_hash = fibonacci_vkey_hash();
+    assert_eq!(
+        check_pinned("FIBONACCI_VKEY_HASH", &vkey_hash, FIBONACCI_VKEY_HASH),
+        Ok(())
+    );
+
+    // Tampering with any digit of the pinned hash fails the check.
+    let mut tampered = FIBONACCI_VKEY_HASH.to_string();
+    tampered.replace_range(65.., "4");
+    let error = check_pinned("FIBONACCI_VKEY_HASH", &vkey_hash, &tampered).unwrap_err();
+    assert_eq!(
+        error,
+        "the vkey hash of FIBONACCI_VKEY_HASH changed: the ELF hashes to \
+         0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83, but \
+         0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f84 is pinned. If the SP1 \
+         program was changed on purpose, pin the new hash"
+    );
+
+    for malformed in [&FIBONACCI_VKEY_HASH[2..], &FIBONACCI_VKEY_HASH[..65], "0x"] {
+        let error = check_pinned("FIBONACCI_VKEY_HASH", &vkey_hash, malformed).unwrap_err();
+        assert!(
+            error.contains("isn't 0x followed by 64 hex digits"),
+            "{error}"
+        );
+    }
+}
+
+#[test]
+fn test_vkey_hash_const() {
+    let source = vkey_hash_const(
+        "FIBONACCI_VKEY_HASH",
+        &fibonacci_vkey_hash(),
+        Path::new("elf/program"),
+    );
+    assert_eq!(
+        source,
+        "/// The SP1 program vkey hash, `vk.bytes32()`, of `elf/program`, computed at build time.\n\
+         pub const FIBONACCI_VKEY_HASH: [u8; 32] = [0, 122, 4, 250, 6, 62, 139, 74, 118, 246, 94, \
+         149, 146, 61, 243, 49, 158, 19, 226, 24, 124, 5, 67, 54, 138, 235, 55, 38, 9, 85, 95, \
+         131];\n"
+    );
+}
+
+#[test]
+fn test_missing_elf() {
+    let error = try_vkey_hash_from_elf(Path::new("missing/elf")).unwrap_err();
+    assert!(
+        error.starts_with("the SP1 program ELF missing/elf can't be read"),
+        "{error}"
+    );
+}
+
+/// The pinned hash of the fibonacci example is the one of its committed ELF.
+#[test]
+#[ignore = "runs the SP1 setup of the program"]
+fn test_fibonacci_vkey_hash() {
+    let elf = Path::new(env!("CARGO_MANIFEST_DIR")).join(FIBONACCI_ELF);
+    assert_eq!(try_vkey_hash_from_elf(&elf), Ok(fibonacci_vkey_hash()));
+}
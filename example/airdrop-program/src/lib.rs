@@ -0,0 +1,312 @@
+//! An example program that pays out an airdrop to claimants proving their allocation with SP1.
+//!
+//! Each claimant submits their own proof, whose public values decode to an [`AirdropClaim`]. All
+//! proofs are of the same SP1 program: the vault and claim PDAs are derived from its vkey hash.
+//! Paying a claim creates the claim PDA of the claimant, so each allocation is paid out once.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use sp1_solana::{nullifier::create_pda, verify_and_decode, GROTH16_VK_3_0_0_BYTES};
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// The seed prefix of vault PDAs, followed by the SP1 program vkey hash.
+pub const VAULT_SEED: &[u8] = b"airdrop-vault";
+
+/// The seed prefix of claim PDAs, followed by the SP1 program vkey hash and the claimant.
+pub const CLAIM_SEED: &[u8] = b"airdrop-claim";
+
+/// The public values of a claim proof.
+///
+/// Its Borsh encoding is the output of `sp1_zkvm::io::commit` on the claimant's address as a
+/// `[u8; 32]`, followed by the allocation as a `u64`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct AirdropClaim {
+    /// The account the allocation is paid to.
+    pub claimant: Pubkey,
+    /// The allocation, in lamports.
+    pub allocation: u64,
+}
+
+/// The data of a claim PDA, recording a paid claim.
+#[derive(Clone, Debug, Default, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct ClaimRecord {
+    /// The allocation that was paid.
+    pub allocation: u64,
+    /// The slot in which it was paid.
+    pub slot: u64,
+}
+
+impl ClaimRecord {
+    /// The length of the serialized record.
+    pub const LEN: usize = 8 + 8;
+}
+
+/// The instructions of the program.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum AirdropInstruction {
+    /// Creates the vault of the airdrop to proofs of `sp1_vkey_hash`, funded with `lamports` on
+    /// top of its rent exemption.
+    ///
+    /// Accounts: the funder (signer, writable), the vault PDA (writable), and the system program.
+    CreateVault {
+        sp1_vkey_hash: [u8; 32],
+        lamports: u64,
+    },
+    /// Pays the allocation of a claim proven by `proof` from the vault to the claimant.
+    ///
+    /// Accounts: the payer (signer, writable), the claimant (writable), the vault PDA (writable),
+    /// the claim PDA of the claimant (writable), and the system program.
+    Claim {
+        sp1_vkey_hash: [u8; 32],
+        /// The proof in SP1's standard format, prefixed with the Groth16 vkey hash.
+        proof: Vec<u8>,
+        /// The SP1 public values, encoding an [`AirdropClaim`].
+        sp1_public_inputs: Vec<u8>,
+    },
+}
+
+/// Errors returned by the program, as `ProgramError::Custom(error as u32)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AirdropError {
+    /// The instruction data doesn't decode to an [`AirdropInstruction`].
+    InvalidInstructionData,
+    /// The payer or funder didn't sign the transaction.
+    MissingSignature,
+    /// The public values don't decode to an [`AirdropClaim`].
+    InvalidPublicValues,
+    /// The proof doesn't verify.
+    InvalidProof,
+    /// The vault or claim account is not the expected PDA.
+    InvalidPdaAddress,
+    /// The claimant account is not the one the proof was generated for.
+    ClaimantMismatch,
+    /// The allocation of the claimant was already paid.
+    AlreadyClaimed,
+    /// The vault can't pay the allocation.
+    InsufficientVaultBalance,
+}
+
+impl From<AirdropError> for ProgramError {
+    fn from(error: AirdropError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = AirdropInstruction::try_from_slice(instruction_data)
+        .map_err(|_| AirdropError::InvalidInstructionData)?;
+
+    match instruction {
+        AirdropInstruction::CreateVault {
+            sp1_vkey_hash,
+            lamports,
+        } => process_create_vault(program_id, accounts, &sp1_vkey_hash, lamports),
+        AirdropInstruction::Claim {
+            sp1_vkey_hash,
+            proof,
+            sp1_public_inputs,
+        } => process_claim(
+            program_id,
+            accounts,
+            &sp1_vkey_hash,
+            &proof,
+            &sp1_public_inputs,
+        ),
+    }
+}
+
+fn process_create_vault(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sp1_vkey_hash: &[u8; 32],
+    lamports: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let funder = next_account_info(account_info_iter)?;
+    let vault = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !funder.is_signer {
+        return Err(AirdropError::MissingSignature.into());
+    }
+
+    let (vault_address, bump) = find_vault_address(program_id, sp1_vkey_hash);
+    if *vault.key != vault_address {
+        return Err(AirdropError::InvalidPdaAddress.into());
+    }
+
+    // The vault holds no data, and is owned by the program so that claims can debit it directly.
+    // Its address is public, so it may have been funded to block its creation.
+    create_pda(
+        funder,
+        vault,
+        system_program,
+        0,
+        program_id,
+        &[VAULT_SEED, sp1_vkey_hash, &[bump]],
+    )?;
+    if lamports > 0 {
+        invoke(
+            &system_instruction::transfer(funder.key, vault.key, lamports),
+            &[funder.clone(), vault.clone(), system_program.clone()],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn process_claim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sp1_vkey_hash: &[u8; 32],
+    proof: &[u8],
+    sp1_public_inputs: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer = next_account_info(account_info_iter)?;
+    let claimant = next_account_info(account_info_iter)?;
+    let vault = next_account_info(account_info_iter)?;
+    let claim_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(AirdropError::MissingSignature.into());
+    }
+
+    let claim: AirdropClaim = verify_and_decode(
+        proof,
+        sp1_public_inputs,
+        sp1_vkey_hash,
+        GROTH16_VK_3_0_0_BYTES,
+    )
+    .map_err(|error| match error {
+        sp1_solana::Error::BorshDeserializeError(_) => AirdropError::InvalidPublicValues,
+        _ => AirdropError::InvalidProof,
+    })?;
+    if *claimant.key != claim.claimant {
+        return Err(AirdropError::ClaimantMismatch.into());
+    }
+
+    if *vault.key != find_vault_address(program_id, sp1_vkey_hash).0 || vault.owner != program_id {
+        return Err(AirdropError::InvalidPdaAddress.into());
+    }
+    let (claim_address, bump) = find_claim_address(program_id, sp1_vkey_hash, claimant.key);
+    if *claim_account.key != claim_address {
+        return Err(AirdropError::InvalidPdaAddress.into());
+    }
+    if claim_account.owner == program_id {
+        return Err(AirdropError::AlreadyClaimed.into());
+    }
+
+    // Keep the vault rent exempt.
+    let rent_exemption = Rent::get()?.minimum_balance(0);
+    if vault.lamports().saturating_sub(rent_exemption) < claim.allocation {
+        return Err(AirdropError::InsufficientVaultBalance.into());
+    }
+
+    // Record the claim, so that it can't be paid again.
+    let record = ClaimRecord {
+        allocation: claim.allocation,
+        slot: Clock::get()?.slot,
+    };
+    create_pda(
+        payer,
+        claim_account,
+        system_program,
+        ClaimRecord::LEN,
+        program_id,
+        &[CLAIM_SEED, sp1_vkey_hash, claimant.key.as_ref(), &[bump]],
+    )?;
+    record
+        .serialize(&mut &mut claim_account.try_borrow_mut_data()?[..])
+        .map_err(|_| ProgramError::AccountDataTooSmall)?;
+
+    **vault.try_borrow_mut_lamports()? -= claim.allocation;
+    **claimant.try_borrow_mut_lamports()? += claim.allocation;
+
+    msg!("Paid {} lamports to {}", claim.allocation, claim.claimant);
+
+    Ok(())
+}
+
+/// Finds the vault PDA of the airdrop to proofs of `sp1_vkey_hash`.
+pub fn find_vault_address(program_id: &Pubkey, sp1_vkey_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, sp1_vkey_hash], program_id)
+}
+
+/// Finds the claim PDA of `claimant`.
+pub fn find_claim_address(
+    program_id: &Pubkey,
+    sp1_vkey_hash: &[u8; 32],
+    claimant: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CLAIM_SEED, sp1_vkey_hash, claimant.as_ref()], program_id)
+}
+
+/// Builds a `CreateVault` instruction.
+pub fn create_vault(
+    program_id: &Pubkey,
+    funder: &Pubkey,
+    sp1_vkey_hash: &[u8; 32],
+    lamports: u64,
+) -> Instruction {
+    let (vault, _) = find_vault_address(program_id, sp1_vkey_hash);
+    Instruction::new_with_borsh(
+        *program_id,
+        &AirdropInstruction::CreateVault {
+            sp1_vkey_hash: *sp1_vkey_hash,
+            lamports,
+        },
+        vec![
+            AccountMeta::new(*funder, true),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
+
+/// Builds a `Claim` instruction paying `claimant`.
+pub fn claim(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    claimant: &Pubkey,
+    sp1_vkey_hash: &[u8; 32],
+    proof: &[u8],
+    sp1_public_inputs: &[u8],
+) -> Instruction {
+    let (vault, _) = find_vault_address(program_id, sp1_vkey_hash);
+    let (claim, _) = find_claim_address(program_id, sp1_vkey_hash, claimant);
+    Instruction::new_with_borsh(
+        *program_id,
+        &AirdropInstruction::Claim {
+            sp1_vkey_hash: *sp1_vkey_hash,
+            proof: proof.to_vec(),
+            sp1_public_inputs: sp1_public_inputs.to_vec(),
+        },
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*claimant, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(claim, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
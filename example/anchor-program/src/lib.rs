@@ -0,0 +1,104 @@
+//! An Anchor version of the fibonacci verifier program.
+//!
+//! The instruction verifies an SP1 Groth16 proof of the fibonacci program, then stores the
+//! decoded public values in a result account.
+
+use anchor_lang::prelude::*;
+
+declare_id!("Gh7sBzx3U8M5DaG3FYYGjUchJqweipXtPJ615SyDqXyu");
+
+/// Derived by running `vk.bytes32()` on the fibonacci program's vkey.
+const FIBONACCI_VKEY_HASH: &str =
+    "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83";
+
+/// The length of a proof in SP1's standard format: the Groth16 vkey hash, then the raw proof.
+const PROOF_LEN: usize = sp1_solana::PROOF_WITH_SELECTOR_LEN;
+
+/// The longest public values the instruction accepts.
+pub const MAX_PUBLIC_VALUES_LEN: usize = 512;
+
+#[program]
+pub mod fibonacci_verifier_anchor {
+    use super::*;
+
+    /// Verifies a proof in SP1's standard format against the SP1 public values, and stores the
+    /// `(n, a, b)` they commit to.
+    ///
+    /// Verifier errors are returned as Anchor errors numbered from
+    /// [`sp1_solana::ANCHOR_ERROR_CODE_OFFSET`].
+    pub fn verify_fibonacci_proof(
+        ctx: Context<VerifyFibonacciProof>,
+        proof: Vec<u8>,
+        public_values: Vec<u8>,
+    ) -> Result<()> {
+        // The verifier also takes raw 256-byte proofs, which have no selector to check against
+        // the verification key's hash, so only proofs with their selector are accepted.
+        require_eq!(proof.len(), PROOF_LEN, FibonacciError::InvalidProofLength);
+        require_gte!(
+            MAX_PUBLIC_VALUES_LEN,
+            public_values.len(),
+            FibonacciError::PublicValuesTooLarge
+        );
+
+        sp1_solana::verify_proof(
+            &proof,
+            &public_values,
+            FIBONACCI_VKEY_HASH,
+            sp1_solana::GROTH16_VK_3_0_0_BYTES,
+        )?;
+
+        let mut reader = public_values.as_slice();
+        let (n, a, b) = <(u32, u32, u32)>::deserialize(&mut reader)
+            .map_err(|_| error!(FibonacciError::InvalidPublicValues))?;
+
+        let result = &mut ctx.accounts.result;
+        result.n = n;
+        result.a = a;
+        result.b = b;
+        msg!("Public values: (n: {}, a: {}, b: {})", n, a, b);
+
+        Ok(())
+    }
+}
+
+/// Adapts the Anchor entrypoint, which needs the accounts and their data to share a lifetime, to
+/// the signature `processor!` expects, for native `solana-program-test` runs.
+///
+/// The accounts are leaked, so this is not meant for on-chain use.
+#[cfg(not(target_os = "solana"))]
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> anchor_lang::solana_program::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    entry(program_id, accounts, instruction_data)
+}
+
+#[derive(Accounts)]
+pub struct VerifyFibonacciProof<'info> {
+    #[account(init, payer = payer, space = 8 + FibonacciResult::INIT_SPACE)]
+    pub result: Account<'info, FibonacciResult>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The public values of a verified fibonacci proof.
+#[account]
+#[derive(InitSpace)]
+pub struct FibonacciResult {
+    pub n: u32,
+    pub a: u32,
+    pub b: u32,
+}
+
+#[error_code]
+pub enum FibonacciError {
+    #[msg("The public values are not a fibonacci (n, a, b) triple")]
+    InvalidPublicValues,
+    #[msg("The proof is not 260 bytes long")]
+    InvalidProofLength,
+    #[msg("The public values are longer than MAX_PUBLIC_VALUES_LEN")]
+    PublicValuesTooLarge,
+}
@@ -0,0 +1,155 @@
+//! Runs `verify_fibonacci_proof` on `solana-program-test` with the pregenerated fibonacci proof.
+
+use anchor_lang::{
+    error::{ErrorCode, ERROR_CODE_OFFSET},
+    AccountDeserialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use fibonacci_verifier_anchor::{FibonacciError, FibonacciResult, MAX_PUBLIC_VALUES_LEN};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    hash::Hash,
+    instruction::{Instruction, InstructionError},
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+
+async fn start() -> (BanksClient, Keypair, Hash) {
+    ProgramTest::new(
+        "fibonacci_verifier_anchor",
+        fibonacci_verifier_anchor::ID,
+        processor!(fibonacci_verifier_anchor::process_instruction),
+    )
+    .start()
+    .await
+}
+
+/// Builds a transaction calling `verify_fibonacci_proof` with raw instruction `data`.
+fn verify_transaction(payer: &Keypair, result: &Keypair, data: Vec<u8>, hash: Hash) -> Transaction {
+    let instruction = Instruction {
+        program_id: fibonacci_verifier_anchor::ID,
+        accounts: fibonacci_verifier_anchor::accounts::VerifyFibonacciProof {
+            result: result.pubkey(),
+            payer: payer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data,
+    };
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[payer, result], hash);
+    transaction
+}
+
+/// Sends `verify_fibonacci_proof`, returning the stored result if the transaction succeeded.
+async fn run_verify_fibonacci_proof(
+    proof: Vec<u8>,
+    public_values: Vec<u8>,
+) -> Option<FibonacciResult> {
+    let (banks_client, payer, recent_blockhash) = start().await;
+
+    let result = Keypair::new();
+    let data = fibonacci_verifier_anchor::instruction::VerifyFibonacciProof {
+        proof,
+        public_values,
+    }
+    .data();
+    let transaction = verify_transaction(&payer, &result, data, recent_blockhash);
+    banks_client.process_transaction(transaction).await.ok()?;
+
+    let account = banks_client
+        .get_account(result.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    Some(FibonacciResult::try_deserialize(&mut account.data.as_slice()).unwrap())
+}
+
+#[tokio::test]
+async fn test_verify_fibonacci_proof() {
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+    let public_values = sp1_proof_with_public_values.public_values.to_vec();
+
+    let result = run_verify_fibonacci_proof(proof.clone(), public_values.clone())
+        .await
+        .unwrap();
+    assert_eq!((result.n, result.a, result.b), (20, 6765, 3027));
+
+    // Tampering with the public values fails verification, and no result is stored.
+    let mut tampered_public_values = public_values;
+    tampered_public_values[0] ^= 1;
+    assert!(run_verify_fibonacci_proof(proof, tampered_public_values)
+        .await
+        .is_none());
+}
+
+/// Sends raw instruction data to `verify_fibonacci_proof`, returning the error code it failed with.
+///
+/// Panics if the transaction succeeded, didn't fail with a custom error, or logged a panic.
+async fn error_code(data: Vec<u8>) -> u32 {
+    let (banks_client, payer, recent_blockhash) = start().await;
+    let transaction = verify_transaction(&payer, &Keypair::new(), data, recent_blockhash);
+    let result = banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+
+    let logs = result.metadata.unwrap().log_messages;
+    assert!(
+        logs.iter().all(|log| !log.contains("panicked")),
+        "{logs:#?}"
+    );
+    match result.result {
+        Err(TransactionError::InstructionError(0, InstructionError::Custom(code))) => code,
+        result => panic!("didn't fail with a custom error: {result:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_malformed_instruction_data() {
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+    let public_values = sp1_proof_with_public_values.public_values.to_vec();
+    let data = |proof: Vec<u8>, public_values: Vec<u8>| {
+        fibonacci_verifier_anchor::instruction::VerifyFibonacciProof {
+            proof,
+            public_values,
+        }
+        .data()
+    };
+
+    // Anchor rejects unknown discriminators and arguments that don't decode.
+    assert_eq!(
+        error_code(vec![0xff; 8]).await,
+        ErrorCode::InstructionFallbackNotFound as u32
+    );
+    let discriminator = fibonacci_verifier_anchor::instruction::VerifyFibonacciProof::DISCRIMINATOR;
+    let mut truncated = discriminator.to_vec();
+    truncated.extend_from_slice(&[1, 2]);
+    assert_eq!(
+        error_code(truncated).await,
+        ErrorCode::InstructionDidNotDeserialize as u32
+    );
+    let valid = data(proof.clone(), public_values.clone());
+    assert_eq!(
+        error_code(valid[..valid.len() - 1].to_vec()).await,
+        ErrorCode::InstructionDidNotDeserialize as u32
+    );
+
+    // The program bounds the lengths of the arguments.
+    let mut padded_proof = proof.clone();
+    padded_proof.extend_from_slice(&[0; 32]);
+    assert_eq!(
+        error_code(data(padded_proof, public_values)).await,
+        ERROR_CODE_OFFSET + FibonacciError::InvalidProofLength as u32
+    );
+    assert_eq!(
+        error_code(data(proof, vec![0; MAX_PUBLIC_VALUES_LEN + 1])).await,
+        ERROR_CODE_OFFSET + FibonacciError::PublicValuesTooLarge as u32
+    );
+}
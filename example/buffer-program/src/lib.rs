@@ -0,0 +1,295 @@
+//! A program that verifies SP1 proofs too large for a single transaction.
+//!
+//! A serialized [`sp1_solana::SP1ProofFixture`] is uploaded to a buffer account in chunks, then
+//! verified in place. The buffer account data is a [`BufferHeader`] followed by the fixture.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    log::sol_log_data,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use sp1_solana::{verify_proof_fixture, SP1ProofFixtureRef};
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// The header of a buffer account.
+#[derive(Clone, Debug, Default, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct BufferHeader {
+    /// The account that initialized the buffer, and the only one allowed to write or close it.
+    pub authority: Pubkey,
+    /// The size of the buffer following the header.
+    pub size: u32,
+    /// Whether the fixture in the buffer was verified. A verified buffer can't be written to.
+    pub verified: bool,
+}
+
+impl BufferHeader {
+    /// The length of the serialized header.
+    pub const LEN: usize = 32 + 4 + 1;
+}
+
+/// The instructions of the program.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum BufferInstruction {
+    /// Creates a buffer of `size` bytes, with the signer as its authority.
+    ///
+    /// Accounts: the authority (signer, writable), the buffer (signer, writable), and the system
+    /// program.
+    InitBuffer { size: u32 },
+    /// Writes `bytes` at `offset` in the buffer.
+    ///
+    /// Accounts: the authority (signer), and the buffer (writable).
+    WriteChunk { offset: u32, bytes: Vec<u8> },
+    /// Verifies the fixture in the buffer against the v3.0.0 Groth16 verification key, and marks
+    /// the buffer verified.
+    ///
+    /// Accounts: the buffer (writable).
+    VerifyBuffer,
+    /// Closes the buffer, returning its rent to the authority.
+    ///
+    /// Accounts: the authority (signer, writable), and the buffer (writable).
+    CloseBuffer,
+}
+
+/// Errors returned by the program, as `ProgramError::Custom(error as u32)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BufferError {
+    /// The instruction data doesn't decode to a [`BufferInstruction`].
+    InvalidInstructionData,
+    /// The signer is not the authority of the buffer.
+    Unauthorized,
+    /// A write falls outside the buffer.
+    OutOfBounds,
+    /// The buffer was already verified, so it can't be written to.
+    AlreadyVerified,
+    /// The buffer doesn't hold a well-formed fixture.
+    InvalidFixture,
+    /// The fixture doesn't verify.
+    VerificationFailed,
+    /// The buffer is not owned by the program.
+    InvalidAccountOwner,
+}
+
+impl From<BufferError> for ProgramError {
+    fn from(error: BufferError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = BufferInstruction::try_from_slice(instruction_data)
+        .map_err(|_| BufferError::InvalidInstructionData)?;
+
+    match instruction {
+        BufferInstruction::InitBuffer { size } => process_init_buffer(program_id, accounts, size),
+        BufferInstruction::WriteChunk { offset, bytes } => {
+            process_write_chunk(program_id, accounts, offset as usize, &bytes)
+        }
+        BufferInstruction::VerifyBuffer => process_verify_buffer(program_id, accounts),
+        BufferInstruction::CloseBuffer => process_close_buffer(program_id, accounts),
+    }
+}
+
+fn process_init_buffer(program_id: &Pubkey, accounts: &[AccountInfo], size: u32) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let buffer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(BufferError::Unauthorized.into());
+    }
+
+    // The buffer signs its own creation, so it must be a fresh keypair.
+    let space = BufferHeader::LEN + size as usize;
+    invoke(
+        &system_instruction::create_account(
+            authority.key,
+            buffer.key,
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            program_id,
+        ),
+        &[authority.clone(), buffer.clone(), system_program.clone()],
+    )?;
+
+    let header = BufferHeader {
+        authority: *authority.key,
+        size,
+        verified: false,
+    };
+    write_header(buffer, &header)
+}
+
+fn process_write_chunk(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    offset: usize,
+    bytes: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let buffer = next_account_info(account_info_iter)?;
+
+    let header = read_header(program_id, buffer)?;
+    if !authority.is_signer || *authority.key != header.authority {
+        return Err(BufferError::Unauthorized.into());
+    }
+    if header.verified {
+        return Err(BufferError::AlreadyVerified.into());
+    }
+
+    let end = offset
+        .checked_add(bytes.len())
+        .filter(|&end| end <= header.size as usize)
+        .ok_or(BufferError::OutOfBounds)?;
+    buffer.try_borrow_mut_data()?[BufferHeader::LEN + offset..BufferHeader::LEN + end]
+        .copy_from_slice(bytes);
+
+    Ok(())
+}
+
+fn process_verify_buffer(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let buffer = next_account_info(account_info_iter)?;
+
+    let mut header = read_header(program_id, buffer)?;
+
+    {
+        // The fixture is verified in place, without deserializing it into an owned copy.
+        let data = buffer.try_borrow_data()?;
+        let fixture = SP1ProofFixtureRef::from_bytes(&data[BufferHeader::LEN..])
+            .map_err(|_| BufferError::InvalidFixture)?;
+        verify_proof_fixture(fixture, sp1_solana::GROTH16_VK_3_0_0_BYTES)
+            .map_err(|_| BufferError::VerificationFailed)?;
+        // Logged as data rather than formatted, which would link `Debug` machinery.
+        msg!("Verified a proof of program:");
+        sol_log_data(&[fixture.sp1_vkey_hash]);
+    }
+
+    header.verified = true;
+    write_header(buffer, &header)
+}
+
+fn process_close_buffer(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let buffer = next_account_info(account_info_iter)?;
+
+    let header = read_header(program_id, buffer)?;
+    if !authority.is_signer || *authority.key != header.authority {
+        return Err(BufferError::Unauthorized.into());
+    }
+
+    // Move the rent out and clear the data; the runtime deletes the emptied account.
+    let lamports = buffer.lamports();
+    **buffer.try_borrow_mut_lamports()? = 0;
+    **authority.try_borrow_mut_lamports()? += lamports;
+    buffer.try_borrow_mut_data()?.fill(0);
+
+    Ok(())
+}
+
+fn read_header(program_id: &Pubkey, buffer: &AccountInfo) -> Result<BufferHeader, ProgramError> {
+    if buffer.owner != program_id {
+        return Err(BufferError::InvalidAccountOwner.into());
+    }
+    let data = buffer.try_borrow_data()?;
+    data.get(..BufferHeader::LEN)
+        .and_then(|header| BufferHeader::try_from_slice(header).ok())
+        .ok_or(ProgramError::InvalidAccountData)
+}
+
+fn write_header(buffer: &AccountInfo, header: &BufferHeader) -> ProgramResult {
+    header
+        .serialize(&mut &mut buffer.try_borrow_mut_data()?[..BufferHeader::LEN])
+        .map_err(|_| ProgramError::AccountDataTooSmall)
+}
+
+/// Builds the instructions uploading `fixture_bytes` to a new buffer, `chunk_len` bytes at a time.
+///
+/// The first instruction creates the buffer and must be signed by both the authority and the
+/// buffer keypair. Each instruction is meant to be sent in its own transaction.
+pub fn upload_instructions(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer: &Pubkey,
+    fixture_bytes: &[u8],
+    chunk_len: usize,
+) -> Vec<Instruction> {
+    let init = Instruction::new_with_borsh(
+        *program_id,
+        &BufferInstruction::InitBuffer {
+            size: fixture_bytes.len() as u32,
+        },
+        vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*buffer, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    );
+    let writes = fixture_bytes
+        .chunks(chunk_len)
+        .enumerate()
+        .map(|(i, bytes)| {
+            write_chunk(program_id, authority, buffer, (i * chunk_len) as u32, bytes)
+        });
+
+    std::iter::once(init).chain(writes).collect()
+}
+
+/// Builds a `WriteChunk` instruction.
+pub fn write_chunk(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer: &Pubkey,
+    offset: u32,
+    bytes: &[u8],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &BufferInstruction::WriteChunk {
+            offset,
+            bytes: bytes.to_vec(),
+        },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*buffer, false),
+        ],
+    )
+}
+
+/// Builds a `VerifyBuffer` instruction.
+pub fn verify_buffer(program_id: &Pubkey, buffer: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &BufferInstruction::VerifyBuffer,
+        vec![AccountMeta::new(*buffer, false)],
+    )
+}
+
+/// Builds a `CloseBuffer` instruction.
+pub fn close_buffer(program_id: &Pubkey, authority: &Pubkey, buffer: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &BufferInstruction::CloseBuffer,
+        vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*buffer, false),
+        ],
+    )
+}
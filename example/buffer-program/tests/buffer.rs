@@ -0,0 +1,214 @@
+//! Uploads fixtures to a buffer in chunks, then verifies them in place.
+
+use borsh::BorshDeserialize;
+use proof_buffer_program::{
+    close_buffer, upload_instructions, verify_buffer, write_chunk, BufferError, BufferHeader,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_solana::{decode_sp1_vkey_hash, SP1ProofFixture};
+
+/// The largest chunk that fits in a `WriteChunk` transaction.
+const MAX_CHUNK_LEN: usize = 900;
+
+async fn start() -> (ProgramTestContext, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let context = ProgramTest::new(
+        "proof_buffer_program",
+        program_id,
+        processor!(proof_buffer_program::process_instruction),
+    )
+    .start_with_context()
+    .await;
+    (context, program_id)
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction =
+        Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    transaction.sign(&all_signers, recent_blockhash);
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap())
+}
+
+fn custom_error(error: BufferError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+/// Uploads `fixture_bytes` to a new buffer, one transaction per chunk.
+async fn upload(
+    context: &mut ProgramTestContext,
+    program_id: Pubkey,
+    fixture_bytes: &[u8],
+    chunk_len: usize,
+) -> Keypair {
+    let buffer = Keypair::new();
+    let authority = context.payer.pubkey();
+    let instructions = upload_instructions(
+        &program_id,
+        &authority,
+        &buffer.pubkey(),
+        fixture_bytes,
+        chunk_len,
+    );
+    let mut instructions = instructions.into_iter();
+
+    let init = instructions.next().unwrap();
+    assert_eq!(process(context, init, &[&buffer]).await, Ok(()));
+    for write in instructions {
+        assert_eq!(process(context, write, &[]).await, Ok(()));
+    }
+
+    buffer
+}
+
+async fn read_header(context: &mut ProgramTestContext, buffer: Pubkey) -> BufferHeader {
+    let account = context
+        .banks_client
+        .get_account(buffer)
+        .await
+        .unwrap()
+        .unwrap();
+    BufferHeader::try_from_slice(&account.data[..BufferHeader::LEN]).unwrap()
+}
+
+fn load_fixture(sp1_public_inputs: Option<&[u8]>) -> SP1ProofFixture {
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    SP1ProofFixture::from_components(
+        &sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs.unwrap_or(sp1_proof_with_public_values.public_values.as_slice()),
+        &sp1_vkey_hash,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_upload_verify_and_close() {
+    let (mut context, program_id) = start().await;
+    let authority = context.payer.pubkey();
+
+    // Upload the fibonacci fixture in 4 chunks.
+    let fixture_bytes = load_fixture(None).to_bytes();
+    let buffer = upload(&mut context, program_id, &fixture_bytes, 100).await;
+    assert!(!read_header(&mut context, buffer.pubkey()).await.verified);
+
+    let verify = verify_buffer(&program_id, &buffer.pubkey());
+    assert_eq!(process(&mut context, verify, &[]).await, Ok(()));
+    assert!(read_header(&mut context, buffer.pubkey()).await.verified);
+
+    // A verified buffer is immutable.
+    let write = write_chunk(&program_id, &authority, &buffer.pubkey(), 0, &[0]);
+    assert_eq!(
+        process(&mut context, write, &[]).await,
+        custom_error(BufferError::AlreadyVerified)
+    );
+
+    // Closing returns the rent to the authority.
+    let rent = context
+        .banks_client
+        .get_balance(buffer.pubkey())
+        .await
+        .unwrap();
+    let balance_before = context.banks_client.get_balance(authority).await.unwrap();
+    let close = close_buffer(&program_id, &authority, &buffer.pubkey());
+    assert_eq!(process(&mut context, close, &[]).await, Ok(()));
+    let balance_after = context.banks_client.get_balance(authority).await.unwrap();
+    assert!(balance_after > balance_before && balance_after <= balance_before + rent);
+    assert!(context
+        .banks_client
+        .get_account(buffer.pubkey())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_upload_larger_than_a_transaction() {
+    let (mut context, program_id) = start().await;
+
+    // A fixture carrying 2 KB of public values doesn't fit in a transaction, and takes 3 chunks.
+    // The only proof at hand is for the fibonacci values, so the upload succeeds but the fixture
+    // doesn't verify.
+    let public_values = vec![7u8; 2048];
+    let fixture_bytes = load_fixture(Some(&public_values)).to_bytes();
+    assert!(fixture_bytes.len() > 1232);
+    assert_eq!(fixture_bytes.len().div_ceil(MAX_CHUNK_LEN), 3);
+
+    let buffer = upload(&mut context, program_id, &fixture_bytes, MAX_CHUNK_LEN).await;
+    let account = context
+        .banks_client
+        .get_account(buffer.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&account.data[BufferHeader::LEN..], &fixture_bytes[..]);
+
+    let verify = verify_buffer(&program_id, &buffer.pubkey());
+    assert_eq!(
+        process(&mut context, verify, &[]).await,
+        custom_error(BufferError::VerificationFailed)
+    );
+    assert!(!read_header(&mut context, buffer.pubkey()).await.verified);
+}
+
+#[tokio::test]
+async fn test_write_checks() {
+    let (mut context, program_id) = start().await;
+    let authority = context.payer.pubkey();
+    let buffer = upload(&mut context, program_id, &[0u8; 64], MAX_CHUNK_LEN).await;
+
+    let write = write_chunk(&program_id, &authority, &buffer.pubkey(), 60, &[1u8; 8]);
+    assert_eq!(
+        process(&mut context, write, &[]).await,
+        custom_error(BufferError::OutOfBounds)
+    );
+
+    let write = write_chunk(
+        &program_id,
+        &authority,
+        &buffer.pubkey(),
+        u32::MAX,
+        &[1u8; 8],
+    );
+    assert_eq!(
+        process(&mut context, write, &[]).await,
+        custom_error(BufferError::OutOfBounds)
+    );
+
+    let other = Keypair::new();
+    let write = write_chunk(&program_id, &other.pubkey(), &buffer.pubkey(), 0, &[1u8; 8]);
+    assert_eq!(
+        process(&mut context, write, &[&other]).await,
+        custom_error(BufferError::Unauthorized)
+    );
+
+    let close = close_buffer(&program_id, &other.pubkey(), &buffer.pubkey());
+    assert_eq!(
+        process(&mut context, close, &[&other]).await,
+        custom_error(BufferError::Unauthorized)
+    );
+}
@@ -0,0 +1,77 @@
+//! An example program that verifies fibonacci proofs by invoking the standalone SP1 verifier
+//! program, instead of embedding the verifier.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use sp1_solana::{cpi::invoke_verify, Sp1PublicValues};
+use sp1_solana_interface::VerifyArgs;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Derived by running `vk.bytes32()` on the fibonacci program's vkey.
+const FIBONACCI_VKEY_HASH: [u8; 32] =
+    hex_literal::hex!("007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83");
+
+/// The address of the standalone verifier program this program trusts.
+///
+/// A proof is only checked if it's this program that is invoked: any program passed in its place
+/// could succeed without checking anything.
+pub const VERIFIER_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("sQxAEoxoJStMgPJuXGcwBGLzTsutDkx9GQ6QaRdyTNz");
+
+/// The instruction data for the program.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SP1Groth16Proof {
+    /// The proof in SP1's standard format, prefixed with the Groth16 vkey hash.
+    pub proof: Vec<u8>,
+    pub sp1_public_inputs: Sp1PublicValues,
+}
+
+/// Expects the verifier program, at [`VERIFIER_PROGRAM_ID`], as its only account.
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let verifier_program = next_account_info(account_info_iter)?;
+    if *verifier_program.key != VERIFIER_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let groth16_proof = SP1Groth16Proof::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    // The verifier program only knows the v3.0.0 verification key.
+    let (selector, proof) = groth16_proof
+        .proof
+        .split_first_chunk::<4>()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    if *selector != sp1_solana::GROTH16_VK_3_0_0_HASH {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let args = VerifyArgs {
+        sp1_vkey_hash: FIBONACCI_VKEY_HASH,
+        committed_values_digest: groth16_proof.sp1_public_inputs.digest().to_bytes(),
+        proof: proof
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?,
+    };
+    invoke_verify(
+        verifier_program.key,
+        &args,
+        core::slice::from_ref(verifier_program),
+    )?;
+
+    msg!("Proof verified by {}", verifier_program.key);
+
+    Ok(())
+}
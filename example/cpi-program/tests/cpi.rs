@@ -0,0 +1,169 @@
+//! Calls the standalone verifier program directly, and through the fibonacci CPI program.
+
+use fibonacci_cpi_program::{SP1Groth16Proof, VERIFIER_PROGRAM_ID};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_solana::hash_public_inputs;
+use sp1_solana_interface::{verify_instruction, VerifyArgs};
+
+const FIBONACCI_VKEY_HASH: [u8; 32] =
+    hex_literal::hex!("007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83");
+
+struct Context {
+    banks_client: BanksClient,
+    payer: Keypair,
+    recent_blockhash: Hash,
+    verifier_program_id: Pubkey,
+    cpi_program_id: Pubkey,
+    rogue_program_id: Pubkey,
+}
+
+/// A program that accepts every instruction, in place of the verifier program.
+fn always_succeed(_: &Pubkey, _: &[AccountInfo], _: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+async fn start() -> Context {
+    let verifier_program_id = VERIFIER_PROGRAM_ID;
+    let cpi_program_id = Pubkey::new_unique();
+    let rogue_program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "sp1_verifier_program",
+        verifier_program_id,
+        processor!(sp1_verifier_program::process_instruction),
+    );
+    program_test.add_program(
+        "fibonacci_cpi_program",
+        cpi_program_id,
+        processor!(fibonacci_cpi_program::process_instruction),
+    );
+    program_test.add_program(
+        "always_succeed",
+        rogue_program_id,
+        processor!(always_succeed),
+    );
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    Context {
+        banks_client,
+        payer,
+        recent_blockhash,
+        verifier_program_id,
+        cpi_program_id,
+        rogue_program_id,
+    }
+}
+
+async fn process(context: &Context, instruction: Instruction) -> bool {
+    let mut transaction =
+        Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer], context.recent_blockhash);
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .is_ok()
+}
+
+fn load_fixture() -> SP1ProofWithPublicValues {
+    SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap()
+}
+
+#[tokio::test]
+async fn test_verify_directly() {
+    let context = start().await;
+    let sp1_proof_with_public_values = load_fixture();
+
+    let mut args = VerifyArgs {
+        sp1_vkey_hash: FIBONACCI_VKEY_HASH,
+        committed_values_digest: hash_public_inputs(
+            sp1_proof_with_public_values.public_values.as_slice(),
+        ),
+        proof: sp1_proof_with_public_values.bytes()[4..]
+            .try_into()
+            .unwrap(),
+    };
+    assert!(
+        process(
+            &context,
+            verify_instruction(&context.verifier_program_id, &args)
+        )
+        .await
+    );
+
+    args.committed_values_digest[31] ^= 1;
+    assert!(
+        !process(
+            &context,
+            verify_instruction(&context.verifier_program_id, &args)
+        )
+        .await
+    );
+}
+
+#[tokio::test]
+async fn test_verify_through_cpi() {
+    let context = start().await;
+    let sp1_proof_with_public_values = load_fixture();
+
+    let mut groth16_proof = SP1Groth16Proof {
+        proof: sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs: sp1_proof_with_public_values.public_values.to_vec().into(),
+    };
+    let accounts = vec![AccountMeta::new_readonly(
+        context.verifier_program_id,
+        false,
+    )];
+
+    let instruction =
+        Instruction::new_with_borsh(context.cpi_program_id, &groth16_proof, accounts.clone());
+    assert!(process(&context, instruction).await);
+
+    // A failed verification in the verifier program fails the calling instruction.
+    let mut sp1_public_inputs = groth16_proof.sp1_public_inputs.to_vec();
+    sp1_public_inputs[0] ^= 1;
+    groth16_proof.sp1_public_inputs = sp1_public_inputs.into();
+    let instruction = Instruction::new_with_borsh(context.cpi_program_id, &groth16_proof, accounts);
+    assert!(!process(&context, instruction).await);
+}
+
+#[tokio::test]
+async fn test_rogue_verifier_program() {
+    let context = start().await;
+    let sp1_proof_with_public_values = load_fixture();
+
+    // Public values the proof doesn't commit to, which a program that checks nothing accepts.
+    let mut sp1_public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+    sp1_public_inputs[0] ^= 1;
+    let groth16_proof = SP1Groth16Proof {
+        proof: sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs: sp1_public_inputs.into(),
+    };
+    let accounts = vec![AccountMeta::new_readonly(context.rogue_program_id, false)];
+    let instruction = Instruction::new_with_borsh(context.cpi_program_id, &groth16_proof, accounts);
+
+    let mut transaction =
+        Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer], context.recent_blockhash);
+    let error = context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(0, InstructionError::IncorrectProgramId)
+    );
+}
@@ -0,0 +1,40 @@
+//! A minimal fibonacci verifier built on `pinocchio` instead of `solana-program`.
+//!
+//! The instruction data is the SP1 Groth16 proof, selector included, followed by the public
+//! values. Verification failures are reported as `ProgramError::Custom(error.code())`, with the
+//! codes of [`sp1_solana::Error`].
+
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+use sp1_solana::{verify_proof_with_digest, CommittedValuesDigest};
+
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
+
+/// The vkey hash of the fibonacci SP1 program.
+pub const FIBONACCI_VKEY_HASH: [u8; 32] =
+    hex_literal::hex!("007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83");
+
+/// The length of an SP1 Groth16 proof: the 4-byte selector and the 256-byte proof.
+pub const PROOF_LEN: usize = sp1_solana::PROOF_WITH_SELECTOR_LEN;
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    if instruction_data.len() < PROOF_LEN {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (proof, sp1_public_inputs) = instruction_data.split_at(PROOF_LEN);
+
+    verify_proof_with_digest(
+        proof,
+        &CommittedValuesDigest::from_public_values(sp1_public_inputs),
+        &FIBONACCI_VKEY_HASH,
+        sp1_solana::GROTH16_VK_3_0_0_BYTES,
+        &sp1_solana::GROTH16_VK_3_0_0_HASH,
+    )
+    .map_err(|error| ProgramError::Custom(error.code()))
+}
@@ -0,0 +1,8 @@
+fn main() {
+    // Fails the build if the fibonacci program's ELF no longer hashes to the pinned vkey hash.
+    sp1_solana_build::vkey_hash_from_elf(
+        "FIBONACCI_VKEY_HASH",
+        "../sp1-program/elf/riscv32im-succinct-zkvm-elf",
+        "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83",
+    );
+}
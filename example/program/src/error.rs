@@ -0,0 +1,85 @@
+use solana_program::program_error::ProgramError;
+
+/// Errors returned by the fibonacci verifier program, as `ProgramError::Custom(error as u32)`.
+///
+/// The codes are part of the program's interface: append new variants, and never reorder them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FibonacciVerifierError {
+    /// The proof was already consumed.
+    NullifierAlreadyUsed,
+    /// The instruction data is empty, or doesn't match its instruction.
+    InvalidInstructionData,
+    /// The leading discriminator doesn't name an instruction.
+    UnknownInstruction,
+    /// The proof was generated for a different Groth16 verification key.
+    Groth16VkeyHashMismatch,
+    /// The proof doesn't verify against the public values.
+    InvalidProof,
+    /// The proof is truncated, or doesn't fit in the account data at the given offset.
+    TruncatedProof,
+    /// A PDA account doesn't match the address derived from the proof.
+    InvalidPdaAddress,
+    /// The verification record doesn't exist.
+    RecordNotFound,
+    /// Only the submitter of a verification record can close it.
+    UnauthorizedClose,
+    /// A required signature is missing.
+    MissingSignature,
+    /// The public values are not a fibonacci `(n, a, b)` triple.
+    InvalidPublicValues,
+    /// The public values account was already initialized.
+    AlreadyInitialized,
+    /// Only the authority of a public values account can write to it.
+    UnauthorizedWrite,
+    /// A write or read falls outside the public values.
+    PublicValuesOutOfBounds,
+    /// The public values don't hash to the expected committed values digest.
+    DigestMismatch,
+    /// The account is not owned by the program.
+    InvalidAccountOwner,
+    /// The proof was generated for a different SP1 program.
+    ProgramVkeyHashMismatch,
+    /// The verification key is longer than [`sp1_solana::vk_account::MAX_VK_LEN`].
+    VkTooLarge,
+    /// The verification key is malformed.
+    InvalidVerificationKey,
+    /// Only the authority of the config can update its verification key.
+    UnauthorizedVkUpdate,
+    /// A proof, the public values or a chunk of them is longer than the program accepts.
+    PayloadTooLarge,
+    /// A batch has no proofs.
+    EmptyBatch,
+    /// Only the authority of the allowlist can add or remove program vkey hashes.
+    UnauthorizedAllowlistUpdate,
+    /// The allowlist already holds [`crate::MAX_ALLOWED_VKEYS`] program vkey hashes.
+    AllowlistFull,
+    /// The program vkey hash is already allowed.
+    VkeyAlreadyAllowed,
+    /// The program vkey hash isn't allowed.
+    VkeyNotAllowed,
+    /// Only the upgrade authority of the program can initialize its config or allowlist.
+    NotUpgradeAuthority,
+}
+
+impl From<FibonacciVerifierError> for ProgramError {
+    fn from(error: FibonacciVerifierError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+impl From<sp1_solana::Error> for FibonacciVerifierError {
+    fn from(error: sp1_solana::Error) -> Self {
+        match error {
+            sp1_solana::Error::Groth16VkeyHashMismatch
+            | sp1_solana::Error::UnsupportedSp1Version { .. } => Self::Groth16VkeyHashMismatch,
+            sp1_solana::Error::InvalidInput => Self::TruncatedProof,
+            sp1_solana::Error::PublicValuesDigestMismatch => Self::DigestMismatch,
+            sp1_solana::Error::NoVkeyHashMatched { .. }
+            | sp1_solana::Error::InvalidProgramVkeyHash => Self::ProgramVkeyHashMismatch,
+            sp1_solana::Error::InputTooLarge
+            | sp1_solana::Error::PublicValuesTooLarge { .. }
+            | sp1_solana::Error::TrailingBytes { .. } => Self::PayloadTooLarge,
+            _ => Self::InvalidProof,
+        }
+    }
+}
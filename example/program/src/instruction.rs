@@ -0,0 +1,552 @@
+//! The instructions of the program.
+//!
+//! With the `cpi` feature, this module also provides a builder for each instruction, for clients
+//! and for programs that invoke this one. Clients encoding instructions in other languages can
+//! check them against the [`InstructionLayout`] of [`VerifierInstruction`], whose golden encodings
+//! are in `tests/golden`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "cpi")]
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+};
+#[cfg(feature = "cpi")]
+use sp1_solana::{
+    allowlist::ProgramVkeyAllowlist, record::find_verification_record_address,
+    upgrade_authority::program_data_address, Nullifier,
+};
+use sp1_solana::{
+    values_account::MAX_CHUNK_LEN, SP1ProofFixture, Sp1PublicValues, PROOF_LEN,
+    PROOF_WITH_SELECTOR_LEN,
+};
+use sp1_solana_interface::layout::{FieldDesc, InstructionLayout, LayoutBuilder};
+
+#[cfg(feature = "cpi")]
+use crate::{find_config_address, FIBONACCI_VKEY_HASH};
+use crate::{FibonacciVerifierError, MAX_PUBLIC_VALUES_LEN};
+
+/// A Groth16 proof and the SP1 public values it commits to.
+#[derive(BorshDeserialize, BorshSerialize, Clone)]
+pub struct SP1Groth16Proof {
+    pub proof: Vec<u8>,
+    pub sp1_public_inputs: Sp1PublicValues,
+}
+
+impl SP1Groth16Proof {
+    fn check_lengths(&self) -> Result<(), FibonacciVerifierError> {
+        if self.proof.len() < PROOF_WITH_SELECTOR_LEN {
+            return Err(FibonacciVerifierError::TruncatedProof);
+        }
+        // The verifier ignores bytes after the proof, so they're rejected here.
+        if self.proof.len() > PROOF_WITH_SELECTOR_LEN
+            || self.sp1_public_inputs.len() > MAX_PUBLIC_VALUES_LEN
+        {
+            return Err(FibonacciVerifierError::PayloadTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Adds the fields of the proof to `layout`, with their names prefixed with `prefix`.
+    fn describe(&self, layout: &mut LayoutBuilder, prefix: &str) {
+        let Self {
+            proof,
+            sp1_public_inputs,
+        } = self;
+        layout.vec(format!("{prefix}proof"), proof.len()).vec(
+            format!("{prefix}sp1_public_inputs"),
+            sp1_public_inputs.len(),
+        );
+    }
+}
+
+/// The instructions of the program.
+///
+/// The instruction data is the Borsh encoding of this enum: a leading one-byte discriminator, the
+/// index of the variant, followed by its fields. New instructions must be appended, so that the
+/// discriminators of existing ones don't change.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum VerifierInstruction {
+    /// Verifies a proof passed in the instruction data.
+    Verify(SP1Groth16Proof),
+    /// Verifies a proof read directly from the data of the first account, which is useful when the
+    /// proof has been uploaded to a buffer account.
+    ///
+    /// The proof is stored at `proof_offset` in SP1's standard format, i.e. prefixed with the first
+    /// 4 bytes of the Groth16 vkey hash.
+    VerifyFromAccount {
+        proof_offset: u32,
+        sp1_public_inputs: Vec<u8>,
+    },
+    /// Verifies a proof, then records it in the verification record PDA of the program vkey hash
+    /// and committed values digest.
+    ///
+    /// Accounts: the payer (signer, writable), the record PDA (writable), and the system program.
+    ///
+    /// Submitting an already recorded proof succeeds without modifying the record, so the record
+    /// keeps the slot and submitter of the first submission.
+    VerifyAndStore(SP1Groth16Proof),
+    /// Verifies a proof and consumes it, by creating its nullifier PDA.
+    ///
+    /// Accounts: the payer (signer, writable), the nullifier PDA (writable), and the system
+    /// program.
+    ///
+    /// The nullifier is salted with `n`, the first public value. Replaying a consumed proof fails
+    /// with [`FibonacciVerifierError::NullifierAlreadyUsed`].
+    VerifyAndNullify(SP1Groth16Proof),
+    /// Succeeds if and only if a proof with this committed values digest was recorded by
+    /// [`VerifierInstruction::VerifyAndStore`].
+    ///
+    /// Accounts: the record PDA.
+    AssertRecorded { committed_values_digest: [u8; 32] },
+    /// Closes a verification record, returning its rent to the submitter.
+    ///
+    /// Accounts: the submitter (signer, writable), and the record PDA (writable).
+    CloseRecord { committed_values_digest: [u8; 32] },
+    /// Initializes a public values account created with
+    /// [`sp1_solana::values_account::create_public_values_account`], making the signer its
+    /// authority.
+    ///
+    /// Accounts: the authority (signer), and the public values account (writable).
+    InitPublicValues { len: u32 },
+    /// Writes a chunk of public values at `offset` in a public values account.
+    ///
+    /// Accounts: the authority (signer), and the public values account (writable).
+    WritePublicValues { offset: u32, bytes: Vec<u8> },
+    /// Verifies a raw 256-byte proof whose public values are read from an account, for public
+    /// values too large to fit in the instruction data.
+    ///
+    /// The values are hashed on-chain and checked against `committed_values_digest` before the
+    /// proof is verified.
+    ///
+    /// Accounts: the public values account.
+    VerifyWithValuesAccount {
+        proof: [u8; PROOF_LEN],
+        committed_values_digest: [u8; 32],
+    },
+    /// Verifies a self-contained proof fixture.
+    ///
+    /// Unlike the other instructions, the fixture names the SP1 program it was generated for, so
+    /// a proof of another program fails with
+    /// [`FibonacciVerifierError::ProgramVkeyHashMismatch`] rather than
+    /// [`FibonacciVerifierError::InvalidProof`].
+    VerifyFixture(SP1ProofFixture),
+    /// Creates the config PDA, holding the gnark verification key `vk`, with the signer as its
+    /// authority. The signer must be the upgrade authority of the program.
+    ///
+    /// Accounts: the authority (signer, writable), the config PDA (writable), the system program,
+    /// and the `ProgramData` account of the program.
+    InitializeConfig { vk: Vec<u8> },
+    /// Replaces the verification key of the config, e.g. after an SP1 circuit upgrade.
+    ///
+    /// Accounts: the authority (signer), and the config PDA (writable).
+    UpdateVk { vk: Vec<u8> },
+    /// Verifies a proof against the verification key of the config, instead of the one embedded
+    /// in the program.
+    ///
+    /// Accounts: the config PDA.
+    VerifyWithConfig(SP1Groth16Proof),
+    /// Verifies a batch of proofs in order, stopping at the first that fails.
+    ///
+    /// A verification event is emitted for each proof. A failing proof fails the whole
+    /// transaction, so either every proof of the batch is accepted or none is.
+    VerifyBatch(Vec<SP1Groth16Proof>),
+    /// Allows proofs of the SP1 program with vkey hash `sp1_vkey_hash` in
+    /// [`VerifierInstruction::VerifyWithAllowlist`], e.g. a new version of the program.
+    ///
+    /// Accounts: the authority (signer), and the allowlist PDA (writable).
+    AddAllowedVkey { sp1_vkey_hash: [u8; 32] },
+    /// Stops accepting proofs of the SP1 program with vkey hash `sp1_vkey_hash`.
+    ///
+    /// Accounts: the authority (signer), and the allowlist PDA (writable).
+    RemoveAllowedVkey { sp1_vkey_hash: [u8; 32] },
+    /// Verifies a proof of any of the SP1 programs in the allowlist, instead of the fibonacci
+    /// program alone.
+    ///
+    /// Accounts: the allowlist PDA.
+    VerifyWithAllowlist(SP1Groth16Proof),
+    /// Creates the empty allowlist PDA, with the signer as its authority. The signer must be the
+    /// upgrade authority of the program.
+    ///
+    /// Accounts: the authority (signer, writable), the allowlist PDA (writable), the system
+    /// program, and the `ProgramData` account of the program.
+    InitializeAllowlist,
+    /// Absorbs the next chunk of public values into the running SHA-256 state of a hash account,
+    /// for public values too large to be hashed in a single transaction.
+    ///
+    /// The hash account is created by the client, owned by the program and
+    /// [`crate::HASH_ACCOUNT_LEN`] bytes long. The first chunk makes the signer its authority.
+    ///
+    /// Accounts: the authority (signer), and the hash account (writable).
+    AbsorbPublicValues { bytes: Vec<u8> },
+    /// Verifies a proof in SP1's standard format against the committed values digest of the
+    /// public values absorbed into a hash account, then closes the account.
+    ///
+    /// Accounts: the authority (signer, writable), and the hash account (writable).
+    FinalizeAndVerify {
+        proof: [u8; PROOF_WITH_SELECTOR_LEN],
+    },
+}
+
+impl VerifierInstruction {
+    /// The number of instructions, i.e. the first unused discriminator.
+    const COUNT: u8 = 20;
+
+    /// The discriminator of [`VerifierInstruction::VerifyFixture`].
+    const VERIFY_FIXTURE: u8 = 9;
+
+    /// Decodes the instruction data, telling unknown discriminators apart from malformed fields.
+    ///
+    /// Fields longer than the program accepts fail with
+    /// [`FibonacciVerifierError::PayloadTooLarge`], before anything is hashed or verified.
+    pub fn unpack(instruction_data: &[u8]) -> Result<Self, FibonacciVerifierError> {
+        let instruction = match instruction_data.split_first() {
+            None => return Err(FibonacciVerifierError::InvalidInstructionData),
+            Some((&discriminator, _)) if discriminator >= Self::COUNT => {
+                return Err(FibonacciVerifierError::UnknownInstruction)
+            }
+            // The length of the public values is checked before they're copied out of the
+            // instruction data, against the program's limit for fixtures, and against
+            // `MAX_SP1_PUBLIC_VALUES_LEN` in the other instructions.
+            Some((&Self::VERIFY_FIXTURE, fixture)) => {
+                SP1ProofFixture::from_bytes_with_limit(fixture, MAX_PUBLIC_VALUES_LEN)
+                    .map(Self::VerifyFixture)
+                    .map_err(payload_error)?
+            }
+            Some(_) => Self::try_from_slice(instruction_data)
+                .map_err(|error| payload_error(sp1_solana::Error::from_borsh(error)))?,
+        };
+        instruction.check_lengths()?;
+        Ok(instruction)
+    }
+
+    /// Checks the variable-length fields that the processor doesn't bound itself, beyond the
+    /// limit the deserializer enforces.
+    fn check_lengths(&self) -> Result<(), FibonacciVerifierError> {
+        match self {
+            Self::Verify(groth16_proof)
+            | Self::VerifyAndStore(groth16_proof)
+            | Self::VerifyAndNullify(groth16_proof)
+            | Self::VerifyWithConfig(groth16_proof)
+            | Self::VerifyWithAllowlist(groth16_proof) => groth16_proof.check_lengths(),
+            Self::VerifyBatch(groth16_proofs) if groth16_proofs.is_empty() => {
+                Err(FibonacciVerifierError::EmptyBatch)
+            }
+            Self::VerifyBatch(groth16_proofs) => groth16_proofs
+                .iter()
+                .try_for_each(SP1Groth16Proof::check_lengths),
+            Self::VerifyFromAccount {
+                sp1_public_inputs, ..
+            } if sp1_public_inputs.len() > MAX_PUBLIC_VALUES_LEN => {
+                Err(FibonacciVerifierError::PayloadTooLarge)
+            }
+            Self::WritePublicValues { bytes, .. } | Self::AbsorbPublicValues { bytes }
+                if bytes.len() > MAX_CHUNK_LEN =>
+            {
+                Err(FibonacciVerifierError::PayloadTooLarge)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The error of instruction data that fails to deserialize: oversized public values are reported
+/// as such, and anything else as invalid data.
+fn payload_error(error: sp1_solana::Error) -> FibonacciVerifierError {
+    match error {
+        sp1_solana::Error::PublicValuesTooLarge { .. } => FibonacciVerifierError::PayloadTooLarge,
+        _ => FibonacciVerifierError::InvalidInstructionData,
+    }
+}
+
+impl InstructionLayout for VerifierInstruction {
+    fn layout(&self) -> Vec<FieldDesc> {
+        let mut layout = LayoutBuilder::new();
+        layout.discriminator();
+        match self {
+            Self::Verify(groth16_proof)
+            | Self::VerifyAndStore(groth16_proof)
+            | Self::VerifyAndNullify(groth16_proof)
+            | Self::VerifyWithConfig(groth16_proof)
+            | Self::VerifyWithAllowlist(groth16_proof) => groth16_proof.describe(&mut layout, ""),
+            Self::VerifyFromAccount {
+                proof_offset: _,
+                sp1_public_inputs,
+            } => {
+                layout
+                    .u32("proof_offset")
+                    .vec("sp1_public_inputs", sp1_public_inputs.len());
+            }
+            Self::AssertRecorded {
+                committed_values_digest,
+            }
+            | Self::CloseRecord {
+                committed_values_digest,
+            } => {
+                layout.bytes("committed_values_digest", committed_values_digest.len());
+            }
+            Self::InitPublicValues { len: _ } => {
+                layout.u32("len");
+            }
+            Self::WritePublicValues { offset: _, bytes } => {
+                layout.u32("offset").vec("bytes", bytes.len());
+            }
+            Self::VerifyWithValuesAccount {
+                proof,
+                committed_values_digest,
+            } => {
+                layout
+                    .bytes("proof", proof.len())
+                    .bytes("committed_values_digest", committed_values_digest.len());
+            }
+            Self::VerifyFixture(SP1ProofFixture {
+                groth16_vkey_hash,
+                proof,
+                sp1_vkey_hash,
+                committed_values_digest,
+                sp1_public_inputs,
+            }) => {
+                layout
+                    .bytes("groth16_vkey_hash", groth16_vkey_hash.len())
+                    .bytes("proof", proof.len())
+                    .bytes("sp1_vkey_hash", sp1_vkey_hash.len())
+                    .bytes("committed_values_digest", committed_values_digest.len())
+                    .vec("sp1_public_inputs", sp1_public_inputs.len());
+            }
+            Self::InitializeConfig { vk } | Self::UpdateVk { vk } => {
+                layout.vec("vk", vk.len());
+            }
+            Self::VerifyBatch(groth16_proofs) => {
+                layout.u32("groth16_proofs.len");
+                for (index, groth16_proof) in groth16_proofs.iter().enumerate() {
+                    groth16_proof.describe(&mut layout, &format!("groth16_proofs[{index}]."));
+                }
+            }
+            Self::AddAllowedVkey { sp1_vkey_hash } | Self::RemoveAllowedVkey { sp1_vkey_hash } => {
+                layout.bytes("sp1_vkey_hash", sp1_vkey_hash.len());
+            }
+            Self::InitializeAllowlist => {}
+            Self::AbsorbPublicValues { bytes } => {
+                layout.vec("bytes", bytes.len());
+            }
+            Self::FinalizeAndVerify { proof } => {
+                layout.bytes("proof", proof.len());
+            }
+        }
+        layout.finish()
+    }
+}
+
+/// Builds a `Verify` instruction.
+#[cfg(feature = "cpi")]
+pub fn verify(program_id: &Pubkey, groth16_proof: SP1Groth16Proof) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::Verify(groth16_proof),
+        vec![],
+    )
+}
+
+/// Builds a `VerifyFromAccount` instruction, verifying the proof stored in `proof_account` at
+/// `proof_offset`.
+#[cfg(feature = "cpi")]
+pub fn verify_from_account(
+    program_id: &Pubkey,
+    proof_account: &Pubkey,
+    proof_offset: u32,
+    sp1_public_inputs: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::VerifyFromAccount {
+            proof_offset,
+            sp1_public_inputs,
+        },
+        vec![AccountMeta::new_readonly(*proof_account, false)],
+    )
+}
+
+/// Builds a `VerifyAndStore` instruction, recording the proof in its verification record PDA.
+#[cfg(feature = "cpi")]
+pub fn verify_and_store(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    groth16_proof: SP1Groth16Proof,
+) -> Instruction {
+    let committed_values_digest = groth16_proof.sp1_public_inputs.digest();
+    let (record_address, _) = find_verification_record_address(
+        program_id,
+        &FIBONACCI_VKEY_HASH,
+        &committed_values_digest,
+    );
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::VerifyAndStore(groth16_proof),
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(record_address, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
+
+/// Builds a `VerifyAndNullify` instruction, consuming the proof.
+#[cfg(feature = "cpi")]
+pub fn verify_and_nullify(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    groth16_proof: SP1Groth16Proof,
+) -> Instruction {
+    let committed_values_digest = groth16_proof.sp1_public_inputs.digest();
+    // Public values too short to be salted are rejected by the program anyway.
+    let salt = groth16_proof.sp1_public_inputs.get(..4).unwrap_or_default();
+    let (nullifier_address, _) =
+        Nullifier::new(&FIBONACCI_VKEY_HASH, &committed_values_digest, salt)
+            .find_program_address(program_id);
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::VerifyAndNullify(groth16_proof),
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(nullifier_address, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
+
+/// Builds an `AssertRecorded` instruction.
+#[cfg(feature = "cpi")]
+pub fn assert_recorded(program_id: &Pubkey, committed_values_digest: [u8; 32]) -> Instruction {
+    let (record_address, _) = find_verification_record_address(
+        program_id,
+        &FIBONACCI_VKEY_HASH,
+        &committed_values_digest,
+    );
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::AssertRecorded {
+            committed_values_digest,
+        },
+        vec![AccountMeta::new_readonly(record_address, false)],
+    )
+}
+
+/// Builds a `CloseRecord` instruction, returning the rent of the record to `submitter`.
+#[cfg(feature = "cpi")]
+pub fn close_record(
+    program_id: &Pubkey,
+    submitter: &Pubkey,
+    committed_values_digest: [u8; 32],
+) -> Instruction {
+    let (record_address, _) = find_verification_record_address(
+        program_id,
+        &FIBONACCI_VKEY_HASH,
+        &committed_values_digest,
+    );
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::CloseRecord {
+            committed_values_digest,
+        },
+        vec![
+            AccountMeta::new(*submitter, true),
+            AccountMeta::new(record_address, false),
+        ],
+    )
+}
+
+/// Builds a `VerifyFixture` instruction.
+#[cfg(feature = "cpi")]
+pub fn verify_fixture(program_id: &Pubkey, fixture: SP1ProofFixture) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::VerifyFixture(fixture),
+        vec![],
+    )
+}
+
+/// Builds a `VerifyWithConfig` instruction.
+#[cfg(feature = "cpi")]
+pub fn verify_with_config(program_id: &Pubkey, groth16_proof: SP1Groth16Proof) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::VerifyWithConfig(groth16_proof),
+        vec![AccountMeta::new_readonly(
+            find_config_address(program_id).0,
+            false,
+        )],
+    )
+}
+
+/// Builds a `VerifyBatch` instruction.
+#[cfg(feature = "cpi")]
+pub fn verify_batch(program_id: &Pubkey, groth16_proofs: Vec<SP1Groth16Proof>) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::VerifyBatch(groth16_proofs),
+        vec![],
+    )
+}
+
+/// Builds an `AddAllowedVkey` instruction.
+#[cfg(feature = "cpi")]
+pub fn add_allowed_vkey(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    sp1_vkey_hash: [u8; 32],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::AddAllowedVkey { sp1_vkey_hash },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(ProgramVkeyAllowlist::find_address(program_id).0, false),
+        ],
+    )
+}
+
+/// Builds a `RemoveAllowedVkey` instruction.
+#[cfg(feature = "cpi")]
+pub fn remove_allowed_vkey(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    sp1_vkey_hash: [u8; 32],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::RemoveAllowedVkey { sp1_vkey_hash },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(ProgramVkeyAllowlist::find_address(program_id).0, false),
+        ],
+    )
+}
+
+/// Builds a `VerifyWithAllowlist` instruction.
+#[cfg(feature = "cpi")]
+pub fn verify_with_allowlist(program_id: &Pubkey, groth16_proof: SP1Groth16Proof) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::VerifyWithAllowlist(groth16_proof),
+        vec![AccountMeta::new_readonly(
+            ProgramVkeyAllowlist::find_address(program_id).0,
+            false,
+        )],
+    )
+}
+
+/// Builds an `InitializeAllowlist` instruction.
+#[cfg(feature = "cpi")]
+pub fn initialize_allowlist(program_id: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &VerifierInstruction::InitializeAllowlist,
+        vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(ProgramVkeyAllowlist::find_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(program_data_address(program_id), false),
+        ],
+    )
+}
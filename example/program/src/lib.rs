@@ -1,58 +1,49 @@
-use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
-    pubkey::Pubkey,
-};
-use sp1_solana::verify_proof;
+//! An example program that verifies SP1 Groth16 proofs of the fibonacci program.
+//!
+//! [`VerifierInstruction`] lists the instructions, and [`FibonacciVerifierError`] the custom
+//! error codes they fail with.
+//!
+//! To depend on the program from another one, enable the `no-entrypoint` feature, so that the two
+//! entrypoints don't clash, or the `cpi` feature, which also provides the instruction builders of
+//! [`instruction`]. Tests can run the program natively with
+//! `processor!(fibonacci_verifier_contract::process_instruction)`.
+
+mod error;
+pub mod instruction;
+mod processor;
+
+pub use error::FibonacciVerifierError;
+pub use instruction::{SP1Groth16Proof, VerifierInstruction};
+pub use processor::process_instruction;
+
+use solana_program::pubkey::Pubkey;
 
 #[cfg(not(feature = "no-entrypoint"))]
 solana_program::entrypoint!(process_instruction);
 
-#[cfg(not(doctest))]
-/// Derived as follows:
+// `FIBONACCI_VKEY_HASH`, computed by the build script from the ELF of the fibonacci SP1 program,
+// as raw bytes so that it doesn't need to be hex-decoded on-chain.
+include!(concat!(env!("OUT_DIR"), "/fibonacci_vkey_hash.rs"));
+
+/// The longest public values accepted in instruction data.
 ///
-/// ```
-/// let client = sp1_sdk::ProverClient::new();
-/// let (pk, vk) = client.setup(YOUR_ELF_HERE);
-/// let vkey_hash = vk.bytes32();
-/// ```
-const FIBONACCI_VKEY_HASH: &str =
-    "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83";
-
-/// The instruction data for the program.
-#[derive(BorshDeserialize, BorshSerialize)]
-pub struct SP1Groth16Proof {
-    pub proof: Vec<u8>,
-    pub sp1_public_inputs: Vec<u8>,
-}
+/// Larger public values must be uploaded to a public values account, and verified with
+/// [`VerifierInstruction::VerifyWithValuesAccount`].
+pub const MAX_PUBLIC_VALUES_LEN: usize = 512;
+
+/// The seed of the config PDA, which holds the verification key used by
+/// [`VerifierInstruction::VerifyWithConfig`].
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// The number of program vkey hashes the allowlist PDA has room for, which
+/// [`VerifierInstruction::VerifyWithAllowlist`] checks proofs against.
+pub const MAX_ALLOWED_VKEYS: usize = 8;
+
+/// The length of a hash account, which [`VerifierInstruction::AbsorbPublicValues`] streams public
+/// values into: its authority, then the running [`sp1_solana::Sha256State`].
+pub const HASH_ACCOUNT_LEN: usize = 32 + sp1_solana::Sha256State::LEN;
 
-pub fn process_instruction(
-    _program_id: &Pubkey,
-    _accounts: &[AccountInfo],
-    instruction_data: &[u8],
-) -> ProgramResult {
-    // Deserialize the SP1Groth16Proof from the instruction data.
-    let groth16_proof = SP1Groth16Proof::try_from_slice(instruction_data)
-        .map_err(|_| ProgramError::InvalidInstructionData)?;
-
-    // Get the SP1 Groth16 verification key from the `sp1-solana` crate.
-    let vk = sp1_solana::GROTH16_VK_3_0_0_BYTES;
-
-    // Verify the proof.
-    verify_proof(
-        &groth16_proof.proof,
-        &groth16_proof.sp1_public_inputs,
-        &FIBONACCI_VKEY_HASH,
-        vk,
-    )
-    .map_err(|_| ProgramError::InvalidInstructionData)?;
-
-    // Print out the public values.
-    let mut reader = groth16_proof.sp1_public_inputs.as_slice();
-    let n = u32::deserialize(&mut reader).unwrap();
-    let a = u32::deserialize(&mut reader).unwrap();
-    let b = u32::deserialize(&mut reader).unwrap();
-    msg!("Public values: (n: {}, a: {}, b: {})", n, a, b);
-
-    Ok(())
+/// Finds the config PDA of the program.
+pub fn find_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
 }
@@ -0,0 +1,798 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use sp1_solana::{
+    allowlist::{ProgramVkeyAllowlist, ALLOWLIST_SEED},
+    event::emit_verification_event,
+    groth16_public_inputs,
+    nullifier::create_pda,
+    record::{find_verification_record_address, verification_record_seeds, VerificationRecord},
+    upgrade_authority::is_upgrade_authority,
+    values_account::{public_values_account_space, read_public_values, PublicValuesHeader},
+    verify_proof_from_slice, verify_proof_raw_exact,
+    vk_account::{read_vk, verify_proof_with_vk_account, vk_account_space, write_vk, MAX_VK_LEN},
+    CommittedValuesDigest, Nullifier, SP1ProofFixture, Sha256State, VerificationContext, VkSource,
+    VkView, NULLIFIER_SEED, PROOF_LEN, PROOF_WITH_SELECTOR_LEN,
+};
+
+use crate::{
+    find_config_address, FibonacciVerifierError, SP1Groth16Proof, VerifierInstruction, CONFIG_SEED,
+    FIBONACCI_VKEY_HASH, HASH_ACCOUNT_LEN, MAX_ALLOWED_VKEYS,
+};
+
+/// Proofs of the fibonacci program against the SP1 Groth16 verification key and its precomputed
+/// hash from the `sp1-solana` crate.
+static CONTEXT: VerificationContext<'static> = VerificationContext::new(
+    VkSource::BytesWithHash {
+        vk: sp1_solana::GROTH16_VK_3_0_0_BYTES,
+        groth16_vkey_hash: sp1_solana::GROTH16_VK_3_0_0_HASH,
+    },
+    &FIBONACCI_VKEY_HASH,
+);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match VerifierInstruction::unpack(instruction_data)? {
+        VerifierInstruction::Verify(groth16_proof) => process_verify(groth16_proof),
+        VerifierInstruction::VerifyFromAccount {
+            proof_offset,
+            sp1_public_inputs,
+        } => process_verify_from_account(accounts, proof_offset as usize, &sp1_public_inputs),
+        VerifierInstruction::VerifyAndStore(groth16_proof) => {
+            process_verify_and_store(program_id, accounts, groth16_proof)
+        }
+        VerifierInstruction::VerifyAndNullify(groth16_proof) => {
+            process_verify_and_nullify(program_id, accounts, groth16_proof)
+        }
+        VerifierInstruction::AssertRecorded {
+            committed_values_digest,
+        } => process_assert_recorded(program_id, accounts, &committed_values_digest),
+        VerifierInstruction::CloseRecord {
+            committed_values_digest,
+        } => process_close_record(program_id, accounts, &committed_values_digest),
+        VerifierInstruction::InitPublicValues { len } => {
+            process_init_public_values(program_id, accounts, len)
+        }
+        VerifierInstruction::WritePublicValues { offset, bytes } => {
+            process_write_public_values(program_id, accounts, offset as usize, &bytes)
+        }
+        VerifierInstruction::VerifyWithValuesAccount {
+            proof,
+            committed_values_digest,
+        } => process_verify_with_values_account(
+            program_id,
+            accounts,
+            &proof,
+            &committed_values_digest,
+        ),
+        VerifierInstruction::VerifyFixture(fixture) => process_verify_fixture(&fixture),
+        VerifierInstruction::InitializeConfig { vk } => {
+            process_initialize_config(program_id, accounts, &vk)
+        }
+        VerifierInstruction::UpdateVk { vk } => process_update_vk(program_id, accounts, &vk),
+        VerifierInstruction::VerifyWithConfig(groth16_proof) => {
+            process_verify_with_config(program_id, accounts, &groth16_proof)
+        }
+        VerifierInstruction::VerifyBatch(groth16_proofs) => process_verify_batch(&groth16_proofs),
+        VerifierInstruction::AddAllowedVkey { sp1_vkey_hash } => {
+            process_add_allowed_vkey(program_id, accounts, sp1_vkey_hash)
+        }
+        VerifierInstruction::RemoveAllowedVkey { sp1_vkey_hash } => {
+            process_remove_allowed_vkey(program_id, accounts, &sp1_vkey_hash)
+        }
+        VerifierInstruction::VerifyWithAllowlist(groth16_proof) => {
+            process_verify_with_allowlist(program_id, accounts, &groth16_proof)
+        }
+        VerifierInstruction::InitializeAllowlist => {
+            process_initialize_allowlist(program_id, accounts)
+        }
+        VerifierInstruction::AbsorbPublicValues { bytes } => {
+            process_absorb_public_values(program_id, accounts, &bytes)
+        }
+        VerifierInstruction::FinalizeAndVerify { proof } => {
+            process_finalize_and_verify(program_id, accounts, &proof)
+        }
+    }
+}
+
+fn process_verify(groth16_proof: SP1Groth16Proof) -> ProgramResult {
+    verify(&groth16_proof)?;
+
+    log_public_values(&groth16_proof.sp1_public_inputs)
+}
+
+/// Verifies the proof, returning its committed values digest.
+fn verify(groth16_proof: &SP1Groth16Proof) -> Result<[u8; 32], FibonacciVerifierError> {
+    // Hash the public values once. The resulting digest is what the Groth16 proof commits to.
+    let committed_values_digest = groth16_proof.sp1_public_inputs.digest();
+
+    CONTEXT.verify_with_digest(&groth16_proof.proof, &committed_values_digest)?;
+
+    emit_verification_event(&FIBONACCI_VKEY_HASH, &committed_values_digest);
+
+    Ok(committed_values_digest.to_bytes())
+}
+
+fn process_verify_batch(groth16_proofs: &[SP1Groth16Proof]) -> ProgramResult {
+    for groth16_proof in groth16_proofs {
+        verify(groth16_proof)?;
+    }
+    msg!("Verified a batch of {} proofs", groth16_proofs.len());
+
+    Ok(())
+}
+
+fn process_verify_fixture(fixture: &SP1ProofFixture) -> ProgramResult {
+    // Checks the program vkey hash, the Groth16 vkey hash and the digest of the public values,
+    // then the proof.
+    CONTEXT
+        .verify_fixture(fixture)
+        .map_err(FibonacciVerifierError::from)?;
+
+    emit_verification_event(&FIBONACCI_VKEY_HASH, &fixture.committed_values_digest);
+
+    // A fixture may carry only the digest of its public values.
+    if fixture.sp1_public_inputs.is_empty() {
+        return Ok(());
+    }
+    log_public_values(&fixture.sp1_public_inputs)
+}
+
+fn process_verify_from_account(
+    accounts: &[AccountInfo],
+    proof_offset: usize,
+    sp1_public_inputs: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let proof_account = next_account_info(account_info_iter)?;
+    let data = proof_account.try_borrow_data()?;
+
+    // Check the Groth16 vkey hash prefix, then verify the proof in place.
+    let selector = data
+        .get(proof_offset..proof_offset + 4)
+        .ok_or(FibonacciVerifierError::TruncatedProof)?;
+    if selector != sp1_solana::GROTH16_VK_3_0_0_HASH {
+        return Err(FibonacciVerifierError::Groth16VkeyHashMismatch.into());
+    }
+
+    let committed_values_digest = CommittedValuesDigest::from_public_values(sp1_public_inputs);
+    let public_inputs = groth16_public_inputs(&FIBONACCI_VKEY_HASH, &committed_values_digest);
+
+    verify_proof_from_slice(
+        &data,
+        proof_offset + 4,
+        &public_inputs,
+        sp1_solana::GROTH16_VK_3_0_0_BYTES,
+    )
+    .map_err(FibonacciVerifierError::from)?;
+
+    emit_verification_event(&FIBONACCI_VKEY_HASH, &committed_values_digest);
+    log_public_values(sp1_public_inputs)
+}
+
+fn process_verify_and_store(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    groth16_proof: SP1Groth16Proof,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer = next_account_info(account_info_iter)?;
+    let record_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(FibonacciVerifierError::MissingSignature.into());
+    }
+
+    let committed_values_digest = verify(&groth16_proof)?;
+
+    let bump = check_record_address(program_id, record_account, &committed_values_digest)?;
+
+    // The proof was already recorded.
+    if record_account.owner == program_id {
+        return Ok(());
+    }
+
+    let [prefix, vkey_hash, digest] =
+        verification_record_seeds(&FIBONACCI_VKEY_HASH, &committed_values_digest);
+    create_pda(
+        payer,
+        record_account,
+        system_program,
+        VerificationRecord::LEN,
+        program_id,
+        &[prefix, vkey_hash, digest, &[bump]],
+    )?;
+
+    let record = VerificationRecord {
+        slot: Clock::get()?.slot,
+        committed_values_digest,
+        submitter: *payer.key,
+    };
+    record
+        .serialize(&mut &mut record_account.try_borrow_mut_data()?[..])
+        .map_err(|_| ProgramError::AccountDataTooSmall)?;
+
+    log_public_values(&groth16_proof.sp1_public_inputs)
+}
+
+fn process_verify_and_nullify(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    groth16_proof: SP1Groth16Proof,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer = next_account_info(account_info_iter)?;
+    let nullifier_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(FibonacciVerifierError::MissingSignature.into());
+    }
+
+    let committed_values_digest = verify(&groth16_proof)?;
+
+    let salt = groth16_proof
+        .sp1_public_inputs
+        .get(..4)
+        .ok_or(FibonacciVerifierError::InvalidPublicValues)?;
+    let nullifier = Nullifier::new(&FIBONACCI_VKEY_HASH, &committed_values_digest, salt);
+    let (nullifier_address, bump) = nullifier.find_program_address(program_id);
+    if *nullifier_account.key != nullifier_address {
+        return Err(FibonacciVerifierError::InvalidPdaAddress.into());
+    }
+
+    if nullifier_account.owner == program_id {
+        return Err(FibonacciVerifierError::NullifierAlreadyUsed.into());
+    }
+
+    // The nullifier PDA holds no data: its existence is what marks the proof as consumed. Its
+    // address is public, so it may have been funded to block its creation.
+    create_pda(
+        payer,
+        nullifier_account,
+        system_program,
+        0,
+        program_id,
+        &[NULLIFIER_SEED, nullifier.as_bytes(), &[bump]],
+    )?;
+
+    log_public_values(&groth16_proof.sp1_public_inputs)
+}
+
+fn process_assert_recorded(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    committed_values_digest: &[u8; 32],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let record_account = next_account_info(account_info_iter)?;
+
+    let record = load_record(program_id, record_account, committed_values_digest)?;
+    // Logged with the syscall rather than formatted, which would link base58 encoding.
+    msg!("Recorded in slot {}", record.slot);
+    record.submitter.log();
+
+    Ok(())
+}
+
+fn process_close_record(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    committed_values_digest: &[u8; 32],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let submitter = next_account_info(account_info_iter)?;
+    let record_account = next_account_info(account_info_iter)?;
+
+    let record = load_record(program_id, record_account, committed_values_digest)?;
+    if !submitter.is_signer || *submitter.key != record.submitter {
+        return Err(FibonacciVerifierError::UnauthorizedClose.into());
+    }
+
+    // Move the rent out and clear the data; the runtime deletes the emptied account.
+    let lamports = record_account.lamports();
+    **record_account.try_borrow_mut_lamports()? = 0;
+    **submitter.try_borrow_mut_lamports()? += lamports;
+    record_account.try_borrow_mut_data()?.fill(0);
+
+    Ok(())
+}
+
+fn process_init_public_values(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    len: u32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let values_account = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(FibonacciVerifierError::MissingSignature.into());
+    }
+    if values_account.owner != program_id {
+        return Err(FibonacciVerifierError::InvalidAccountOwner.into());
+    }
+
+    let mut data = values_account.try_borrow_mut_data()?;
+    if data.len() < public_values_account_space(len as usize) {
+        return Err(FibonacciVerifierError::PublicValuesOutOfBounds.into());
+    }
+    // A freshly created account is zeroed, so a set authority means it was initialized before.
+    if data[..32] != [0u8; 32] {
+        return Err(FibonacciVerifierError::AlreadyInitialized.into());
+    }
+
+    let header = PublicValuesHeader {
+        authority: *authority.key,
+        len,
+    };
+    header
+        .serialize(&mut &mut data[..PublicValuesHeader::LEN])
+        .map_err(|_| ProgramError::AccountDataTooSmall)?;
+
+    Ok(())
+}
+
+fn process_write_public_values(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    offset: usize,
+    bytes: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let values_account = next_account_info(account_info_iter)?;
+
+    if values_account.owner != program_id {
+        return Err(FibonacciVerifierError::InvalidAccountOwner.into());
+    }
+
+    let mut data = values_account.try_borrow_mut_data()?;
+    let (header, _) =
+        read_public_values(&data).map_err(|_| FibonacciVerifierError::PublicValuesOutOfBounds)?;
+    if !authority.is_signer || *authority.key != header.authority {
+        return Err(FibonacciVerifierError::UnauthorizedWrite.into());
+    }
+
+    let end = offset
+        .checked_add(bytes.len())
+        .filter(|&end| end <= header.len as usize)
+        .ok_or(FibonacciVerifierError::PublicValuesOutOfBounds)?;
+    data[PublicValuesHeader::LEN + offset..PublicValuesHeader::LEN + end].copy_from_slice(bytes);
+
+    Ok(())
+}
+
+fn process_verify_with_values_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    proof: &[u8; PROOF_LEN],
+    committed_values_digest: &[u8; 32],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let values_account = next_account_info(account_info_iter)?;
+
+    if values_account.owner != program_id {
+        return Err(FibonacciVerifierError::InvalidAccountOwner.into());
+    }
+
+    let data = values_account.try_borrow_data()?;
+    let (_, sp1_public_inputs) =
+        read_public_values(&data).map_err(|_| FibonacciVerifierError::PublicValuesOutOfBounds)?;
+
+    // Hashing goes through the `sol_sha256` syscall, so large public values stay cheap.
+    let recomputed = CommittedValuesDigest::from_public_values(sp1_public_inputs);
+    if recomputed.as_bytes() != committed_values_digest {
+        return Err(FibonacciVerifierError::DigestMismatch.into());
+    }
+
+    verify_proof_raw_exact(
+        proof,
+        &groth16_public_inputs(&FIBONACCI_VKEY_HASH, &recomputed),
+        sp1_solana::GROTH16_VK_3_0_0_BYTES,
+    )
+    .map_err(FibonacciVerifierError::from)?;
+
+    emit_verification_event(&FIBONACCI_VKEY_HASH, committed_values_digest);
+    msg!(
+        "Verified {} bytes of public values",
+        sp1_public_inputs.len()
+    );
+
+    Ok(())
+}
+
+fn process_absorb_public_values(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    bytes: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let hash_account = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(FibonacciVerifierError::MissingSignature.into());
+    }
+    if hash_account.owner != program_id {
+        return Err(FibonacciVerifierError::InvalidAccountOwner.into());
+    }
+
+    let mut data = hash_account.try_borrow_mut_data()?;
+    let (stored_authority, state) = data
+        .get_mut(..HASH_ACCOUNT_LEN)
+        .ok_or(ProgramError::InvalidAccountData)?
+        .split_at_mut(32);
+    // A freshly created account is zeroed: the first chunk makes the signer its authority.
+    let mut sha256_state = if *stored_authority == [0u8; 32] {
+        stored_authority.copy_from_slice(authority.key.as_ref());
+        Sha256State::new()
+    } else if *stored_authority == *authority.key.as_ref() {
+        Sha256State::try_from_slice(state).map_err(|_| ProgramError::InvalidAccountData)?
+    } else {
+        return Err(FibonacciVerifierError::UnauthorizedWrite.into());
+    };
+
+    sha256_state.absorb(bytes);
+    sha256_state
+        .serialize(&mut &mut state[..])
+        .map_err(|_| ProgramError::AccountDataTooSmall)
+}
+
+fn process_finalize_and_verify(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    proof: &[u8; PROOF_WITH_SELECTOR_LEN],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let hash_account = next_account_info(account_info_iter)?;
+
+    if hash_account.owner != program_id {
+        return Err(FibonacciVerifierError::InvalidAccountOwner.into());
+    }
+
+    let sha256_state = {
+        let data = hash_account.try_borrow_data()?;
+        let (stored_authority, state) = data
+            .get(..HASH_ACCOUNT_LEN)
+            .ok_or(ProgramError::InvalidAccountData)?
+            .split_at(32);
+        if !authority.is_signer || stored_authority != authority.key.as_ref() {
+            return Err(FibonacciVerifierError::UnauthorizedWrite.into());
+        }
+        Sha256State::try_from_slice(state).map_err(|_| ProgramError::InvalidAccountData)?
+    };
+
+    // The digest-based entry point: the public values were hashed as they were absorbed.
+    let committed_values_digest = sha256_state.finalize_masked();
+    CONTEXT
+        .verify_with_digest(proof, &committed_values_digest)
+        .map_err(FibonacciVerifierError::from)?;
+
+    emit_verification_event(&FIBONACCI_VKEY_HASH, committed_values_digest.as_bytes());
+
+    // The absorbed values can't be extended after they're verified, so the account is closed:
+    // move the rent out and clear the data, and the runtime deletes the emptied account.
+    let lamports = hash_account.lamports();
+    **hash_account.try_borrow_mut_lamports()? = 0;
+    **authority.try_borrow_mut_lamports()? += lamports;
+    hash_account.try_borrow_mut_data()?.fill(0);
+
+    Ok(())
+}
+
+fn process_initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    vk: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let program_data = next_account_info(account_info_iter)?;
+
+    // Whoever initializes the config controls the key proofs are verified against, so only the
+    // upgrade authority can, rather than whoever calls first.
+    if !is_upgrade_authority(program_id, program_data, authority)? {
+        return Err(FibonacciVerifierError::NotUpgradeAuthority.into());
+    }
+    if vk.len() > MAX_VK_LEN {
+        return Err(FibonacciVerifierError::VkTooLarge.into());
+    }
+    // Reject keys followed by other bytes, which `write_vk` would store along with them.
+    VkView::new_exact(vk).map_err(|_| FibonacciVerifierError::InvalidVerificationKey)?;
+
+    let (config_address, bump) = find_config_address(program_id);
+    if *config_account.key != config_address {
+        return Err(FibonacciVerifierError::InvalidPdaAddress.into());
+    }
+    if config_account.owner == program_id {
+        return Err(FibonacciVerifierError::AlreadyInitialized.into());
+    }
+
+    // Leave room for a larger key, so that it can be updated in place.
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            config_account.key,
+            Rent::get()?.minimum_balance(vk_account_space()),
+            vk_account_space() as u64,
+            program_id,
+        ),
+        &[
+            authority.clone(),
+            config_account.clone(),
+            system_program.clone(),
+        ],
+        &[&[CONFIG_SEED, &[bump]]],
+    )?;
+
+    write_vk(
+        &mut config_account.try_borrow_mut_data()?,
+        authority.key,
+        vk,
+    )
+    .map_err(|_| FibonacciVerifierError::InvalidVerificationKey.into())
+}
+
+fn process_update_vk(program_id: &Pubkey, accounts: &[AccountInfo], vk: &[u8]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+
+    check_config_account(program_id, config_account)?;
+    let (header, _) = read_vk(&config_account.try_borrow_data()?)
+        .map_err(|_| FibonacciVerifierError::InvalidVerificationKey)?;
+    if !authority.is_signer || *authority.key != header.authority {
+        return Err(FibonacciVerifierError::UnauthorizedVkUpdate.into());
+    }
+    if vk.len() > MAX_VK_LEN {
+        return Err(FibonacciVerifierError::VkTooLarge.into());
+    }
+    // Reject keys followed by other bytes, which `write_vk` would store along with them.
+    VkView::new_exact(vk).map_err(|_| FibonacciVerifierError::InvalidVerificationKey)?;
+
+    write_vk(
+        &mut config_account.try_borrow_mut_data()?,
+        authority.key,
+        vk,
+    )
+    .map_err(|_| FibonacciVerifierError::InvalidVerificationKey.into())
+}
+
+fn process_verify_with_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    groth16_proof: &SP1Groth16Proof,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config_account = next_account_info(account_info_iter)?;
+
+    check_config_account(program_id, config_account)?;
+
+    let committed_values_digest = groth16_proof.sp1_public_inputs.digest();
+    verify_proof_with_vk_account(
+        &groth16_proof.proof,
+        &committed_values_digest,
+        &FIBONACCI_VKEY_HASH,
+        config_account,
+    )
+    .map_err(FibonacciVerifierError::from)?;
+
+    emit_verification_event(&FIBONACCI_VKEY_HASH, &committed_values_digest);
+
+    log_public_values(&groth16_proof.sp1_public_inputs)
+}
+
+fn process_initialize_allowlist(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let allowlist_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let program_data = next_account_info(account_info_iter)?;
+
+    // The authority of the allowlist chooses the SP1 programs whose proofs are accepted, so only
+    // the upgrade authority can create it, rather than whoever calls first.
+    if !is_upgrade_authority(program_id, program_data, authority)? {
+        return Err(FibonacciVerifierError::NotUpgradeAuthority.into());
+    }
+    let (allowlist_address, bump) = ProgramVkeyAllowlist::find_address(program_id);
+    if *allowlist_account.key != allowlist_address {
+        return Err(FibonacciVerifierError::InvalidPdaAddress.into());
+    }
+    if allowlist_account.owner == program_id {
+        return Err(FibonacciVerifierError::AlreadyInitialized.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            allowlist_account.key,
+            Rent::get()?.minimum_balance(ProgramVkeyAllowlist::space(MAX_ALLOWED_VKEYS)),
+            ProgramVkeyAllowlist::space(MAX_ALLOWED_VKEYS) as u64,
+            program_id,
+        ),
+        &[
+            authority.clone(),
+            allowlist_account.clone(),
+            system_program.clone(),
+        ],
+        &[&[ALLOWLIST_SEED, &[bump]]],
+    )?;
+
+    let allowlist = ProgramVkeyAllowlist {
+        authority: *authority.key,
+        hashes: Vec::new(),
+    };
+    save_allowlist(allowlist_account, &allowlist)
+}
+
+fn process_add_allowed_vkey(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sp1_vkey_hash: [u8; 32],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let allowlist_account = next_account_info(account_info_iter)?;
+
+    let mut allowlist = load_allowlist(program_id, allowlist_account)?;
+    if !authority.is_signer || *authority.key != allowlist.authority {
+        return Err(FibonacciVerifierError::UnauthorizedAllowlistUpdate.into());
+    }
+    if allowlist.hashes.len() >= MAX_ALLOWED_VKEYS {
+        return Err(FibonacciVerifierError::AllowlistFull.into());
+    }
+    match allowlist.add(sp1_vkey_hash) {
+        Ok(true) => {}
+        Ok(false) => return Err(FibonacciVerifierError::VkeyAlreadyAllowed.into()),
+        Err(_) => return Err(FibonacciVerifierError::InvalidInstructionData.into()),
+    }
+
+    save_allowlist(allowlist_account, &allowlist)
+}
+
+fn process_remove_allowed_vkey(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sp1_vkey_hash: &[u8; 32],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let allowlist_account = next_account_info(account_info_iter)?;
+
+    let mut allowlist = load_allowlist(program_id, allowlist_account)?;
+    if !authority.is_signer || *authority.key != allowlist.authority {
+        return Err(FibonacciVerifierError::UnauthorizedAllowlistUpdate.into());
+    }
+    if !allowlist.remove(sp1_vkey_hash) {
+        return Err(FibonacciVerifierError::VkeyNotAllowed.into());
+    }
+
+    save_allowlist(allowlist_account, &allowlist)
+}
+
+fn process_verify_with_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    groth16_proof: &SP1Groth16Proof,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let allowlist_account = next_account_info(account_info_iter)?;
+
+    let allowlist = load_allowlist(program_id, allowlist_account)?;
+    // The public values are hashed, and the proof and key parsed, once for all the hashes.
+    let index = allowlist
+        .verify(
+            &groth16_proof.proof,
+            &groth16_proof.sp1_public_inputs,
+            sp1_solana::GROTH16_VK_3_0_0_BYTES,
+        )
+        .map_err(FibonacciVerifierError::from)?;
+
+    emit_verification_event(
+        &allowlist.hashes[index],
+        &groth16_proof.sp1_public_inputs.digest(),
+    );
+
+    log_public_values(&groth16_proof.sp1_public_inputs)
+}
+
+/// Reads the allowlist PDA, which must have been created by `InitializeAllowlist`.
+fn load_allowlist(
+    program_id: &Pubkey,
+    allowlist_account: &AccountInfo,
+) -> Result<ProgramVkeyAllowlist, ProgramError> {
+    if *allowlist_account.key != ProgramVkeyAllowlist::find_address(program_id).0 {
+        return Err(FibonacciVerifierError::InvalidPdaAddress.into());
+    }
+    if allowlist_account.owner != program_id {
+        return Err(FibonacciVerifierError::InvalidAccountOwner.into());
+    }
+    ProgramVkeyAllowlist::from_account_data(&allowlist_account.try_borrow_data()?)
+        .map_err(|_| ProgramError::InvalidAccountData)
+}
+
+fn save_allowlist(
+    allowlist_account: &AccountInfo,
+    allowlist: &ProgramVkeyAllowlist,
+) -> ProgramResult {
+    allowlist
+        .to_account_data(&mut allowlist_account.try_borrow_mut_data()?)
+        .map_err(|_| ProgramError::AccountDataTooSmall)
+}
+
+/// Checks that `config_account` is the initialized config PDA.
+fn check_config_account(
+    program_id: &Pubkey,
+    config_account: &AccountInfo,
+) -> Result<(), FibonacciVerifierError> {
+    if *config_account.key != find_config_address(program_id).0 {
+        return Err(FibonacciVerifierError::InvalidPdaAddress);
+    }
+    if config_account.owner != program_id {
+        return Err(FibonacciVerifierError::InvalidAccountOwner);
+    }
+    Ok(())
+}
+
+/// Checks that `record_account` is the verification record PDA of the proof, returning its bump.
+fn check_record_address(
+    program_id: &Pubkey,
+    record_account: &AccountInfo,
+    committed_values_digest: &[u8; 32],
+) -> Result<u8, FibonacciVerifierError> {
+    let (record_address, bump) =
+        find_verification_record_address(program_id, &FIBONACCI_VKEY_HASH, committed_values_digest);
+    if *record_account.key != record_address {
+        return Err(FibonacciVerifierError::InvalidPdaAddress);
+    }
+    Ok(bump)
+}
+
+/// Reads the verification record of the proof from `record_account`.
+fn load_record(
+    program_id: &Pubkey,
+    record_account: &AccountInfo,
+    committed_values_digest: &[u8; 32],
+) -> Result<VerificationRecord, FibonacciVerifierError> {
+    check_record_address(program_id, record_account, committed_values_digest)?;
+    if record_account.owner != program_id {
+        return Err(FibonacciVerifierError::RecordNotFound);
+    }
+
+    // A record closed earlier in the same transaction is still owned by the program, but zeroed.
+    let data = record_account
+        .try_borrow_data()
+        .map_err(|_| FibonacciVerifierError::RecordNotFound)?;
+    match VerificationRecord::try_from_slice(&data) {
+        Ok(record) if record.committed_values_digest == *committed_values_digest => Ok(record),
+        _ => Err(FibonacciVerifierError::RecordNotFound),
+    }
+}
+
+/// Prints out the fibonacci public values.
+fn log_public_values(sp1_public_inputs: &[u8]) -> ProgramResult {
+    let (n, a, b) = <(u32, u32, u32)>::deserialize(&mut &sp1_public_inputs[..])
+        .map_err(|_| FibonacciVerifierError::InvalidPublicValues)?;
+    msg!("Public values: (n: {}, a: {}, b: {})", n, a, b);
+
+    Ok(())
+}
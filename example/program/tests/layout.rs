@@ -0,0 +1,168 @@
+//! Golden encodings of every instruction, for clients encoding them in other languages.
+//!
+//! Each file of `tests/golden` is the [`to_markdown`] layout of an instruction, and its bytes
+//! column is the instruction data. A change to the wire format fails here until the golden files
+//! are updated in the same change, so that it shows in review.
+
+use fibonacci_verifier_contract::{SP1Groth16Proof, VerifierInstruction};
+use sp1_solana::SP1ProofFixture;
+use sp1_solana_interface::layout::{assert_encodes_to, to_markdown};
+
+/// `len` bytes, different for each `seed`.
+fn bytes(len: usize, seed: u8) -> Vec<u8> {
+    (0..len)
+        .map(|i| (i as u8).wrapping_mul(7).wrapping_add(seed))
+        .collect()
+}
+
+fn array<const N: usize>(seed: u8) -> [u8; N] {
+    bytes(N, seed).try_into().unwrap()
+}
+
+fn groth16_proof(seed: u8) -> SP1Groth16Proof {
+    SP1Groth16Proof {
+        proof: bytes(260, seed),
+        sp1_public_inputs: bytes(12, seed.wrapping_add(1)).into(),
+    }
+}
+
+/// The instruction data of a golden file: the concatenation of its bytes column.
+fn golden_bytes(golden: &str) -> Vec<u8> {
+    golden
+        .lines()
+        .skip(2)
+        .flat_map(|row| {
+            let hex = row.split('|').nth(5).unwrap().trim();
+            (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[test]
+fn test_instruction_layouts() {
+    // One instruction of each kind, in the order of their discriminators.
+    let instructions = [
+        ("verify", VerifierInstruction::Verify(groth16_proof(0x10))),
+        (
+            "verify_from_account",
+            VerifierInstruction::VerifyFromAccount {
+                proof_offset: 8,
+                sp1_public_inputs: bytes(12, 0x20),
+            },
+        ),
+        (
+            "verify_and_store",
+            VerifierInstruction::VerifyAndStore(groth16_proof(0x30)),
+        ),
+        (
+            "verify_and_nullify",
+            VerifierInstruction::VerifyAndNullify(groth16_proof(0x40)),
+        ),
+        (
+            "assert_recorded",
+            VerifierInstruction::AssertRecorded {
+                committed_values_digest: array(0x50),
+            },
+        ),
+        (
+            "close_record",
+            VerifierInstruction::CloseRecord {
+                committed_values_digest: array(0x60),
+            },
+        ),
+        (
+            "init_public_values",
+            VerifierInstruction::InitPublicValues { len: 1024 },
+        ),
+        (
+            "write_public_values",
+            VerifierInstruction::WritePublicValues {
+                offset: 512,
+                bytes: bytes(16, 0x70),
+            },
+        ),
+        (
+            "verify_with_values_account",
+            VerifierInstruction::VerifyWithValuesAccount {
+                proof: array(0x80),
+                committed_values_digest: array(0x90),
+            },
+        ),
+        (
+            "verify_fixture",
+            VerifierInstruction::VerifyFixture(SP1ProofFixture {
+                groth16_vkey_hash: array(0xa0),
+                proof: array(0xa1),
+                sp1_vkey_hash: array(0xa2),
+                committed_values_digest: array(0xa3),
+                sp1_public_inputs: bytes(12, 0xa4).into(),
+            }),
+        ),
+        (
+            "initialize_config",
+            VerifierInstruction::InitializeConfig {
+                vk: bytes(16, 0xb0),
+            },
+        ),
+        (
+            "update_vk",
+            VerifierInstruction::UpdateVk {
+                vk: bytes(16, 0xc0),
+            },
+        ),
+        (
+            "verify_with_config",
+            VerifierInstruction::VerifyWithConfig(groth16_proof(0xd0)),
+        ),
+        (
+            "verify_batch",
+            VerifierInstruction::VerifyBatch(vec![groth16_proof(0xe0), groth16_proof(0xe8)]),
+        ),
+        (
+            "add_allowed_vkey",
+            VerifierInstruction::AddAllowedVkey {
+                sp1_vkey_hash: array(0xf0),
+            },
+        ),
+        (
+            "remove_allowed_vkey",
+            VerifierInstruction::RemoveAllowedVkey {
+                sp1_vkey_hash: array(0xf8),
+            },
+        ),
+        (
+            "verify_with_allowlist",
+            VerifierInstruction::VerifyWithAllowlist(groth16_proof(0x08)),
+        ),
+        (
+            "initialize_allowlist",
+            VerifierInstruction::InitializeAllowlist,
+        ),
+        (
+            "absorb_public_values",
+            VerifierInstruction::AbsorbPublicValues {
+                bytes: bytes(16, 0x18),
+            },
+        ),
+        (
+            "finalize_and_verify",
+            VerifierInstruction::FinalizeAndVerify { proof: array(0x28) },
+        ),
+    ];
+
+    for (discriminator, (name, instruction)) in instructions.iter().enumerate() {
+        let path = format!("{}/tests/golden/{name}.md", env!("CARGO_MANIFEST_DIR"));
+        let golden = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(to_markdown(instruction), golden, "{path}");
+        let data = golden_bytes(&golden);
+        assert_encodes_to(instruction, &data);
+        assert_eq!(data[0] as usize, discriminator, "{name}");
+
+        // The program decodes the golden bytes back into the same instruction.
+        let decoded = VerifierInstruction::unpack(&data).unwrap();
+        assert_eq!(borsh::to_vec(&decoded).unwrap(), data, "{name}");
+    }
+}
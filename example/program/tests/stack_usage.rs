@@ -0,0 +1,31 @@
+//! Checks that the verifier fits within the Solana BPF stack frame limit.
+//!
+//! This test shells out to `cargo build-sbf`, so it runs with the `sbf-build` feature, as in CI:
+//! `cargo test --package fibonacci-verifier-contract --features sbf-build --test stack_usage`.
+#![cfg(feature = "sbf-build")]
+
+use std::process::Command;
+
+#[test]
+fn test_no_stack_offset_warnings() {
+    let output = Command::new("cargo")
+        .args(["build-sbf", "--manifest-path"])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .output()
+        .expect("failed to run `cargo build-sbf`");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "cargo build-sbf failed:\n{stderr}");
+
+    let warnings: Vec<&str> = stdout
+        .lines()
+        .chain(stderr.lines())
+        .filter(|line| line.contains("Stack offset") || line.contains("stack frame"))
+        .collect();
+    assert!(
+        warnings.is_empty(),
+        "stack size warnings emitted:\n{}",
+        warnings.join("\n")
+    );
+}
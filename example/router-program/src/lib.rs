@@ -0,0 +1,344 @@
+//! A verifier router, dispatching each proof to the verifier of its Groth16 selector.
+//!
+//! Like the `SP1VerifierGateway` contract on Ethereum, programs verify proofs through the router
+//! instead of embedding a verification key, so that supporting a new SP1 release only takes an
+//! admin registering its selector. The routes are kept in a
+//! [`sp1_solana::router::VerifierRegistry`] PDA, whose admin is set by the router's upgrade
+//! authority.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    log::sol_log_data,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use sp1_solana::{
+    cpi::{invoke_verify, VerifyArgs},
+    proof_selector,
+    router::{Route, RouteTarget, VerifierRegistry, MAX_ROUTES, REGISTRY_SEED},
+    upgrade_authority::{is_upgrade_authority, program_data_address},
+    vk_account::verify_proof_with_vk_account,
+    CommittedValuesDigest,
+};
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// The instructions of the router.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum RouterInstruction {
+    /// Creates the registry, with the signer as its admin. Only the upgrade authority of the router
+    /// can, so that the registry can't be taken over by whoever calls first.
+    ///
+    /// Accounts: the admin (signer, writable), the registry PDA (writable), the system program,
+    /// and the router's `ProgramData` account.
+    InitializeRegistry,
+    /// Routes the proofs of `selector` to `target`.
+    ///
+    /// Accounts: the admin (signer), and the registry PDA (writable).
+    AddRoute {
+        selector: [u8; 4],
+        target: RouteTarget,
+    },
+    /// Stops routing the proofs of `selector`.
+    ///
+    /// Accounts: the admin (signer), and the registry PDA (writable).
+    RemoveRoute { selector: [u8; 4] },
+    /// Verifies a proof in SP1's standard format with the target of its selector.
+    ///
+    /// Accounts: the registry PDA, and the target of the selector.
+    Verify {
+        sp1_vkey_hash: [u8; 32],
+        committed_values_digest: [u8; 32],
+        proof: Vec<u8>,
+    },
+}
+
+/// Errors returned by the router, as `ProgramError::Custom(error as u32)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouterError {
+    /// The instruction data doesn't decode to a [`RouterInstruction`].
+    InvalidInstructionData,
+    /// The signer is not the admin of the registry, or, when initializing it, the upgrade authority
+    /// of the router.
+    Unauthorized,
+    /// The registry is not the router's registry PDA.
+    InvalidRegistry,
+    /// The selector of the proof has no route.
+    UnregisteredSelector,
+    /// The selector already has a route.
+    SelectorAlreadyRegistered,
+    /// The registry has no room for another route.
+    RegistryFull,
+    /// The account passed as the target is not the target of the selector.
+    TargetMismatch,
+    /// The proof is shorter than its selector.
+    TruncatedProof,
+    /// The proof doesn't verify.
+    InvalidProof,
+}
+
+impl From<RouterError> for ProgramError {
+    fn from(error: RouterError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = RouterInstruction::try_from_slice(instruction_data)
+        .map_err(|_| RouterError::InvalidInstructionData)?;
+
+    match instruction {
+        RouterInstruction::InitializeRegistry => process_initialize_registry(program_id, accounts),
+        RouterInstruction::AddRoute { selector, target } => {
+            process_add_route(program_id, accounts, Route { selector, target })
+        }
+        RouterInstruction::RemoveRoute { selector } => {
+            process_remove_route(program_id, accounts, &selector)
+        }
+        RouterInstruction::Verify {
+            sp1_vkey_hash,
+            committed_values_digest,
+            proof,
+        } => process_verify(
+            program_id,
+            accounts,
+            &sp1_vkey_hash,
+            &committed_values_digest,
+            &proof,
+        ),
+    }
+}
+
+fn process_initialize_registry(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin = next_account_info(account_info_iter)?;
+    let registry_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let program_data = next_account_info(account_info_iter)?;
+
+    if !is_upgrade_authority(program_id, program_data, admin)? {
+        return Err(RouterError::Unauthorized.into());
+    }
+    let (registry_address, bump) = VerifierRegistry::find_address(program_id);
+    if *registry_account.key != registry_address {
+        return Err(RouterError::InvalidRegistry.into());
+    }
+
+    // Creating the PDA fails if it already exists, so the registry can only be initialized once.
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            registry_account.key,
+            Rent::get()?.minimum_balance(VerifierRegistry::LEN),
+            VerifierRegistry::LEN as u64,
+            program_id,
+        ),
+        &[
+            admin.clone(),
+            registry_account.clone(),
+            system_program.clone(),
+        ],
+        &[&[REGISTRY_SEED, &[bump]]],
+    )?;
+
+    let registry = VerifierRegistry {
+        admin: *admin.key,
+        routes: Vec::new(),
+    };
+    save_registry(registry_account, &registry)
+}
+
+fn process_add_route(program_id: &Pubkey, accounts: &[AccountInfo], route: Route) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin = next_account_info(account_info_iter)?;
+    let registry_account = next_account_info(account_info_iter)?;
+
+    let mut registry = load_registry(program_id, registry_account)?;
+    if !admin.is_signer || *admin.key != registry.admin {
+        return Err(RouterError::Unauthorized.into());
+    }
+    if registry.target(&route.selector).is_some() {
+        return Err(RouterError::SelectorAlreadyRegistered.into());
+    }
+    if registry.routes.len() >= MAX_ROUTES {
+        return Err(RouterError::RegistryFull.into());
+    }
+
+    registry.routes.push(route);
+    save_registry(registry_account, &registry)
+}
+
+fn process_remove_route(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    selector: &[u8; 4],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin = next_account_info(account_info_iter)?;
+    let registry_account = next_account_info(account_info_iter)?;
+
+    let mut registry = load_registry(program_id, registry_account)?;
+    if !admin.is_signer || *admin.key != registry.admin {
+        return Err(RouterError::Unauthorized.into());
+    }
+    if registry.target(selector).is_none() {
+        return Err(RouterError::UnregisteredSelector.into());
+    }
+
+    registry.routes.retain(|route| route.selector != *selector);
+    save_registry(registry_account, &registry)
+}
+
+fn process_verify(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sp1_vkey_hash: &[u8; 32],
+    committed_values_digest: &[u8; 32],
+    proof: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let registry_account = next_account_info(account_info_iter)?;
+    let target_account = next_account_info(account_info_iter)?;
+
+    let registry = load_registry(program_id, registry_account)?;
+    let selector = proof_selector(proof).map_err(|_| RouterError::TruncatedProof)?;
+    let target = registry
+        .target(selector)
+        .ok_or(RouterError::UnregisteredSelector)?;
+    if target_account.key != target.address() {
+        return Err(RouterError::TargetMismatch.into());
+    }
+
+    match target {
+        RouteTarget::Program(_) => {
+            let args = VerifyArgs {
+                sp1_vkey_hash: *sp1_vkey_hash,
+                committed_values_digest: *committed_values_digest,
+                proof: proof[4..]
+                    .try_into()
+                    .map_err(|_| RouterError::TruncatedProof)?,
+            };
+            invoke_verify(
+                target_account.key,
+                &args,
+                core::slice::from_ref(target_account),
+            )?;
+        }
+        RouteTarget::VkAccount(_) => {
+            CommittedValuesDigest::from_masked_bytes(*committed_values_digest)
+                .and_then(|committed_values_digest| {
+                    verify_proof_with_vk_account(
+                        proof,
+                        &committed_values_digest,
+                        sp1_vkey_hash,
+                        target_account,
+                    )
+                })
+                .map_err(|_| RouterError::InvalidProof)?;
+        }
+    }
+
+    // The selector is logged as data rather than formatted, which would link `Debug` machinery.
+    msg!("Proof verified, with selector:");
+    sol_log_data(&[selector]);
+
+    Ok(())
+}
+
+fn load_registry(
+    program_id: &Pubkey,
+    registry_account: &AccountInfo,
+) -> Result<VerifierRegistry, ProgramError> {
+    if registry_account.owner != program_id
+        || *registry_account.key != VerifierRegistry::find_address(program_id).0
+    {
+        return Err(RouterError::InvalidRegistry.into());
+    }
+    VerifierRegistry::from_account_data(&registry_account.try_borrow_data()?)
+        .map_err(|_| RouterError::InvalidRegistry.into())
+}
+
+fn save_registry(registry_account: &AccountInfo, registry: &VerifierRegistry) -> ProgramResult {
+    registry
+        .to_account_data(&mut registry_account.try_borrow_mut_data()?)
+        .map_err(|_| ProgramError::AccountDataTooSmall)
+}
+
+/// Builds an `InitializeRegistry` instruction, signed by `admin`, the upgrade authority of the
+/// router.
+pub fn initialize_registry(program_id: &Pubkey, admin: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &RouterInstruction::InitializeRegistry,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(VerifierRegistry::find_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(program_data_address(program_id), false),
+        ],
+    )
+}
+
+/// Builds an `AddRoute` instruction.
+pub fn add_route(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    selector: [u8; 4],
+    target: RouteTarget,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &RouterInstruction::AddRoute { selector, target },
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(VerifierRegistry::find_address(program_id).0, false),
+        ],
+    )
+}
+
+/// Builds a `RemoveRoute` instruction.
+pub fn remove_route(program_id: &Pubkey, admin: &Pubkey, selector: [u8; 4]) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &RouterInstruction::RemoveRoute { selector },
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(VerifierRegistry::find_address(program_id).0, false),
+        ],
+    )
+}
+
+/// Builds a `Verify` instruction, routing `proof` to `target`, the target of its selector.
+pub fn verify(
+    program_id: &Pubkey,
+    target: &RouteTarget,
+    sp1_vkey_hash: [u8; 32],
+    committed_values_digest: [u8; 32],
+    proof: &[u8],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &RouterInstruction::Verify {
+            sp1_vkey_hash,
+            committed_values_digest,
+            proof: proof.to_vec(),
+        },
+        vec![
+            AccountMeta::new_readonly(VerifierRegistry::find_address(program_id).0, false),
+            AccountMeta::new_readonly(*target.address(), false),
+        ],
+    )
+}
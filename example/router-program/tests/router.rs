@@ -0,0 +1,320 @@
+//! Routes proofs to the verifier registered for their Groth16 selector.
+
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_solana::{
+    hash_public_inputs,
+    router::RouteTarget,
+    upgrade_authority::program_data_address,
+    vk_account::{vk_account_space, write_vk},
+    GROTH16_VK_2_0_0_HASH, GROTH16_VK_3_0_0_BYTES, GROTH16_VK_3_0_0_HASH,
+    GROTH16_VK_3_0_0_RC4_BYTES, GROTH16_VK_3_0_0_RC4_HASH,
+};
+use sp1_verifier_router::{add_route, initialize_registry, remove_route, verify, RouterError};
+
+const FIBONACCI_VKEY_HASH: [u8; 32] =
+    hex_literal::hex!("007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83");
+
+struct Context {
+    context: ProgramTestContext,
+    router_program_id: Pubkey,
+    verifier_program_id: Pubkey,
+    /// Accounts holding the v3.0.0 and v3.0.0-rc4 verification keys.
+    vk_accounts: [Pubkey; 2],
+}
+
+fn vk_account(vk: &[u8]) -> Account {
+    let mut data = vec![0u8; vk_account_space()];
+    write_vk(&mut data, &Pubkey::new_unique(), vk).unwrap();
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: Pubkey::new_unique(),
+        ..Account::default()
+    }
+}
+
+/// The `ProgramData` account of a program deployed by `upgrade_authority`.
+fn program_data(upgrade_authority: Option<Pubkey>) -> AccountSharedData {
+    let data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: upgrade_authority,
+    })
+    .unwrap();
+    AccountSharedData::from(Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: bpf_loader_upgradeable::id(),
+        ..Account::default()
+    })
+}
+
+/// Starts the router, the standalone verifier program, and the verification key accounts, then
+/// initializes the registry with the payer, the router's upgrade authority, as its admin.
+async fn start() -> Context {
+    let mut context = start_uninitialized().await;
+    let admin = context.context.payer.pubkey();
+    let instruction = initialize_registry(&context.router_program_id, &admin);
+    assert_eq!(context.process(instruction, &[]).await, Ok(()));
+    context
+}
+
+/// Starts the router with the payer as its upgrade authority, without a registry.
+async fn start_uninitialized() -> Context {
+    let router_program_id = Pubkey::new_unique();
+    let verifier_program_id = Pubkey::new_unique();
+    let vk_accounts = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let mut program_test = ProgramTest::new(
+        "sp1_verifier_router",
+        router_program_id,
+        processor!(sp1_verifier_router::process_instruction),
+    );
+    program_test.add_program(
+        "sp1_verifier_program",
+        verifier_program_id,
+        processor!(sp1_verifier_program::process_instruction),
+    );
+    program_test.add_account(vk_accounts[0], vk_account(GROTH16_VK_3_0_0_BYTES));
+    program_test.add_account(vk_accounts[1], vk_account(GROTH16_VK_3_0_0_RC4_BYTES));
+
+    let mut context = program_test.start_with_context().await;
+    let upgrade_authority = context.payer.pubkey();
+    context.set_account(
+        &program_data_address(&router_program_id),
+        &program_data(Some(upgrade_authority)),
+    );
+    Context {
+        context,
+        router_program_id,
+        verifier_program_id,
+        vk_accounts,
+    }
+}
+
+impl Context {
+    async fn process(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let recent_blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut transaction =
+            Transaction::new_with_payer(&[instruction], Some(&self.context.payer.pubkey()));
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        transaction.sign(&all_signers, recent_blockhash);
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|error| error.unwrap())
+    }
+
+    async fn add_route(&mut self, selector: [u8; 4], target: RouteTarget) {
+        let admin = self.context.payer.pubkey();
+        let instruction = add_route(&self.router_program_id, &admin, selector, target);
+        assert_eq!(self.process(instruction, &[]).await, Ok(()));
+    }
+
+    /// Verifies `proof` through the router, passing `target` as the target account.
+    async fn verify(&mut self, proof: &[u8], target: &RouteTarget) -> Result<(), TransactionError> {
+        let sp1_proof_with_public_values = load_fibonacci_proof();
+        let committed_values_digest =
+            hash_public_inputs(sp1_proof_with_public_values.public_values.as_slice());
+        let instruction = verify(
+            &self.router_program_id,
+            target,
+            FIBONACCI_VKEY_HASH,
+            committed_values_digest,
+            proof,
+        );
+        self.process(instruction, &[]).await
+    }
+}
+
+fn load_fibonacci_proof() -> SP1ProofWithPublicValues {
+    SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap()
+}
+
+fn load_proof() -> Vec<u8> {
+    load_fibonacci_proof().bytes()
+}
+
+fn with_selector(proof: &[u8], selector: [u8; 4]) -> Vec<u8> {
+    let mut proof = proof.to_vec();
+    proof[..4].copy_from_slice(&selector);
+    proof
+}
+
+fn custom_error(error: RouterError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_route_to_verifier_program() {
+    let mut context = start().await;
+    let target = RouteTarget::Program(context.verifier_program_id);
+    context.add_route(GROTH16_VK_3_0_0_HASH, target).await;
+
+    let proof = load_proof();
+    assert_eq!(context.verify(&proof, &target).await, Ok(()));
+
+    // The inner verifier's error is passed through.
+    let mut tampered = proof.clone();
+    tampered[4] ^= 1;
+    assert!(context.verify(&tampered, &target).await.is_err());
+}
+
+#[tokio::test]
+async fn test_route_by_selector() {
+    let mut context = start().await;
+    let v3_target = RouteTarget::VkAccount(context.vk_accounts[0]);
+    let rc4_target = RouteTarget::VkAccount(context.vk_accounts[1]);
+    context.add_route(GROTH16_VK_3_0_0_HASH, v3_target).await;
+    context
+        .add_route(GROTH16_VK_3_0_0_RC4_HASH, rc4_target)
+        .await;
+
+    let proof = load_proof();
+    assert_eq!(context.verify(&proof, &v3_target).await, Ok(()));
+
+    // The target must be the one registered for the selector.
+    assert_eq!(
+        context.verify(&proof, &rc4_target).await,
+        custom_error(RouterError::TargetMismatch)
+    );
+
+    // With the rc4 selector, the proof is routed to the rc4 key, which it wasn't generated for.
+    let rc4_proof = with_selector(&proof, GROTH16_VK_3_0_0_RC4_HASH);
+    assert_eq!(
+        context.verify(&rc4_proof, &rc4_target).await,
+        custom_error(RouterError::InvalidProof)
+    );
+
+    let v2_proof = with_selector(&proof, GROTH16_VK_2_0_0_HASH);
+    assert_eq!(
+        context.verify(&v2_proof, &v3_target).await,
+        custom_error(RouterError::UnregisteredSelector)
+    );
+}
+
+#[tokio::test]
+async fn test_registry_admin() {
+    let mut context = start().await;
+    let router_program_id = context.router_program_id;
+    let admin = context.context.payer.pubkey();
+    let target = RouteTarget::VkAccount(context.vk_accounts[0]);
+
+    // Only the admin can manage routes.
+    let other = Keypair::new();
+    let instruction = add_route(
+        &router_program_id,
+        &other.pubkey(),
+        GROTH16_VK_3_0_0_HASH,
+        target,
+    );
+    assert_eq!(
+        context.process(instruction, &[&other]).await,
+        custom_error(RouterError::Unauthorized)
+    );
+
+    context.add_route(GROTH16_VK_3_0_0_HASH, target).await;
+    let instruction = add_route(&router_program_id, &admin, GROTH16_VK_3_0_0_HASH, target);
+    assert_eq!(
+        context.process(instruction, &[]).await,
+        custom_error(RouterError::SelectorAlreadyRegistered)
+    );
+
+    let instruction = remove_route(&router_program_id, &other.pubkey(), GROTH16_VK_3_0_0_HASH);
+    assert_eq!(
+        context.process(instruction, &[&other]).await,
+        custom_error(RouterError::Unauthorized)
+    );
+
+    // A removed selector is no longer routed.
+    let instruction = remove_route(&router_program_id, &admin, GROTH16_VK_3_0_0_HASH);
+    assert_eq!(context.process(instruction, &[]).await, Ok(()));
+    assert_eq!(
+        context.verify(&load_proof(), &target).await,
+        custom_error(RouterError::UnregisteredSelector)
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_registry_authority() {
+    let mut context = start_uninitialized().await;
+    let router_program_id = context.router_program_id;
+    let program_data_address = program_data_address(&router_program_id);
+
+    // A signer other than the upgrade authority can't take the registry over.
+    let other = Keypair::new();
+    let recent_blockhash = context.context.get_new_latest_blockhash().await.unwrap();
+    let fund = solana_sdk::system_transaction::transfer(
+        &context.context.payer,
+        &other.pubkey(),
+        1_000_000_000,
+        recent_blockhash,
+    );
+    context
+        .context
+        .banks_client
+        .process_transaction(fund)
+        .await
+        .unwrap();
+    let instruction = initialize_registry(&router_program_id, &other.pubkey());
+    assert_eq!(
+        context.process(instruction, &[&other]).await,
+        custom_error(RouterError::Unauthorized)
+    );
+
+    // Nor can anyone once the program is immutable.
+    context
+        .context
+        .set_account(&program_data_address, &program_data(None));
+    let admin = context.context.payer.pubkey();
+    let instruction = initialize_registry(&router_program_id, &admin);
+    assert_eq!(
+        context.process(instruction.clone(), &[]).await,
+        custom_error(RouterError::Unauthorized)
+    );
+
+    // A `ProgramData` account at another address is rejected.
+    let mut forged = instruction.clone();
+    forged.accounts[3].pubkey = Pubkey::new_unique();
+    context
+        .context
+        .set_account(&forged.accounts[3].pubkey, &program_data(Some(admin)));
+    assert_eq!(
+        context.process(forged, &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidAccountData
+        ))
+    );
+
+    // The upgrade authority can, once.
+    context
+        .context
+        .set_account(&program_data_address, &program_data(Some(admin)));
+    assert_eq!(context.process(instruction.clone(), &[]).await, Ok(()));
+    context
+        .add_route(
+            GROTH16_VK_3_0_0_HASH,
+            RouteTarget::VkAccount(context.vk_accounts[0]),
+        )
+        .await;
+    assert!(context.process(instruction, &[]).await.is_err());
+}
@@ -8,4 +8,11 @@ fn main() {
             ..Default::default()
         },
     );
+    sp1_build::build_program_with_args(
+        "../token-claim-sp1-program",
+        BuildArgs {
+            docker: true,
+            ..Default::default()
+        },
+    );
 }
@@ -0,0 +1,17 @@
+//! Helpers running the airdrop example under `solana-program-test`.
+
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::pubkey::Pubkey;
+
+/// Starts a test validator running the airdrop program.
+pub async fn start() -> (ProgramTestContext, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let context = ProgramTest::new(
+        "sp1-airdrop-program",
+        program_id,
+        processor!(sp1_airdrop_program::process_instruction),
+    )
+    .start_with_context()
+    .await;
+    (context, program_id)
+}
@@ -0,0 +1,63 @@
+//! Packs proofs into `VerifyBatch` instructions of the example program.
+//!
+//! Each batch is limited both by compute units, using the per-proof cost measured with
+//! [`crate::cu::measure_units_consumed`], and by the size of a transaction.
+
+use fibonacci_verifier_contract::{instruction::verify_batch, SP1Groth16Proof};
+use solana_sdk::{
+    instruction::Instruction, packet::PACKET_DATA_SIZE, pubkey::Pubkey, transaction::Transaction,
+};
+
+/// The serialized size of a transaction paid and signed by `payer` alone, holding `instruction`.
+pub fn transaction_size(payer: &Pubkey, instruction: Instruction) -> usize {
+    let transaction = Transaction::new_with_payer(&[instruction], Some(payer));
+    bincode::serialized_size(&transaction).unwrap() as usize
+}
+
+/// Packs `groth16_proofs`, in order, into as few `VerifyBatch` instructions as possible.
+///
+/// Each batch holds at most `cu_budget / units_per_proof` proofs, and fits in a transaction paid
+/// by `payer` with no other instruction. Returns `None` if a single proof doesn't fit.
+pub fn pack_verify_batches(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    groth16_proofs: Vec<SP1Groth16Proof>,
+    units_per_proof: u64,
+    cu_budget: u64,
+) -> Option<Vec<Instruction>> {
+    let max_batch_len = (cu_budget / units_per_proof.max(1)) as usize;
+    if max_batch_len == 0 {
+        return None;
+    }
+
+    let fits = |batch: &[SP1Groth16Proof]| {
+        transaction_size(payer, verify_batch(program_id, batch.to_vec())) <= PACKET_DATA_SIZE
+    };
+
+    let mut instructions = Vec::new();
+    let mut batch: Vec<SP1Groth16Proof> = Vec::new();
+    for groth16_proof in groth16_proofs {
+        batch.push(groth16_proof);
+        if batch.len() <= max_batch_len && fits(&batch) {
+            continue;
+        }
+
+        // The proof overflows the current batch, so it starts the next one.
+        let groth16_proof = batch.pop().unwrap();
+        if batch.is_empty() {
+            return None;
+        }
+        instructions.push(verify_batch(
+            program_id,
+            std::mem::replace(&mut batch, vec![groth16_proof]),
+        ));
+        if !fits(&batch) {
+            return None;
+        }
+    }
+    if !batch.is_empty() {
+        instructions.push(verify_batch(program_id, batch));
+    }
+
+    Some(instructions)
+}
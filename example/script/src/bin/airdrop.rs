@@ -0,0 +1,97 @@
+//! Pays out an airdrop to two claimants proving their allocations, on `solana-program-test`.
+//!
+//! ```shell
+//! RUST_LOG=info cargo run --release --bin airdrop -- --prove
+//! ```
+
+use clap::Parser;
+use example_script::{airdrop::start, send};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signer::Signer};
+use sp1_airdrop_program::{claim, create_vault};
+use sp1_sdk::{include_elf, utils, HashableKey, ProverClient, SP1ProofWithPublicValues, SP1Stdin};
+use sp1_solana::decode_sp1_vkey_hash;
+
+#[derive(clap::Parser)]
+#[command(name = "Airdrop claim proof generator")]
+struct Cli {
+    #[arg(
+        long,
+        value_name = "prove",
+        default_value = "false",
+        help = "Specifies whether to generate a proof for the program."
+    )]
+    prove: bool,
+}
+
+/// The ELF binary of the SP1 program.
+const ELF: &[u8] = include_elf!("token-claim-program");
+
+/// The claimants and allocations proven by the saved proofs, and the files they're saved to.
+const CLAIMS: [(Pubkey, u64, &str); 2] = [
+    (
+        Pubkey::new_from_array([1u8; 32]),
+        LAMPORTS_PER_SOL,
+        "../../proofs/airdrop_claim_0.bin",
+    ),
+    (
+        Pubkey::new_from_array([2u8; 32]),
+        2 * LAMPORTS_PER_SOL,
+        "../../proofs/airdrop_claim_1.bin",
+    ),
+];
+
+#[tokio::main]
+async fn main() {
+    // Setup logging for the application.
+    utils::setup_logger();
+
+    // Parse command line arguments.
+    let args = Cli::parse();
+
+    let client = ProverClient::new();
+    let (pk, vk) = client.setup(ELF);
+    let sp1_vkey_hash = decode_sp1_vkey_hash(&vk.bytes32()).unwrap();
+
+    // Only generate the proofs if the prove flag is set. Each claimant proves their own claim.
+    if args.prove {
+        for (claimant, allocation, proof_file) in CLAIMS {
+            let mut stdin = SP1Stdin::new();
+            stdin.write(&claimant.to_bytes());
+            stdin.write(&allocation);
+
+            let proof = client
+                .prove(&pk, stdin)
+                .groth16()
+                .run()
+                .expect("Groth16 proof generation failed");
+            proof.save(proof_file).unwrap();
+        }
+    }
+
+    // Fund the vault of the airdrop.
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+    let instruction = create_vault(&program_id, &payer, &sp1_vkey_hash, 5 * LAMPORTS_PER_SOL);
+    send(&mut context, &[instruction], &[]).await.unwrap();
+
+    // Every claim is verified against the same program vkey hash, and paid once.
+    for (claimant, allocation, proof_file) in CLAIMS {
+        let sp1_proof_with_public_values = SP1ProofWithPublicValues::load(proof_file).unwrap();
+        let instruction = claim(
+            &program_id,
+            &payer,
+            &claimant,
+            &sp1_vkey_hash,
+            &sp1_proof_with_public_values.bytes(),
+            sp1_proof_with_public_values.public_values.as_slice(),
+        );
+        send(&mut context, &[instruction.clone()], &[])
+            .await
+            .unwrap();
+        assert!(send(&mut context, &[instruction], &[]).await.is_err());
+
+        let balance = context.banks_client.get_balance(claimant).await.unwrap();
+        assert_eq!(balance, allocation);
+        println!("Paid {allocation} lamports to {claimant}");
+    }
+}
@@ -0,0 +1,98 @@
+//! Mints SPL tokens with a proof of a token claim, on `solana-program-test`.
+//!
+//! ```shell
+//! RUST_LOG=info cargo run --release --bin token_mint -- --prove
+//! ```
+
+use borsh::BorshDeserialize;
+use clap::Parser;
+use example_script::{
+    send,
+    token_mint::{create_mint, create_token_account, start, token_balance},
+};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use sp1_sdk::{include_elf, utils, HashableKey, ProverClient, SP1ProofWithPublicValues, SP1Stdin};
+use sp1_solana::decode_sp1_vkey_hash;
+use sp1_token_mint_program::{claim, find_mint_authority_address, ClaimArgs, MintClaim};
+
+#[derive(clap::Parser)]
+#[command(name = "Token claim proof generator")]
+struct Cli {
+    #[arg(
+        long,
+        value_name = "prove",
+        default_value = "false",
+        help = "Specifies whether to generate a proof for the program."
+    )]
+    prove: bool,
+}
+
+/// The ELF binary of the SP1 program.
+const ELF: &[u8] = include_elf!("token-claim-program");
+
+/// The recipient of the claim proven by the saved proof.
+const RECIPIENT: Pubkey = Pubkey::new_from_array([7u8; 32]);
+
+/// The amount of tokens claimed by the saved proof.
+const AMOUNT: u64 = 1_000;
+
+#[tokio::main]
+async fn main() {
+    // Setup logging for the application.
+    utils::setup_logger();
+
+    // Where to save / load the sp1 proof from.
+    let proof_file = "../../proofs/token_claim_proof.bin";
+
+    // Parse command line arguments.
+    let args = Cli::parse();
+
+    let client = ProverClient::new();
+    let (pk, vk) = client.setup(ELF);
+
+    // Only generate a proof if the prove flag is set.
+    if args.prove {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&RECIPIENT.to_bytes());
+        stdin.write(&AMOUNT);
+
+        let proof = client
+            .prove(&pk, stdin)
+            .groth16()
+            .run()
+            .expect("Groth16 proof generation failed");
+        proof.save(proof_file).unwrap();
+    }
+
+    let sp1_proof_with_public_values = SP1ProofWithPublicValues::load(proof_file).unwrap();
+    let args = ClaimArgs {
+        sp1_vkey_hash: decode_sp1_vkey_hash(&vk.bytes32()).unwrap(),
+        proof: sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs: sp1_proof_with_public_values.public_values.to_vec(),
+    };
+    assert_eq!(
+        MintClaim::try_from_slice(&args.sp1_public_inputs).unwrap(),
+        MintClaim {
+            recipient: RECIPIENT,
+            amount: AMOUNT,
+        }
+    );
+
+    // Create a mint whose authority is the PDA of the token claim program.
+    let (mut context, program_id) = start().await;
+    let (mint_authority, _) = find_mint_authority_address(&program_id, &args.sp1_vkey_hash);
+    let mint = create_mint(&mut context, &mint_authority).await;
+    let token_account = create_token_account(&mut context, &mint, &RECIPIENT).await;
+
+    // Mint the claimed tokens.
+    let payer = context.payer.pubkey();
+    let instruction = claim(&program_id, &payer, &mint, &token_account, &args);
+    send(&mut context, &[instruction], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &token_account).await, AMOUNT);
+    println!("Minted {AMOUNT} tokens to {RECIPIENT}");
+
+    // The claim can only be minted once.
+    let instruction = claim(&program_id, &payer, &mint, &token_account, &args);
+    assert!(send(&mut context, &[instruction], &[]).await.is_err());
+    assert_eq!(token_balance(&mut context, &token_account).await, AMOUNT);
+}
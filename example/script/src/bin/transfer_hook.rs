@@ -0,0 +1,105 @@
+//! Attests a token owner with SP1, then transfers tokens of a gated Token-2022 mint, on
+//! `solana-program-test`.
+//!
+//! ```shell
+//! RUST_LOG=info cargo run --release --bin transfer_hook -- --prove
+//! ```
+
+use borsh::BorshDeserialize;
+use clap::Parser;
+use example_script::{
+    send,
+    transfer_hook::{
+        attested_owner, create_mint, create_token_account, start, token_balance,
+        transfer_instruction,
+    },
+};
+use solana_sdk::signer::Signer;
+use sp1_sdk::{include_elf, utils, HashableKey, ProverClient, SP1ProofWithPublicValues, SP1Stdin};
+use sp1_solana::decode_sp1_vkey_hash;
+use sp1_transfer_hook_program::{attest, initialize_gate, Attestation};
+
+#[derive(clap::Parser)]
+#[command(name = "Attestation proof generator")]
+struct Cli {
+    #[arg(
+        long,
+        value_name = "prove",
+        default_value = "false",
+        help = "Specifies whether to generate a proof for the program."
+    )]
+    prove: bool,
+}
+
+/// The ELF binary of the SP1 program.
+const ELF: &[u8] = include_elf!("token-claim-program");
+
+/// The last slot in which the saved attestation is valid.
+const EXPIRY_SLOT: u64 = 1_000_000;
+
+#[tokio::main]
+async fn main() {
+    // Setup logging for the application.
+    utils::setup_logger();
+
+    // Where to save / load the sp1 proof from.
+    let proof_file = "../../proofs/attestation_proof.bin";
+
+    // Parse command line arguments.
+    let args = Cli::parse();
+
+    let client = ProverClient::new();
+    let (pk, vk) = client.setup(ELF);
+    let owner = attested_owner();
+
+    // Only generate a proof if the prove flag is set.
+    if args.prove {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&owner.pubkey().to_bytes());
+        stdin.write(&EXPIRY_SLOT);
+
+        let proof = client
+            .prove(&pk, stdin)
+            .groth16()
+            .run()
+            .expect("Groth16 proof generation failed");
+        proof.save(proof_file).unwrap();
+    }
+
+    let sp1_proof_with_public_values = SP1ProofWithPublicValues::load(proof_file).unwrap();
+    let sp1_vkey_hash = decode_sp1_vkey_hash(&vk.bytes32()).unwrap();
+    let sp1_public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+    assert_eq!(
+        Attestation::try_from_slice(&sp1_public_inputs).unwrap(),
+        Attestation {
+            owner: owner.pubkey(),
+            expiry_slot: EXPIRY_SLOT,
+        }
+    );
+
+    // Create a mint whose transfers require an attestation of the SP1 program.
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+    let mint = create_mint(&mut context, &program_id).await;
+    let instruction = initialize_gate(&program_id, &payer, &mint, &payer, sp1_vkey_hash);
+    send(&mut context, &[instruction], &[]).await.unwrap();
+    let source = create_token_account(&mut context, &mint, &owner.pubkey(), 100).await;
+    let destination = create_token_account(&mut context, &mint, &payer, 0).await;
+
+    // Verify the proof once, then transfer without a proof.
+    let instruction = attest(
+        &program_id,
+        &payer,
+        &owner.pubkey(),
+        sp1_vkey_hash,
+        sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs,
+    );
+    send(&mut context, &[instruction], &[]).await.unwrap();
+
+    let instruction =
+        transfer_instruction(&context, &source, &mint, &destination, &owner.pubkey(), 40).await;
+    send(&mut context, &[instruction], &[&owner]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &destination).await, 40);
+    println!("Transferred 40 tokens from attested {}", owner.pubkey());
+}
@@ -0,0 +1,31 @@
+//! Regenerates `verifier/testdata/vectors.json`, the canonical test vectors of the fibonacci proof,
+//! for implementations of the verifier in other languages.
+//!
+//! ```shell
+//! cargo run --release --bin vectors
+//! ```
+
+use example_script::proof_json::ProofJson;
+use sp1_solana::vectors::{to_json, TestVectors};
+
+/// The proof the vectors are computed from.
+const PROOF_PATH: &str = "../../proofs/fibonacci_proof.json";
+
+/// The vectors file, checked by the verifier's tests.
+const VECTORS_PATH: &str = "../../verifier/testdata/vectors.json";
+
+fn main() {
+    let loaded = ProofJson::load(PROOF_PATH)
+        .and_then(|proof| proof.decode())
+        .unwrap_or_else(|error| panic!("{error}"));
+    let vectors = TestVectors::new(
+        "fibonacci",
+        &loaded.sp1_vkey_hash,
+        &loaded.groth16_proof.sp1_public_inputs,
+        &loaded.groth16_proof.proof,
+    )
+    .expect("the fibonacci proof has vectors");
+
+    std::fs::write(VECTORS_PATH, to_json(&[vectors])).expect("failed to write the vectors");
+    println!("Wrote {VECTORS_PATH}");
+}
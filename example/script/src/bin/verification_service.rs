@@ -0,0 +1,43 @@
+//! Serves the HTTP verification oracle of [`example_script::service`].
+//!
+//! ```shell
+//! cargo run --release --features service --bin verification_service -- --listen 127.0.0.1:3000
+//! curl -X POST -H 'Content-Type: application/json' -d @../../proofs/fibonacci_proof.json \
+//!     http://127.0.0.1:3000/verify
+//! ```
+
+use clap::Parser;
+use example_script::{
+    proof_file::VkVersion,
+    service::{serve, Verifier},
+};
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+
+#[derive(clap::Parser)]
+#[command(name = "SP1 proof verification service")]
+struct Cli {
+    #[arg(
+        long,
+        default_value = "127.0.0.1:3000",
+        help = "The address to listen on."
+    )]
+    listen: SocketAddr,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = VkVersion::V3,
+        help = "The Groth16 verification key to verify against."
+    )]
+    vk_version: VkVersion,
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    let verifier = Verifier::new(cli.vk_version).expect("invalid verification key");
+    let listener = TcpListener::bind(cli.listen).await.unwrap();
+    println!("Listening on http://{}", listener.local_addr().unwrap());
+    serve(listener, verifier).await.unwrap();
+}
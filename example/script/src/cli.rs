@@ -0,0 +1,233 @@
+//! The command line of the example script: one subcommand per job, sharing [`CommonArgs`].
+
+use crate::{
+    fixture::FixtureFormat,
+    proof_file::VkVersion,
+    prover::{ProverKind, ProverOptions, DEFAULT_PRIVATE_KEY_ENV},
+    report::DEFAULT_COMPUTE_UNIT_PRICE,
+    rpc::{Network, RetryPolicy, DEFAULT_COMPUTE_UNIT_LIMIT},
+};
+use clap::{Args, Parser, Subcommand};
+use solana_sdk::pubkey::Pubkey;
+use std::{path::PathBuf, time::Duration};
+
+/// Where `prove` saves the proof, and where the other subcommands load it from.
+pub const DEFAULT_PROOF_FILE: &str = "../../proofs/fibonacci_proof.bin";
+
+/// Where `prove --prover mock` saves its proof by default, so that it never replaces the real one.
+pub const DEFAULT_MOCK_PROOF_FILE: &str = "../../proofs/fibonacci_mock_proof.bin";
+
+#[derive(Parser, Debug)]
+#[command(name = "zkVM Proof Generator")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generates a proof of the fibonacci program, and saves it or a fixture of it.
+    Prove(ProveArgs),
+    /// Verifies a proof or fixture file natively.
+    Verify(VerifyArgs),
+    /// Sends a proof or fixture file to the example program, on solana-program-test or a cluster.
+    Submit(SubmitArgs),
+    /// Prints the vkey hashes, digest and sizes of a proof or fixture file.
+    Inspect(InspectArgs),
+}
+
+/// The flags shared by every subcommand.
+#[derive(Args, Debug)]
+pub struct CommonArgs {
+    #[arg(
+        long,
+        value_name = "PATH",
+        default_value = DEFAULT_PROOF_FILE,
+        help = "The proof file: an SP1ProofWithPublicValues, a JSON proof (see \
+                example_script::proof_json) or a fixture saved by prove --save-fixture."
+    )]
+    pub proof: PathBuf,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = VkVersion::V3,
+        help = "The Groth16 verification key to verify against."
+    )]
+    pub vk_version: VkVersion,
+}
+
+#[derive(Args, Debug)]
+pub struct ProveArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[arg(long, value_enum, default_value_t = ProverKind::Cpu, help = "The prover to use.")]
+    pub prover: ProverKind,
+
+    #[arg(
+        long,
+        value_name = "VAR",
+        default_value = DEFAULT_PRIVATE_KEY_ENV,
+        help = "The environment variable holding the private key of --prover network."
+    )]
+    pub private_key_env: String,
+
+    #[arg(long, help = "Proves without executing the program first.")]
+    pub skip_simulation: bool,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "How long to wait for the prover network to return the proof."
+    )]
+    pub timeout: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Proves again even if the cached proof is of the current program."
+    )]
+    pub force_prove: bool,
+
+    #[arg(
+        long,
+        help = "Executes the SP1 program without proving, and prints its public values."
+    )]
+    pub execute_only: bool,
+
+    #[arg(
+        long,
+        requires = "execute_only",
+        help = "With --execute-only, runs the example program's account logic on the public values \
+                with its test-only mock verifier. Needs the mock-verifier feature."
+    )]
+    pub mock_submit: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "execute_only",
+        help = "Prints the vkey hash of the SP1 program, and exits with an error unless it matches \
+                FIBONACCI_VKEY_HASH."
+    )]
+    pub vkey_check: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Also saves the proof as an SP1ProofFixture, and checks that it reloads and verifies."
+    )]
+    pub save_fixture: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = FixtureFormat::Bin,
+        help = "The file format of --save-fixture."
+    )]
+    pub fixture_format: FixtureFormat,
+}
+
+impl ProveArgs {
+    /// Where to save the proof: mock proofs go to [`DEFAULT_MOCK_PROOF_FILE`] unless `--proof` is
+    /// given.
+    pub fn proof_file(&self) -> PathBuf {
+        if self.prover == ProverKind::Mock && self.common.proof == PathBuf::from(DEFAULT_PROOF_FILE)
+        {
+            PathBuf::from(DEFAULT_MOCK_PROOF_FILE)
+        } else {
+            self.common.proof.clone()
+        }
+    }
+
+    pub fn prover_options(&self) -> ProverOptions {
+        ProverOptions {
+            kind: self.prover,
+            private_key_env: self.private_key_env.clone(),
+            skip_simulation: self.skip_simulation,
+            timeout: self.timeout.map(Duration::from_secs),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct SubmitArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[arg(
+        long,
+        value_name = "localnet|devnet|mainnet|URL",
+        help = "Submits the proof to this cluster over RPC, instead of solana-program-test."
+    )]
+    pub network: Option<Network>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "The payer keypair used with --network. Defaults to ~/.config/solana/id.json."
+    )]
+    pub keypair: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PUBKEY",
+        help = "The deployed verifier program used with --network. Defaults to the program keypair \
+                written by cargo build-sbf."
+    )]
+    pub program_id: Option<Pubkey>,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_COMPUTE_UNIT_LIMIT,
+        help = "The compute unit limit of the verify transaction used with --network."
+    )]
+    pub compute_unit_limit: u32,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_COMPUTE_UNIT_PRICE,
+        help = "The compute unit price, in micro-lamports, at which to estimate the priority fee."
+    )]
+    pub compute_unit_price: u64,
+
+    #[arg(
+        long,
+        help = "Sends the verify transaction used with --network without a preflight simulation, so \
+                that it lands even if the program rejects it."
+    )]
+    pub skip_preflight: bool,
+
+    #[arg(
+        long,
+        help = "Submits the proof even if it doesn't verify natively against the program's \
+                verification key and vkey hash."
+    )]
+    pub force: bool,
+
+    #[arg(
+        long,
+        help = "Prints the compute units, fees and logs of the verification as JSON."
+    )]
+    pub json: bool,
+}
+
+impl SubmitArgs {
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            skip_preflight: self.skip_preflight,
+            ..RetryPolicy::default()
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct InspectArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+}
@@ -0,0 +1,125 @@
+//! Compute unit regression checks.
+//!
+//! [`assert_cu_within`] runs an instruction under `solana-program-test`, reads `units_consumed`
+//! from the simulation, and compares it against a JSON baseline committed alongside the test. This
+//! catches dependency upgrades that silently push an instruction over its compute budget.
+
+use serde::{Deserialize, Serialize};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction, instruction::Instruction, signature::Keypair,
+    signer::Signer, transaction::Transaction,
+};
+use std::path::Path;
+
+/// The compute unit limit instructions are measured with: the most a transaction can request.
+///
+/// Without an explicit limit, the runtime grants 200,000 units per instruction, so an instruction
+/// going over that would fail instead of being compared against its baseline.
+pub const MEASURED_CU_LIMIT: u32 = 1_400_000;
+
+/// When set, [`assert_cu_within`] records the measured units as the new baseline instead of
+/// checking against the old one.
+pub const UPDATE_BASELINE_ENV: &str = "UPDATE_CU_BASELINE";
+
+/// The committed compute unit baseline for a single instruction.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CuBaseline {
+    pub units_consumed: u64,
+}
+
+/// A successful simulation of an instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    pub units_consumed: u64,
+    pub logs: Vec<String>,
+}
+
+/// Simulates `instruction`, signed by the payer and `signers`, against a fresh `program_test`
+/// bank, panicking if it fails.
+///
+/// The transaction requests [`MEASURED_CU_LIMIT`] units first, and the units consumed include
+/// the 150 of that compute budget instruction.
+///
+/// To measure the real on-chain cost, `program_test` must load the program's BPF build (see
+/// [`ProgramTest::prefer_bpf`]) rather than a native `processor!`.
+pub async fn simulate(
+    program_test: ProgramTest,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Simulation {
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(MEASURED_CU_LIMIT),
+            instruction,
+        ],
+        Some(&payer.pubkey()),
+    );
+    let mut keypairs = vec![&payer];
+    keypairs.extend_from_slice(signers);
+    transaction.sign(&keypairs, recent_blockhash);
+
+    let simulation = banks_client
+        .simulate_transaction(transaction)
+        .await
+        .expect("failed to simulate transaction");
+    let details = simulation
+        .simulation_details
+        .expect("simulation returned no details");
+
+    if let Some(Err(err)) = simulation.result {
+        panic!("instruction failed: {err}\n{}", details.logs.join("\n"));
+    }
+
+    Simulation {
+        units_consumed: details.units_consumed,
+        logs: details.logs,
+    }
+}
+
+/// Simulates `instruction` like [`simulate`], signed by the payer alone, returning the units it
+/// consumed.
+pub async fn measure_units_consumed(program_test: ProgramTest, instruction: Instruction) -> u64 {
+    simulate(program_test, instruction, &[])
+        .await
+        .units_consumed
+}
+
+/// Asserts that `instruction` consumes at most `tolerance` (a fraction, e.g. `0.05`) more compute
+/// units than recorded in the JSON file at `baseline`, returning the measured units.
+///
+/// Set [`UPDATE_BASELINE_ENV`] to write the measurement to `baseline` instead.
+pub async fn assert_cu_within(
+    program_test: ProgramTest,
+    instruction: Instruction,
+    baseline: &Path,
+    tolerance: f64,
+) -> u64 {
+    let units_consumed = measure_units_consumed(program_test, instruction).await;
+
+    if std::env::var_os(UPDATE_BASELINE_ENV).is_some() {
+        let json = serde_json::to_string_pretty(&CuBaseline { units_consumed }).unwrap();
+        std::fs::write(baseline, json + "\n").expect("failed to write compute unit baseline");
+        return units_consumed;
+    }
+
+    let json = std::fs::read_to_string(baseline).unwrap_or_else(|_| {
+        panic!(
+            "missing compute unit baseline {}, rerun with {UPDATE_BASELINE_ENV}=1 to record it",
+            baseline.display()
+        )
+    });
+    let expected: CuBaseline = serde_json::from_str(&json).expect("invalid compute unit baseline");
+
+    let ceiling = (expected.units_consumed as f64 * (1.0 + tolerance)) as u64;
+    assert!(
+        units_consumed <= ceiling,
+        "consumed {units_consumed} compute units, above the baseline of {} (+{:.1}% = {ceiling})",
+        expected.units_consumed,
+        tolerance * 100.0,
+    );
+
+    units_consumed
+}
@@ -0,0 +1,108 @@
+//! Saves and loads [`SP1ProofFixture`]s, in the formats of the script's `--fixture-format`.
+
+use serde::{Deserialize, Serialize};
+use sp1_solana::{verify_proof_fixture, SP1ProofFixture};
+use std::path::Path;
+
+/// The file format of a saved fixture.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FixtureFormat {
+    /// The Borsh encoding, as read by `SP1ProofFixture::load`.
+    #[default]
+    Bin,
+    /// A JSON object of hex strings.
+    Json,
+    /// The Borsh encoding, hex-encoded.
+    Hex,
+}
+
+/// The JSON form of a fixture. Every field is `0x`-prefixed hex.
+#[derive(Serialize, Deserialize)]
+struct FixtureJson {
+    groth16_vkey_hash: String,
+    proof: String,
+    sp1_vkey_hash: String,
+    committed_values_digest: String,
+    sp1_public_inputs: String,
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn decode_hex<const N: usize>(hex: &str) -> Result<[u8; N], String> {
+    decode_hex_vec(hex)?
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("expected {N} bytes, got {}", bytes.len()))
+}
+
+fn decode_hex_vec(hex: &str) -> Result<Vec<u8>, String> {
+    hex::decode(hex.strip_prefix("0x").unwrap_or(hex)).map_err(|error| error.to_string())
+}
+
+/// Encodes `fixture` in `format`.
+pub fn encode_fixture(fixture: &SP1ProofFixture, format: FixtureFormat) -> Vec<u8> {
+    match format {
+        FixtureFormat::Bin => fixture.to_bytes(),
+        FixtureFormat::Hex => hex::encode(fixture.to_bytes()).into_bytes(),
+        FixtureFormat::Json => {
+            let json = FixtureJson {
+                groth16_vkey_hash: encode_hex(&fixture.groth16_vkey_hash),
+                proof: encode_hex(&fixture.proof),
+                sp1_vkey_hash: encode_hex(&fixture.sp1_vkey_hash),
+                committed_values_digest: encode_hex(&fixture.committed_values_digest),
+                sp1_public_inputs: encode_hex(&fixture.sp1_public_inputs),
+            };
+            (serde_json::to_string_pretty(&json).unwrap() + "\n").into_bytes()
+        }
+    }
+}
+
+/// Decodes a fixture encoded with [`encode_fixture`].
+pub fn decode_fixture(bytes: &[u8], format: FixtureFormat) -> Result<SP1ProofFixture, String> {
+    match format {
+        FixtureFormat::Bin => SP1ProofFixture::from_bytes(bytes).map_err(|error| error.to_string()),
+        FixtureFormat::Hex => {
+            let hex = std::str::from_utf8(bytes).map_err(|error| error.to_string())?;
+            let bytes = hex::decode(hex.trim()).map_err(|error| error.to_string())?;
+            SP1ProofFixture::from_bytes(&bytes).map_err(|error| error.to_string())
+        }
+        FixtureFormat::Json => {
+            let json: FixtureJson =
+                serde_json::from_slice(bytes).map_err(|error| error.to_string())?;
+            Ok(SP1ProofFixture {
+                groth16_vkey_hash: decode_hex(&json.groth16_vkey_hash)?,
+                proof: decode_hex(&json.proof)?,
+                sp1_vkey_hash: decode_hex(&json.sp1_vkey_hash)?,
+                committed_values_digest: decode_hex(&json.committed_values_digest)?,
+                sp1_public_inputs: decode_hex_vec(&json.sp1_public_inputs)?.into(),
+            })
+        }
+    }
+}
+
+/// Saves `fixture` to `path` in `format`, then reloads it and verifies it natively against
+/// `groth16_vk`, returning the reloaded fixture.
+pub fn save_fixture(
+    fixture: &SP1ProofFixture,
+    path: &Path,
+    format: FixtureFormat,
+    groth16_vk: &[u8],
+) -> Result<SP1ProofFixture, String> {
+    std::fs::write(path, encode_fixture(fixture, format))
+        .map_err(|error| format!("failed to write {}: {error}", path.display()))?;
+
+    let bytes = std::fs::read(path)
+        .map_err(|error| format!("failed to read {}: {error}", path.display()))?;
+    let reloaded = decode_fixture(&bytes, format)?;
+    if reloaded != *fixture {
+        return Err(format!(
+            "{} doesn't reload to the same fixture",
+            path.display()
+        ));
+    }
+    verify_proof_fixture(&reloaded, groth16_vk)
+        .map_err(|error| format!("the saved fixture doesn't verify: {error}"))?;
+
+    Ok(reloaded)
+}
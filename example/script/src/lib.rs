@@ -0,0 +1,95 @@
+//! Helpers shared by the example script and its tests.
+
+use fibonacci_verifier_contract::{instruction::verify, SP1Groth16Proof};
+use report::CostReport;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+pub mod airdrop;
+pub mod batch;
+pub mod cli;
+pub mod cu;
+pub mod fixture;
+#[cfg(feature = "mock-verifier")]
+pub mod mock;
+pub mod packer;
+pub mod proof_file;
+pub mod proof_json;
+pub mod prover;
+pub mod report;
+pub mod rpc;
+#[cfg(feature = "service")]
+pub mod service;
+pub mod size;
+pub mod token_mint;
+pub mod transfer_hook;
+pub mod vkey;
+
+/// Sends `instructions` in one transaction, signed by the payer and `signers`.
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction = Transaction::new_with_payer(instructions, Some(&context.payer.pubkey()));
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    transaction.sign(&all_signers, recent_blockhash);
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap())
+}
+
+/// Verifies `groth16_proof` with the example program on `solana-program-test`, reporting its cost
+/// with the priority fee estimated at `compute_unit_price` micro-lamports per unit.
+///
+/// The program runs natively, where only syscalls are metered, unless `SBF_OUT_DIR` points to its
+/// `cargo build-sbf` output.
+pub async fn run_verify_instruction(
+    groth16_proof: SP1Groth16Proof,
+    compute_unit_price: u64,
+) -> CostReport {
+    let program_id = Pubkey::new_unique();
+
+    // Create program test environment
+    let (banks_client, payer, recent_blockhash) = ProgramTest::new(
+        "fibonacci_verifier_contract",
+        program_id,
+        processor!(fibonacci_verifier_contract::process_instruction),
+    )
+    .start()
+    .await;
+
+    // Create and send transaction, keeping its metadata
+    let mut transaction =
+        Transaction::new_with_payer(&[verify(&program_id, groth16_proof)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+    let num_signatures = transaction.signatures.len() as u64;
+    let result = banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    let metadata = result.metadata.expect("transaction returned no metadata");
+    if let Err(error) = result.result {
+        panic!(
+            "verify failed: {error}\n{}",
+            metadata.log_messages.join("\n")
+        );
+    }
+
+    CostReport::new(
+        metadata.compute_units_consumed,
+        metadata.log_messages,
+        num_signatures,
+        compute_unit_price,
+    )
+}
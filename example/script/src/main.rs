@@ -1,98 +1,247 @@
+use borsh::BorshDeserialize;
 use clap::Parser;
-use fibonacci_verifier_contract::SP1Groth16Proof;
-use solana_program_test::{processor, ProgramTest};
+use example_script::{
+    cli::{Cli, Command, CommonArgs, ProveArgs, SubmitArgs},
+    fixture::save_fixture,
+    proof_file::{check_before_submit, groth16_proof_from_fixture, load_proof_file, Inspection},
+    prover::load_cached_proof,
+    report::CostReport,
+    rpc::{check_program_deployed, send_verify_transaction, Network},
+    run_verify_instruction,
+    vkey::check_vkey_hash,
+};
+use fibonacci_verifier_contract::{SP1Groth16Proof, FIBONACCI_VKEY_HASH};
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
-    instruction::{AccountMeta, Instruction},
-    pubkey::Pubkey,
-    signer::Signer,
-    transaction::Transaction,
+    commitment_config::CommitmentConfig, signature::read_keypair_file, signer::Signer,
 };
-use sp1_sdk::{include_elf, utils, ProverClient, SP1ProofWithPublicValues, SP1Stdin};
-
-#[derive(clap::Parser)]
-#[command(name = "zkVM Proof Generator")]
-struct Cli {
-    #[arg(
-        long,
-        value_name = "prove",
-        default_value = "false",
-        help = "Specifies whether to generate a proof for the program."
-    )]
-    prove: bool,
-}
+use sp1_sdk::{include_elf, utils, HashableKey, ProverClient, SP1Stdin};
+use sp1_solana::{
+    decode_sp1_vkey_hash, hash_public_inputs, verify_proof_fixture, SP1ProofFixture,
+    GROTH16_VK_3_0_0_BYTES,
+};
+use std::path::PathBuf;
+
+/// The program keypair written by `cargo build-sbf --sbf-out-dir ./target` in `example/program`.
+const PROGRAM_KEYPAIR_FILE: &str = "../program/target/fibonacci_verifier_contract-keypair.json";
 
 /// The ELF binary of the SP1 program.
 const ELF: &[u8] = include_elf!("fibonacci-program");
 
-/// Invokes the solana program using Solana Program Test.
-async fn run_verify_instruction(groth16_proof: SP1Groth16Proof) {
-    let program_id = Pubkey::new_unique();
-
-    // Create program test environment
-    let (banks_client, payer, recent_blockhash) = ProgramTest::new(
-        "fibonacci-verifier-contract",
-        program_id,
-        processor!(fibonacci_verifier_contract::process_instruction),
+/// Submits the proof to a live cluster, returning the cost of the transaction.
+async fn run_verify_transaction(
+    args: &SubmitArgs,
+    network: &Network,
+    groth16_proof: SP1Groth16Proof,
+) -> CostReport {
+    let keypair_file = args.keypair.clone().unwrap_or_else(|| {
+        PathBuf::from(std::env::var("HOME").expect("HOME is not set"))
+            .join(".config/solana/id.json")
+    });
+    let payer = read_keypair_file(&keypair_file).unwrap_or_else(|error| {
+        panic!("failed to read keypair {}: {error}", keypair_file.display())
+    });
+    let program_id = args.program_id.unwrap_or_else(|| {
+        read_keypair_file(PROGRAM_KEYPAIR_FILE)
+            .unwrap_or_else(|error| {
+                panic!("failed to read {PROGRAM_KEYPAIR_FILE}, pass --program-id: {error}")
+            })
+            .pubkey()
+    });
+
+    let client =
+        RpcClient::new_with_commitment(network.url().to_string(), CommitmentConfig::confirmed());
+    check_program_deployed(&client, &program_id)
+        .await
+        .unwrap_or_else(|error| panic!("{error}"));
+
+    send_verify_transaction(
+        &client,
+        &payer,
+        &program_id,
+        groth16_proof,
+        args.compute_unit_limit,
+        args.compute_unit_price,
+        &args.retry_policy(),
     )
-    .start()
-    .await;
+    .await
+    .unwrap_or_else(|error| panic!("verify transaction failed: {error}"))
+}
 
-    let instruction = Instruction::new_with_borsh(
-        program_id,
-        &groth16_proof,
-        vec![AccountMeta::new(payer.pubkey(), false)],
+/// Executes the SP1 program, printing the public values, their digest and the vkey hash, and
+/// optionally submits them to the mock verifier.
+async fn execute_only(mock_submit: bool) {
+    let client = ProverClient::new();
+    let (_, vk) = client.setup(ELF);
+
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&20u32);
+    let (public_values, report) = client.execute(ELF, stdin).run().expect("execution failed");
+    println!("Executed {} cycles", report.total_instruction_count());
+
+    let sp1_public_inputs = public_values.to_vec();
+    let (n, a, b) = <(u32, u32, u32)>::try_from_slice(&sp1_public_inputs)
+        .expect("the public values are not a fibonacci (n, a, b) triple");
+    println!("Public values: 0x{}", hex::encode(&sp1_public_inputs));
+    println!("Decoded public values: (n: {n}, a: {a}, b: {b})");
+    println!(
+        "Committed values digest: 0x{}",
+        hex::encode(hash_public_inputs(&sp1_public_inputs))
     );
+    println!("Program vkey hash: {}", vk.bytes32());
 
-    // Create and send transaction
-    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
-    transaction.sign(&[&payer], recent_blockhash);
-    banks_client.process_transaction(transaction).await.unwrap();
+    if mock_submit {
+        mock_submit_public_values(sp1_public_inputs).await;
+    }
 }
 
-#[tokio::main]
-async fn main() {
-    // Setup logging for the application.
-    utils::setup_logger();
+/// Runs the account logic of the example program on the public values, with its mock verifier.
+#[cfg(feature = "mock-verifier")]
+async fn mock_submit_public_values(sp1_public_inputs: Vec<u8>) {
+    example_script::mock::run_mock_submit(sp1_public_inputs)
+        .await
+        .expect("mock submission failed");
+    println!("Stored, checked and nullified the public values with the mock verifier");
+}
 
-    // Where to save / load the sp1 proof from.
-    let proof_file = "../../proofs/fibonacci_proof.bin";
+#[cfg(not(feature = "mock-verifier"))]
+async fn mock_submit_public_values(_sp1_public_inputs: Vec<u8>) {
+    panic!("--mock-submit needs the mock-verifier feature: cargo run --features mock-verifier");
+}
 
-    // Parse command line arguments.
-    let args = Cli::parse();
+/// Exits with an error if proofs of `sp1_vkey_hash` would be rejected by the example program.
+fn exit_on_vkey_mismatch(sp1_vkey_hash: &[u8; 32]) {
+    if let Err(diff) = check_vkey_hash(sp1_vkey_hash, &FIBONACCI_VKEY_HASH) {
+        eprintln!("{diff}");
+        std::process::exit(1);
+    }
+}
+
+/// Loads the proof file of a subcommand, exiting with an error if it can't be read.
+fn load_proof(common: &CommonArgs) -> SP1ProofFixture {
+    load_proof_file(&common.proof).unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(1);
+    })
+}
+
+/// Proves the fibonacci program, saving the proof and optionally a fixture of it.
+async fn prove(args: &ProveArgs) {
+    if args.vkey_check {
+        let (_, vk) = ProverClient::new().setup(ELF);
+        println!("Program vkey hash: {}", vk.bytes32());
+        exit_on_vkey_mismatch(&decode_sp1_vkey_hash(&vk.bytes32()).unwrap());
+        println!("It matches FIBONACCI_VKEY_HASH");
+        return;
+    }
+
+    if args.execute_only {
+        execute_only(args.mock_submit).await;
+        return;
+    }
 
-    // Only generate a proof if the prove flag is set.
-    if args.prove {
-        // Initialize the prover client
-        let client = ProverClient::new();
-        let (pk, vk) = client.setup(ELF);
+    // Initialize the prover client
+    let options = args.prover_options();
+    let client = options.client().unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(1);
+    });
+    let (pk, vk) = client.setup(ELF);
 
+    println!("Program Verification Key Bytes {:?}", vk.bytes32());
+
+    // Don't spend minutes proving for a program that the contract would reject.
+    if args.save_fixture.is_none() {
+        exit_on_vkey_mismatch(&decode_sp1_vkey_hash(&vk.bytes32()).unwrap());
+    }
+
+    // Reuse the cached proof if it's of the current program.
+    let proof_file = args.proof_file();
+    if !args.force_prove && load_cached_proof(&proof_file, &vk).is_some() {
         println!(
-            "Program Verification Key Bytes {:?}",
-            sp1_sdk::HashableKey::bytes32(&vk)
+            "Reusing the proof in {}, pass --force-prove to prove again",
+            proof_file.display()
         );
-
+    } else {
         // In our SP1 program, compute the 20th fibonacci number.
         let mut stdin = SP1Stdin::new();
         stdin.write(&20u32);
 
         // Generate a proof for the fibonacci program.
-        let proof = client
-            .prove(&pk, stdin)
-            .groth16()
-            .run()
-            .expect("Groth16 proof generation failed");
+        let proof = options
+            .prove_groth16(&client, &pk, stdin)
+            .unwrap_or_else(|error| panic!("{error}"));
 
         // Save the generated proof to `proof_file`.
         proof.save(&proof_file).unwrap();
+        println!("Saved the proof to {}", proof_file.display());
     }
 
-    // Load the proof from the file, and convert it to a Borsh-serializable `SP1Groth16Proof`.
-    let sp1_proof_with_public_values = SP1ProofWithPublicValues::load(&proof_file).unwrap();
-    let groth16_proof = SP1Groth16Proof {
-        proof: sp1_proof_with_public_values.bytes(),
-        sp1_public_inputs: sp1_proof_with_public_values.public_values.to_vec(),
+    if let Some(path) = &args.save_fixture {
+        let fixture = load_proof_file(&proof_file).unwrap_or_else(|error| panic!("{error}"));
+        save_fixture(
+            &fixture,
+            path,
+            args.fixture_format,
+            args.common.vk_version.groth16_vk(),
+        )
+        .unwrap_or_else(|error| panic!("{error}"));
+        println!("Saved a verified fixture to {}", path.display());
+    }
+}
+
+/// Sends the proof to the example program, on the given cluster or on `solana-program-test`.
+async fn submit(args: &SubmitArgs) {
+    let fixture = load_proof(&args.common);
+
+    // Refuse to submit a proof that the program would reject, explaining why.
+    if !args.force {
+        if let Err(explanation) = check_before_submit(&fixture, GROTH16_VK_3_0_0_BYTES) {
+            eprintln!("{explanation}\n\nPass --force to submit it anyway.");
+            std::process::exit(1);
+        }
+    }
+
+    let groth16_proof = groth16_proof_from_fixture(&fixture);
+    let report = match &args.network {
+        Some(network) => run_verify_transaction(args, network, groth16_proof).await,
+        None => run_verify_instruction(groth16_proof, args.compute_unit_price).await,
     };
 
-    // Send the proof to the contract, and verify it on `solana-program-test`.
-    run_verify_instruction(groth16_proof).await;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return;
+    }
+    report.print();
+    if let (Some(network), Some(signature)) = (&args.network, &report.signature) {
+        println!("{}", network.explorer_url(signature));
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // Setup logging for the application.
+    utils::setup_logger();
+
+    // Parse command line arguments.
+    let cli = Cli::parse();
+
+    match &cli.command {
+        Command::Prove(args) => prove(args).await,
+        Command::Verify(args) => {
+            let fixture = load_proof(&args.common);
+            if let Err(error) = verify_proof_fixture(&fixture, args.common.vk_version.groth16_vk())
+            {
+                eprintln!("{} doesn't verify: {error}", args.common.proof.display());
+                std::process::exit(1);
+            }
+            println!(
+                "{} verifies, for program vkey hash 0x{}",
+                args.common.proof.display(),
+                hex::encode(fixture.sp1_vkey_hash)
+            );
+        }
+        Command::Submit(args) => submit(args).await,
+        Command::Inspect(args) => Inspection::new(&load_proof(&args.common)).print(),
+    }
 }
@@ -0,0 +1,59 @@
+//! Exercises the example program without a proof, through the test-only mock verifier of
+//! `sp1-solana`.
+//!
+//! The mock verifier skips the pairing check alone: the proof's selector is checked, and the
+//! public values are hashed into the committed values digest the proof is checked against, as
+//! with a real proof. This tests the public values and account logic around verification. It is
+//! enabled by the `mock-verifier` feature of this crate, and must never be used against a
+//! deployed program.
+
+use crate::send;
+use fibonacci_verifier_contract::{
+    instruction::{assert_recorded, verify_and_nullify, verify_and_store},
+    SP1Groth16Proof, FIBONACCI_VKEY_HASH,
+};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{pubkey::Pubkey, signer::Signer, transaction::TransactionError};
+use sp1_solana::{hash_public_inputs, SP1ProofFixture};
+
+/// A fake proof of `sp1_public_inputs`, in SP1's standard format, built from
+/// [`SP1ProofFixture::mock`]: it passes the mock verifier, and only it.
+pub fn mock_proof(sp1_public_inputs: Vec<u8>) -> SP1Groth16Proof {
+    let fixture = SP1ProofFixture::mock(&FIBONACCI_VKEY_HASH, &sp1_public_inputs);
+    SP1Groth16Proof {
+        proof: [&fixture.groth16_vkey_hash[..], &fixture.proof].concat(),
+        sp1_public_inputs: fixture.sp1_public_inputs,
+    }
+}
+
+/// Stores, checks and nullifies a mock proof of `sp1_public_inputs` with the mock verifier on
+/// `solana-program-test`.
+pub async fn run_mock_submit(sp1_public_inputs: Vec<u8>) -> Result<(), TransactionError> {
+    let program_id = Pubkey::new_unique();
+    let mut context = ProgramTest::new(
+        "fibonacci_verifier_contract",
+        program_id,
+        processor!(fibonacci_verifier_contract::process_instruction),
+    )
+    .start_with_context()
+    .await;
+    let payer = context.payer.pubkey();
+
+    let committed_values_digest = hash_public_inputs(&sp1_public_inputs);
+    let groth16_proof = mock_proof(sp1_public_inputs);
+    send(
+        &mut context,
+        &[
+            verify_and_store(&program_id, &payer, groth16_proof.clone()),
+            assert_recorded(&program_id, committed_values_digest),
+        ],
+        &[],
+    )
+    .await?;
+    send(
+        &mut context,
+        &[verify_and_nullify(&program_id, &payer, groth16_proof)],
+        &[],
+    )
+    .await
+}
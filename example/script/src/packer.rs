@@ -0,0 +1,302 @@
+//! Packs verify instructions, in order, into as few transactions as the cluster's limits allow.
+//!
+//! Where [`crate::batch`] merges proofs into `VerifyBatch` instructions, [`TransactionPacker`]
+//! keeps each instruction as is, e.g. a mix of `Verify` and `VerifyFixture` instructions drained
+//! from a queue. Each transaction starts with a compute unit limit covering its instructions, and
+//! is checked against the size, compute unit and account limits as it will be sent, rather than
+//! estimated.
+
+use fibonacci_verifier_contract::{
+    instruction::{verify, verify_fixture},
+    SP1Groth16Proof,
+};
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, CompileError, VersionedMessage},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{signers::Signers, SignerError},
+    transaction::VersionedTransaction,
+};
+use sp1_solana::SP1ProofFixture;
+use std::ops::Range;
+
+/// The most compute units a transaction can request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+/// The most accounts a transaction can lock, including those loaded from lookup tables.
+pub const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+
+/// The compute units of the `SetComputeUnitLimit` instruction starting each transaction.
+pub const COMPUTE_BUDGET_UNITS: u64 = 150;
+
+/// An instruction to pack, with the compute units it consumes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackItem {
+    pub instruction: Instruction,
+    /// The compute units the instruction consumes, e.g. as measured with
+    /// [`crate::cu::measure_units_consumed`].
+    pub compute_units: u64,
+}
+
+impl PackItem {
+    pub fn new(instruction: Instruction, compute_units: u64) -> Self {
+        Self {
+            instruction,
+            compute_units,
+        }
+    }
+
+    /// A `Verify` instruction of `groth16_proof`.
+    pub fn verify(program_id: &Pubkey, groth16_proof: SP1Groth16Proof, compute_units: u64) -> Self {
+        Self::new(verify(program_id, groth16_proof), compute_units)
+    }
+
+    /// A `VerifyFixture` instruction of `fixture`.
+    pub fn verify_fixture(
+        program_id: &Pubkey,
+        fixture: SP1ProofFixture,
+        compute_units: u64,
+    ) -> Self {
+        Self::new(verify_fixture(program_id, fixture), compute_units)
+    }
+}
+
+/// The limits of each packed transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackLimits {
+    /// The largest serialized transaction, signatures included.
+    pub max_size: usize,
+    /// The most compute units a transaction requests, those of its compute unit limit included.
+    pub max_compute_units: u64,
+    /// The most accounts a transaction references, including those loaded from lookup tables.
+    pub max_accounts: usize,
+}
+
+impl Default for PackLimits {
+    fn default() -> Self {
+        Self {
+            max_size: PACKET_DATA_SIZE,
+            max_compute_units: MAX_COMPUTE_UNIT_LIMIT,
+            max_accounts: MAX_TX_ACCOUNT_LOCKS,
+        }
+    }
+}
+
+impl PackLimits {
+    /// Whether a transaction using `utilization` is within the limits.
+    pub fn allow(&self, utilization: &Utilization) -> bool {
+        utilization.size <= self.max_size
+            && utilization.compute_units <= self.max_compute_units
+            && utilization.accounts <= self.max_accounts
+    }
+}
+
+/// How much of each limit a packed transaction uses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Utilization {
+    /// The indices of the packed items the transaction holds.
+    pub items: Range<usize>,
+    /// The serialized size of the signed transaction.
+    pub size: usize,
+    /// The compute units the transaction requests.
+    pub compute_units: u64,
+    /// The accounts the transaction references, including those loaded from lookup tables.
+    pub accounts: usize,
+}
+
+impl std::fmt::Display for Utilization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "items {}..{}: {} bytes, {} compute units, {} accounts",
+            self.items.start, self.items.end, self.size, self.compute_units, self.accounts
+        )
+    }
+}
+
+/// A transaction of packed items, with placeholder signatures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedTransaction {
+    /// The transaction, whose default signatures take the space of the real ones.
+    pub transaction: VersionedTransaction,
+    pub utilization: Utilization,
+}
+
+impl PackedTransaction {
+    /// Signs the transaction with the payer and any other signer of its instructions.
+    pub fn sign<T: Signers + ?Sized>(
+        self,
+        signers: &T,
+    ) -> Result<VersionedTransaction, SignerError> {
+        VersionedTransaction::try_new(self.transaction.message, signers)
+    }
+}
+
+/// An item that fits in no transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackError {
+    /// The item at `index` exceeds a limit in a transaction of its own.
+    ExceedsLimits {
+        index: usize,
+        utilization: Utilization,
+    },
+    /// The message of a transaction holding only the item at `index` can't be compiled, e.g.
+    /// because it references more than 256 accounts.
+    Compile { index: usize, error: CompileError },
+}
+
+impl std::fmt::Display for PackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ExceedsLimits { index, utilization } => write!(
+                f,
+                "item {index} exceeds the transaction limits on its own ({utilization})"
+            ),
+            Self::Compile { index, error } => {
+                write!(f, "item {index} can't be compiled into a message: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+/// Packs instructions into versioned transactions paid by one payer.
+#[derive(Clone, Debug)]
+pub struct TransactionPacker {
+    payer: Pubkey,
+    limits: PackLimits,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl TransactionPacker {
+    pub fn new(payer: Pubkey, limits: PackLimits) -> Self {
+        Self {
+            payer,
+            limits,
+            lookup_tables: Vec::new(),
+        }
+    }
+
+    /// Loads the accounts of `lookup_table` from it, rather than listing them in each transaction.
+    ///
+    /// Program ids and signers are never loaded from a lookup table.
+    pub fn with_lookup_table(mut self, lookup_table: AddressLookupTableAccount) -> Self {
+        self.lookup_tables.push(lookup_table);
+        self
+    }
+
+    pub fn limits(&self) -> &PackLimits {
+        &self.limits
+    }
+
+    /// Packs `items`, in order, into transactions with `recent_blockhash`.
+    ///
+    /// Each transaction takes items until the next one would exceed a limit, which then starts the
+    /// next transaction. Each item is in exactly one transaction. Fails if an item exceeds a limit
+    /// on its own.
+    pub fn pack(
+        &self,
+        items: impl IntoIterator<Item = PackItem>,
+        recent_blockhash: Hash,
+    ) -> Result<Vec<PackedTransaction>, PackError> {
+        let mut packed = Vec::new();
+        let mut batch: Vec<PackItem> = Vec::new();
+        let mut start = 0;
+        let mut last_fit = None;
+        for (index, item) in items.into_iter().enumerate() {
+            batch.push(item);
+            if let Some(transaction) = self.try_build(start, &batch, recent_blockhash) {
+                last_fit = Some(transaction);
+                continue;
+            }
+
+            if batch.len() > 1 {
+                // The item overflows the current transaction, so it starts the next one.
+                let item = batch.pop().unwrap();
+                packed.extend(last_fit.take());
+                batch = vec![item];
+                start = index;
+                if let Some(transaction) = self.try_build(start, &batch, recent_blockhash) {
+                    last_fit = Some(transaction);
+                    continue;
+                }
+            }
+            return Err(match self.build(start, &batch, recent_blockhash) {
+                Ok(transaction) => PackError::ExceedsLimits {
+                    index,
+                    utilization: transaction.utilization,
+                },
+                Err(error) => PackError::Compile { index, error },
+            });
+        }
+        packed.extend(last_fit);
+
+        Ok(packed)
+    }
+
+    /// The transaction holding `items`, if it is within the limits.
+    fn try_build(
+        &self,
+        start: usize,
+        items: &[PackItem],
+        recent_blockhash: Hash,
+    ) -> Option<PackedTransaction> {
+        self.build(start, items, recent_blockhash)
+            .ok()
+            .filter(|transaction| self.limits.allow(&transaction.utilization))
+    }
+
+    /// The transaction holding `items`, the packed items from `start` on.
+    fn build(
+        &self,
+        start: usize,
+        items: &[PackItem],
+        recent_blockhash: Hash,
+    ) -> Result<PackedTransaction, CompileError> {
+        let compute_units = items
+            .iter()
+            .map(|item| item.compute_units)
+            .fold(COMPUTE_BUDGET_UNITS, u64::saturating_add);
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+            u32::try_from(compute_units).unwrap_or(u32::MAX),
+        )];
+        instructions.extend(items.iter().map(|item| item.instruction.clone()));
+
+        let message = v0::Message::try_compile(
+            &self.payer,
+            &instructions,
+            &self.lookup_tables,
+            recent_blockhash,
+        )?;
+        let accounts = message.account_keys.len()
+            + message
+                .address_table_lookups
+                .iter()
+                .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+                .sum::<usize>();
+        let transaction = VersionedTransaction {
+            signatures: vec![
+                Signature::default();
+                usize::from(message.header.num_required_signatures)
+            ],
+            message: VersionedMessage::V0(message),
+        };
+        let size = bincode::serialized_size(&transaction).unwrap() as usize;
+
+        Ok(PackedTransaction {
+            transaction,
+            utilization: Utilization {
+                items: start..start + items.len(),
+                size,
+                compute_units,
+                accounts,
+            },
+        })
+    }
+}
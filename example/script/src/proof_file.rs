@@ -0,0 +1,228 @@
+//! Loads the proof files read by the script's `verify`, `submit` and `inspect` subcommands.
+//!
+//! Every kind of file the script writes is accepted, and normalized to an [`SP1ProofFixture`]:
+//!
+//! - an `SP1ProofWithPublicValues`, as saved by `prove`;
+//! - a JSON proof, in the schema of [`crate::proof_json`];
+//! - a fixture saved with `prove --save-fixture`, in any of its [`FixtureFormat`]s.
+//!
+//! The kind is told apart by the file extension, `.json` or `.hex`, and otherwise by trying the
+//! Borsh fixture encoding before the `SP1ProofWithPublicValues` one.
+
+use crate::{
+    fixture::{decode_fixture, FixtureFormat},
+    proof_json::ProofJson,
+    vkey::{check_vkey_hash, vkey_hash_from_public_input},
+};
+use clap::ValueEnum;
+use fibonacci_verifier_contract::{instruction::verify, SP1Groth16Proof, FIBONACCI_VKEY_HASH};
+use solana_sdk::pubkey::Pubkey;
+use sp1_sdk::{SP1Proof, SP1ProofWithPublicValues};
+use sp1_solana::{
+    diagnose_proof, ProofMismatch, SP1ProofFixture, GROTH16_VK_2_0_0_BYTES, GROTH16_VK_2_0_0_HASH,
+    GROTH16_VK_3_0_0_BYTES, GROTH16_VK_3_0_0_HASH, GROTH16_VK_3_0_0_RC4_BYTES,
+    GROTH16_VK_3_0_0_RC4_HASH,
+};
+use std::path::Path;
+
+/// A Groth16 verification key shipped with `sp1-solana`.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VkVersion {
+    #[default]
+    #[value(name = "v3.0.0")]
+    V3,
+    #[value(name = "v3.0.0rc4")]
+    V3Rc4,
+    #[value(name = "v2.0.0")]
+    V2,
+}
+
+impl VkVersion {
+    const ALL: [Self; 3] = [Self::V3, Self::V3Rc4, Self::V2];
+
+    /// The Groth16 verification key.
+    pub fn groth16_vk(self) -> &'static [u8] {
+        match self {
+            Self::V3 => GROTH16_VK_3_0_0_BYTES,
+            Self::V3Rc4 => GROTH16_VK_3_0_0_RC4_BYTES,
+            Self::V2 => GROTH16_VK_2_0_0_BYTES,
+        }
+    }
+
+    /// The 4-byte selector prefixed to proofs for the key.
+    pub fn groth16_vkey_hash(self) -> [u8; 4] {
+        match self {
+            Self::V3 => GROTH16_VK_3_0_0_HASH,
+            Self::V3Rc4 => GROTH16_VK_3_0_0_RC4_HASH,
+            Self::V2 => GROTH16_VK_2_0_0_HASH,
+        }
+    }
+
+    /// The version whose selector is `groth16_vkey_hash`, if any.
+    pub fn from_groth16_vkey_hash(groth16_vkey_hash: &[u8; 4]) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|version| version.groth16_vkey_hash() == *groth16_vkey_hash)
+    }
+}
+
+/// Loads a proof file of any kind the script writes. The proof isn't verified.
+pub fn load_proof_file(path: &Path) -> Result<SP1ProofFixture, String> {
+    let bytes = std::fs::read(path)
+        .map_err(|error| format!("failed to read {}: {error}", path.display()))?;
+    let invalid = |error: String| format!("invalid proof file {}: {error}", path.display());
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => decode_fixture(&bytes, FixtureFormat::Json).or_else(|_| {
+            let loaded = ProofJson::load(path)?.decode()?;
+            fixture_from_groth16_proof(&loaded.groth16_proof, &loaded.sp1_vkey_hash)
+                .map_err(invalid)
+        }),
+        Some("hex") => decode_fixture(&bytes, FixtureFormat::Hex).map_err(invalid),
+        _ => decode_fixture(&bytes, FixtureFormat::Bin).or_else(|_| {
+            let proof =
+                SP1ProofWithPublicValues::load(path).map_err(|error| invalid(error.to_string()))?;
+            fixture_from_sp1_proof(&proof).map_err(invalid)
+        }),
+    }
+}
+
+/// Converts a Groth16 `SP1ProofWithPublicValues` to a fixture.
+pub fn fixture_from_sp1_proof(proof: &SP1ProofWithPublicValues) -> Result<SP1ProofFixture, String> {
+    let groth16_proof = match &proof.proof {
+        SP1Proof::Groth16(groth16_proof) => groth16_proof,
+        SP1Proof::Plonk(_) => {
+            return Err(
+                "a Plonk proof, which sp1-solana can't verify; prove with .groth16()".to_string(),
+            )
+        }
+        _ => return Err("not a Groth16 proof".to_string()),
+    };
+    // The first Groth16 public input is the vkey hash of the proven program.
+    let sp1_vkey_hash = vkey_hash_from_public_input(&groth16_proof.public_inputs[0])?;
+    let sp1_public_inputs = proof.public_values.to_vec();
+
+    // Mock proofs carry no Groth16 proof, so the mock verifier is sent a placeholder instead.
+    if groth16_proof.raw_proof.is_empty() {
+        #[cfg(feature = "mock-verifier")]
+        return fixture_from_groth16_proof(
+            &crate::mock::mock_proof(sp1_public_inputs),
+            &sp1_vkey_hash,
+        );
+        #[cfg(not(feature = "mock-verifier"))]
+        return Err("a mock proof, which needs the mock-verifier feature".to_string());
+    }
+
+    SP1ProofFixture::from_components(&proof.bytes(), &sp1_public_inputs, &sp1_vkey_hash)
+        .map_err(|error| error.to_string())
+}
+
+/// Converts a proof in SP1's standard format to a fixture.
+pub fn fixture_from_groth16_proof(
+    groth16_proof: &SP1Groth16Proof,
+    sp1_vkey_hash: &[u8; 32],
+) -> Result<SP1ProofFixture, String> {
+    SP1ProofFixture::from_components(
+        &groth16_proof.proof,
+        &groth16_proof.sp1_public_inputs,
+        sp1_vkey_hash,
+    )
+    .map_err(|error| error.to_string())
+}
+
+/// Converts a fixture back to the proof and public values sent to the example program.
+pub fn groth16_proof_from_fixture(fixture: &SP1ProofFixture) -> SP1Groth16Proof {
+    SP1Groth16Proof {
+        proof: [&fixture.groth16_vkey_hash[..], &fixture.proof[..]].concat(),
+        sp1_public_inputs: fixture.sp1_public_inputs.clone(),
+    }
+}
+
+/// Verifies `fixture` natively as the example program would, against `groth16_vk` and
+/// `FIBONACCI_VKEY_HASH`, explaining every mismatch on failure.
+///
+/// `submit` runs this before building the transaction, since the program only reports a failed
+/// verification as a bare error code.
+pub fn check_before_submit(fixture: &SP1ProofFixture, groth16_vk: &[u8]) -> Result<(), String> {
+    let mismatches = diagnose_proof(fixture, &FIBONACCI_VKEY_HASH, groth16_vk).err();
+    let Some(mismatches) = mismatches else {
+        return Ok(());
+    };
+    let explanations: Vec<String> = mismatches
+        .iter()
+        .map(|mismatch| match mismatch {
+            ProofMismatch::Sp1VkeyHash { proof, expected } => {
+                check_vkey_hash(proof, expected).unwrap_err()
+            }
+            ProofMismatch::Groth16VkeyHash { .. } => format!(
+                "{mismatch}\nThe proof was generated by another SP1 version than the program \
+                 verifies; prove it again."
+            ),
+            _ => mismatch.to_string(),
+        })
+        .collect();
+    Err(format!(
+        "the proof doesn't verify natively, so the program would reject it:\n\n{}",
+        explanations.join("\n\n")
+    ))
+}
+
+/// What `inspect` prints about a proof file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inspection {
+    /// The Groth16 verification key selector, and the version it names if known.
+    pub groth16_vkey_hash: [u8; 4],
+    pub vk_version: Option<VkVersion>,
+    pub sp1_vkey_hash: [u8; 32],
+    pub committed_values_digest: [u8; 32],
+    pub sp1_public_inputs: Vec<u8>,
+    /// The length of the proof in SP1's standard format, selector included.
+    pub proof_len: usize,
+    /// The length of the fixture's Borsh encoding.
+    pub fixture_len: usize,
+    /// The length of the example program's `Verify` instruction data.
+    pub instruction_data_len: usize,
+}
+
+impl Inspection {
+    pub fn new(fixture: &SP1ProofFixture) -> Self {
+        let groth16_proof = groth16_proof_from_fixture(fixture);
+        Self {
+            groth16_vkey_hash: fixture.groth16_vkey_hash,
+            vk_version: VkVersion::from_groth16_vkey_hash(&fixture.groth16_vkey_hash),
+            sp1_vkey_hash: fixture.sp1_vkey_hash,
+            committed_values_digest: fixture.committed_values_digest,
+            sp1_public_inputs: fixture.sp1_public_inputs.to_vec(),
+            proof_len: groth16_proof.proof.len(),
+            fixture_len: fixture.to_bytes().len(),
+            instruction_data_len: verify(&Pubkey::default(), groth16_proof).data.len(),
+        }
+    }
+
+    pub fn print(&self) {
+        let vk_version = match self
+            .vk_version
+            .and_then(|version| version.to_possible_value())
+        {
+            Some(version) => version.get_name().to_string(),
+            None => "unknown version".to_string(),
+        };
+        println!(
+            "Groth16 vkey hash: 0x{} ({vk_version})",
+            hex::encode(self.groth16_vkey_hash)
+        );
+        println!("Program vkey hash: 0x{}", hex::encode(self.sp1_vkey_hash));
+        println!(
+            "Committed values digest: 0x{}",
+            hex::encode(self.committed_values_digest)
+        );
+        println!("Public values: 0x{}", hex::encode(&self.sp1_public_inputs));
+        println!("Proof: {} bytes", self.proof_len);
+        println!("Public values: {} bytes", self.sp1_public_inputs.len());
+        println!("Fixture: {} bytes", self.fixture_len);
+        println!(
+            "Verify instruction data: {} bytes",
+            self.instruction_data_len
+        );
+    }
+}
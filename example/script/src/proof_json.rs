@@ -0,0 +1,111 @@
+//! Loads proofs from JSON, as emitted by provers that don't write `SP1ProofWithPublicValues`
+//! files.
+//!
+//! The schema, with every byte string `0x`-prefixed hex:
+//!
+//! ```json
+//! {
+//!   "vkey_hash": "0x00...",
+//!   "proof": "0x...",
+//!   "public_values": "0x...",
+//!   "public_inputs": ["<decimal>", "<decimal>"]
+//! }
+//! ```
+//!
+//! `vkey_hash` is the SP1 program vkey hash, from `vk.bytes32()`. `proof` is in SP1's standard
+//! format, i.e. the output of `SP1ProofWithPublicValues::bytes()`. `public_inputs` is optional: if
+//! present, it holds the two Groth16 public inputs, the vkey hash and the committed values digest,
+//! as decimal field elements, and must match the other fields. See `proofs/fibonacci_proof.json`.
+
+use fibonacci_verifier_contract::SP1Groth16Proof;
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use sp1_solana::{decode_sp1_vkey_hash, hash_public_inputs, verify_proof};
+use std::path::Path;
+
+/// A proof in the JSON schema of the module.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProofJson {
+    pub vkey_hash: String,
+    pub proof: String,
+    pub public_values: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_inputs: Option<[String; 2]>,
+}
+
+/// A proof loaded from JSON and verified natively.
+pub struct LoadedProof {
+    pub groth16_proof: SP1Groth16Proof,
+    pub sp1_vkey_hash: [u8; 32],
+}
+
+fn decode_hex(field: &str, hex: &str) -> Result<Vec<u8>, String> {
+    hex::decode(hex.strip_prefix("0x").unwrap_or(hex))
+        .map_err(|error| format!("invalid {field}: {error}"))
+}
+
+impl ProofJson {
+    /// Reads a proof from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let json = std::fs::read(path)
+            .map_err(|error| format!("failed to read {}: {error}", path.display()))?;
+        serde_json::from_slice(&json)
+            .map_err(|error| format!("invalid proof JSON {}: {error}", path.display()))
+    }
+
+    /// Decodes the proof, checks the public inputs if present, and verifies the proof natively
+    /// against `groth16_vk`.
+    pub fn verify(&self, groth16_vk: &[u8]) -> Result<LoadedProof, String> {
+        let loaded = self.decode()?;
+        verify_proof(
+            &loaded.groth16_proof.proof,
+            &loaded.groth16_proof.sp1_public_inputs,
+            &self.vkey_hash,
+            groth16_vk,
+        )
+        .map_err(|error| format!("the proof doesn't verify: {error}"))?;
+        Ok(loaded)
+    }
+
+    /// Decodes the proof and checks the public inputs if present, without verifying the proof.
+    pub fn decode(&self) -> Result<LoadedProof, String> {
+        if !self.vkey_hash.starts_with("0x") {
+            return Err("vkey_hash must be 0x-prefixed".to_string());
+        }
+        let sp1_vkey_hash = decode_sp1_vkey_hash(&self.vkey_hash)
+            .map_err(|error| format!("invalid vkey_hash: {error}"))?;
+        let proof = decode_hex("proof", &self.proof)?;
+        let sp1_public_inputs = decode_hex("public_values", &self.public_values)?;
+
+        if let Some([vkey_hash, committed_values_digest]) = &self.public_inputs {
+            let expected = [
+                BigUint::from_bytes_be(&sp1_vkey_hash),
+                BigUint::from_bytes_be(&hash_public_inputs(&sp1_public_inputs)),
+            ];
+            for (name, decimal, expected) in [
+                ("vkey hash", vkey_hash, &expected[0]),
+                (
+                    "committed values digest",
+                    committed_values_digest,
+                    &expected[1],
+                ),
+            ] {
+                let input: BigUint = decimal
+                    .parse()
+                    .map_err(|error| format!("invalid {name} public input: {error}"))?;
+                if input != *expected {
+                    return Err(format!("the {name} public input doesn't match the proof"));
+                }
+            }
+        }
+
+        Ok(LoadedProof {
+            groth16_proof: SP1Groth16Proof {
+                proof,
+                sp1_public_inputs: sp1_public_inputs.into(),
+            },
+            sp1_vkey_hash,
+        })
+    }
+}
@@ -0,0 +1,113 @@
+//! Chooses the SP1 prover the example script proves with.
+
+use sp1_sdk::{
+    HashableKey, NetworkProver, ProverClient, SP1Proof, SP1ProofWithPublicValues, SP1ProvingKey,
+    SP1Stdin, SP1VerifyingKey,
+};
+use std::{path::Path, time::Duration};
+
+use crate::vkey::vkey_hash_from_public_input;
+
+/// The environment variable holding the prover network private key by default.
+pub const DEFAULT_PRIVATE_KEY_ENV: &str = "SP1_PRIVATE_KEY";
+
+/// The prover of `--prover`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProverKind {
+    /// Proves locally on the CPU. Groth16 wrapping needs Docker.
+    #[default]
+    Cpu,
+    /// Proves locally on a GPU. Needs the `cuda` feature of this crate.
+    Cuda,
+    /// Proves on the SP1 prover network, authenticated with a private key.
+    Network,
+    /// Executes the program and returns a mock proof, which only verifies with the example
+    /// program's test-only `mock-verifier` feature.
+    Mock,
+}
+
+/// How to generate a proof.
+#[derive(Clone, Debug)]
+pub struct ProverOptions {
+    pub kind: ProverKind,
+    /// The environment variable holding the prover network private key.
+    pub private_key_env: String,
+    /// Skips executing the program before proving it.
+    pub skip_simulation: bool,
+    /// How long to wait for the prover network.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for ProverOptions {
+    fn default() -> Self {
+        Self {
+            kind: ProverKind::default(),
+            private_key_env: DEFAULT_PRIVATE_KEY_ENV.to_string(),
+            skip_simulation: false,
+            timeout: None,
+        }
+    }
+}
+
+impl ProverOptions {
+    /// Builds the prover client, failing if the network private key is missing.
+    pub fn client(&self) -> Result<ProverClient, String> {
+        match self.kind {
+            ProverKind::Cpu => Ok(ProverClient::local()),
+            ProverKind::Mock => Ok(ProverClient::mock()),
+            #[cfg(feature = "cuda")]
+            ProverKind::Cuda => Ok(ProverClient::cuda()),
+            #[cfg(not(feature = "cuda"))]
+            ProverKind::Cuda => {
+                Err("--prover cuda needs the cuda feature: cargo run --features cuda".to_string())
+            }
+            ProverKind::Network => {
+                let private_key = std::env::var(&self.private_key_env)
+                    .ok()
+                    .filter(|key| !key.is_empty())
+                    .ok_or_else(|| {
+                        format!(
+                            "--prover network needs a prover network private key in ${}; set it, \
+                             or name another variable with --private-key-env",
+                            self.private_key_env
+                        )
+                    })?;
+                Ok(ProverClient {
+                    prover: Box::new(NetworkProver::new_from_key(&private_key)),
+                })
+            }
+        }
+    }
+
+    /// Generates a Groth16 proof of the program of `pk` on `stdin`.
+    pub fn prove_groth16(
+        &self,
+        client: &ProverClient,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1ProofWithPublicValues, String> {
+        let mut action = client
+            .prove(pk, stdin)
+            .groth16()
+            .skip_simulation(self.skip_simulation);
+        if let Some(timeout) = self.timeout {
+            action = action.timeout(timeout);
+        }
+        action
+            .run()
+            .map_err(|error| format!("Groth16 proof generation failed: {error}"))
+    }
+}
+
+/// Loads the proof cached at `path` if it's a Groth16 proof of the program of `vk`.
+pub fn load_cached_proof(
+    path: impl AsRef<Path>,
+    vk: &SP1VerifyingKey,
+) -> Option<SP1ProofWithPublicValues> {
+    let proof = SP1ProofWithPublicValues::load(path).ok()?;
+    let SP1Proof::Groth16(groth16_proof) = &proof.proof else {
+        return None;
+    };
+    let cached_vkey_hash = vkey_hash_from_public_input(&groth16_proof.public_inputs[0]).ok()?;
+    (hex::encode(cached_vkey_hash) == vk.bytes32().trim_start_matches("0x")).then_some(proof)
+}
@@ -0,0 +1,78 @@
+//! The cost of a verify transaction, printed by the example script.
+
+use serde::{Deserialize, Serialize};
+
+/// The base fee of each transaction signature, in lamports.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// The default compute unit price used to estimate priority fees, in micro-lamports.
+pub const DEFAULT_COMPUTE_UNIT_PRICE: u64 = 1_000;
+
+/// The compute units, fees and logs of a verify transaction.
+///
+/// With `--json`, the script prints this struct as JSON, so fields must only be appended.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CostReport {
+    /// The signature of the transaction, if it was sent to a cluster.
+    pub signature: Option<String>,
+    /// The compute units consumed by the transaction.
+    pub units_consumed: u64,
+    /// The compute unit price the priority fee is estimated at, in micro-lamports.
+    pub compute_unit_price: u64,
+    /// The base fee of the transaction's signatures, in lamports.
+    pub base_fee_lamports: u64,
+    /// The priority fee of the transaction, had it requested exactly the units it consumed.
+    pub priority_fee_lamports: u64,
+    /// The log messages of the transaction.
+    pub logs: Vec<String>,
+}
+
+impl CostReport {
+    /// Estimates the fees of a transaction with `num_signatures` signatures that consumed
+    /// `units_consumed` compute units.
+    pub fn new(
+        units_consumed: u64,
+        logs: Vec<String>,
+        num_signatures: u64,
+        compute_unit_price: u64,
+    ) -> Self {
+        Self {
+            signature: None,
+            units_consumed,
+            compute_unit_price,
+            base_fee_lamports: num_signatures * LAMPORTS_PER_SIGNATURE,
+            priority_fee_lamports: priority_fee(units_consumed, compute_unit_price),
+            logs,
+        }
+    }
+
+    /// The base fee plus the priority fee, in lamports.
+    pub fn total_fee_lamports(&self) -> u64 {
+        self.base_fee_lamports + self.priority_fee_lamports
+    }
+
+    /// Prints the report for a human reader.
+    pub fn print(&self) {
+        if let Some(signature) = &self.signature {
+            println!("Signature: {signature}");
+        }
+        println!("Compute units consumed: {}", self.units_consumed);
+        println!(
+            "Estimated fee: {} lamports ({} base + {} priority at {} micro-lamports per unit)",
+            self.total_fee_lamports(),
+            self.base_fee_lamports,
+            self.priority_fee_lamports,
+            self.compute_unit_price,
+        );
+        println!("Logs:");
+        for log in &self.logs {
+            println!("  {log}");
+        }
+    }
+}
+
+/// The priority fee of `units` compute units at `compute_unit_price` micro-lamports each, in
+/// lamports, rounded up like the runtime does.
+pub fn priority_fee(units: u64, compute_unit_price: u64) -> u64 {
+    (units as u128 * compute_unit_price as u128).div_ceil(1_000_000) as u64
+}
@@ -0,0 +1,316 @@
+//! Submits verify transactions to a live cluster over RPC, instead of `solana-program-test`.
+
+use crate::report::CostReport;
+use fibonacci_verifier_contract::{instruction::verify, SP1Groth16Proof};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::RpcSendTransactionConfig,
+    rpc_custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+    rpc_request::{RpcError, RpcResponseErrorData},
+};
+use solana_sdk::{
+    bpf_loader_upgradeable,
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use std::{str::FromStr, time::Duration};
+
+/// The compute unit limit requested for a verify transaction, with headroom over the cost of a
+/// Groth16 verification.
+pub const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 500_000;
+
+/// The cluster to submit to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Network {
+    /// A `solana-test-validator` on its default port.
+    Localnet,
+    Devnet,
+    Mainnet,
+    /// Any other RPC endpoint.
+    Custom(String),
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(network: &str) -> Result<Self, Self::Err> {
+        match network {
+            "localnet" | "localhost" => Ok(Self::Localnet),
+            "devnet" => Ok(Self::Devnet),
+            "mainnet" | "mainnet-beta" => Ok(Self::Mainnet),
+            url if url.starts_with("http://") || url.starts_with("https://") => {
+                Ok(Self::Custom(url.to_string()))
+            }
+            _ => Err(format!(
+                "unknown network {network}, expected localnet, devnet, mainnet or an RPC URL"
+            )),
+        }
+    }
+}
+
+impl Network {
+    /// The RPC URL of the cluster.
+    pub fn url(&self) -> &str {
+        match self {
+            Self::Localnet => "http://127.0.0.1:8899",
+            Self::Devnet => "https://api.devnet.solana.com",
+            Self::Mainnet => "https://api.mainnet-beta.solana.com",
+            Self::Custom(url) => url,
+        }
+    }
+
+    /// The Solana Explorer link of a transaction on the cluster.
+    pub fn explorer_url(&self, signature: &impl std::fmt::Display) -> String {
+        let base = format!("https://explorer.solana.com/tx/{signature}");
+        match self {
+            Self::Mainnet => base,
+            Self::Devnet => format!("{base}?cluster=devnet"),
+            Self::Localnet | Self::Custom(_) => {
+                format!("{base}?cluster=custom&customUrl={}", self.url())
+            }
+        }
+    }
+}
+
+/// Checks that `program_id` is a deployed, executable program on the cluster.
+pub async fn check_program_deployed(client: &RpcClient, program_id: &Pubkey) -> Result<(), String> {
+    let account = client
+        .get_account_with_commitment(program_id, CommitmentConfig::confirmed())
+        .await
+        .map_err(|error| format!("failed to fetch program {program_id}: {error}"))?
+        .value
+        .ok_or_else(|| format!("program {program_id} is not deployed"))?;
+    if !account.executable || account.owner != bpf_loader_upgradeable::ID {
+        return Err(format!(
+            "account {program_id} is not an upgradeable program"
+        ));
+    }
+    Ok(())
+}
+
+/// How [`submit_with_retries`] retries a transaction that fails for a transient reason.
+///
+/// Only errors that a fresh blockhash or a later attempt can fix are retried: an expired
+/// blockhash, or an RPC node that is behind or unreachable. A transaction the program rejects is
+/// returned at once.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// The number of times the transaction is sent, including the first.
+    pub max_attempts: u32,
+    /// The delay before the first retry, doubled after each one.
+    pub initial_backoff: Duration,
+    /// The longest delay between two attempts.
+    pub max_backoff: Duration,
+    /// How often the status of a sent transaction is polled.
+    pub poll_interval: Duration,
+    /// Sends the transaction without a preflight simulation, so that a failing transaction lands
+    /// and pays its fee instead of being rejected by the RPC node.
+    pub skip_preflight: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            poll_interval: Duration::from_millis(500),
+            skip_preflight: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before the `retry`th retry, starting at 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1 << retry.saturating_sub(1).min(31))
+            .min(self.max_backoff)
+    }
+}
+
+/// An error from [`submit_with_retries`].
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The transaction was rejected in preflight or failed on chain.
+    Failed {
+        error: TransactionError,
+        /// The program logs of the preflight simulation, empty if the transaction failed on
+        /// chain.
+        logs: Vec<String>,
+    },
+    /// An RPC error that is not retried, or the last transient error once every attempt failed.
+    Rpc(ClientError),
+}
+
+impl SubmitError {
+    /// The custom error code returned by the failing program, such as a `VerifierError`.
+    pub fn custom_error_code(&self) -> Option<u32> {
+        match self {
+            Self::Failed {
+                error: TransactionError::InstructionError(_, InstructionError::Custom(code)),
+                ..
+            } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Failed { error, logs } => {
+                write!(f, "transaction failed: {error}")?;
+                if let Some(code) = self.custom_error_code() {
+                    write!(f, " (custom program error {code:#x})")?;
+                }
+                for log in logs {
+                    write!(f, "\n  {log}")?;
+                }
+                Ok(())
+            }
+            Self::Rpc(error) => write!(f, "RPC error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+impl From<ClientError> for SubmitError {
+    fn from(error: ClientError) -> Self {
+        Self::Rpc(error)
+    }
+}
+
+/// Whether a failed send is worth retrying with a fresh blockhash, or the transaction failed.
+fn classify(error: ClientError) -> Result<ClientError, SubmitError> {
+    match error.kind() {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => Ok(error),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, data, .. }) => match data {
+            RpcResponseErrorData::SendTransactionPreflightFailure(simulation) => {
+                match &simulation.err {
+                    Some(TransactionError::BlockhashNotFound) | None => Ok(error),
+                    Some(transaction_error) => Err(SubmitError::Failed {
+                        error: transaction_error.clone(),
+                        logs: simulation.logs.clone().unwrap_or_default(),
+                    }),
+                }
+            }
+            RpcResponseErrorData::NodeUnhealthy { .. } => Ok(error),
+            _ if *code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY => Ok(error),
+            _ => Err(SubmitError::Rpc(error)),
+        },
+        ClientErrorKind::TransactionError(TransactionError::BlockhashNotFound) => Ok(error),
+        ClientErrorKind::TransactionError(transaction_error) => Err(SubmitError::Failed {
+            error: transaction_error.clone(),
+            logs: vec![],
+        }),
+        _ => Err(SubmitError::Rpc(error)),
+    }
+}
+
+/// Sends the transaction built by `build_transaction` and waits for its confirmation, retrying
+/// transient failures under `policy`.
+///
+/// Each attempt fetches a new blockhash and rebuilds the transaction with it. A sent transaction
+/// is only given up on once its blockhash has expired, so an earlier attempt can never land after
+/// a later one was sent.
+pub async fn submit_with_retries(
+    client: &RpcClient,
+    build_transaction: impl Fn(Hash) -> Transaction,
+    policy: &RetryPolicy,
+) -> Result<Signature, SubmitError> {
+    let config = RpcSendTransactionConfig {
+        skip_preflight: policy.skip_preflight,
+        preflight_commitment: Some(client.commitment().commitment),
+        ..RpcSendTransactionConfig::default()
+    };
+    let mut attempt = 1;
+    loop {
+        let error = match send_and_confirm(client, &build_transaction, &config, policy).await {
+            Ok(signature) => return Ok(signature),
+            Err(error) => classify(error)?,
+        };
+        if attempt >= policy.max_attempts {
+            return Err(SubmitError::Rpc(error));
+        }
+        eprintln!("attempt {attempt} failed, retrying: {error}");
+        tokio::time::sleep(policy.backoff(attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// Sends one attempt of [`submit_with_retries`], polling its status until it is confirmed or its
+/// blockhash expires.
+async fn send_and_confirm(
+    client: &RpcClient,
+    build_transaction: &impl Fn(Hash) -> Transaction,
+    config: &RpcSendTransactionConfig,
+    policy: &RetryPolicy,
+) -> ClientResult<Signature> {
+    let blockhash = client.get_latest_blockhash().await?;
+    let transaction = build_transaction(blockhash);
+    let signature = client
+        .send_transaction_with_config(&transaction, *config)
+        .await?;
+    loop {
+        if let Some(result) = client.get_signature_status(&signature).await? {
+            return result.map(|()| signature).map_err(Into::into);
+        }
+        if !client
+            .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+            .await?
+        {
+            return Err(TransactionError::BlockhashNotFound.into());
+        }
+        tokio::time::sleep(policy.poll_interval).await;
+    }
+}
+
+/// Sends a `Verify` instruction of `groth16_proof`, preceded by a compute unit limit, and waits
+/// for its confirmation, retrying transient failures under `policy`.
+///
+/// The transaction is simulated first, to report the compute units it consumes and its logs. Its
+/// priority fee is estimated at `compute_unit_price` micro-lamports per unit.
+pub async fn send_verify_transaction(
+    client: &RpcClient,
+    payer: &dyn Signer,
+    program_id: &Pubkey,
+    groth16_proof: SP1Groth16Proof,
+    compute_unit_limit: u32,
+    compute_unit_price: u64,
+    policy: &RetryPolicy,
+) -> Result<CostReport, SubmitError> {
+    let instructions = [
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+        verify(program_id, groth16_proof),
+    ];
+    let build_transaction = |recent_blockhash| {
+        Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[payer],
+            recent_blockhash,
+        )
+    };
+
+    let transaction = build_transaction(client.get_latest_blockhash().await?);
+    let simulation = client.simulate_transaction(&transaction).await?.value;
+    let mut report = CostReport::new(
+        simulation.units_consumed.unwrap_or_default(),
+        simulation.logs.unwrap_or_default(),
+        transaction.signatures.len() as u64,
+        compute_unit_price,
+    );
+
+    let signature = submit_with_retries(client, build_transaction, policy).await?;
+    report.signature = Some(signature.to_string());
+    Ok(report)
+}
@@ -0,0 +1,184 @@
+//! An HTTP verification oracle, for services that would rather `curl` a proof than link the
+//! verifier.
+//!
+//! - `POST /verify` takes a proof in the JSON schema of [`crate::proof_json`] and answers with a
+//!   [`VerifyResponse`]: whether the proof verifies and, if not, the [`FailureStage`] reported by
+//!   [`diagnose_proof`].
+//! - `GET /healthz` answers `ok`.
+//!
+//! The Groth16 verification key is decompressed once at startup and shared by every request, and
+//! verification runs on tokio's blocking thread pool, so requests are verified concurrently.
+
+use crate::{
+    proof_file::{fixture_from_groth16_proof, VkVersion},
+    proof_json::ProofJson,
+};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sp1_solana::{
+    diagnose_proof, negate_proof_a, verify_groth16_prenegated, Error, ProofMismatch,
+    SP1ProofFixture, VerificationKey, VkView,
+};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// The check a proof failed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureStage {
+    /// The request isn't a valid proof JSON.
+    Decode,
+    /// The proof is for another Groth16 verification key.
+    Groth16VkeyHash,
+    /// The proof is of another SP1 program.
+    Sp1VkeyHash,
+    /// The public values don't match the proof's committed values digest.
+    CommittedValuesDigest,
+    /// The pairing check fails.
+    Proof,
+}
+
+impl From<&ProofMismatch> for FailureStage {
+    fn from(mismatch: &ProofMismatch) -> Self {
+        match mismatch {
+            ProofMismatch::Groth16VkeyHash { .. } => Self::Groth16VkeyHash,
+            ProofMismatch::Sp1VkeyHash { .. } => Self::Sp1VkeyHash,
+            ProofMismatch::CommittedValuesDigest { .. } => Self::CommittedValuesDigest,
+            ProofMismatch::InvalidProof(_) => Self::Proof,
+        }
+    }
+}
+
+/// The answer to `POST /verify`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VerifyResponse {
+    pub verified: bool,
+    /// The Groth16 verification key the proof was checked against.
+    pub vk_version: String,
+    /// The SP1 program vkey hash, unless the request couldn't be decoded.
+    pub vkey_hash: Option<String>,
+    /// The committed values digest of the public values, unless the request couldn't be decoded.
+    pub committed_values_digest: Option<String>,
+    /// The first check the proof failed, if any.
+    pub stage: Option<FailureStage>,
+    /// Every check the proof failed, explained.
+    pub errors: Vec<String>,
+}
+
+/// Verifies proofs against one Groth16 verification key, decompressed once.
+#[derive(Debug)]
+pub struct Verifier {
+    vk_version: VkVersion,
+    vk: VerificationKey,
+}
+
+impl Verifier {
+    pub fn new(vk_version: VkVersion) -> Result<Self, Error> {
+        Ok(Self {
+            vk_version,
+            vk: VkView::new(vk_version.groth16_vk())?.to_verification_key()?,
+        })
+    }
+
+    /// Runs the checks of `verify_proof` against the decompressed key.
+    pub fn verify(&self, fixture: &SP1ProofFixture) -> Result<(), Error> {
+        if fixture.groth16_vkey_hash != self.vk_version.groth16_vkey_hash() {
+            return Err(Error::Groth16VkeyHashMismatch);
+        }
+        // The vkey hash public input is the vkey hash with its top byte cleared.
+        let mut vkey_hash_input = fixture.sp1_vkey_hash;
+        vkey_hash_input[0] = 0;
+        verify_groth16_prenegated(
+            &negate_proof_a(&fixture.proof)?,
+            &[vkey_hash_input, fixture.committed_values_digest],
+            &self.vk,
+        )
+    }
+
+    /// Answers a `POST /verify` request.
+    pub fn respond(&self, request: &ProofJson) -> (StatusCode, VerifyResponse) {
+        let mut response = VerifyResponse {
+            verified: false,
+            vk_version: self
+                .vk_version
+                .to_possible_value()
+                .unwrap()
+                .get_name()
+                .to_string(),
+            vkey_hash: None,
+            committed_values_digest: None,
+            stage: None,
+            errors: Vec::new(),
+        };
+        let fixture = match request.decode().and_then(|loaded| {
+            fixture_from_groth16_proof(&loaded.groth16_proof, &loaded.sp1_vkey_hash)
+        }) {
+            Ok(fixture) => fixture,
+            Err(error) => {
+                response.stage = Some(FailureStage::Decode);
+                response.errors.push(error);
+                return (StatusCode::BAD_REQUEST, response);
+            }
+        };
+        response.vkey_hash = Some(format!("0x{}", hex::encode(fixture.sp1_vkey_hash)));
+        response.committed_values_digest = Some(format!(
+            "0x{}",
+            hex::encode(fixture.committed_values_digest)
+        ));
+
+        match self.verify(&fixture) {
+            Ok(()) => response.verified = true,
+            Err(error) => match diagnose_proof(
+                &fixture,
+                &fixture.sp1_vkey_hash,
+                self.vk_version.groth16_vk(),
+            ) {
+                Err(mismatches) => {
+                    response.stage = mismatches.first().map(FailureStage::from);
+                    response.errors = mismatches.iter().map(ToString::to_string).collect();
+                }
+                // Both paths run the same checks, so this is only reached if they disagree.
+                Ok(()) => {
+                    response.stage = Some(FailureStage::Proof);
+                    response
+                        .errors
+                        .push(format!("the proof doesn't verify: {error}"));
+                }
+            },
+        }
+        (StatusCode::OK, response)
+    }
+}
+
+async fn verify(
+    State(verifier): State<Arc<Verifier>>,
+    Json(request): Json<ProofJson>,
+) -> (StatusCode, Json<VerifyResponse>) {
+    let (status, response) = tokio::task::spawn_blocking(move || verifier.respond(&request))
+        .await
+        .expect("verification panicked");
+    (status, Json(response))
+}
+
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// The routes of the service.
+pub fn router(verifier: Arc<Verifier>) -> Router {
+    Router::new()
+        .route("/verify", post(verify))
+        .route("/healthz", get(healthz))
+        .with_state(verifier)
+}
+
+/// Serves requests on `listener` until the task is dropped.
+pub async fn serve(listener: TcpListener, verifier: Verifier) -> std::io::Result<()> {
+    axum::serve(listener, router(Arc::new(verifier))).await
+}
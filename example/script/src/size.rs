@@ -0,0 +1,131 @@
+//! Binary size checks of the example programs.
+//!
+//! [`SizeReport::read`] reads the size of each section of a program's `.so`, as built by
+//! `cargo build-sbf`, and [`assert_size_within`] compares the file size against a JSON budget
+//! committed alongside the test. The budget also keeps the sections it was recorded with, so that
+//! a regression can be traced to code, read-only data or relocations.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+
+/// When set, [`assert_size_within`] records the measured sizes as the new budget instead of
+/// checking against the old one.
+pub const UPDATE_BUDGET_ENV: &str = "UPDATE_SIZE_BUDGET";
+
+/// The sizes of a program's `.so`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SizeReport {
+    pub file_size: u64,
+    /// The size of each section loaded with the program, e.g. `.text` and `.rodata`, by name.
+    pub sections: BTreeMap<String, u64>,
+}
+
+/// The committed size budget of a program's `.so`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SizeBudget {
+    pub max_file_size: u64,
+    /// The sections of the build the budget was recorded from, for comparison.
+    pub sections: BTreeMap<String, u64>,
+}
+
+impl SizeReport {
+    /// Reads the sizes of the `.so` at `path`.
+    pub fn read(path: &Path) -> Self {
+        let elf = std::fs::read(path).unwrap_or_else(|_| {
+            panic!("missing {}, build it with cargo build-sbf", path.display())
+        });
+        Self::from_elf(&elf).unwrap_or_else(|error| panic!("{}: {error}", path.display()))
+    }
+
+    /// Reads the section headers of a 64-bit little-endian ELF file, which `cargo build-sbf`
+    /// outputs.
+    pub fn from_elf(elf: &[u8]) -> Result<Self, String> {
+        /// Set on the sections loaded in memory.
+        const SHF_ALLOC: u64 = 0x2;
+
+        let read = |offset: usize, len: usize| {
+            elf.get(offset..offset + len)
+                .ok_or_else(|| format!("truncated at byte {offset}"))
+        };
+        let u16_at =
+            |offset| read(offset, 2).map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()));
+        let u32_at =
+            |offset| read(offset, 4).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
+        let u64_at =
+            |offset| read(offset, 8).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()));
+
+        if read(0, 6)? != b"\x7fELF\x02\x01" {
+            return Err("not a 64-bit little-endian ELF file".to_string());
+        }
+        let section_headers = u64_at(0x28)? as usize;
+        let section_header_len = u16_at(0x3a)? as usize;
+        let num_sections = u16_at(0x3c)? as usize;
+        let names_index = u16_at(0x3e)? as usize;
+        let section_header = |index: usize| section_headers + index * section_header_len;
+        let names = u64_at(section_header(names_index) + 0x18)? as usize;
+
+        let mut sections = BTreeMap::new();
+        // The first section header is always null.
+        for index in 1..num_sections {
+            let header = section_header(index);
+            if u64_at(header + 0x08)? & SHF_ALLOC == 0 {
+                continue;
+            }
+            let name_offset = names + u32_at(header)? as usize;
+            let name = elf
+                .get(name_offset..)
+                .and_then(|rest| rest.split(|&byte| byte == 0).next())
+                .ok_or_else(|| format!("section name out of bounds at byte {name_offset}"))?;
+            sections.insert(
+                String::from_utf8_lossy(name).into_owned(),
+                u64_at(header + 0x20)?,
+            );
+        }
+
+        Ok(Self {
+            file_size: elf.len() as u64,
+            sections,
+        })
+    }
+}
+
+/// Asserts that the `.so` at `so_path` is at most as large as the JSON budget at `budget`,
+/// returning its sizes.
+///
+/// Set [`UPDATE_BUDGET_ENV`] to write the measurement to `budget` instead.
+pub fn assert_size_within(so_path: &Path, budget: &Path) -> SizeReport {
+    let report = SizeReport::read(so_path);
+
+    if std::env::var_os(UPDATE_BUDGET_ENV).is_some() {
+        let json = serde_json::to_string_pretty(&SizeBudget {
+            max_file_size: report.file_size,
+            sections: report.sections.clone(),
+        })
+        .unwrap();
+        std::fs::write(budget, json + "\n").expect("failed to write size budget");
+        return report;
+    }
+
+    let json = std::fs::read_to_string(budget).unwrap_or_else(|_| {
+        panic!(
+            "missing size budget {}, rerun with {UPDATE_BUDGET_ENV}=1 to record it",
+            budget.display()
+        )
+    });
+    let expected: SizeBudget = serde_json::from_str(&json).expect("invalid size budget");
+
+    assert!(
+        report.file_size <= expected.max_file_size,
+        "{} is {} bytes, above its budget of {}. Sections, against the budget's: {:#?}",
+        so_path.display(),
+        report.file_size,
+        expected.max_file_size,
+        report
+            .sections
+            .iter()
+            .map(|(name, size)| (name, *size, expected.sections.get(name).copied()))
+            .collect::<Vec<_>>(),
+    );
+
+    report
+}
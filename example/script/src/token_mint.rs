@@ -0,0 +1,88 @@
+//! Helpers running the SPL token mint example under `solana-program-test`.
+
+use crate::send;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    program_pack::Pack, pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction,
+};
+
+/// Starts a test validator running the token mint program. The SPL token program is built in.
+pub async fn start() -> (ProgramTestContext, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let context = ProgramTest::new(
+        "sp1-token-mint-program",
+        program_id,
+        processor!(sp1_token_mint_program::process_instruction),
+    )
+    .start_with_context()
+    .await;
+    (context, program_id)
+}
+
+/// Creates a mint with no decimals, whose authority is `mint_authority`.
+pub async fn create_mint(context: &mut ProgramTestContext, mint_authority: &Pubkey) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &context.payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint2(
+            &spl_token::ID,
+            &mint.pubkey(),
+            mint_authority,
+            None,
+            0,
+        )
+        .unwrap(),
+    ];
+    send(context, &instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+/// Creates a token account of `mint` owned by `owner`.
+pub async fn create_token_account(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Pubkey {
+    let token_account = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &context.payer.pubkey(),
+            &token_account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_account3(
+            &spl_token::ID,
+            &token_account.pubkey(),
+            mint,
+            owner,
+        )
+        .unwrap(),
+    ];
+    send(context, &instructions, &[&token_account])
+        .await
+        .unwrap();
+    token_account.pubkey()
+}
+
+/// Reads the balance of a token account.
+pub async fn token_balance(context: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    spl_token::state::Account::unpack(&account.data)
+        .unwrap()
+        .amount
+}
@@ -0,0 +1,162 @@
+//! Helpers running the transfer hook example under `solana-program-test`.
+
+use crate::send;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::{keypair::keypair_from_seed, Signer},
+    system_instruction,
+};
+use spl_token_2022::{
+    extension::{transfer_hook, ExtensionType, StateWithExtensions},
+    state::{Account, Mint},
+};
+
+/// Starts a test validator running the transfer hook program. The Token-2022 program is built in.
+pub async fn start() -> (ProgramTestContext, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let context = ProgramTest::new(
+        "sp1-transfer-hook-program",
+        program_id,
+        processor!(sp1_transfer_hook_program::process_instruction),
+    )
+    .start_with_context()
+    .await;
+    (context, program_id)
+}
+
+/// The owner attested by the saved attestation proof.
+pub fn attested_owner() -> Keypair {
+    keypair_from_seed(&[7u8; 32]).unwrap()
+}
+
+/// Creates a Token-2022 mint with no decimals whose transfers call the hook `program_id`. The
+/// payer is its mint authority.
+pub async fn create_mint(context: &mut ProgramTestContext, program_id: &Pubkey) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook]).unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        transfer_hook::instruction::initialize(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            Some(payer),
+            Some(*program_id),
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &payer,
+            None,
+            0,
+        )
+        .unwrap(),
+    ];
+    send(context, &instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+/// Creates a token account of `mint` owned by `owner`, and mints `amount` tokens to it.
+pub async fn create_token_account(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let token_account = Keypair::new();
+    let payer = context.payer.pubkey();
+    let space =
+        ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::TransferHookAccount])
+            .unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &token_account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        spl_token_2022::instruction::initialize_account3(
+            &spl_token_2022::ID,
+            &token_account.pubkey(),
+            mint,
+            owner,
+        )
+        .unwrap(),
+        spl_token_2022::instruction::mint_to(
+            &spl_token_2022::ID,
+            mint,
+            &token_account.pubkey(),
+            &payer,
+            &[],
+            amount,
+        )
+        .unwrap(),
+    ];
+    send(context, &instructions, &[&token_account])
+        .await
+        .unwrap();
+    token_account.pubkey()
+}
+
+/// Builds a `TransferChecked` instruction of a mint with no decimals, with the extra accounts of
+/// its transfer hook resolved from the mint's validation account.
+pub async fn transfer_instruction(
+    context: &ProgramTestContext,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let banks_client = context.banks_client.clone();
+    spl_token_2022::offchain::create_transfer_checked_instruction_with_extra_metas(
+        &spl_token_2022::ID,
+        source,
+        mint,
+        destination,
+        owner,
+        &[],
+        amount,
+        0,
+        |address| {
+            let mut banks_client = banks_client.clone();
+            async move {
+                banks_client
+                    .get_account(address)
+                    .await
+                    .map(|account| account.map(|account| account.data))
+                    .map_err(Into::into)
+            }
+        },
+    )
+    .await
+    .unwrap()
+}
+
+/// Reads the balance of a token account.
+pub async fn token_balance(context: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    StateWithExtensions::<Account>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
@@ -0,0 +1,40 @@
+//! Cross-checks SP1 program vkey hashes against the one embedded in the example program.
+
+use num_bigint::BigUint;
+
+/// Compares the vkey hash of a proof or ELF, `actual`, with the one the program accepts,
+/// `expected`, returning a diff of the two on mismatch.
+pub fn check_vkey_hash(actual: &[u8; 32], expected: &[u8; 32]) -> Result<(), String> {
+    if actual == expected {
+        return Ok(());
+    }
+
+    // Underline the differing hex digits.
+    let (actual_hex, expected_hex) = (hex::encode(actual), hex::encode(expected));
+    let markers: String = actual_hex
+        .chars()
+        .zip(expected_hex.chars())
+        .map(|(a, e)| if a == e { ' ' } else { '^' })
+        .collect();
+    Err(format!(
+        "vkey hash mismatch, the program will reject this proof\n\
+         \x20 actual:   0x{actual_hex}\n\
+         \x20 expected: 0x{expected_hex}\n\
+         \x20             {markers}\n\
+         Update FIBONACCI_VKEY_HASH in example/program/src/lib.rs after rebuilding the SP1 program."
+    ))
+}
+
+/// Decodes the vkey hash public input of a Groth16 proof, a decimal field element.
+pub fn vkey_hash_from_public_input(public_input: &str) -> Result<[u8; 32], String> {
+    let value: BigUint = public_input
+        .parse()
+        .map_err(|error| format!("invalid vkey hash public input: {error}"))?;
+    let bytes = value.to_bytes_be();
+    if bytes.len() > 32 {
+        return Err("the vkey hash public input is longer than 32 bytes".to_string());
+    }
+    let mut vkey_hash = [0u8; 32];
+    vkey_hash[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(vkey_hash)
+}
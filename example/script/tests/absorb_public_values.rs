@@ -0,0 +1,156 @@
+//! Verifies proofs whose public values are hashed on-chain chunk by chunk, with
+//! `AbsorbPublicValues` and `FinalizeAndVerify`.
+
+mod common;
+
+use borsh::BorshDeserialize;
+use common::{custom_error, load_groth16_proof, process, start};
+use fibonacci_verifier_contract::{FibonacciVerifierError, VerifierInstruction, HASH_ACCOUNT_LEN};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+    transaction::Transaction,
+};
+use sp1_solana::{values_account::MAX_CHUNK_LEN, CommittedValuesDigest, Sha256State};
+
+/// Creates an empty hash account owned by the program.
+async fn create_hash_account(context: &mut ProgramTestContext, program_id: Pubkey) -> Pubkey {
+    let hash_account = Keypair::new();
+    let payer = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let create = system_instruction::create_account(
+        &payer,
+        &hash_account.pubkey(),
+        rent.minimum_balance(HASH_ACCOUNT_LEN),
+        HASH_ACCOUNT_LEN as u64,
+        &program_id,
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction = Transaction::new_with_payer(&[create], Some(&payer));
+    transaction.sign(&[&context.payer, &hash_account], recent_blockhash);
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+    hash_account.pubkey()
+}
+
+fn absorb_public_values(
+    program_id: Pubkey,
+    authority: Pubkey,
+    hash_account: Pubkey,
+    bytes: &[u8],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::AbsorbPublicValues {
+            bytes: bytes.to_vec(),
+        },
+        vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(hash_account, false),
+        ],
+    )
+}
+
+fn finalize_and_verify(
+    program_id: Pubkey,
+    authority: Pubkey,
+    hash_account: Pubkey,
+    proof: &[u8],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::FinalizeAndVerify {
+            proof: proof.try_into().unwrap(),
+        },
+        vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new(hash_account, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_absorb_and_verify() {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+    let groth16_proof = load_groth16_proof();
+    let hash_account = create_hash_account(&mut context, program_id).await;
+
+    // Stream the public values in chunks that don't line up with anything, empty ones included.
+    let values = groth16_proof.sp1_public_inputs.to_vec();
+    for chunk in [&values[..5], &values[5..5], &values[5..]] {
+        let instruction = absorb_public_values(program_id, payer, hash_account, chunk);
+        assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    }
+
+    let instruction = finalize_and_verify(program_id, payer, hash_account, &groth16_proof.proof);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+
+    // The account is closed, so the values can't be extended and verified again.
+    assert_eq!(
+        context
+            .banks_client
+            .get_account(hash_account)
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_absorb_large_public_values() {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+    let groth16_proof = load_groth16_proof();
+    let hash_account = create_hash_account(&mut context, program_id).await;
+
+    // 10 KB of public values, far more than fit in a transaction.
+    let values: Vec<u8> = (0..10 * 1024).map(|i| (i % 251) as u8).collect();
+    for chunk in values.chunks(MAX_CHUNK_LEN) {
+        let instruction = absorb_public_values(program_id, payer, hash_account, chunk);
+        assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    }
+
+    // Only the authority set by the first chunk can absorb more.
+    let other = Keypair::new();
+    let instruction = absorb_public_values(program_id, other.pubkey(), hash_account, &[0]);
+    assert_eq!(
+        process(&mut context, instruction, &[&other]).await,
+        custom_error(FibonacciVerifierError::UnauthorizedWrite)
+    );
+
+    // The state stored across the transactions hashes to the digest of the whole payload.
+    let account = context
+        .banks_client
+        .get_account(hash_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&account.data[..32], payer.as_ref());
+    let sha256_state = Sha256State::try_from_slice(&account.data[32..]).unwrap();
+    assert_eq!(
+        sha256_state.finalize_masked(),
+        CommittedValuesDigest::from_public_values(&values)
+    );
+
+    // The fibonacci proof commits to other public values.
+    let instruction = finalize_and_verify(program_id, payer, hash_account, &groth16_proof.proof);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidProof)
+    );
+
+    let instruction =
+        absorb_public_values(program_id, payer, hash_account, &vec![0; MAX_CHUNK_LEN + 1]);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::PayloadTooLarge)
+    );
+}
@@ -0,0 +1,246 @@
+//! Pays out airdrop claims with `sp1-airdrop-program`.
+
+use borsh::BorshDeserialize;
+use example_script::{airdrop::start, send};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::InstructionError, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signer::Signer,
+    system_instruction, transaction::TransactionError,
+};
+use sp1_airdrop_program::{
+    claim, create_vault, find_claim_address, find_vault_address, AirdropClaim, AirdropError,
+    ClaimRecord,
+};
+use sp1_sdk::{include_elf, HashableKey, ProverClient, SP1ProofWithPublicValues};
+use sp1_solana::decode_sp1_vkey_hash;
+
+fn custom_error(error: AirdropError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+/// Starts the airdrop to proofs of `sp1_vkey_hash`, with a vault of 5 SOL.
+async fn start_airdrop(sp1_vkey_hash: &[u8; 32]) -> (ProgramTestContext, Pubkey) {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+    let instruction = create_vault(&program_id, &payer, sp1_vkey_hash, 5 * LAMPORTS_PER_SOL);
+    assert_eq!(send(&mut context, &[instruction], &[]).await, Ok(()));
+    (context, program_id)
+}
+
+#[tokio::test]
+async fn test_rejects_unproven_claims() {
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let (mut context, program_id) = start_airdrop(&sp1_vkey_hash).await;
+    let payer = context.payer.pubkey();
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+
+    // The fibonacci proof verifies, but its public values aren't an `AirdropClaim`.
+    let instruction = claim(
+        &program_id,
+        &payer,
+        &payer,
+        &sp1_vkey_hash,
+        &proof,
+        sp1_proof_with_public_values.public_values.as_slice(),
+    );
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(AirdropError::InvalidPublicValues)
+    );
+
+    // A claim that the proof doesn't commit to.
+    let claimant = Pubkey::new_unique();
+    let sp1_public_inputs = borsh::to_vec(&AirdropClaim {
+        claimant,
+        allocation: LAMPORTS_PER_SOL,
+    })
+    .unwrap();
+    let instruction = claim(
+        &program_id,
+        &payer,
+        &claimant,
+        &sp1_vkey_hash,
+        &proof,
+        &sp1_public_inputs,
+    );
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(AirdropError::InvalidProof)
+    );
+    assert_eq!(context.banks_client.get_balance(claimant).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_create_prefunded_vault() {
+    let sp1_vkey_hash = [1; 32];
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+    let (vault, _) = find_vault_address(&program_id, &sp1_vkey_hash);
+
+    // The vault address is public, so anyone can fund it before the airdrop starts.
+    let instruction = system_instruction::transfer(&payer, &vault, 1);
+    assert_eq!(send(&mut context, &[instruction], &[]).await, Ok(()));
+
+    let instruction = create_vault(&program_id, &payer, &sp1_vkey_hash, 5 * LAMPORTS_PER_SOL);
+    assert_eq!(send(&mut context, &[instruction], &[]).await, Ok(()));
+    let account = context
+        .banks_client
+        .get_account(vault)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(account.owner, program_id);
+    assert_eq!(
+        account.lamports,
+        rent.minimum_balance(0) + 5 * LAMPORTS_PER_SOL
+    );
+}
+
+/// Pays a claim whose claim PDA was funded to block it, with a mock proof.
+///
+/// ```shell
+/// cargo test -p example-script --features mock-verifier --test airdrop
+/// ```
+#[cfg(feature = "mock-verifier")]
+#[tokio::test]
+async fn test_claim_prefunded() {
+    use sp1_solana::SP1ProofFixture;
+
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let (mut context, program_id) = start_airdrop(&sp1_vkey_hash).await;
+    let payer = context.payer.pubkey();
+
+    let claimant = Pubkey::new_unique();
+    let sp1_public_inputs = borsh::to_vec(&AirdropClaim {
+        claimant,
+        allocation: LAMPORTS_PER_SOL,
+    })
+    .unwrap();
+    let fixture = SP1ProofFixture::mock(&sp1_vkey_hash, &sp1_public_inputs);
+    let proof = [&fixture.groth16_vkey_hash[..], &fixture.proof].concat();
+
+    // The claim address is derived from public data, so anyone can fund it before the claim.
+    let (claim_address, _) = find_claim_address(&program_id, &sp1_vkey_hash, &claimant);
+    let instruction = system_instruction::transfer(&payer, &claim_address, 1);
+    assert_eq!(send(&mut context, &[instruction], &[]).await, Ok(()));
+
+    let instruction = claim(
+        &program_id,
+        &payer,
+        &claimant,
+        &sp1_vkey_hash,
+        &proof,
+        &sp1_public_inputs,
+    );
+    assert_eq!(
+        send(&mut context, &[instruction.clone()], &[]).await,
+        Ok(())
+    );
+    assert_eq!(
+        context.banks_client.get_balance(claimant).await.unwrap(),
+        LAMPORTS_PER_SOL
+    );
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(AirdropError::AlreadyClaimed)
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs proofs/airdrop_claim_{0,1}.bin, generated by `cargo run --bin airdrop -- --prove`"]
+async fn test_claims() {
+    let (_, vk) = ProverClient::new().setup(include_elf!("token-claim-program"));
+    let sp1_vkey_hash = decode_sp1_vkey_hash(&vk.bytes32()).unwrap();
+    let (mut context, program_id) = start_airdrop(&sp1_vkey_hash).await;
+    let payer = context.payer.pubkey();
+
+    let claims: Vec<_> = ["0", "1"]
+        .iter()
+        .map(|i| {
+            let sp1_proof_with_public_values =
+                SP1ProofWithPublicValues::load(format!("../../proofs/airdrop_claim_{i}.bin"))
+                    .unwrap();
+            let sp1_public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+            let airdrop_claim = AirdropClaim::try_from_slice(&sp1_public_inputs).unwrap();
+            (
+                airdrop_claim,
+                sp1_proof_with_public_values.bytes(),
+                sp1_public_inputs,
+            )
+        })
+        .collect();
+
+    // A proof generated for one claimant can't pay another.
+    let (_, proof, sp1_public_inputs) = &claims[0];
+    let instruction = claim(
+        &program_id,
+        &payer,
+        &claims[1].0.claimant,
+        &sp1_vkey_hash,
+        proof,
+        sp1_public_inputs,
+    );
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(AirdropError::ClaimantMismatch)
+    );
+
+    // Both claims verify against the same program vkey hash, and are paid once.
+    let (vault, _) = find_vault_address(&program_id, &sp1_vkey_hash);
+    let vault_balance = context.banks_client.get_balance(vault).await.unwrap();
+    for (airdrop_claim, proof, sp1_public_inputs) in &claims {
+        let instruction = claim(
+            &program_id,
+            &payer,
+            &airdrop_claim.claimant,
+            &sp1_vkey_hash,
+            proof,
+            sp1_public_inputs,
+        );
+        assert_eq!(
+            send(&mut context, &[instruction.clone()], &[]).await,
+            Ok(())
+        );
+        assert_eq!(
+            send(&mut context, &[instruction], &[]).await,
+            custom_error(AirdropError::AlreadyClaimed)
+        );
+
+        let balance = context
+            .banks_client
+            .get_balance(airdrop_claim.claimant)
+            .await
+            .unwrap();
+        assert_eq!(balance, airdrop_claim.allocation);
+
+        let (claim_address, _) =
+            find_claim_address(&program_id, &sp1_vkey_hash, &airdrop_claim.claimant);
+        let account = context
+            .banks_client
+            .get_account(claim_address)
+            .await
+            .unwrap()
+            .unwrap();
+        let record = ClaimRecord::try_from_slice(&account.data).unwrap();
+        assert_eq!(record.allocation, airdrop_claim.allocation);
+    }
+
+    let paid: u64 = claims
+        .iter()
+        .map(|(airdrop_claim, ..)| airdrop_claim.allocation)
+        .sum();
+    assert_eq!(
+        context.banks_client.get_balance(vault).await.unwrap(),
+        vault_balance - paid
+    );
+}
@@ -0,0 +1,165 @@
+//! Verifies proofs against an allowlist of program vkey hashes, and rotates them.
+
+mod common;
+
+use common::{custom_error, load_groth16_proof, process, start};
+use fibonacci_verifier_contract::{
+    instruction::{
+        add_allowed_vkey, initialize_allowlist, remove_allowed_vkey, verify_with_allowlist,
+    },
+    FibonacciVerifierError, FIBONACCI_VKEY_HASH, MAX_ALLOWED_VKEYS,
+};
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer, system_instruction,
+};
+use sp1_solana::allowlist::ProgramVkeyAllowlist;
+
+/// The vkey hash of another SP1 program, standing in for the next version of the fibonacci one.
+fn other_vkey_hash(seed: u8) -> [u8; 32] {
+    let mut hash = [seed; 32];
+    hash[0] = 0;
+    hash
+}
+
+#[tokio::test]
+async fn test_rotate_allowed_vkey() {
+    let (mut context, program_id) = start().await;
+    let authority = context.payer.pubkey();
+    let verify = || verify_with_allowlist(&program_id, load_groth16_proof());
+
+    // Before the allowlist exists, there is nothing to verify against.
+    assert_eq!(
+        process(&mut context, verify(), &[]).await,
+        custom_error(FibonacciVerifierError::InvalidAccountOwner)
+    );
+
+    let instruction = initialize_allowlist(&program_id, &authority);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    let instruction = add_allowed_vkey(&program_id, &authority, FIBONACCI_VKEY_HASH);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    assert_eq!(process(&mut context, verify(), &[]).await, Ok(()));
+
+    // Rotate to the next version: proofs of both are accepted until the old one is removed.
+    let next = other_vkey_hash(1);
+    let instruction = add_allowed_vkey(&program_id, &authority, next);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    assert_eq!(process(&mut context, verify(), &[]).await, Ok(()));
+
+    let instruction = remove_allowed_vkey(&program_id, &authority, FIBONACCI_VKEY_HASH);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    assert_eq!(
+        process(&mut context, verify(), &[]).await,
+        custom_error(FibonacciVerifierError::ProgramVkeyHashMismatch)
+    );
+
+    let allowlist = context
+        .banks_client
+        .get_account(ProgramVkeyAllowlist::find_address(&program_id).0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        ProgramVkeyAllowlist::from_account_data(&allowlist.data).unwrap(),
+        ProgramVkeyAllowlist {
+            authority,
+            hashes: vec![next],
+        }
+    );
+    assert_eq!(
+        allowlist.data.len(),
+        ProgramVkeyAllowlist::space(MAX_ALLOWED_VKEYS)
+    );
+}
+
+#[tokio::test]
+async fn test_allowlist_checks() {
+    let (mut context, program_id) = start().await;
+    let authority = context.payer.pubkey();
+
+    let instruction = initialize_allowlist(&program_id, &authority);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    let instruction = add_allowed_vkey(&program_id, &authority, FIBONACCI_VKEY_HASH);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+
+    // Only the authority can change the allowlist.
+    let other = Keypair::new();
+    let instruction = add_allowed_vkey(&program_id, &other.pubkey(), other_vkey_hash(1));
+    assert_eq!(
+        process(&mut context, instruction, &[&other]).await,
+        custom_error(FibonacciVerifierError::UnauthorizedAllowlistUpdate)
+    );
+    let instruction = remove_allowed_vkey(&program_id, &other.pubkey(), FIBONACCI_VKEY_HASH);
+    assert_eq!(
+        process(&mut context, instruction, &[&other]).await,
+        custom_error(FibonacciVerifierError::UnauthorizedAllowlistUpdate)
+    );
+
+    let instruction = add_allowed_vkey(&program_id, &authority, FIBONACCI_VKEY_HASH);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::VkeyAlreadyAllowed)
+    );
+    let instruction = remove_allowed_vkey(&program_id, &authority, other_vkey_hash(1));
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::VkeyNotAllowed)
+    );
+    let instruction = add_allowed_vkey(&program_id, &authority, [1; 32]);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidInstructionData)
+    );
+
+    // The allowlist has room for a fixed number of hashes.
+    for seed in 1..MAX_ALLOWED_VKEYS as u8 {
+        let instruction = add_allowed_vkey(&program_id, &authority, other_vkey_hash(seed));
+        assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    }
+    let instruction = add_allowed_vkey(&program_id, &authority, other_vkey_hash(0xff));
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::AllowlistFull)
+    );
+
+    // The fibonacci hash was added first, so it's still found with the list full.
+    assert_eq!(
+        process(
+            &mut context,
+            verify_with_allowlist(&program_id, load_groth16_proof()),
+            &[]
+        )
+        .await,
+        Ok(())
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_allowlist_authority() {
+    let (mut context, program_id) = start().await;
+    let authority = context.payer.pubkey();
+
+    // Adding a hash no longer creates the allowlist.
+    let instruction = add_allowed_vkey(&program_id, &authority, FIBONACCI_VKEY_HASH);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidAccountOwner)
+    );
+
+    // A funded signer other than the upgrade authority can't front-run the initialization.
+    let other = Keypair::new();
+    let instruction = system_instruction::transfer(&authority, &other.pubkey(), LAMPORTS_PER_SOL);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    let instruction = initialize_allowlist(&program_id, &other.pubkey());
+    assert_eq!(
+        process(&mut context, instruction, &[&other]).await,
+        custom_error(FibonacciVerifierError::NotUpgradeAuthority)
+    );
+
+    let instruction = initialize_allowlist(&program_id, &authority);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    let instruction = initialize_allowlist(&program_id, &authority);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::AlreadyInitialized)
+    );
+}
@@ -0,0 +1,145 @@
+//! Verifies batches of proofs with `VerifyBatch`, and packs proofs into batches.
+
+mod common;
+
+use borsh::BorshDeserialize;
+use common::{custom_error, load_groth16_proof, process, start};
+use example_script::batch::{pack_verify_batches, transaction_size};
+use fibonacci_verifier_contract::{
+    instruction::{verify_and_store, verify_batch},
+    FibonacciVerifierError, VerifierInstruction, FIBONACCI_VKEY_HASH,
+};
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use sp1_solana::{
+    event::{parse_verification_events, VerificationEvent},
+    hash_public_inputs,
+    record::find_verification_record_address,
+};
+
+#[tokio::test]
+async fn test_verify_batch() {
+    let (mut context, program_id) = start().await;
+
+    let groth16_proof = load_groth16_proof();
+    let instruction = verify_batch(
+        &program_id,
+        vec![groth16_proof.clone(), groth16_proof.clone()],
+    );
+    let mut transaction =
+        Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer], context.last_blockhash);
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result.result.unwrap();
+
+    // One event per proof.
+    let event = VerificationEvent {
+        sp1_vkey_hash: FIBONACCI_VKEY_HASH,
+        committed_values_digest: hash_public_inputs(&groth16_proof.sp1_public_inputs),
+    };
+    assert_eq!(
+        parse_verification_events(&result.metadata.unwrap().log_messages),
+        vec![event.clone(), event]
+    );
+
+    let instruction = verify_batch(&program_id, vec![]);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::EmptyBatch)
+    );
+}
+
+#[tokio::test]
+async fn test_verify_batch_is_atomic() {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+
+    let groth16_proof = load_groth16_proof();
+    let committed_values_digest = hash_public_inputs(&groth16_proof.sp1_public_inputs);
+    let (record_address, _) = find_verification_record_address(
+        &program_id,
+        &FIBONACCI_VKEY_HASH,
+        &committed_values_digest,
+    );
+    let store = verify_and_store(&program_id, &payer, groth16_proof.clone());
+
+    // The second proof of the batch is invalid.
+    let mut bad_proof = groth16_proof.clone();
+    bad_proof.sp1_public_inputs[0] ^= 1;
+    let batch = verify_batch(&program_id, vec![groth16_proof, bad_proof]);
+
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction = Transaction::new_with_payer(&[store, batch], Some(&payer));
+    transaction.sign(&[&context.payer], recent_blockhash);
+    let error = context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(FibonacciVerifierError::InvalidProof as u32)
+        )
+    );
+
+    // The record created by the first instruction was rolled back with the batch.
+    let record = context
+        .banks_client
+        .get_account(record_address)
+        .await
+        .unwrap();
+    assert!(record.is_none());
+}
+
+#[test]
+fn test_pack_verify_batches() {
+    let program_id = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let groth16_proofs = vec![load_groth16_proof(); 10];
+
+    let batch_lens = |instructions: &[Instruction]| -> Vec<usize> {
+        instructions
+            .iter()
+            .map(|instruction| {
+                assert!(transaction_size(&payer, instruction.clone()) <= PACKET_DATA_SIZE);
+                match VerifierInstruction::try_from_slice(&instruction.data).unwrap() {
+                    VerifierInstruction::VerifyBatch(batch) => batch.len(),
+                    _ => panic!("not a batch"),
+                }
+            })
+            .collect()
+    };
+
+    // Bound by the transaction size rather than the compute budget.
+    let instructions =
+        pack_verify_batches(&program_id, &payer, groth16_proofs.clone(), 1, 1_400_000).unwrap();
+    let lens = batch_lens(&instructions);
+    assert_eq!(lens.iter().sum::<usize>(), 10);
+    assert!(lens[0] > 1);
+
+    // Bound by the compute budget.
+    let instructions = pack_verify_batches(
+        &program_id,
+        &payer,
+        groth16_proofs.clone(),
+        300_000,
+        300_000,
+    )
+    .unwrap();
+    assert_eq!(batch_lens(&instructions), vec![1; 10]);
+
+    // A budget too small for a single proof.
+    assert!(pack_verify_batches(&program_id, &payer, groth16_proofs, 300_000, 299_999).is_none());
+}
@@ -0,0 +1,29 @@
+//! Binary size budget of the fibonacci verifier program.
+//!
+//! Build the program first, then run the test with the `cu-regression` feature:
+//!
+//! ```shell
+//! cargo build-sbf --manifest-path example/program/Cargo.toml --sbf-out-dir target/deploy
+//! SBF_OUT_DIR=$PWD/target/deploy cargo test -p example-script --features cu-regression --test binary_size -- --nocapture
+//! ```
+//!
+//! The build is reproducible from `Cargo.lock` with a given `cargo build-sbf` toolchain. The
+//! budget belongs in `tests/size_budget.json`, which isn't committed until it is measured: until
+//! then the test fails, and `UPDATE_SIZE_BUDGET=1` records the sizes of a build as the budget.
+#![cfg(feature = "cu-regression")]
+
+use example_script::size::assert_size_within;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_verifier_program_size() {
+    let out_dir = std::env::var_os("SBF_OUT_DIR").expect("SBF_OUT_DIR is not set");
+    let so_path = PathBuf::from(out_dir).join("fibonacci_verifier_contract.so");
+    let budget = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/size_budget.json");
+
+    let report = assert_size_within(&so_path, &budget);
+    println!("fibonacci_verifier_contract.so: {} bytes", report.file_size);
+    for (name, size) in &report.sections {
+        println!("  {name}: {size} bytes");
+    }
+}
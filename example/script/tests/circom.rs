@@ -0,0 +1,79 @@
+//! Verifies the circom multiplier fixture of the verifier crate under solana-program-test, the
+//! way a program verifying circom proofs would: the proof is converted off-chain and the program
+//! only runs the pairing check.
+
+mod common;
+
+use common::process;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    transaction::TransactionError,
+};
+use sp1_solana::{
+    circom::{circom_proof_to_bytes, public_inputs_from_snarkjs, verification_key_from_snarkjs},
+    verify_groth16_prenegated,
+};
+
+const VERIFICATION_KEY: &str =
+    include_str!("../../../verifier/testdata/circom/verification_key.json");
+const PROOF: &str = include_str!("../../../verifier/testdata/circom/proof.json");
+const PUBLIC: &str = include_str!("../../../verifier/testdata/circom/public.json");
+
+/// A program verifying multiplier proofs: its instruction data is the 256-byte proof, with pi_a
+/// negated, followed by the public output.
+fn process_multiplier_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    if instruction_data.len() != 256 + 32 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (proof, public_input) = instruction_data.split_at(256);
+    // A deployed program would embed the key; parsing it keeps the test to the fixture files.
+    let vk = verification_key_from_snarkjs(VERIFICATION_KEY).unwrap();
+    verify_groth16_prenegated(proof, &[public_input.try_into().unwrap()], &vk)
+        .map_err(|_| ProgramError::InvalidArgument)
+}
+
+async fn start() -> (ProgramTestContext, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let context = ProgramTest::new(
+        "circom-multiplier",
+        program_id,
+        processor!(process_multiplier_instruction),
+    )
+    .start_with_context()
+    .await;
+    (context, program_id)
+}
+
+fn instruction(program_id: &Pubkey, proof: &[u8; 256], public_input: &[u8; 32]) -> Instruction {
+    Instruction::new_with_bytes(*program_id, &[&proof[..], public_input].concat(), vec![])
+}
+
+#[tokio::test]
+async fn test_verify_circom_proof() {
+    let (mut context, program_id) = start().await;
+    let proof = circom_proof_to_bytes(PROOF).unwrap();
+    let public_inputs = public_inputs_from_snarkjs(PUBLIC).unwrap();
+
+    let verify = instruction(&program_id, &proof, &public_inputs[0]);
+    assert_eq!(process(&mut context, verify, &[]).await, Ok(()));
+
+    let mut wrong_output = public_inputs[0];
+    wrong_output[31] += 1;
+    let verify = instruction(&program_id, &proof, &wrong_output);
+    assert_eq!(
+        process(&mut context, verify, &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+}
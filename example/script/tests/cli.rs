@@ -0,0 +1,182 @@
+//! Covers the subcommands of the example script, offline against the committed fibonacci proofs.
+
+mod common;
+
+use clap::Parser;
+use common::load_groth16_proof;
+use example_script::{
+    cli::{Cli, Command, DEFAULT_MOCK_PROOF_FILE, DEFAULT_PROOF_FILE},
+    fixture::{save_fixture, FixtureFormat},
+    proof_file::{
+        check_before_submit, groth16_proof_from_fixture, load_proof_file, Inspection, VkVersion,
+    },
+    prover::ProverKind,
+    rpc::Network,
+    run_verify_instruction,
+};
+use fibonacci_verifier_contract::{instruction::verify, FIBONACCI_VKEY_HASH};
+use solana_sdk::pubkey::Pubkey;
+use sp1_solana::{
+    hash_public_inputs, verify_proof_fixture, GROTH16_VK_2_0_0_BYTES, GROTH16_VK_3_0_0_BYTES,
+    GROTH16_VK_3_0_0_HASH,
+};
+use std::path::{Path, PathBuf};
+
+const PROOF_JSON: &str = "../../proofs/fibonacci_proof.json";
+
+fn parse(args: &[&str]) -> Command {
+    Cli::try_parse_from([&["example-script"], args].concat())
+        .unwrap()
+        .command
+}
+
+#[test]
+fn test_prove_args() {
+    let Command::Prove(args) = parse(&["prove"]) else {
+        panic!("expected prove");
+    };
+    assert_eq!(args.prover, ProverKind::Cpu);
+    assert_eq!(args.proof_file(), PathBuf::from(DEFAULT_PROOF_FILE));
+
+    // Mock proofs never replace the real one, unless asked to.
+    let Command::Prove(args) = parse(&["prove", "--prover", "mock"]) else {
+        panic!("expected prove");
+    };
+    assert_eq!(args.proof_file(), PathBuf::from(DEFAULT_MOCK_PROOF_FILE));
+    let Command::Prove(args) = parse(&["prove", "--prover", "mock", "--proof", "mock.bin"]) else {
+        panic!("expected prove");
+    };
+    assert_eq!(args.proof_file(), PathBuf::from("mock.bin"));
+
+    assert!(Cli::try_parse_from(["example-script", "prove", "--mock-submit"]).is_err());
+    assert!(Cli::try_parse_from(["example-script"]).is_err());
+}
+
+#[test]
+fn test_verify() {
+    let Command::Verify(args) = parse(&["verify", "--proof", PROOF_JSON, "--vk-version", "v2.0.0"])
+    else {
+        panic!("expected verify");
+    };
+    assert_eq!(args.common.vk_version, VkVersion::V2);
+
+    // Both the bincode and JSON proofs verify against v3.0.0 only.
+    let fixture = load_proof_file(Path::new(DEFAULT_PROOF_FILE)).unwrap();
+    assert_eq!(
+        load_proof_file(args.common.proof.as_path()).unwrap(),
+        fixture
+    );
+    verify_proof_fixture(&fixture, VkVersion::V3.groth16_vk()).unwrap();
+    assert!(verify_proof_fixture(&fixture, args.common.vk_version.groth16_vk()).is_err());
+
+    // Fixtures saved in every format load back to the same proof.
+    for (format, extension) in [
+        (FixtureFormat::Bin, "bin"),
+        (FixtureFormat::Json, "json"),
+        (FixtureFormat::Hex, "hex"),
+    ] {
+        let path = std::env::temp_dir().join(format!(
+            "fibonacci_cli_fixture_{}.{extension}",
+            std::process::id()
+        ));
+        save_fixture(&fixture, &path, format, VkVersion::V3.groth16_vk()).unwrap();
+        assert_eq!(load_proof_file(&path).unwrap(), fixture, "{format:?}");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    assert!(load_proof_file(Path::new("../../proofs/missing.bin")).is_err());
+}
+
+#[test]
+fn test_inspect() {
+    let Command::Inspect(args) = parse(&["inspect"]) else {
+        panic!("expected inspect");
+    };
+    let inspection = Inspection::new(&load_proof_file(&args.common.proof).unwrap());
+
+    let public_values = hex::decode("140000006d1a0000d30b0000").unwrap();
+    assert_eq!(inspection.groth16_vkey_hash, GROTH16_VK_3_0_0_HASH);
+    assert_eq!(inspection.vk_version, Some(VkVersion::V3));
+    assert_eq!(inspection.sp1_vkey_hash, FIBONACCI_VKEY_HASH);
+    assert_eq!(
+        inspection.committed_values_digest,
+        hash_public_inputs(&public_values)
+    );
+    assert_eq!(inspection.sp1_public_inputs, public_values);
+    assert_eq!(inspection.proof_len, 260);
+    // The fixed-size fields, the public values and their Borsh length prefix.
+    assert_eq!(inspection.fixture_len, 4 + 256 + 32 + 32 + 4 + 12);
+    assert_eq!(
+        inspection.instruction_data_len,
+        verify(&Pubkey::default(), load_groth16_proof()).data.len()
+    );
+}
+
+#[tokio::test]
+async fn test_submit() {
+    let Command::Submit(args) = parse(&["submit", "--network", "devnet", "--skip-preflight"])
+    else {
+        panic!("expected submit");
+    };
+    assert_eq!(args.network, Some(Network::Devnet));
+    assert!(args.retry_policy().skip_preflight);
+
+    let Command::Submit(args) = parse(&["submit", "--proof", PROOF_JSON]) else {
+        panic!("expected submit");
+    };
+    assert_eq!(args.network, None);
+    let groth16_proof = groth16_proof_from_fixture(&load_proof_file(&args.common.proof).unwrap());
+    let program_id = Pubkey::new_unique();
+    assert_eq!(
+        verify(&program_id, groth16_proof.clone()).data,
+        verify(&program_id, load_groth16_proof()).data
+    );
+
+    // Panics unless the program accepts the proof.
+    let report = run_verify_instruction(groth16_proof, args.compute_unit_price).await;
+    assert!(report
+        .logs
+        .iter()
+        .any(|log| log.contains("Public values: (n: 20, a: 6765, b: 3027)")));
+}
+
+#[test]
+fn test_check_before_submit() {
+    let fixture = load_proof_file(Path::new(DEFAULT_PROOF_FILE)).unwrap();
+    check_before_submit(&fixture, GROTH16_VK_3_0_0_BYTES).unwrap();
+
+    // A proof against another verification key explains the selector mismatch.
+    let explanation = check_before_submit(&fixture, GROTH16_VK_2_0_0_BYTES).unwrap_err();
+    assert!(
+        explanation.contains("Groth16 vkey hash mismatch"),
+        "{explanation}"
+    );
+    assert!(explanation.contains("0x09069090"), "{explanation}");
+    assert!(explanation.contains("0x6a2906ac"), "{explanation}");
+
+    // A proof of another program shows the vkey hash diff.
+    let mut other_program = fixture.clone();
+    other_program.sp1_vkey_hash[31] ^= 1;
+    let explanation = check_before_submit(&other_program, GROTH16_VK_3_0_0_BYTES).unwrap_err();
+    assert!(explanation.contains("vkey hash mismatch"), "{explanation}");
+    assert!(
+        explanation.contains("the proof doesn't verify: "),
+        "{explanation}"
+    );
+
+    // Tampered public values show the recomputed digest.
+    let mut tampered = fixture.clone();
+    tampered.sp1_public_inputs[0] ^= 1;
+    let explanation = check_before_submit(&tampered, GROTH16_VK_3_0_0_BYTES).unwrap_err();
+    assert!(
+        explanation.contains(&hex::encode(hash_public_inputs(
+            &tampered.sp1_public_inputs
+        ))),
+        "{explanation}"
+    );
+
+    let Command::Submit(args) = parse(&["submit", "--force"]) else {
+        panic!("expected submit");
+    };
+    assert!(args.force);
+}
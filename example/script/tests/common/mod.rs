@@ -0,0 +1,91 @@
+//! Helpers shared by the example program tests.
+
+// Each test binary uses a different subset of the helpers.
+#![allow(dead_code)]
+
+use fibonacci_verifier_contract::{FibonacciVerifierError, SP1Groth16Proof};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_solana::upgrade_authority::program_data_address;
+
+/// Starts a test validator running the example program, with the payer as its upgrade authority.
+pub async fn start() -> (ProgramTestContext, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let mut context = ProgramTest::new(
+        "fibonacci-verifier-contract",
+        program_id,
+        processor!(fibonacci_verifier_contract::process_instruction),
+    )
+    .start_with_context()
+    .await;
+    let upgrade_authority = context.payer.pubkey();
+    set_upgrade_authority(&mut context, &program_id, Some(upgrade_authority));
+    (context, program_id)
+}
+
+/// Replaces the `ProgramData` account of `program_id`, as if it had been deployed by
+/// `upgrade_authority`, or made immutable if `None`.
+pub fn set_upgrade_authority(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    upgrade_authority: Option<Pubkey>,
+) {
+    let data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: upgrade_authority,
+    })
+    .unwrap();
+    let program_data = AccountSharedData::from(Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: bpf_loader_upgradeable::id(),
+        ..Account::default()
+    });
+    context.set_account(&program_data_address(program_id), &program_data);
+}
+
+/// Sends `instruction`, signed by the payer and `signers`.
+pub async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction =
+        Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    transaction.sign(&all_signers, recent_blockhash);
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap())
+}
+
+/// The error of a transaction whose only instruction failed with `error`.
+pub fn custom_error(error: FibonacciVerifierError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+/// Loads the pregenerated fibonacci proof.
+pub fn load_groth16_proof() -> SP1Groth16Proof {
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    SP1Groth16Proof {
+        proof: sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs: sp1_proof_with_public_values.public_values.to_vec().into(),
+    }
+}
@@ -0,0 +1,124 @@
+//! Compute unit regression test for the fibonacci verifier program.
+//!
+//! Build the program first, then run the test with the `cu-regression` feature:
+//!
+//! ```shell
+//! cargo build-sbf --manifest-path example/program/Cargo.toml --sbf-out-dir target/deploy
+//! SBF_OUT_DIR=$PWD/target/deploy cargo test -p example-script --features cu-regression
+//! ```
+//!
+//! The baseline is `tests/cu_baseline.json`, recorded from a run with `UPDATE_CU_BASELINE=1`: the
+//! test fails until a measured one is committed. Set `CU_TOLERANCE` to override the allowed
+//! fractional increase (5% by default).
+//! Whatever the baseline and tolerance, the instruction must fit in [`VERIFY_CU_BUDGET`].
+//!
+//! The same build also compares verification with the precomputed Groth16 vk hash against
+//! verification hashing the key.
+#![cfg(feature = "cu-regression")]
+
+use example_script::cu::{assert_cu_within, measure_units_consumed};
+use fibonacci_verifier_contract::{find_config_address, SP1Groth16Proof, VerifierInstruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_solana::vk_account::{vk_account_space, write_vk};
+use std::path::Path;
+
+/// The compute unit limit the verify transactions are sent with on mainnet.
+const VERIFY_CU_BUDGET: u64 = 280_000;
+
+/// A bank running the BPF build of the program.
+fn program_test(program_id: Pubkey) -> ProgramTest {
+    let mut program_test = ProgramTest::new("fibonacci_verifier_contract", program_id, None);
+    program_test.prefer_bpf(true);
+    program_test
+}
+
+fn load_groth16_proof() -> SP1Groth16Proof {
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    SP1Groth16Proof {
+        proof: sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs: sp1_proof_with_public_values.public_values.to_vec().into(),
+    }
+}
+
+#[tokio::test]
+async fn test_verify_cu_regression() {
+    let program_id = Pubkey::new_unique();
+    let instruction = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::Verify(load_groth16_proof()),
+        vec![],
+    );
+
+    let tolerance = std::env::var("CU_TOLERANCE")
+        .map(|tolerance| tolerance.parse().expect("invalid CU_TOLERANCE"))
+        .unwrap_or(0.05);
+    let baseline = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cu_baseline.json");
+
+    let units_consumed =
+        assert_cu_within(program_test(program_id), instruction, &baseline, tolerance).await;
+    println!("verify instruction consumed {units_consumed} compute units");
+    assert!(
+        units_consumed <= VERIFY_CU_BUDGET,
+        "consumed {units_consumed} compute units, above the budget of {VERIFY_CU_BUDGET}"
+    );
+}
+
+/// The compute units the `sol_sha256` syscall charges to hash `len` bytes in a single slice: a
+/// base cost of 85, then one unit per 2 bytes.
+const fn sha256_syscall_cost(len: usize) -> u64 {
+    85 + len as u64 / 2
+}
+
+/// `Verify` checks the proof's selector against the precomputed `GROTH16_VK_3_0_0_HASH`, while
+/// `VerifyWithConfig` reads the same key from the config PDA, so it has to hash it. The
+/// precomputed hash must save at least the syscall hashing the 396-byte key, 283 units.
+#[tokio::test]
+async fn test_precomputed_vk_hash_cu() {
+    let program_id = Pubkey::new_unique();
+    let verify = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::Verify(load_groth16_proof()),
+        vec![],
+    );
+    let precomputed = measure_units_consumed(program_test(program_id), verify).await;
+
+    let (config_address, _) = find_config_address(&program_id);
+    let mut data = vec![0; vk_account_space()];
+    write_vk(
+        &mut data,
+        &Pubkey::new_unique(),
+        sp1_solana::GROTH16_VK_3_0_0_BYTES,
+    )
+    .unwrap();
+    let mut with_config = program_test(program_id);
+    with_config.add_account(
+        config_address,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    let verify_with_config = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::VerifyWithConfig(load_groth16_proof()),
+        vec![AccountMeta::new_readonly(config_address, false)],
+    );
+    let hashed = measure_units_consumed(with_config, verify_with_config).await;
+
+    println!("verify consumed {precomputed} compute units, {hashed} when hashing the vk");
+    let min_saving = sha256_syscall_cost(sp1_solana::GROTH16_VK_3_0_0_BYTES.len());
+    assert!(
+        precomputed + min_saving <= hashed,
+        "the precomputed vk hash cost {precomputed} compute units, against {hashed} hashing the \
+         vk: less than the {min_saving} units of the hash saved"
+    );
+}
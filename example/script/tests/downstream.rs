@@ -0,0 +1,113 @@
+//! Uses the example program the way a downstream crate would: as a dependency with the `cpi`
+//! feature, driven directly with its instruction builders and through a CPI from another program.
+
+mod common;
+
+use borsh::BorshDeserialize;
+use common::{custom_error, load_groth16_proof, process};
+use fibonacci_verifier_contract::{
+    instruction::{assert_recorded, verify, verify_and_store},
+    FibonacciVerifierError, SP1Groth16Proof,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signer::Signer,
+};
+use sp1_solana::hash_public_inputs;
+
+/// A downstream program that verifies the proof in its instruction data by invoking the example
+/// program, its first account.
+fn process_caller_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let verifier_program = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let groth16_proof = SP1Groth16Proof::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    invoke(
+        &verify(verifier_program.key, groth16_proof),
+        &[verifier_program.clone()],
+    )
+}
+
+/// Starts a test validator running the example program and the downstream program, returning
+/// their ids.
+async fn start() -> (ProgramTestContext, Pubkey, Pubkey) {
+    let verifier_program_id = Pubkey::new_unique();
+    let caller_program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "fibonacci-verifier-contract",
+        verifier_program_id,
+        processor!(fibonacci_verifier_contract::process_instruction),
+    );
+    program_test.add_program(
+        "downstream-caller",
+        caller_program_id,
+        processor!(process_caller_instruction),
+    );
+    (
+        program_test.start_with_context().await,
+        verifier_program_id,
+        caller_program_id,
+    )
+}
+
+fn caller_instruction(
+    caller_program_id: &Pubkey,
+    verifier_program_id: &Pubkey,
+    groth16_proof: &SP1Groth16Proof,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *caller_program_id,
+        groth16_proof,
+        vec![AccountMeta::new_readonly(*verifier_program_id, false)],
+    )
+}
+
+#[tokio::test]
+async fn test_direct() {
+    let (mut context, verifier_program_id, _) = start().await;
+    let payer = context.payer.pubkey();
+    let groth16_proof = load_groth16_proof();
+
+    let instruction = verify(&verifier_program_id, groth16_proof.clone());
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+
+    let committed_values_digest = hash_public_inputs(&groth16_proof.sp1_public_inputs);
+    let instruction = assert_recorded(&verifier_program_id, committed_values_digest);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::RecordNotFound)
+    );
+
+    let instruction = verify_and_store(&verifier_program_id, &payer, groth16_proof);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    let instruction = assert_recorded(&verifier_program_id, committed_values_digest);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+}
+
+#[tokio::test]
+async fn test_cpi() {
+    let (mut context, verifier_program_id, caller_program_id) = start().await;
+
+    let groth16_proof = load_groth16_proof();
+    let instruction = caller_instruction(&caller_program_id, &verifier_program_id, &groth16_proof);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+
+    // Errors of the example program are returned unchanged through the CPI.
+    let mut groth16_proof = load_groth16_proof();
+    groth16_proof.sp1_public_inputs[0] ^= 1;
+    let instruction = caller_instruction(&caller_program_id, &verifier_program_id, &groth16_proof);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidProof)
+    );
+}
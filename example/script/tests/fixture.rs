@@ -0,0 +1,62 @@
+//! Saves the fibonacci proof as a fixture in each format of `--fixture-format`, and reloads it.
+
+mod common;
+
+use common::load_groth16_proof;
+use example_script::fixture::{decode_fixture, encode_fixture, save_fixture, FixtureFormat};
+use fibonacci_verifier_contract::FIBONACCI_VKEY_HASH;
+use sp1_solana::{SP1ProofFixture, GROTH16_VK_2_0_0_BYTES, GROTH16_VK_3_0_0_BYTES};
+
+fn fixture() -> SP1ProofFixture {
+    let groth16_proof = load_groth16_proof();
+    SP1ProofFixture::from_components(
+        &groth16_proof.proof,
+        &groth16_proof.sp1_public_inputs,
+        &FIBONACCI_VKEY_HASH,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_save_fixture() {
+    let fixture = fixture();
+    for format in [FixtureFormat::Bin, FixtureFormat::Json, FixtureFormat::Hex] {
+        let path = std::env::temp_dir().join(format!(
+            "fibonacci_fixture_{}_{format:?}",
+            std::process::id()
+        ));
+        let reloaded = save_fixture(&fixture, &path, format, GROTH16_VK_3_0_0_BYTES).unwrap();
+        assert_eq!(reloaded, fixture);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    // The binary format is the one `SP1ProofFixture::load` reads.
+    let path = std::env::temp_dir().join(format!("fibonacci_fixture_{}", std::process::id()));
+    save_fixture(&fixture, &path, FixtureFormat::Bin, GROTH16_VK_3_0_0_BYTES).unwrap();
+    assert_eq!(SP1ProofFixture::load(&path).unwrap(), fixture);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_save_fixture_checks_verification() {
+    let path = std::env::temp_dir().join(format!("fibonacci_fixture_bad_{}", std::process::id()));
+    assert!(save_fixture(
+        &fixture(),
+        &path,
+        FixtureFormat::Bin,
+        GROTH16_VK_2_0_0_BYTES
+    )
+    .is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_decode_malformed_fixture() {
+    let json = encode_fixture(&fixture(), FixtureFormat::Json);
+    let json = String::from_utf8(json)
+        .unwrap()
+        .replace("\"proof\": \"0x", "\"proof\": \"0x00");
+    assert!(decode_fixture(json.as_bytes(), FixtureFormat::Json).is_err());
+    assert!(decode_fixture(b"not hex", FixtureFormat::Hex).is_err());
+    assert!(decode_fixture(&[0; 10], FixtureFormat::Bin).is_err());
+}
@@ -0,0 +1,280 @@
+//! Submits malformed instruction data to the example program: empty, truncated, oversized and
+//! random. Each must fail with its custom error code, and never panic.
+
+mod common;
+
+use common::{load_groth16_proof, start};
+use fibonacci_verifier_contract::{
+    FibonacciVerifierError, VerifierInstruction, FIBONACCI_VKEY_HASH, MAX_PUBLIC_VALUES_LEN,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use sp1_solana::{values_account::MAX_CHUNK_LEN, SP1ProofFixture, FIXTURE_MIN_LEN};
+
+/// Sends `data` to the program, returning the error code it failed with.
+///
+/// Panics if the transaction succeeded, didn't fail with a custom error, or logged a panic.
+async fn error_code(context: &mut ProgramTestContext, program_id: Pubkey, data: &[u8]) -> u32 {
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let instruction = Instruction::new_with_bytes(program_id, data, vec![]);
+    let mut transaction =
+        Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer], recent_blockhash);
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+
+    let logs = result.metadata.unwrap().log_messages;
+    assert!(
+        logs.iter().all(|log| !log.contains("panicked")),
+        "{data:?} panicked: {logs:#?}"
+    );
+    match result.result {
+        Err(TransactionError::InstructionError(0, InstructionError::Custom(code))) => code,
+        result => panic!("{data:?} didn't fail with a custom error: {result:?}"),
+    }
+}
+
+async fn assert_error(
+    context: &mut ProgramTestContext,
+    program_id: Pubkey,
+    data: &[u8],
+    error: FibonacciVerifierError,
+) {
+    assert_eq!(
+        error_code(context, program_id, data).await,
+        error as u32,
+        "{data:?}"
+    );
+}
+
+fn verify_data() -> Vec<u8> {
+    borsh::to_vec(&VerifierInstruction::Verify(load_groth16_proof())).unwrap()
+}
+
+fn fixture() -> SP1ProofFixture {
+    let groth16_proof = load_groth16_proof();
+    SP1ProofFixture::from_components(
+        &groth16_proof.proof,
+        &groth16_proof.sp1_public_inputs,
+        &FIBONACCI_VKEY_HASH,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_empty_and_unknown() {
+    let (mut context, program_id) = start().await;
+
+    assert_error(
+        &mut context,
+        program_id,
+        &[],
+        FibonacciVerifierError::InvalidInstructionData,
+    )
+    .await;
+    for discriminator in [20, 0x7f, 0xff] {
+        assert_error(
+            &mut context,
+            program_id,
+            &[discriminator],
+            FibonacciVerifierError::UnknownInstruction,
+        )
+        .await;
+    }
+
+    // Every instruction but `InitializeAllowlist` (17) has fields, so a lone discriminator is
+    // truncated.
+    for discriminator in (0..17).chain(18..20) {
+        assert_error(
+            &mut context,
+            program_id,
+            &[discriminator],
+            FibonacciVerifierError::InvalidInstructionData,
+        )
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn test_truncated_borsh() {
+    let (mut context, program_id) = start().await;
+
+    let data = verify_data();
+    for len in [2, 5, 100, 264, 265, data.len() - 1] {
+        assert_error(
+            &mut context,
+            program_id,
+            &data[..len],
+            FibonacciVerifierError::InvalidInstructionData,
+        )
+        .await;
+    }
+
+    let data = borsh::to_vec(&VerifierInstruction::VerifyFixture(fixture())).unwrap();
+    for len in [2, 300, data.len() - 1] {
+        assert_error(
+            &mut context,
+            program_id,
+            &data[..len],
+            FibonacciVerifierError::InvalidInstructionData,
+        )
+        .await;
+    }
+
+    // Trailing bytes after a valid instruction.
+    let mut data = verify_data();
+    data.push(0);
+    assert_error(
+        &mut context,
+        program_id,
+        &data,
+        FibonacciVerifierError::InvalidInstructionData,
+    )
+    .await;
+
+    // A length prefix far beyond the instruction data.
+    let mut data = verify_data();
+    data[1..5].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_error(
+        &mut context,
+        program_id,
+        &data,
+        FibonacciVerifierError::InvalidInstructionData,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_oversized_payloads() {
+    let (mut context, program_id) = start().await;
+
+    // A valid proof followed by extra bytes.
+    let mut groth16_proof = load_groth16_proof();
+    groth16_proof.proof.extend_from_slice(&[0; 32]);
+    let data = borsh::to_vec(&VerifierInstruction::Verify(groth16_proof)).unwrap();
+    assert_error(
+        &mut context,
+        program_id,
+        &data,
+        FibonacciVerifierError::PayloadTooLarge,
+    )
+    .await;
+
+    let mut groth16_proof = load_groth16_proof();
+    groth16_proof.sp1_public_inputs = vec![0; MAX_PUBLIC_VALUES_LEN + 1].into();
+    let data = borsh::to_vec(&VerifierInstruction::VerifyAndStore(groth16_proof)).unwrap();
+    assert_error(
+        &mut context,
+        program_id,
+        &data,
+        FibonacciVerifierError::PayloadTooLarge,
+    )
+    .await;
+
+    let data = borsh::to_vec(&VerifierInstruction::VerifyFromAccount {
+        proof_offset: 0,
+        sp1_public_inputs: vec![0; MAX_PUBLIC_VALUES_LEN + 1],
+    })
+    .unwrap();
+    assert_error(
+        &mut context,
+        program_id,
+        &data,
+        FibonacciVerifierError::PayloadTooLarge,
+    )
+    .await;
+
+    let mut fixture = fixture();
+    fixture.sp1_public_inputs = vec![0; MAX_PUBLIC_VALUES_LEN + 1].into();
+    let data = borsh::to_vec(&VerifierInstruction::VerifyFixture(fixture)).unwrap();
+    assert_error(
+        &mut context,
+        program_id,
+        &data,
+        FibonacciVerifierError::PayloadTooLarge,
+    )
+    .await;
+
+    let data = borsh::to_vec(&VerifierInstruction::WritePublicValues {
+        offset: 0,
+        bytes: vec![0; MAX_CHUNK_LEN + 1],
+    })
+    .unwrap();
+    assert_error(
+        &mut context,
+        program_id,
+        &data,
+        FibonacciVerifierError::PayloadTooLarge,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_forged_public_values_len() {
+    let (mut context, program_id) = start().await;
+
+    // A length prefix of 10 MB on the public values, followed by a few bytes of them. It's
+    // rejected before they are allocated, as too large rather than truncated.
+    let forged_len = (10u32 << 20).to_le_bytes();
+
+    let groth16_proof = load_groth16_proof();
+    let offset = 1 + 4 + groth16_proof.proof.len();
+    let mut data = verify_data();
+    data[offset..offset + 4].copy_from_slice(&forged_len);
+    data.truncate(offset + 8);
+    assert_error(
+        &mut context,
+        program_id,
+        &data,
+        FibonacciVerifierError::PayloadTooLarge,
+    )
+    .await;
+
+    let offset = 1 + FIXTURE_MIN_LEN - 4;
+    let mut data = borsh::to_vec(&VerifierInstruction::VerifyFixture(fixture())).unwrap();
+    data[offset..offset + 4].copy_from_slice(&forged_len);
+    data.truncate(offset + 8);
+    assert_error(
+        &mut context,
+        program_id,
+        &data,
+        FibonacciVerifierError::PayloadTooLarge,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_random_bytes() {
+    let (mut context, program_id) = start().await;
+
+    // A fixed xorshift sequence, so that failures reproduce.
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for _ in 0..64 {
+        // Longer than every fixed-size instruction, so that random bytes never decode.
+        let len = 330 + (next() % 600) as usize;
+        let data: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+        let values_len = u32::from_le_bytes(data[325..329].try_into().unwrap()) as usize;
+        let expected = match data[0] {
+            20.. => FibonacciVerifierError::UnknownInstruction,
+            // The fixture parser checks the length of the public values first.
+            9 if values_len > MAX_PUBLIC_VALUES_LEN => FibonacciVerifierError::PayloadTooLarge,
+            _ => FibonacciVerifierError::InvalidInstructionData,
+        };
+        assert_error(&mut context, program_id, &data, expected).await;
+    }
+}
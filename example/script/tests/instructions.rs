@@ -0,0 +1,252 @@
+//! Covers the instruction dispatcher of the example program, and the custom error code of each
+//! failure mode.
+
+mod common;
+
+use common::{custom_error, load_groth16_proof, process, start};
+use fibonacci_verifier_contract::{
+    FibonacciVerifierError, VerifierInstruction, FIBONACCI_VKEY_HASH,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, system_program,
+};
+use sp1_solana::{hash_public_inputs, record::find_verification_record_address, SP1ProofFixture};
+
+#[tokio::test]
+async fn test_malformed_instruction_data() {
+    let (mut context, program_id) = start().await;
+
+    let instruction = Instruction::new_with_bytes(program_id, &[0xff, 1, 2, 3], vec![]);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::UnknownInstruction)
+    );
+
+    let instruction = Instruction::new_with_bytes(program_id, &[], vec![]);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidInstructionData)
+    );
+
+    // A known discriminator with truncated fields.
+    let instruction = Instruction::new_with_bytes(program_id, &[0, 1, 2], vec![]);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidInstructionData)
+    );
+}
+
+#[tokio::test]
+async fn test_verify() {
+    let (mut context, program_id) = start().await;
+
+    let instruction = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::Verify(load_groth16_proof()),
+        vec![],
+    );
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+
+    let mut groth16_proof = load_groth16_proof();
+    groth16_proof.sp1_public_inputs[0] ^= 1;
+    let instruction = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::Verify(groth16_proof),
+        vec![],
+    );
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidProof)
+    );
+
+    let mut groth16_proof = load_groth16_proof();
+    groth16_proof.proof[0] ^= 1;
+    let instruction = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::Verify(groth16_proof),
+        vec![],
+    );
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::Groth16VkeyHashMismatch)
+    );
+
+    let mut groth16_proof = load_groth16_proof();
+    groth16_proof.proof.truncate(100);
+    let instruction = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::Verify(groth16_proof),
+        vec![],
+    );
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::TruncatedProof)
+    );
+}
+
+#[tokio::test]
+async fn test_verify_fixture() {
+    let (mut context, program_id) = start().await;
+
+    let groth16_proof = load_groth16_proof();
+    let fixture = SP1ProofFixture::from_components(
+        &groth16_proof.proof,
+        &groth16_proof.sp1_public_inputs,
+        &FIBONACCI_VKEY_HASH,
+    )
+    .unwrap();
+    let verify_fixture = |fixture: SP1ProofFixture| {
+        Instruction::new_with_borsh(
+            program_id,
+            &VerifierInstruction::VerifyFixture(fixture),
+            vec![],
+        )
+    };
+
+    assert_eq!(
+        process(&mut context, verify_fixture(fixture.clone()), &[]).await,
+        Ok(())
+    );
+
+    // Each induced failure has its own error code.
+    let instruction = Instruction::new_with_bytes(program_id, &[9, 1, 2, 3], vec![]);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidInstructionData)
+    );
+
+    let mut tampered = fixture.clone();
+    tampered.groth16_vkey_hash = sp1_solana::GROTH16_VK_2_0_0_HASH;
+    assert_eq!(
+        process(&mut context, verify_fixture(tampered), &[]).await,
+        custom_error(FibonacciVerifierError::Groth16VkeyHashMismatch)
+    );
+
+    let mut tampered = fixture.clone();
+    tampered.sp1_vkey_hash[31] ^= 1;
+    assert_eq!(
+        process(&mut context, verify_fixture(tampered), &[]).await,
+        custom_error(FibonacciVerifierError::ProgramVkeyHashMismatch)
+    );
+
+    let mut tampered = fixture.clone();
+    tampered.sp1_public_inputs[0] ^= 1;
+    assert_eq!(
+        process(&mut context, verify_fixture(tampered.clone()), &[]).await,
+        custom_error(FibonacciVerifierError::DigestMismatch)
+    );
+
+    // With a matching digest, the tampered values reach the pairing check.
+    tampered.committed_values_digest = hash_public_inputs(&tampered.sp1_public_inputs);
+    assert_eq!(
+        process(&mut context, verify_fixture(tampered), &[]).await,
+        custom_error(FibonacciVerifierError::InvalidProof)
+    );
+}
+
+#[tokio::test]
+async fn test_assert_and_close_record() {
+    let (mut context, program_id) = start().await;
+
+    let groth16_proof = load_groth16_proof();
+    let committed_values_digest = hash_public_inputs(&groth16_proof.sp1_public_inputs);
+    let (record_address, _) = find_verification_record_address(
+        &program_id,
+        &FIBONACCI_VKEY_HASH,
+        &committed_values_digest,
+    );
+
+    let assert_recorded = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::AssertRecorded {
+            committed_values_digest,
+        },
+        vec![AccountMeta::new_readonly(record_address, false)],
+    );
+    let close_record = |submitter: Pubkey| {
+        Instruction::new_with_borsh(
+            program_id,
+            &VerifierInstruction::CloseRecord {
+                committed_values_digest,
+            },
+            vec![
+                AccountMeta::new(submitter, true),
+                AccountMeta::new(record_address, false),
+            ],
+        )
+    };
+
+    // Nothing is recorded yet.
+    assert_eq!(
+        process(&mut context, assert_recorded.clone(), &[]).await,
+        custom_error(FibonacciVerifierError::RecordNotFound)
+    );
+
+    // A record must be read from its PDA.
+    let wrong_record = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::AssertRecorded {
+            committed_values_digest,
+        },
+        vec![AccountMeta::new_readonly(Pubkey::new_unique(), false)],
+    );
+    assert_eq!(
+        process(&mut context, wrong_record, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidPdaAddress)
+    );
+
+    let payer = context.payer.pubkey();
+    let verify_and_store = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::VerifyAndStore(groth16_proof),
+        vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(record_address, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    );
+    assert_eq!(process(&mut context, verify_and_store, &[]).await, Ok(()));
+    assert_eq!(
+        process(&mut context, assert_recorded.clone(), &[]).await,
+        Ok(())
+    );
+
+    // Only the submitter can close the record.
+    let other = Keypair::new();
+    let fund_other = system_instruction::transfer(&payer, &other.pubkey(), 1_000_000_000);
+    assert_eq!(process(&mut context, fund_other, &[]).await, Ok(()));
+    assert_eq!(
+        process(&mut context, close_record(other.pubkey()), &[&other]).await,
+        custom_error(FibonacciVerifierError::UnauthorizedClose)
+    );
+
+    // Closing returns the rent to the submitter, and deletes the record.
+    let record_lamports = context
+        .banks_client
+        .get_balance(record_address)
+        .await
+        .unwrap();
+    let balance_before = context.banks_client.get_balance(payer).await.unwrap();
+    assert_eq!(
+        process(&mut context, close_record(payer), &[]).await,
+        Ok(())
+    );
+    let balance_after = context.banks_client.get_balance(payer).await.unwrap();
+    // The payer also paid the transaction fee.
+    assert!(balance_after > balance_before);
+    assert!(balance_after <= balance_before + record_lamports);
+    assert!(context
+        .banks_client
+        .get_account(record_address)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        process(&mut context, assert_recorded, &[]).await,
+        custom_error(FibonacciVerifierError::RecordNotFound)
+    );
+}
@@ -0,0 +1,81 @@
+//! Runs the public values and account logic of the example program with the test-only mock
+//! verifier of `sp1-solana`.
+//!
+//! ```shell
+//! cargo test -p example-script --features mock-verifier --test mock
+//! ```
+#![cfg(feature = "mock-verifier")]
+
+mod common;
+
+use common::{custom_error, process, start};
+use example_script::mock::{mock_proof, run_mock_submit};
+use fibonacci_verifier_contract::{
+    instruction::{assert_recorded, verify, verify_and_nullify, verify_and_store},
+    FibonacciVerifierError,
+};
+use solana_sdk::signer::Signer;
+use sp1_solana::{hash_public_inputs, GROTH16_VK_3_0_0_RC4_HASH, SELECTOR_LEN};
+
+/// The public values `(20, 6765, 3027)` of the fibonacci program.
+fn fibonacci_public_values() -> Vec<u8> {
+    borsh::to_vec(&(20u32, 6765u32, 3027u32)).unwrap()
+}
+
+#[tokio::test]
+async fn test_mock_submit() {
+    run_mock_submit(fibonacci_public_values()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_mock_verifier_keeps_account_checks() {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+
+    // The pairing isn't checked, but the public values must still decode.
+    let instruction = verify(&program_id, mock_proof(fibonacci_public_values()));
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    let instruction = verify(&program_id, mock_proof(vec![1, 2, 3]));
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidPublicValues)
+    );
+
+    // Nullifiers still prevent replays.
+    let groth16_proof = mock_proof(fibonacci_public_values());
+    let instruction = verify_and_nullify(&program_id, &payer, groth16_proof.clone());
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    let instruction = verify_and_nullify(&program_id, &payer, groth16_proof);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::NullifierAlreadyUsed)
+    );
+}
+
+#[tokio::test]
+async fn test_mock_verifier_checks_public_values() {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+
+    // The selector is checked: a mock proof for another Groth16 key is rejected.
+    let mut groth16_proof = mock_proof(fibonacci_public_values());
+    groth16_proof.proof[..SELECTOR_LEN].copy_from_slice(&GROTH16_VK_3_0_0_RC4_HASH);
+    let instruction = verify(&program_id, groth16_proof);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::Groth16VkeyHashMismatch)
+    );
+
+    // The record is keyed by the digest of the public values the proof was submitted with.
+    let instruction = verify_and_store(&program_id, &payer, mock_proof(fibonacci_public_values()));
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    let digest = hash_public_inputs(&fibonacci_public_values());
+    let instruction = assert_recorded(&program_id, digest);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    let other_values = borsh::to_vec(&(21u32, 10946u32, 6765u32)).unwrap();
+    let instruction = assert_recorded(&program_id, hash_public_inputs(&other_values));
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::RecordNotFound)
+    );
+}
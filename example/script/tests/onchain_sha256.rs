@@ -0,0 +1,117 @@
+//! Checks the `sol_sha256` syscall path of the verifier's SHA-256 against the host's `sha2`, in
+//! the BPF build of the fibonacci verifier program.
+//!
+//! Build the program first, then run the test with the `cu-regression` feature:
+//!
+//! ```shell
+//! cargo build-sbf --manifest-path example/program/Cargo.toml --sbf-out-dir target/deploy
+//! SBF_OUT_DIR=$PWD/target/deploy cargo test -p example-script --features cu-regression --test onchain_sha256 -- --nocapture
+//! ```
+#![cfg(feature = "cu-regression")]
+
+use example_script::cu::simulate;
+use fibonacci_verifier_contract::{SP1Groth16Proof, VerifierInstruction, HASH_ACCOUNT_LEN};
+use sha2::{Digest, Sha256};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_solana::{event::parse_verification_events, values_account::MAX_CHUNK_LEN};
+
+/// A bank running the BPF build of the program.
+fn program_test(program_id: Pubkey) -> ProgramTest {
+    let mut program_test = ProgramTest::new("fibonacci_verifier_contract", program_id, None);
+    program_test.prefer_bpf(true);
+    program_test
+}
+
+/// The compute units the `sol_sha256` syscall charges to hash `len` bytes in a single slice: a
+/// base cost of 85, then one unit per 2 bytes.
+const fn sha256_syscall_cost(len: usize) -> u64 {
+    85 + len as u64 / 2
+}
+
+#[tokio::test]
+async fn test_onchain_digest_matches_host() {
+    let program_id = Pubkey::new_unique();
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let public_values = sp1_proof_with_public_values.public_values.to_vec();
+    let instruction = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::Verify(SP1Groth16Proof {
+            proof: sp1_proof_with_public_values.bytes(),
+            sp1_public_inputs: public_values.clone().into(),
+        }),
+        vec![],
+    );
+
+    // The program hashes the public values with the syscall, and logs the digest in its event.
+    let simulation = simulate(program_test(program_id), instruction, &[]).await;
+    let events = parse_verification_events(&simulation.logs);
+    assert_eq!(events.len(), 1);
+
+    let mut expected: [u8; 32] = Sha256::digest(&public_values).into();
+    expected[0] &= 0b0001_1111;
+    assert_eq!(events[0].committed_values_digest, expected);
+}
+
+/// `AbsorbPublicValues` hashes its chunk with the `sha2` compression function in BPF, as the
+/// verifier did before it used the syscall. The syscall must be at least 10 times cheaper than
+/// the difference of an absorbed chunk with an empty one, which also covers decoding the chunk.
+#[tokio::test]
+async fn test_sha256_syscall_cu_saving() {
+    let program_id = Pubkey::new_unique();
+    let authority = Keypair::new();
+
+    // Absorbs `len` bytes into a fresh hash account.
+    let absorb = |len: usize| {
+        let hash_account = Pubkey::new_unique();
+        let mut program_test = program_test(program_id);
+        program_test.add_account(
+            hash_account,
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![0; HASH_ACCOUNT_LEN],
+                owner: program_id,
+                ..Account::default()
+            },
+        );
+        let instruction = Instruction::new_with_borsh(
+            program_id,
+            &VerifierInstruction::AbsorbPublicValues {
+                bytes: vec![0x5a; len],
+            },
+            vec![
+                AccountMeta::new_readonly(authority.pubkey(), true),
+                AccountMeta::new(hash_account, false),
+            ],
+        );
+        (program_test, instruction)
+    };
+
+    // Whole blocks only, so that all of them are compressed.
+    let len = MAX_CHUNK_LEN / 64 * 64;
+    let (bank, instruction) = absorb(0);
+    let empty = simulate(bank, instruction, &[&authority])
+        .await
+        .units_consumed;
+    let (bank, instruction) = absorb(len);
+    let full = simulate(bank, instruction, &[&authority])
+        .await
+        .units_consumed;
+
+    let in_bpf = full - empty;
+    let syscall = sha256_syscall_cost(len);
+    println!("hashing {len} bytes: {in_bpf} compute units in BPF, {syscall} with the syscall");
+    assert!(
+        in_bpf >= 10 * syscall,
+        "hashing {len} bytes in BPF cost {in_bpf} compute units, less than 10 times the \
+         {syscall} of the syscall"
+    );
+}
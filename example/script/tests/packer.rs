@@ -0,0 +1,281 @@
+//! Packs verify instructions into transactions within the size, compute unit and account limits.
+
+mod common;
+
+use common::load_groth16_proof;
+use example_script::packer::{
+    PackError, PackItem, PackLimits, PackedTransaction, TransactionPacker, COMPUTE_BUDGET_UNITS,
+};
+use fibonacci_verifier_contract::FIBONACCI_VKEY_HASH;
+use proptest::prelude::*;
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use sp1_solana::SP1ProofFixture;
+
+/// The accounts the generated instructions reference, all in the lookup table of the tests.
+const POOL_LEN: u8 = 24;
+
+fn pool_account(index: u8) -> Pubkey {
+    Pubkey::new_from_array([index + 1; 32])
+}
+
+fn lookup_table() -> AddressLookupTableAccount {
+    AddressLookupTableAccount {
+        key: Pubkey::new_from_array([0xff; 32]),
+        addresses: (0..POOL_LEN).map(pool_account).collect(),
+    }
+}
+
+/// Checks that each transaction is within `limits`, and that together they hold `items`, each
+/// exactly once and in order.
+fn check_packing(
+    items: &[PackItem],
+    packed: &[PackedTransaction],
+    limits: &PackLimits,
+    lookup_tables: &[AddressLookupTableAccount],
+) {
+    let mut next_item = 0;
+    for PackedTransaction {
+        transaction,
+        utilization,
+    } in packed
+    {
+        assert!(limits.allow(utilization), "{utilization}");
+        assert_eq!(
+            bincode::serialized_size(transaction).unwrap() as usize,
+            utilization.size
+        );
+        assert!(utilization.size <= PACKET_DATA_SIZE);
+        assert_eq!(utilization.items.start, next_item);
+        assert!(!utilization.items.is_empty());
+        next_item = utilization.items.end;
+
+        // The static accounts, then those loaded from lookup tables, writable ones first.
+        let message = &transaction.message;
+        let mut account_keys = message.static_account_keys().to_vec();
+        let lookups = message.address_table_lookups().unwrap_or_default();
+        for writable in [true, false] {
+            for lookup in lookups {
+                let table = lookup_tables
+                    .iter()
+                    .find(|table| table.key == lookup.account_key)
+                    .unwrap();
+                let indexes = if writable {
+                    &lookup.writable_indexes
+                } else {
+                    &lookup.readonly_indexes
+                };
+                account_keys.extend(indexes.iter().map(|&i| table.addresses[usize::from(i)]));
+            }
+        }
+        assert_eq!(account_keys.len(), utilization.accounts);
+
+        let instructions = message.instructions();
+        assert_eq!(instructions.len(), 1 + utilization.items.len());
+        let compute_budget =
+            ComputeBudgetInstruction::set_compute_unit_limit(utilization.compute_units as u32);
+        assert_eq!(instructions[0].data, compute_budget.data);
+        assert_eq!(
+            utilization.compute_units,
+            items[utilization.items.clone()]
+                .iter()
+                .map(|item| item.compute_units)
+                .sum::<u64>()
+                + COMPUTE_BUDGET_UNITS
+        );
+        for (compiled, item) in instructions[1..]
+            .iter()
+            .zip(&items[utilization.items.clone()])
+        {
+            let instruction = &item.instruction;
+            assert_eq!(
+                account_keys[usize::from(compiled.program_id_index)],
+                instruction.program_id
+            );
+            assert_eq!(compiled.data, instruction.data);
+            let accounts: Vec<Pubkey> = compiled
+                .accounts
+                .iter()
+                .map(|&i| account_keys[usize::from(i)])
+                .collect();
+            let expected: Vec<Pubkey> = instruction
+                .accounts
+                .iter()
+                .map(|account| account.pubkey)
+                .collect();
+            assert_eq!(accounts, expected);
+        }
+    }
+    assert_eq!(next_item, items.len());
+}
+
+/// An instruction of one of two programs, with `data_len` bytes of data and the pool accounts
+/// of `accounts`, each writable or not.
+fn item(
+    index: usize,
+    program: bool,
+    data_len: usize,
+    accounts: &[(u8, bool)],
+    compute_units: u64,
+) -> PackItem {
+    let program_id = Pubkey::new_from_array([0xf0 + u8::from(program); 32]);
+    let accounts = accounts
+        .iter()
+        .map(|&(account, writable)| {
+            if writable {
+                AccountMeta::new(pool_account(account), false)
+            } else {
+                AccountMeta::new_readonly(pool_account(account), false)
+            }
+        })
+        .collect();
+    PackItem::new(
+        Instruction::new_with_bytes(program_id, &vec![index as u8; data_len], accounts),
+        compute_units,
+    )
+}
+
+proptest! {
+    #[test]
+    fn test_packing_respects_limits(
+        max_compute_units in 200_000u64..=1_400_000,
+        max_accounts in 12usize..=64,
+        use_lookup_table in any::<bool>(),
+        specs in prop::collection::vec(
+            (
+                any::<bool>(),
+                0usize..700,
+                prop::collection::vec((0..POOL_LEN, any::<bool>()), 0..8),
+                0u64..=1_000,
+            ),
+            0..24,
+        ),
+    ) {
+        // Every item fits in a transaction of its own.
+        let items: Vec<PackItem> = specs
+            .iter()
+            .enumerate()
+            .map(|(index, (program, data_len, accounts, permille))| {
+                let compute_units = (max_compute_units - COMPUTE_BUDGET_UNITS) * permille / 1_000;
+                item(index, *program, *data_len, accounts, compute_units)
+            })
+            .collect();
+        let limits = PackLimits {
+            max_compute_units,
+            max_accounts,
+            ..PackLimits::default()
+        };
+        let mut packer = TransactionPacker::new(Pubkey::new_unique(), limits.clone());
+        let mut lookup_tables = vec![];
+        if use_lookup_table {
+            packer = packer.with_lookup_table(lookup_table());
+            lookup_tables.push(lookup_table());
+        }
+
+        let packed = packer.pack(items.clone(), Hash::new_unique()).unwrap();
+        check_packing(&items, &packed, &limits, &lookup_tables);
+    }
+}
+
+#[test]
+fn test_pack_verify_instructions() {
+    let program_id = Pubkey::new_unique();
+    let payer = Keypair::new();
+    let groth16_proof = load_groth16_proof();
+    let fixture = SP1ProofFixture::from_components(
+        &groth16_proof.proof,
+        &groth16_proof.sp1_public_inputs,
+        &FIBONACCI_VKEY_HASH,
+    )
+    .unwrap();
+    let items = |compute_units| -> Vec<PackItem> {
+        (0..5)
+            .flat_map(|_| {
+                [
+                    PackItem::verify(&program_id, groth16_proof.clone(), compute_units),
+                    PackItem::verify_fixture(&program_id, fixture.clone(), compute_units),
+                ]
+            })
+            .collect()
+    };
+
+    // Bound by the transaction size rather than the compute units.
+    let limits = PackLimits::default();
+    let packer = TransactionPacker::new(payer.pubkey(), limits.clone());
+    let packed = packer.pack(items(1), Hash::new_unique()).unwrap();
+    check_packing(&items(1), &packed, &limits, &[]);
+    assert!(packed[0].utilization.items.len() > 1);
+    assert!(packed.len() < 10);
+
+    // Bound by the compute units.
+    let limits = PackLimits {
+        max_compute_units: 400_000,
+        ..PackLimits::default()
+    };
+    let packer = TransactionPacker::new(payer.pubkey(), limits.clone());
+    let packed = packer.pack(items(300_000), Hash::new_unique()).unwrap();
+    check_packing(&items(300_000), &packed, &limits, &[]);
+    assert_eq!(packed.len(), 10);
+
+    // Signing replaces the placeholder signatures, leaving the size unchanged.
+    let signed = packed[0].clone().sign(&[&payer]).unwrap();
+    assert!(signed.verify_with_results().into_iter().all(|valid| valid));
+    assert_eq!(
+        bincode::serialized_size(&signed).unwrap() as usize,
+        packed[0].utilization.size
+    );
+
+    // An item over the compute units on its own.
+    let mut items = items(300_000);
+    items[3].compute_units = 400_000;
+    let error = packer.pack(items, Hash::new_unique()).unwrap_err();
+    let PackError::ExceedsLimits { index, utilization } = error else {
+        panic!("{error}");
+    };
+    assert_eq!(index, 3);
+    assert_eq!(utilization.compute_units, 400_150);
+}
+
+#[test]
+fn test_pack_with_lookup_table() {
+    // Each item references the same 6 accounts, which a lookup table loads in a byte each.
+    let accounts: Vec<(u8, bool)> = (0..6).map(|account| (account, false)).collect();
+    let items: Vec<PackItem> = (0..18)
+        .map(|index| item(index, false, 100, &accounts, 1_000))
+        .collect();
+    let limits = PackLimits::default();
+    let payer = Pubkey::new_unique();
+
+    let packed = TransactionPacker::new(payer, limits.clone())
+        .pack(items.clone(), Hash::new_unique())
+        .unwrap();
+    check_packing(&items, &packed, &limits, &[]);
+
+    let packer = TransactionPacker::new(payer, limits.clone()).with_lookup_table(lookup_table());
+    let packed_with_table = packer.pack(items.clone(), Hash::new_unique()).unwrap();
+    check_packing(&items, &packed_with_table, &limits, &[lookup_table()]);
+    assert!(packed_with_table.len() < packed.len());
+
+    // The account limit counts the accounts loaded from the table.
+    let limits = PackLimits {
+        max_accounts: 8,
+        ..PackLimits::default()
+    };
+    let error = TransactionPacker::new(payer, limits)
+        .with_lookup_table(lookup_table())
+        .pack(items, Hash::new_unique())
+        .unwrap_err();
+    let PackError::ExceedsLimits { index, utilization } = error else {
+        panic!("{error}");
+    };
+    assert_eq!(index, 0);
+    assert_eq!(utilization.accounts, 9);
+}
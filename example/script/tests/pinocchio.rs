@@ -0,0 +1,77 @@
+//! Compares the `pinocchio` verifier with the fibonacci verifier program: the size of their
+//! `.so` files and the compute units they consume to verify the same proof.
+//!
+//! Build both programs first, then run the test with the `cu-regression` feature:
+//!
+//! ```shell
+//! cargo build-sbf --manifest-path example/program/Cargo.toml --sbf-out-dir target/deploy
+//! cargo build-sbf --manifest-path example/pinocchio-program/Cargo.toml --sbf-out-dir target/deploy
+//! SBF_OUT_DIR=$PWD/target/deploy cargo test -p example-script --features cu-regression --test pinocchio -- --nocapture
+//! ```
+#![cfg(feature = "cu-regression")]
+
+use example_script::cu::measure_units_consumed;
+use fibonacci_verifier_contract::{SP1Groth16Proof, VerifierInstruction};
+use solana_program_test::ProgramTest;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use sp1_sdk::SP1ProofWithPublicValues;
+use std::path::PathBuf;
+
+/// Returns the size of the `.so` file of `program_name` in `SBF_OUT_DIR`.
+fn program_size(program_name: &str) -> u64 {
+    let out_dir = std::env::var_os("SBF_OUT_DIR").expect("SBF_OUT_DIR is not set");
+    let path = PathBuf::from(out_dir).join(format!("{program_name}.so"));
+    std::fs::metadata(&path)
+        .unwrap_or_else(|_| panic!("missing {}, build it with cargo build-sbf", path.display()))
+        .len()
+}
+
+/// Measures the compute units `program_name` consumes to verify an instruction built from the
+/// program id.
+async fn units_consumed(program_name: &str, instruction: impl Fn(Pubkey) -> Instruction) -> u64 {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(program_name, program_id, None);
+    program_test.prefer_bpf(true);
+    measure_units_consumed(program_test, instruction(program_id)).await
+}
+
+#[tokio::test]
+async fn test_pinocchio_size_and_cu() {
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+    let sp1_public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+
+    let standard_units = units_consumed("fibonacci_verifier_contract", |program_id| {
+        let groth16_proof = SP1Groth16Proof {
+            proof: proof.clone(),
+            sp1_public_inputs: sp1_public_inputs.clone().into(),
+        };
+        Instruction::new_with_borsh(
+            program_id,
+            &VerifierInstruction::Verify(groth16_proof),
+            vec![],
+        )
+    })
+    .await;
+    let pinocchio_units = units_consumed("sp1_pinocchio_verifier", |program_id| {
+        Instruction::new_with_bytes(
+            program_id,
+            &[&proof[..], &sp1_public_inputs].concat(),
+            vec![],
+        )
+    })
+    .await;
+
+    let standard_size = program_size("fibonacci_verifier_contract");
+    let pinocchio_size = program_size("sp1_pinocchio_verifier");
+
+    println!("program                      .so bytes  compute units");
+    println!("fibonacci_verifier_contract  {standard_size:>9}  {standard_units:>13}");
+    println!("sp1_pinocchio_verifier       {pinocchio_size:>9}  {pinocchio_units:>13}");
+
+    assert!(
+        pinocchio_size <= standard_size,
+        "the pinocchio verifier is larger than the standard one"
+    );
+}
@@ -0,0 +1,54 @@
+//! Loads the fibonacci proof from `proofs/fibonacci_proof.json`, the sample of the JSON proof
+//! schema.
+
+mod common;
+
+use common::load_groth16_proof;
+use example_script::proof_json::ProofJson;
+use fibonacci_verifier_contract::{instruction::verify, FIBONACCI_VKEY_HASH};
+use solana_sdk::pubkey::Pubkey;
+use sp1_solana::GROTH16_VK_3_0_0_BYTES;
+
+const PROOF_JSON: &str = "../../proofs/fibonacci_proof.json";
+
+#[test]
+fn test_json_matches_bincode() {
+    let loaded = ProofJson::load(PROOF_JSON)
+        .unwrap()
+        .verify(GROTH16_VK_3_0_0_BYTES)
+        .unwrap();
+    assert_eq!(loaded.sp1_vkey_hash, FIBONACCI_VKEY_HASH);
+
+    let program_id = Pubkey::new_unique();
+    assert_eq!(
+        verify(&program_id, loaded.groth16_proof).data,
+        verify(&program_id, load_groth16_proof()).data
+    );
+}
+
+#[test]
+fn test_invalid_json_proof() {
+    let proof_json = ProofJson::load(PROOF_JSON).unwrap();
+
+    // The public inputs are optional.
+    let mut without_inputs = proof_json.clone();
+    without_inputs.public_inputs = None;
+    assert!(without_inputs.verify(GROTH16_VK_3_0_0_BYTES).is_ok());
+
+    let mut wrong_input = proof_json.clone();
+    wrong_input.public_inputs.as_mut().unwrap()[1].push('0');
+    assert!(wrong_input.verify(GROTH16_VK_3_0_0_BYTES).is_err());
+
+    let mut wrong_values = proof_json.clone();
+    wrong_values.public_values = "0x150000006d1a0000d30b0000".to_string();
+    wrong_values.public_inputs = None;
+    assert!(wrong_values.verify(GROTH16_VK_3_0_0_BYTES).is_err());
+
+    let mut unprefixed = proof_json.clone();
+    unprefixed.vkey_hash = "0".to_string();
+    assert!(unprefixed.verify(GROTH16_VK_3_0_0_BYTES).is_err());
+
+    let mut bad_hex = proof_json;
+    bad_hex.proof.push('z');
+    assert!(bad_hex.verify(GROTH16_VK_3_0_0_BYTES).is_err());
+}
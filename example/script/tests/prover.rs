@@ -0,0 +1,54 @@
+//! Covers the prover selection of the example script, end to end with the mock prover.
+
+use example_script::prover::{load_cached_proof, ProverKind, ProverOptions};
+use sp1_sdk::{include_elf, SP1Stdin};
+
+const ELF: &[u8] = include_elf!("fibonacci-program");
+
+#[test]
+fn test_network_prover_needs_private_key() {
+    let options = ProverOptions {
+        kind: ProverKind::Network,
+        private_key_env: "SP1_SOLANA_TEST_UNSET_PRIVATE_KEY".to_string(),
+        ..Default::default()
+    };
+    let error = options.client().err().unwrap();
+    assert!(
+        error.contains("$SP1_SOLANA_TEST_UNSET_PRIVATE_KEY"),
+        "{error}"
+    );
+}
+
+#[test]
+fn test_mock_prover_and_cache() {
+    let options = ProverOptions {
+        kind: ProverKind::Mock,
+        ..Default::default()
+    };
+    let client = options.client().unwrap();
+    let (pk, vk) = client.setup(ELF);
+
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&20u32);
+    let proof = options.prove_groth16(&client, &pk, stdin).unwrap();
+    let (n, a, b): (u32, u32, u32) = borsh::from_slice(proof.public_values.as_slice()).unwrap();
+    assert_eq!((n, a, b), (20, 6765, 3027));
+
+    // The cache is reused only for proofs of the same program.
+    let path = std::env::temp_dir().join(format!("fibonacci_mock_proof_{}", std::process::id()));
+    proof.save(&path).unwrap();
+    assert!(load_cached_proof(&path, &vk).is_some());
+    let (_, other_vk) = client.setup(include_elf!("token-claim-program"));
+    assert!(load_cached_proof(&path, &other_vk).is_none());
+    std::fs::remove_file(&path).unwrap();
+    assert!(load_cached_proof(&path, &vk).is_none());
+
+    // Mock proofs are only accepted by the example program's mock verifier.
+    #[cfg(feature = "mock-verifier")]
+    tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(example_script::mock::run_mock_submit(
+            proof.public_values.to_vec(),
+        ))
+        .unwrap();
+}
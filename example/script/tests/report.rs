@@ -0,0 +1,66 @@
+//! Checks the cost report printed by the example script, and the schema of its JSON output.
+
+mod common;
+
+use common::load_groth16_proof;
+use example_script::{
+    report::{priority_fee, CostReport, LAMPORTS_PER_SIGNATURE},
+    run_verify_instruction,
+};
+use serde_json::Value;
+
+#[test]
+fn test_priority_fee() {
+    assert_eq!(priority_fee(0, 1_000), 0);
+    assert_eq!(priority_fee(200_000, 0), 0);
+    assert_eq!(priority_fee(200_000, 1_000), 200);
+    // Fractions of a lamport are rounded up.
+    assert_eq!(priority_fee(1, 1), 1);
+    assert_eq!(priority_fee(1_000_001, 1), 2);
+
+    let report = CostReport::new(300_000, vec![], 2, 10_000);
+    assert_eq!(report.base_fee_lamports, 2 * LAMPORTS_PER_SIGNATURE);
+    assert_eq!(report.priority_fee_lamports, 3_000);
+    assert_eq!(report.total_fee_lamports(), 13_000);
+}
+
+#[tokio::test]
+async fn test_report_json_schema() {
+    let report = run_verify_instruction(load_groth16_proof(), 1_000).await;
+    assert!(report
+        .logs
+        .iter()
+        .any(|log| log.contains("Public values: (n: 20, a: 6765, b: 3027)")));
+
+    let json: Value = serde_json::from_str(&serde_json::to_string(&report).unwrap()).unwrap();
+    let object = json.as_object().unwrap();
+    let mut keys: Vec<&str> = object.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    assert_eq!(
+        keys,
+        [
+            "base_fee_lamports",
+            "compute_unit_price",
+            "logs",
+            "priority_fee_lamports",
+            "signature",
+            "units_consumed",
+        ]
+    );
+    assert!(object["signature"].is_null());
+    for key in [
+        "base_fee_lamports",
+        "compute_unit_price",
+        "priority_fee_lamports",
+        "units_consumed",
+    ] {
+        assert!(object[key].is_u64(), "{key} is not an integer");
+    }
+    assert!(object["logs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(Value::is_string));
+
+    assert_eq!(serde_json::from_value::<CostReport>(json).unwrap(), report);
+}
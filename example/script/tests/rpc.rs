@@ -0,0 +1,305 @@
+//! Covers the `--network` path of the example script.
+//!
+//! The retry tests run [`submit_with_retries`] against a mocked RPC sender. The localnet test
+//! needs a `solana-test-validator` running the example program:
+//!
+//! ```shell
+//! cargo build-sbf --manifest-path example/program/Cargo.toml --sbf-out-dir target/deploy
+//! solana-test-validator --reset --bpf-program <PROGRAM_ID> target/deploy/fibonacci_verifier_contract.so
+//! FIBONACCI_PROGRAM_ID=<PROGRAM_ID> cargo test -p example-script --test rpc -- --ignored
+//! ```
+
+mod common;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use common::load_groth16_proof;
+use example_script::{
+    report::DEFAULT_COMPUTE_UNIT_PRICE,
+    rpc::{
+        check_program_deployed, send_verify_transaction, submit_with_retries, Network, RetryPolicy,
+        SubmitError, DEFAULT_COMPUTE_UNIT_LIMIT,
+    },
+};
+use fibonacci_verifier_contract::FibonacciVerifierError;
+use serde_json::{json, Value};
+use solana_client::{
+    client_error::{ClientError, Result as ClientResult},
+    nonblocking::rpc_client::RpcClient,
+    rpc_client::RpcClientConfig,
+    rpc_request::{RpcError, RpcRequest, RpcResponseErrorData},
+    rpc_response::RpcSimulateTransactionResult,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, hash::Hash, native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction,
+    transaction::Transaction,
+};
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// An RPC sender whose `sendTransaction` fails with `fail(attempt)` until it returns `None`.
+struct MockSender {
+    fail: fn(u32) -> Option<ClientError>,
+    sends: Arc<AtomicU32>,
+    blockhashes: Arc<AtomicU32>,
+    skip_preflight: bool,
+}
+
+#[async_trait::async_trait]
+impl RpcSender for MockSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let context = json!({ "slot": 1 });
+        Ok(match request {
+            RpcRequest::GetVersion => json!({ "solana-core": "2.1.6", "feature-set": 0 }),
+            RpcRequest::GetLatestBlockhash => {
+                self.blockhashes.fetch_add(1, Ordering::SeqCst);
+                json!({
+                    "context": context,
+                    "value": {
+                        "blockhash": Hash::new_unique().to_string(),
+                        "lastValidBlockHeight": 100,
+                    },
+                })
+            }
+            RpcRequest::SendTransaction => {
+                assert_eq!(params[1]["skipPreflight"], self.skip_preflight);
+                let attempt = self.sends.fetch_add(1, Ordering::SeqCst) + 1;
+                if let Some(error) = (self.fail)(attempt) {
+                    return Err(error);
+                }
+                let wire_transaction = STANDARD.decode(params[0].as_str().unwrap()).unwrap();
+                let transaction: Transaction = bincode::deserialize(&wire_transaction).unwrap();
+                json!(transaction.signatures[0].to_string())
+            }
+            RpcRequest::GetSignatureStatuses => json!({
+                "context": context,
+                "value": [{
+                    "slot": 1,
+                    "confirmations": null,
+                    "status": { "Ok": null },
+                    "err": null,
+                    "confirmationStatus": "confirmed",
+                }],
+            }),
+            RpcRequest::IsBlockhashValid => json!({ "context": context, "value": true }),
+            request => panic!("unexpected request {request}"),
+        })
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "mock".to_string()
+    }
+}
+
+/// Submits a transfer through a [`MockSender`], returning the result and the number of sends and
+/// blockhash fetches.
+async fn submit_mocked(
+    fail: fn(u32) -> Option<ClientError>,
+    skip_preflight: bool,
+) -> (Result<(), SubmitError>, u32, u32) {
+    let sends = Arc::new(AtomicU32::new(0));
+    let blockhashes = Arc::new(AtomicU32::new(0));
+    let client = RpcClient::new_sender(
+        MockSender {
+            fail,
+            sends: sends.clone(),
+            blockhashes: blockhashes.clone(),
+            skip_preflight,
+        },
+        RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
+    );
+    let policy = RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(2),
+        poll_interval: Duration::from_millis(1),
+        skip_preflight,
+    };
+    let payer = Keypair::new();
+    let instruction = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+    let result = submit_with_retries(
+        &client,
+        |blockhash| {
+            Transaction::new_signed_with_payer(
+                &[instruction.clone()],
+                Some(&payer.pubkey()),
+                &[&payer],
+                blockhash,
+            )
+        },
+        &policy,
+    )
+    .await
+    .map(|_| ());
+    (
+        result,
+        sends.load(Ordering::SeqCst),
+        blockhashes.load(Ordering::SeqCst),
+    )
+}
+
+/// A preflight failure of `sendTransaction`, as returned by an RPC node.
+fn preflight_failure(err: Value, logs: &[&str]) -> ClientError {
+    let simulation: RpcSimulateTransactionResult =
+        serde_json::from_value(json!({ "err": err, "logs": logs })).unwrap();
+    RpcError::RpcResponseError {
+        code: -32002,
+        message: "Transaction simulation failed".to_string(),
+        data: RpcResponseErrorData::SendTransactionPreflightFailure(simulation),
+    }
+    .into()
+}
+
+fn node_behind() -> ClientError {
+    RpcError::RpcResponseError {
+        code: -32005,
+        message: "Node is behind by 42 slots".to_string(),
+        data: RpcResponseErrorData::NodeUnhealthy {
+            num_slots_behind: Some(42),
+        },
+    }
+    .into()
+}
+
+#[test]
+fn test_network() {
+    assert_eq!("localnet".parse(), Ok(Network::Localnet));
+    assert_eq!("devnet".parse(), Ok(Network::Devnet));
+    assert_eq!("mainnet".parse(), Ok(Network::Mainnet));
+    let network: Network = "https://rpc.example.com".parse().unwrap();
+    assert_eq!(network.url(), "https://rpc.example.com");
+    assert!("testnet2".parse::<Network>().is_err());
+
+    let signature = solana_sdk::signature::Signature::default();
+    assert_eq!(
+        Network::Devnet.explorer_url(&signature),
+        format!("https://explorer.solana.com/tx/{signature}?cluster=devnet")
+    );
+    assert_eq!(
+        Network::Mainnet.explorer_url(&signature),
+        format!("https://explorer.solana.com/tx/{signature}")
+    );
+}
+
+#[test]
+fn test_backoff() {
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(500),
+        ..RetryPolicy::default()
+    };
+    let backoffs: Vec<_> = (1..=5).map(|retry| policy.backoff(retry)).collect();
+    assert_eq!(
+        backoffs,
+        [100, 200, 400, 500, 500].map(Duration::from_millis)
+    );
+    assert_eq!(policy.backoff(u32::MAX), policy.max_backoff);
+}
+
+#[tokio::test]
+async fn test_retry_expired_blockhash() {
+    let (result, sends, blockhashes) = submit_mocked(
+        |attempt| (attempt <= 2).then(|| preflight_failure(json!("BlockhashNotFound"), &[])),
+        false,
+    )
+    .await;
+    result.unwrap();
+    assert_eq!(sends, 3);
+    // Every attempt is rebuilt with a fresh blockhash.
+    assert_eq!(blockhashes, 3);
+}
+
+#[tokio::test]
+async fn test_retry_node_behind() {
+    let (result, sends, _) = submit_mocked(|attempt| (attempt == 1).then(node_behind), false).await;
+    result.unwrap();
+    assert_eq!(sends, 2);
+}
+
+#[tokio::test]
+async fn test_retries_exhausted() {
+    let (result, sends, _) = submit_mocked(|_| Some(node_behind()), false).await;
+    assert!(matches!(result, Err(SubmitError::Rpc(_))));
+    assert_eq!(sends, 3);
+}
+
+#[tokio::test]
+async fn test_preflight_failure_not_retried() {
+    let code = FibonacciVerifierError::InvalidProof as u32;
+    let (result, sends, _) = submit_mocked(
+        |_| {
+            Some(preflight_failure(
+                json!({ "InstructionError": [1, { "Custom": FibonacciVerifierError::InvalidProof as u32 }] }),
+                &["Program log: proof verification failed"],
+            ))
+        },
+        false,
+    )
+    .await;
+    assert_eq!(sends, 1);
+    let error = result.unwrap_err();
+    assert_eq!(error.custom_error_code(), Some(code));
+    let SubmitError::Failed { logs, .. } = &error else {
+        panic!("expected a failed transaction, got {error}");
+    };
+    assert_eq!(logs, &["Program log: proof verification failed"]);
+    assert!(error.to_string().contains(&format!("{code:#x}")));
+}
+
+#[tokio::test]
+async fn test_skip_preflight() {
+    let (result, sends, _) = submit_mocked(|_| None, true).await;
+    result.unwrap();
+    assert_eq!(sends, 1);
+}
+
+#[tokio::test]
+#[ignore = "needs a solana-test-validator running the example program at FIBONACCI_PROGRAM_ID"]
+async fn test_localnet_verify() {
+    let program_id: Pubkey = std::env::var("FIBONACCI_PROGRAM_ID")
+        .expect("FIBONACCI_PROGRAM_ID is not set")
+        .parse()
+        .unwrap();
+    let client = RpcClient::new_with_commitment(
+        Network::Localnet.url().to_string(),
+        CommitmentConfig::confirmed(),
+    );
+    check_program_deployed(&client, &program_id).await.unwrap();
+    assert!(check_program_deployed(&client, &Pubkey::new_unique())
+        .await
+        .is_err());
+
+    let payer = Keypair::new();
+    let airdrop = client
+        .request_airdrop(&payer.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    while !client.confirm_transaction(&airdrop).await.unwrap() {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+
+    let report = send_verify_transaction(
+        &client,
+        &payer,
+        &program_id,
+        load_groth16_proof(),
+        DEFAULT_COMPUTE_UNIT_LIMIT,
+        DEFAULT_COMPUTE_UNIT_PRICE,
+        &RetryPolicy::default(),
+    )
+    .await
+    .unwrap();
+    assert!(report.units_consumed > 0);
+    let signature = report.signature.unwrap();
+    println!("{}", Network::Localnet.explorer_url(&signature));
+}
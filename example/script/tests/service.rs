@@ -0,0 +1,110 @@
+//! Drives the verification service over localhost.
+
+#![cfg(feature = "service")]
+
+use example_script::{
+    proof_file::VkVersion,
+    proof_json::ProofJson,
+    service::{serve, FailureStage, Verifier, VerifyResponse},
+};
+use fibonacci_verifier_contract::FIBONACCI_VKEY_HASH;
+use reqwest::StatusCode;
+use sp1_solana::hash_public_inputs;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+
+const PROOF_JSON: &str = "../../proofs/fibonacci_proof.json";
+
+/// Starts the service on an ephemeral port, returning its address.
+async fn start(vk_version: VkVersion) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(serve(listener, Verifier::new(vk_version).unwrap()));
+    address
+}
+
+async fn post(address: SocketAddr, request: &ProofJson) -> (StatusCode, VerifyResponse) {
+    let response = reqwest::Client::new()
+        .post(format!("http://{address}/verify"))
+        .json(request)
+        .send()
+        .await
+        .unwrap();
+    (response.status(), response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_healthz() {
+    let address = start(VkVersion::V3).await;
+    let response = reqwest::get(format!("http://{address}/healthz"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn test_verify() {
+    let address = start(VkVersion::V3).await;
+    let request = ProofJson::load(PROOF_JSON).unwrap();
+
+    let (status, response) = post(address, &request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        response,
+        VerifyResponse {
+            verified: true,
+            vk_version: "v3.0.0".to_string(),
+            vkey_hash: Some(format!("0x{}", hex::encode(FIBONACCI_VKEY_HASH))),
+            committed_values_digest: Some(format!(
+                "0x{}",
+                hex::encode(hash_public_inputs(
+                    &hex::decode("140000006d1a0000d30b0000").unwrap()
+                ))
+            )),
+            stage: None,
+            errors: vec![],
+        }
+    );
+
+    // Concurrent requests share the decompressed key.
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let request = request.clone();
+            tokio::spawn(async move { post(address, &request).await })
+        })
+        .collect();
+    for task in tasks {
+        assert!(task.await.unwrap().1.verified);
+    }
+}
+
+#[tokio::test]
+async fn test_failure_stages() {
+    let request = ProofJson::load(PROOF_JSON).unwrap();
+
+    // A proof for another Groth16 verification key.
+    let (status, response) = post(start(VkVersion::V2).await, &request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!response.verified);
+    assert_eq!(response.vk_version, "v2.0.0");
+    assert_eq!(response.stage, Some(FailureStage::Groth16VkeyHash));
+
+    let address = start(VkVersion::V3).await;
+
+    // Tampered public values, without the public inputs that would be rejected while decoding.
+    let mut tampered = request.clone();
+    tampered.public_values = "0x150000006d1a0000d30b0000".to_string();
+    tampered.public_inputs = None;
+    let (status, response) = post(address, &tampered).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response.stage, Some(FailureStage::Proof));
+    assert!(response.errors[0].starts_with("the proof doesn't verify: "));
+
+    let mut invalid = request.clone();
+    invalid.proof = "0xzz".to_string();
+    let (status, response) = post(address, &invalid).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.stage, Some(FailureStage::Decode));
+    assert_eq!(response.vkey_hash, None);
+}
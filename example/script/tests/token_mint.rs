@@ -0,0 +1,162 @@
+//! Mints SPL tokens with `sp1-token-mint-program`.
+
+use example_script::{
+    send,
+    token_mint::{create_mint, create_token_account, start, token_balance},
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::InstructionError, pubkey::Pubkey, signer::Signer, transaction::TransactionError,
+};
+use sp1_sdk::{include_elf, HashableKey, ProverClient, SP1ProofWithPublicValues};
+use sp1_solana::decode_sp1_vkey_hash;
+use sp1_token_mint_program::{claim, find_mint_authority_address, ClaimArgs, TokenMintError};
+
+fn custom_error(error: TokenMintError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+/// Creates a mint for the proofs of `sp1_vkey_hash`, and a token account of `recipient`.
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    sp1_vkey_hash: &[u8; 32],
+    recipient: &Pubkey,
+) -> (Pubkey, Pubkey) {
+    let (mint_authority, _) = find_mint_authority_address(program_id, sp1_vkey_hash);
+    let mint = create_mint(context, &mint_authority).await;
+    let token_account = create_token_account(context, &mint, recipient).await;
+    (mint, token_account)
+}
+
+fn load_claim_args(path: &str, sp1_vkey_hash: [u8; 32]) -> ClaimArgs {
+    let sp1_proof_with_public_values = SP1ProofWithPublicValues::load(path).unwrap();
+    ClaimArgs {
+        sp1_vkey_hash,
+        proof: sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs: sp1_proof_with_public_values.public_values.to_vec(),
+    }
+}
+
+#[tokio::test]
+async fn test_rejects_public_values_of_another_shape() {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+
+    // The fibonacci proof verifies, but its public values aren't a `MintClaim`.
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let args = load_claim_args("../../proofs/fibonacci_proof.bin", sp1_vkey_hash);
+    let (mint, token_account) = setup(&mut context, &program_id, &sp1_vkey_hash, &payer).await;
+
+    let instruction = claim(&program_id, &payer, &mint, &token_account, &args);
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(TokenMintError::InvalidPublicValues)
+    );
+    assert_eq!(token_balance(&mut context, &token_account).await, 0);
+}
+
+#[tokio::test]
+async fn test_rejects_unproven_claims() {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+
+    // Public values that decode to a claim for the payer, which the proof doesn't commit to.
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let mut args = load_claim_args("../../proofs/fibonacci_proof.bin", sp1_vkey_hash);
+    args.sp1_public_inputs = borsh::to_vec(&(payer, 1_000u64)).unwrap();
+    let (mint, token_account) = setup(&mut context, &program_id, &sp1_vkey_hash, &payer).await;
+
+    let instruction = claim(&program_id, &payer, &mint, &token_account, &args);
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(TokenMintError::InvalidProof)
+    );
+    assert_eq!(token_balance(&mut context, &token_account).await, 0);
+}
+
+/// Mints a claim whose nullifier PDA was funded to block it, with a mock proof.
+///
+/// ```shell
+/// cargo test -p example-script --features mock-verifier --test token_mint
+/// ```
+#[cfg(feature = "mock-verifier")]
+#[tokio::test]
+async fn test_claim_prefunded_nullifier() {
+    use solana_sdk::system_instruction;
+    use sp1_solana::SP1ProofFixture;
+    use sp1_token_mint_program::MintClaim;
+
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let sp1_public_inputs = borsh::to_vec(&MintClaim {
+        recipient: payer,
+        amount: 1_000,
+    })
+    .unwrap();
+    let fixture = SP1ProofFixture::mock(&sp1_vkey_hash, &sp1_public_inputs);
+    let args = ClaimArgs {
+        sp1_vkey_hash,
+        proof: [&fixture.groth16_vkey_hash[..], &fixture.proof].concat(),
+        sp1_public_inputs,
+    };
+    let (mint, token_account) = setup(&mut context, &program_id, &sp1_vkey_hash, &payer).await;
+
+    // The nullifier address is derived from public data, so anyone can fund it before the claim.
+    let (nullifier, _) = args.nullifier().find_program_address(&program_id);
+    let instruction = system_instruction::transfer(&payer, &nullifier, 1);
+    assert_eq!(send(&mut context, &[instruction], &[]).await, Ok(()));
+
+    let instruction = claim(&program_id, &payer, &mint, &token_account, &args);
+    assert_eq!(send(&mut context, &[instruction], &[]).await, Ok(()));
+    assert_eq!(token_balance(&mut context, &token_account).await, 1_000);
+
+    let instruction = claim(&program_id, &payer, &mint, &token_account, &args);
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(TokenMintError::AlreadyClaimed)
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs proofs/token_claim_proof.bin, generated by `cargo run --bin token_mint -- --prove`"]
+async fn test_mint_once() {
+    let (_, vk) = ProverClient::new().setup(include_elf!("token-claim-program"));
+    let sp1_vkey_hash = decode_sp1_vkey_hash(&vk.bytes32()).unwrap();
+    let args = load_claim_args("../../proofs/token_claim_proof.bin", sp1_vkey_hash);
+    let recipient = Pubkey::try_from(&args.sp1_public_inputs[..32]).unwrap();
+
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+    let (mint, token_account) = setup(&mut context, &program_id, &sp1_vkey_hash, &recipient).await;
+
+    // Tokens can't be minted to an account of someone else.
+    let other_account = create_token_account(&mut context, &mint, &payer).await;
+    let instruction = claim(&program_id, &payer, &mint, &other_account, &args);
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(TokenMintError::RecipientMismatch)
+    );
+
+    let instruction = claim(&program_id, &payer, &mint, &token_account, &args);
+    assert_eq!(send(&mut context, &[instruction], &[]).await, Ok(()));
+    let amount = u64::from_le_bytes(args.sp1_public_inputs[32..].try_into().unwrap());
+    assert_eq!(token_balance(&mut context, &token_account).await, amount);
+
+    let instruction = claim(&program_id, &payer, &mint, &token_account, &args);
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(TokenMintError::AlreadyClaimed)
+    );
+    assert_eq!(token_balance(&mut context, &token_account).await, amount);
+}
@@ -0,0 +1,273 @@
+//! Transfers tokens of a mint gated by `sp1-transfer-hook-program`.
+
+use example_script::{
+    send,
+    transfer_hook::{
+        attested_owner, create_mint, create_token_account, start, token_balance,
+        transfer_instruction,
+    },
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    clock::Clock,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::TransactionError,
+};
+use sp1_sdk::{include_elf, HashableKey, ProverClient, SP1ProofWithPublicValues};
+use sp1_solana::decode_sp1_vkey_hash;
+use sp1_transfer_hook_program::{
+    attest, find_attestation_address, initialize_gate, Attestation, TransferHookError,
+};
+
+fn custom_error(error: TransferHookError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error as u32),
+    ))
+}
+
+fn fibonacci_vkey_hash() -> [u8; 32] {
+    decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+        .unwrap()
+}
+
+struct Gate {
+    mint: Pubkey,
+    owner: Keypair,
+    source: Pubkey,
+    destination: Pubkey,
+}
+
+/// Creates a mint gated on the proofs of `sp1_vkey_hash`, and funds a token account of `owner`,
+/// transferring to a token account of the payer.
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    sp1_vkey_hash: [u8; 32],
+    owner: Keypair,
+) -> Gate {
+    let payer = context.payer.pubkey();
+    let mint = create_mint(context, program_id).await;
+    let instruction = initialize_gate(program_id, &payer, &mint, &payer, sp1_vkey_hash);
+    send(context, &[instruction], &[]).await.unwrap();
+
+    let source = create_token_account(context, &mint, &owner.pubkey(), 100).await;
+    let destination = create_token_account(context, &mint, &payer, 0).await;
+    Gate {
+        mint,
+        owner,
+        source,
+        destination,
+    }
+}
+
+/// Stores an attestation of `owner` directly, as `Attest` would once its proof verified.
+fn set_attestation(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    sp1_vkey_hash: &[u8; 32],
+    attestation: Attestation,
+) {
+    let (address, _) = find_attestation_address(program_id, sp1_vkey_hash, &attestation.owner);
+    let account = Account {
+        lamports: 1_000_000_000,
+        data: borsh::to_vec(&attestation).unwrap(),
+        owner: *program_id,
+        ..Account::default()
+    };
+    context.set_account(&address, &AccountSharedData::from(account));
+}
+
+async fn current_slot(context: &mut ProgramTestContext) -> u64 {
+    context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .slot
+}
+
+async fn transfer(
+    context: &mut ProgramTestContext,
+    gate: &Gate,
+    amount: u64,
+) -> Result<(), TransactionError> {
+    let instruction = transfer_instruction(
+        context,
+        &gate.source,
+        &gate.mint,
+        &gate.destination,
+        &gate.owner.pubkey(),
+        amount,
+    )
+    .await;
+    send(context, &[instruction], &[&gate.owner]).await
+}
+
+#[tokio::test]
+async fn test_transfer_with_fresh_attestation() {
+    let (mut context, program_id) = start().await;
+    let sp1_vkey_hash = fibonacci_vkey_hash();
+    let gate = setup(&mut context, &program_id, sp1_vkey_hash, Keypair::new()).await;
+
+    let expiry_slot = current_slot(&mut context).await + 1_000;
+    let attestation = Attestation {
+        owner: gate.owner.pubkey(),
+        expiry_slot,
+    };
+    set_attestation(&mut context, &program_id, &sp1_vkey_hash, attestation);
+
+    assert_eq!(transfer(&mut context, &gate, 40).await, Ok(()));
+    assert_eq!(token_balance(&mut context, &gate.source).await, 60);
+    assert_eq!(token_balance(&mut context, &gate.destination).await, 40);
+}
+
+#[tokio::test]
+async fn test_transfer_without_attestation() {
+    let (mut context, program_id) = start().await;
+    let sp1_vkey_hash = fibonacci_vkey_hash();
+    let gate = setup(&mut context, &program_id, sp1_vkey_hash, Keypair::new()).await;
+
+    assert_eq!(
+        transfer(&mut context, &gate, 40).await,
+        custom_error(TransferHookError::MissingAttestation)
+    );
+
+    // An attestation proven by another SP1 program doesn't count.
+    let expiry_slot = current_slot(&mut context).await + 1_000;
+    let attestation = Attestation {
+        owner: gate.owner.pubkey(),
+        expiry_slot,
+    };
+    set_attestation(&mut context, &program_id, &[1u8; 32], attestation);
+    assert_eq!(
+        transfer(&mut context, &gate, 40).await,
+        custom_error(TransferHookError::MissingAttestation)
+    );
+    assert_eq!(token_balance(&mut context, &gate.source).await, 100);
+}
+
+#[tokio::test]
+async fn test_transfer_after_expiry() {
+    let (mut context, program_id) = start().await;
+    let sp1_vkey_hash = fibonacci_vkey_hash();
+    let gate = setup(&mut context, &program_id, sp1_vkey_hash, Keypair::new()).await;
+
+    let expiry_slot = current_slot(&mut context).await + 100;
+    let attestation = Attestation {
+        owner: gate.owner.pubkey(),
+        expiry_slot,
+    };
+    set_attestation(&mut context, &program_id, &sp1_vkey_hash, attestation);
+    assert_eq!(transfer(&mut context, &gate, 40).await, Ok(()));
+
+    context.warp_to_slot(expiry_slot + 1).unwrap();
+    assert_eq!(
+        transfer(&mut context, &gate, 40).await,
+        custom_error(TransferHookError::AttestationExpired)
+    );
+    assert_eq!(token_balance(&mut context, &gate.source).await, 60);
+}
+
+#[tokio::test]
+async fn test_gate_requires_mint_authority() {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+    let mint = create_mint(&mut context, &program_id).await;
+
+    let other = Keypair::new();
+    let instruction = initialize_gate(
+        &program_id,
+        &payer,
+        &mint,
+        &other.pubkey(),
+        fibonacci_vkey_hash(),
+    );
+    assert_eq!(
+        send(&mut context, &[instruction], &[&other]).await,
+        custom_error(TransferHookError::MintAuthorityMismatch)
+    );
+}
+
+#[tokio::test]
+async fn test_rejects_unproven_attestations() {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+
+    // Public values that decode to an attestation of the payer, which the proof doesn't commit to.
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let attestation = Attestation {
+        owner: payer,
+        expiry_slot: u64::MAX,
+    };
+    let instruction = attest(
+        &program_id,
+        &payer,
+        &payer,
+        fibonacci_vkey_hash(),
+        sp1_proof_with_public_values.bytes(),
+        borsh::to_vec(&attestation).unwrap(),
+    );
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(TransferHookError::InvalidProof)
+    );
+
+    // The fibonacci proof verifies, but its public values aren't an `Attestation`.
+    let instruction = attest(
+        &program_id,
+        &payer,
+        &payer,
+        fibonacci_vkey_hash(),
+        sp1_proof_with_public_values.bytes(),
+        sp1_proof_with_public_values.public_values.to_vec(),
+    );
+    assert_eq!(
+        send(&mut context, &[instruction], &[]).await,
+        custom_error(TransferHookError::InvalidPublicValues)
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs proofs/attestation_proof.bin, generated by `cargo run --bin transfer_hook -- --prove`"]
+async fn test_attest_and_transfer() {
+    let (_, vk) = ProverClient::new().setup(include_elf!("token-claim-program"));
+    let sp1_vkey_hash = decode_sp1_vkey_hash(&vk.bytes32()).unwrap();
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/attestation_proof.bin").unwrap();
+    let attestation: Attestation =
+        borsh::from_slice(sp1_proof_with_public_values.public_values.as_slice()).unwrap();
+
+    let (mut context, program_id) = start().await;
+    let owner = attested_owner();
+    assert_eq!(attestation.owner, owner.pubkey());
+    let gate = setup(&mut context, &program_id, sp1_vkey_hash, owner).await;
+    assert_eq!(
+        transfer(&mut context, &gate, 40).await,
+        custom_error(TransferHookError::MissingAttestation)
+    );
+
+    let payer = context.payer.pubkey();
+    let instruction = attest(
+        &program_id,
+        &payer,
+        &attestation.owner,
+        sp1_vkey_hash,
+        sp1_proof_with_public_values.bytes(),
+        sp1_proof_with_public_values.public_values.to_vec(),
+    );
+    assert_eq!(send(&mut context, &[instruction], &[]).await, Ok(()));
+    assert_eq!(transfer(&mut context, &gate, 40).await, Ok(()));
+    assert_eq!(token_balance(&mut context, &gate.destination).await, 40);
+
+    context.warp_to_slot(attestation.expiry_slot + 1).unwrap();
+    assert_eq!(
+        transfer(&mut context, &gate, 40).await,
+        custom_error(TransferHookError::AttestationExpired)
+    );
+}
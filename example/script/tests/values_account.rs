@@ -0,0 +1,178 @@
+//! Verifies proofs whose public values are uploaded to an account with `WritePublicValues`.
+
+mod common;
+
+use common::{custom_error, load_groth16_proof, process, start};
+use fibonacci_verifier_contract::{FibonacciVerifierError, VerifierInstruction};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::Transaction,
+};
+use sp1_solana::{
+    hash_public_inputs,
+    values_account::{chunks, create_public_values_account, public_values_account_space},
+};
+
+/// Creates and initializes a public values account, then uploads `values` in chunks.
+async fn upload_public_values(
+    context: &mut ProgramTestContext,
+    program_id: Pubkey,
+    values: &[u8],
+) -> Pubkey {
+    let values_account = Keypair::new();
+    let payer = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let create = create_public_values_account(
+        &payer,
+        &values_account.pubkey(),
+        &program_id,
+        values.len(),
+        rent.minimum_balance(public_values_account_space(values.len())),
+    );
+    let init = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::InitPublicValues {
+            len: values.len() as u32,
+        },
+        vec![
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new(values_account.pubkey(), false),
+        ],
+    );
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction = Transaction::new_with_payer(&[create, init], Some(&payer));
+    transaction.sign(&[&context.payer, &values_account], recent_blockhash);
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    for (offset, bytes) in chunks(values) {
+        let write = write_public_values(program_id, payer, values_account.pubkey(), offset, bytes);
+        assert_eq!(process(context, write, &[]).await, Ok(()));
+    }
+
+    values_account.pubkey()
+}
+
+fn write_public_values(
+    program_id: Pubkey,
+    authority: Pubkey,
+    values_account: Pubkey,
+    offset: u32,
+    bytes: &[u8],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::WritePublicValues {
+            offset,
+            bytes: bytes.to_vec(),
+        },
+        vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(values_account, false),
+        ],
+    )
+}
+
+fn verify_with_values_account(
+    program_id: Pubkey,
+    values_account: Pubkey,
+    proof: &[u8],
+    committed_values_digest: [u8; 32],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::VerifyWithValuesAccount {
+            proof: proof.try_into().unwrap(),
+            committed_values_digest,
+        },
+        vec![AccountMeta::new_readonly(values_account, false)],
+    )
+}
+
+#[tokio::test]
+async fn test_verify_with_values_account() {
+    let (mut context, program_id) = start().await;
+    let groth16_proof = load_groth16_proof();
+    let public_values = groth16_proof.sp1_public_inputs;
+    let proof = &groth16_proof.proof[4..];
+
+    let values_account = upload_public_values(&mut context, program_id, &public_values).await;
+    let instruction = verify_with_values_account(
+        program_id,
+        values_account,
+        proof,
+        hash_public_inputs(&public_values),
+    );
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+}
+
+#[tokio::test]
+async fn test_large_public_values() {
+    let (mut context, program_id) = start().await;
+    let groth16_proof = load_groth16_proof();
+    let proof = &groth16_proof.proof[4..];
+
+    // 4 KB of public values take several transactions to upload.
+    let public_values: Vec<u8> = (0..4096).map(|i| i as u8).collect();
+    let values_account = upload_public_values(&mut context, program_id, &public_values).await;
+
+    let instruction = verify_with_values_account(program_id, values_account, proof, [0u8; 32]);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::DigestMismatch)
+    );
+
+    // The digest of the uploaded values matches, so the values were hashed on-chain in full. There
+    // is no proof for these values, so the pairing check fails.
+    let instruction = verify_with_values_account(
+        program_id,
+        values_account,
+        proof,
+        hash_public_inputs(&public_values),
+    );
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidProof)
+    );
+}
+
+#[tokio::test]
+async fn test_write_public_values_checks() {
+    let (mut context, program_id) = start().await;
+    let payer = context.payer.pubkey();
+    let values_account = upload_public_values(&mut context, program_id, &[0u8; 64]).await;
+
+    let write = write_public_values(program_id, payer, values_account, 60, &[1u8; 8]);
+    assert_eq!(
+        process(&mut context, write, &[]).await,
+        custom_error(FibonacciVerifierError::PublicValuesOutOfBounds)
+    );
+
+    let other = Keypair::new();
+    let write = write_public_values(program_id, other.pubkey(), values_account, 0, &[1u8; 8]);
+    assert_eq!(
+        process(&mut context, write, &[&other]).await,
+        custom_error(FibonacciVerifierError::UnauthorizedWrite)
+    );
+
+    let init = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::InitPublicValues { len: 64 },
+        vec![
+            AccountMeta::new_readonly(other.pubkey(), true),
+            AccountMeta::new(values_account, false),
+        ],
+    );
+    assert_eq!(
+        process(&mut context, init, &[&other]).await,
+        custom_error(FibonacciVerifierError::AlreadyInitialized)
+    );
+}
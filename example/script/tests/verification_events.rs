@@ -0,0 +1,57 @@
+//! Decodes the verification event of a `Verify` instruction from the transaction logs.
+
+use fibonacci_verifier_contract::{SP1Groth16Proof, VerifierInstruction};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signer::Signer, transaction::Transaction,
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_solana::{
+    decode_sp1_vkey_hash,
+    event::{parse_verification_events, VerificationEvent},
+    hash_public_inputs,
+};
+
+#[tokio::test]
+async fn test_verification_events() {
+    let program_id = Pubkey::new_unique();
+    let (banks_client, payer, recent_blockhash) = ProgramTest::new(
+        "fibonacci-verifier-contract",
+        program_id,
+        processor!(fibonacci_verifier_contract::process_instruction),
+    )
+    .start()
+    .await;
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let groth16_proof = SP1Groth16Proof {
+        proof: sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs: sp1_proof_with_public_values.public_values.to_vec().into(),
+    };
+    let instruction = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::Verify(groth16_proof),
+        vec![],
+    );
+
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+    let result = banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result.result.unwrap();
+    let logs = result.metadata.unwrap().log_messages;
+
+    let expected = VerificationEvent {
+        sp1_vkey_hash: decode_sp1_vkey_hash(
+            "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83",
+        )
+        .unwrap(),
+        committed_values_digest: hash_public_inputs(
+            sp1_proof_with_public_values.public_values.as_slice(),
+        ),
+    };
+    assert_eq!(parse_verification_events(&logs), vec![expected]);
+}
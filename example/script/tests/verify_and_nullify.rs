@@ -0,0 +1,161 @@
+//! Consumes a proof with the `VerifyAndNullify` instruction, and checks that it can't be replayed.
+
+use fibonacci_verifier_contract::{FibonacciVerifierError, SP1Groth16Proof, VerifierInstruction};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signer::Signer,
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_solana::{decode_sp1_vkey_hash, hash_public_inputs, Nullifier};
+
+async fn send_verify_and_nullify(
+    context: &mut ProgramTestContext,
+    program_id: Pubkey,
+    nullifier_address: Pubkey,
+    groth16_proof: SP1Groth16Proof,
+) -> Result<(), BanksClientError> {
+    let instruction = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::VerifyAndNullify(groth16_proof),
+        vec![
+            AccountMeta::new(context.payer.pubkey(), true),
+            AccountMeta::new(nullifier_address, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    );
+
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction =
+        Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer], recent_blockhash);
+    context.banks_client.process_transaction(transaction).await
+}
+
+#[tokio::test]
+async fn test_verify_and_nullify() {
+    let program_id = Pubkey::new_unique();
+    let mut context = ProgramTest::new(
+        "fibonacci-verifier-contract",
+        program_id,
+        processor!(fibonacci_verifier_contract::process_instruction),
+    )
+    .start_with_context()
+    .await;
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let public_values = sp1_proof_with_public_values.public_values.to_vec();
+    let groth16_proof = || SP1Groth16Proof {
+        proof: sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs: public_values.clone().into(),
+    };
+
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let nullifier = Nullifier::new(
+        &sp1_vkey_hash,
+        &hash_public_inputs(&public_values),
+        &public_values[..4],
+    );
+    let (nullifier_address, _) = nullifier.find_program_address(&program_id);
+
+    // The first use creates the nullifier.
+    send_verify_and_nullify(&mut context, program_id, nullifier_address, groth16_proof())
+        .await
+        .unwrap();
+    let nullifier_account = context
+        .banks_client
+        .get_account(nullifier_address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(nullifier_account.owner, program_id);
+
+    // A replay is rejected.
+    let error =
+        send_verify_and_nullify(&mut context, program_id, nullifier_address, groth16_proof())
+            .await
+            .unwrap_err();
+    assert_eq!(
+        error.unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(FibonacciVerifierError::NullifierAlreadyUsed as u32)
+        )
+    );
+
+    // A proof of different public values has a different nullifier, so it isn't blocked by this
+    // one.
+    let mut other_public_values = public_values.clone();
+    other_public_values[8] ^= 1;
+    let other_nullifier = Nullifier::new(
+        &sp1_vkey_hash,
+        &hash_public_inputs(&other_public_values),
+        &other_public_values[..4],
+    );
+    assert_ne!(other_nullifier, nullifier);
+    assert_ne!(
+        other_nullifier.find_program_address(&program_id).0,
+        nullifier_address
+    );
+}
+
+#[tokio::test]
+async fn test_verify_and_nullify_prefunded() {
+    let program_id = Pubkey::new_unique();
+    let mut context = ProgramTest::new(
+        "fibonacci-verifier-contract",
+        program_id,
+        processor!(fibonacci_verifier_contract::process_instruction),
+    )
+    .start_with_context()
+    .await;
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let public_values = sp1_proof_with_public_values.public_values.to_vec();
+    let groth16_proof = SP1Groth16Proof {
+        proof: sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs: public_values.clone().into(),
+    };
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let nullifier = Nullifier::new(
+        &sp1_vkey_hash,
+        &hash_public_inputs(&public_values),
+        &public_values[..4],
+    );
+    let (nullifier_address, _) = nullifier.find_program_address(&program_id);
+
+    // Anyone can send lamports to the nullifier address before the proof is used, less than the
+    // rent-exempt minimum of the account.
+    let transfer = system_instruction::transfer(&context.payer.pubkey(), &nullifier_address, 1);
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction = Transaction::new_with_payer(&[transfer], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer], recent_blockhash);
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    // The proof is still consumed, and the nullifier topped up to the rent-exempt minimum.
+    send_verify_and_nullify(&mut context, program_id, nullifier_address, groth16_proof)
+        .await
+        .unwrap();
+    let nullifier_account = context
+        .banks_client
+        .get_account(nullifier_address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(nullifier_account.owner, program_id);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(nullifier_account.lamports, rent.minimum_balance(0));
+}
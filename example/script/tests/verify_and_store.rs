@@ -0,0 +1,94 @@
+//! Records a verified proof with the `VerifyAndStore` instruction, and reads the record back.
+
+use borsh::BorshDeserialize;
+use fibonacci_verifier_contract::{SP1Groth16Proof, VerifierInstruction};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+    system_program,
+    transaction::Transaction,
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_solana::{
+    decode_sp1_vkey_hash, hash_public_inputs,
+    record::{find_verification_record_address, VerificationRecord},
+};
+
+async fn send_verify_and_store(
+    context: &mut ProgramTestContext,
+    program_id: Pubkey,
+    record_address: Pubkey,
+    groth16_proof: SP1Groth16Proof,
+) {
+    let instruction = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::VerifyAndStore(groth16_proof),
+        vec![
+            AccountMeta::new(context.payer.pubkey(), true),
+            AccountMeta::new(record_address, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    );
+
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction =
+        Transaction::new_with_payer(&[instruction], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer], recent_blockhash);
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+async fn read_record(
+    context: &mut ProgramTestContext,
+    record_address: Pubkey,
+) -> VerificationRecord {
+    let account = context
+        .banks_client
+        .get_account(record_address)
+        .await
+        .unwrap()
+        .expect("the verification record was not created");
+    VerificationRecord::try_from_slice(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn test_verify_and_store() {
+    let program_id = Pubkey::new_unique();
+    let mut context = ProgramTest::new(
+        "fibonacci-verifier-contract",
+        program_id,
+        processor!(fibonacci_verifier_contract::process_instruction),
+    )
+    .start_with_context()
+    .await;
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let groth16_proof = || SP1Groth16Proof {
+        proof: sp1_proof_with_public_values.bytes(),
+        sp1_public_inputs: sp1_proof_with_public_values.public_values.to_vec().into(),
+    };
+
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let committed_values_digest =
+        hash_public_inputs(sp1_proof_with_public_values.public_values.as_slice());
+    let (record_address, _) =
+        find_verification_record_address(&program_id, &sp1_vkey_hash, &committed_values_digest);
+
+    send_verify_and_store(&mut context, program_id, record_address, groth16_proof()).await;
+    let record = read_record(&mut context, record_address).await;
+    assert_eq!(record.committed_values_digest, committed_values_digest);
+    assert_eq!(record.submitter, context.payer.pubkey());
+
+    // Submitting the same proof again succeeds, and leaves the record untouched.
+    context.warp_to_slot(record.slot + 100).unwrap();
+    send_verify_and_store(&mut context, program_id, record_address, groth16_proof()).await;
+    assert_eq!(read_record(&mut context, record_address).await, record);
+}
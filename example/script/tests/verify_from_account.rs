@@ -0,0 +1,79 @@
+//! Verifies a proof stored in account data with the `VerifyFromAccount` instruction.
+
+use fibonacci_verifier_contract::VerifierInstruction;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::Transaction,
+};
+use sp1_sdk::SP1ProofWithPublicValues;
+
+/// Runs `VerifyFromAccount` against an account holding `data`.
+async fn run_verify_from_account(
+    data: Vec<u8>,
+    proof_offset: u32,
+    sp1_public_inputs: Vec<u8>,
+) -> bool {
+    let program_id = Pubkey::new_unique();
+    let proof_account = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(
+        "fibonacci-verifier-contract",
+        program_id,
+        processor!(fibonacci_verifier_contract::process_instruction),
+    );
+    program_test.add_account(
+        proof_account,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: Pubkey::new_unique(),
+            ..Account::default()
+        },
+    );
+    let (banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let instruction = Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::VerifyFromAccount {
+            proof_offset,
+            sp1_public_inputs,
+        },
+        vec![AccountMeta::new_readonly(proof_account, false)],
+    );
+
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.is_ok()
+}
+
+#[tokio::test]
+async fn test_verify_from_account() {
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+    let sp1_public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+
+    // Store the proof in the middle of an account larger than a transaction.
+    let proof_offset = 512;
+    let mut data = vec![0u8; 2048];
+    data[proof_offset..proof_offset + proof.len()].copy_from_slice(&proof);
+
+    assert!(
+        run_verify_from_account(data.clone(), proof_offset as u32, sp1_public_inputs.clone()).await
+    );
+
+    // A wrong offset, or one past the end of the account, is rejected.
+    assert!(
+        !run_verify_from_account(
+            data.clone(),
+            proof_offset as u32 + 1,
+            sp1_public_inputs.clone()
+        )
+        .await
+    );
+    assert!(!run_verify_from_account(data, 4096, sp1_public_inputs).await);
+}
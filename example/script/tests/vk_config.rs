@@ -0,0 +1,196 @@
+//! Verifies proofs against a verification key stored in the config PDA, and updates it.
+
+mod common;
+
+use common::{custom_error, load_groth16_proof, process, set_upgrade_authority, start};
+use fibonacci_verifier_contract::{
+    find_config_address, FibonacciVerifierError, VerifierInstruction,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, system_program,
+    transaction::TransactionError,
+};
+use sp1_solana::{
+    upgrade_authority::program_data_address,
+    vk_account::{read_vk, MAX_VK_LEN},
+};
+
+fn initialize_config(program_id: Pubkey, authority: Pubkey, vk: &[u8]) -> Instruction {
+    Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::InitializeConfig { vk: vk.to_vec() },
+        vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new(find_config_address(&program_id).0, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(program_data_address(&program_id), false),
+        ],
+    )
+}
+
+fn update_vk(program_id: Pubkey, authority: Pubkey, vk: &[u8]) -> Instruction {
+    Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::UpdateVk { vk: vk.to_vec() },
+        vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(find_config_address(&program_id).0, false),
+        ],
+    )
+}
+
+fn verify_with_config(program_id: Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        program_id,
+        &VerifierInstruction::VerifyWithConfig(load_groth16_proof()),
+        vec![AccountMeta::new_readonly(
+            find_config_address(&program_id).0,
+            false,
+        )],
+    )
+}
+
+#[tokio::test]
+async fn test_verify_after_vk_update() {
+    let (mut context, program_id) = start().await;
+    let authority = context.payer.pubkey();
+
+    // Before the config exists, there is no key to verify against.
+    assert_eq!(
+        process(&mut context, verify_with_config(program_id), &[]).await,
+        custom_error(FibonacciVerifierError::InvalidAccountOwner)
+    );
+
+    // Start with the key of the previous release: the v3.0.0 proof was generated for another key.
+    let instruction = initialize_config(
+        program_id,
+        authority,
+        sp1_solana::GROTH16_VK_3_0_0_RC4_BYTES,
+    );
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    assert_eq!(
+        process(&mut context, verify_with_config(program_id), &[]).await,
+        custom_error(FibonacciVerifierError::Groth16VkeyHashMismatch)
+    );
+
+    // The config can only be initialized once.
+    let instruction = initialize_config(program_id, authority, sp1_solana::GROTH16_VK_3_0_0_BYTES);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::AlreadyInitialized)
+    );
+
+    let instruction = update_vk(program_id, authority, sp1_solana::GROTH16_VK_3_0_0_BYTES);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    assert_eq!(
+        process(&mut context, verify_with_config(program_id), &[]).await,
+        Ok(())
+    );
+
+    let config = context
+        .banks_client
+        .get_account(find_config_address(&program_id).0)
+        .await
+        .unwrap()
+        .unwrap();
+    let (header, vk) = read_vk(&config.data).unwrap();
+    assert_eq!(header.authority, authority);
+    assert_eq!(vk, sp1_solana::GROTH16_VK_3_0_0_BYTES);
+}
+
+#[tokio::test]
+async fn test_update_vk_checks() {
+    let (mut context, program_id) = start().await;
+    let authority = context.payer.pubkey();
+
+    let instruction = initialize_config(program_id, authority, sp1_solana::GROTH16_VK_3_0_0_BYTES);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+
+    // Only the authority can update the key.
+    let other = Keypair::new();
+    let instruction = update_vk(
+        program_id,
+        other.pubkey(),
+        sp1_solana::GROTH16_VK_3_0_0_RC4_BYTES,
+    );
+    assert_eq!(
+        process(&mut context, instruction, &[&other]).await,
+        custom_error(FibonacciVerifierError::UnauthorizedVkUpdate)
+    );
+
+    let instruction = update_vk(program_id, authority, &[0u8; MAX_VK_LEN + 1]);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::VkTooLarge)
+    );
+
+    let instruction = update_vk(program_id, authority, &[0u8; 16]);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidVerificationKey)
+    );
+
+    // Keys are parsed strictly, so bytes after the key are rejected rather than stored.
+    let padded = [sp1_solana::GROTH16_VK_3_0_0_RC4_BYTES, &[0]].concat();
+    let instruction = update_vk(program_id, authority, &padded);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::InvalidVerificationKey)
+    );
+
+    // The failed updates left the key untouched.
+    assert_eq!(
+        process(&mut context, verify_with_config(program_id), &[]).await,
+        Ok(())
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_config_authority() {
+    let (mut context, program_id) = start().await;
+
+    // A funded signer other than the upgrade authority can't front-run the initialization.
+    let other = Keypair::new();
+    let instruction =
+        system_instruction::transfer(&context.payer.pubkey(), &other.pubkey(), LAMPORTS_PER_SOL);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+    let instruction = initialize_config(
+        program_id,
+        other.pubkey(),
+        sp1_solana::GROTH16_VK_3_0_0_BYTES,
+    );
+    assert_eq!(
+        process(&mut context, instruction, &[&other]).await,
+        custom_error(FibonacciVerifierError::NotUpgradeAuthority)
+    );
+
+    // Nor can anyone once the program is immutable.
+    let authority = context.payer.pubkey();
+    set_upgrade_authority(&mut context, &program_id, None);
+    let instruction = initialize_config(program_id, authority, sp1_solana::GROTH16_VK_3_0_0_BYTES);
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        custom_error(FibonacciVerifierError::NotUpgradeAuthority)
+    );
+
+    // The `ProgramData` account must be the program's.
+    set_upgrade_authority(&mut context, &program_id, Some(authority));
+    let mut instruction =
+        initialize_config(program_id, authority, sp1_solana::GROTH16_VK_3_0_0_BYTES);
+    instruction.accounts[3].pubkey = program_data_address(&Pubkey::new_unique());
+    assert_eq!(
+        process(&mut context, instruction, &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidAccountData
+        ))
+    );
+
+    let instruction = initialize_config(program_id, authority, sp1_solana::GROTH16_VK_3_0_0_BYTES);
+    assert_eq!(process(&mut context, instruction, &[]).await, Ok(()));
+}
@@ -0,0 +1,40 @@
+//! Cross-checks `FIBONACCI_VKEY_HASH` against the fibonacci ELF and the pregenerated proof.
+
+use example_script::vkey::{check_vkey_hash, vkey_hash_from_public_input};
+use fibonacci_verifier_contract::FIBONACCI_VKEY_HASH;
+use sp1_sdk::{include_elf, HashableKey, ProverClient, SP1Proof, SP1ProofWithPublicValues};
+use sp1_solana::decode_sp1_vkey_hash;
+
+#[test]
+fn test_elf_vkey_hash() {
+    let (_, vk) = ProverClient::new().setup(include_elf!("fibonacci-program"));
+    let sp1_vkey_hash = decode_sp1_vkey_hash(&vk.bytes32()).unwrap();
+    check_vkey_hash(&sp1_vkey_hash, &FIBONACCI_VKEY_HASH).unwrap();
+}
+
+#[test]
+fn test_proof_vkey_hash() {
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../../proofs/fibonacci_proof.bin").unwrap();
+    let SP1Proof::Groth16(proof) = &sp1_proof_with_public_values.proof else {
+        panic!("not a Groth16 proof");
+    };
+    assert_eq!(
+        vkey_hash_from_public_input(&proof.public_inputs[0]).unwrap(),
+        FIBONACCI_VKEY_HASH
+    );
+}
+
+#[test]
+fn test_vkey_hash_mismatch() {
+    let mut other = FIBONACCI_VKEY_HASH;
+    other[31] ^= 0x0f;
+    let diff = check_vkey_hash(&other, &FIBONACCI_VKEY_HASH).unwrap_err();
+    assert!(diff.contains("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83"));
+    assert!(diff.contains("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f8c"));
+    // Only the last hex digit differs.
+    assert!(diff.contains(&format!("{}^\n", " ".repeat(63))));
+
+    assert!(vkey_hash_from_public_input("not a number").is_err());
+    assert!(vkey_hash_from_public_input(&"9".repeat(80)).is_err());
+}
@@ -0,0 +1,19 @@
+//! A program that proves a claim: a recipient and the amount they're owed.
+//!
+//! The claim is simply read from the input here. A real program would prove why the recipient is
+//! owed, e.g. by checking a signature or a Merkle proof of an allowlist.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+pub fn main() {
+    // The recipient's Solana address, and the amount owed to them.
+    let recipient = sp1_zkvm::io::read::<[u8; 32]>();
+    let amount = sp1_zkvm::io::read::<u64>();
+
+    // The committed values are the Borsh encoding of `sp1_token_mint_program::MintClaim`, of
+    // `sp1_airdrop_program::AirdropClaim`, and of `sp1_transfer_hook_program::Attestation`, whose
+    // amount is the expiry slot.
+    sp1_zkvm::io::commit(&recipient);
+    sp1_zkvm::io::commit(&amount);
+}
@@ -0,0 +1,213 @@
+//! An example program that mints SPL tokens to the recipient of a claim proven with SP1.
+//!
+//! The public values of the proof decode to a [`MintClaim`]. The mint authority is a PDA of the
+//! SP1 program vkey hash, so a mint only accepts proofs of the program it was created for. A
+//! minted claim is nullified, so replaying its proof fails.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+};
+use sp1_solana::{
+    hash_public_inputs, nullifier::create_pda, verify_and_decode, Nullifier,
+    GROTH16_VK_3_0_0_BYTES, NULLIFIER_SEED,
+};
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// The seed prefix of mint authority PDAs, followed by the SP1 program vkey hash.
+pub const MINT_AUTHORITY_SEED: &[u8] = b"mint-authority";
+
+/// The public values of a claim proof.
+///
+/// Its Borsh encoding is the output of `sp1_zkvm::io::commit` on the recipient's address as a
+/// `[u8; 32]`, followed by the amount as a `u64`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct MintClaim {
+    /// The owner of the token account to mint to.
+    pub recipient: Pubkey,
+    /// The amount to mint, in base units of the mint.
+    pub amount: u64,
+}
+
+/// The instruction data of the program.
+///
+/// Accounts: the payer (signer, writable), the mint (writable), the recipient's token account
+/// (writable), the mint authority PDA, the nullifier PDA (writable), the SPL token program and the
+/// system program.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ClaimArgs {
+    /// The vkey hash of the SP1 program that proved the claim, from `vk.bytes32()`.
+    pub sp1_vkey_hash: [u8; 32],
+    /// The proof in SP1's standard format, prefixed with the Groth16 vkey hash.
+    pub proof: Vec<u8>,
+    /// The SP1 public values, encoding a [`MintClaim`].
+    pub sp1_public_inputs: Vec<u8>,
+}
+
+impl ClaimArgs {
+    /// The nullifier of the claim, which is created once it's minted.
+    pub fn nullifier(&self) -> Nullifier {
+        Nullifier::new(
+            &self.sp1_vkey_hash,
+            &hash_public_inputs(&self.sp1_public_inputs),
+            &[],
+        )
+    }
+}
+
+/// Errors returned by the program, as `ProgramError::Custom(error as u32)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenMintError {
+    /// The instruction data doesn't decode to [`ClaimArgs`].
+    InvalidInstructionData,
+    /// The payer didn't sign the transaction.
+    MissingSignature,
+    /// The public values don't decode to a [`MintClaim`].
+    InvalidPublicValues,
+    /// The proof doesn't verify.
+    InvalidProof,
+    /// The mint authority or nullifier account is not the expected PDA.
+    InvalidPdaAddress,
+    /// The claim was already minted.
+    AlreadyClaimed,
+    /// The token account isn't owned by the recipient of the claim.
+    RecipientMismatch,
+}
+
+impl From<TokenMintError> for ProgramError {
+    fn from(error: TokenMintError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let args = ClaimArgs::try_from_slice(instruction_data)
+        .map_err(|_| TokenMintError::InvalidInstructionData)?;
+
+    let account_info_iter = &mut accounts.iter();
+    let payer = next_account_info(account_info_iter)?;
+    let mint = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let mint_authority = next_account_info(account_info_iter)?;
+    let nullifier_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(TokenMintError::MissingSignature.into());
+    }
+    if *token_program.key != spl_token::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let claim: MintClaim = verify_and_decode(
+        &args.proof,
+        &args.sp1_public_inputs,
+        &args.sp1_vkey_hash,
+        GROTH16_VK_3_0_0_BYTES,
+    )
+    .map_err(|error| match error {
+        sp1_solana::Error::BorshDeserializeError(_) => TokenMintError::InvalidPublicValues,
+        _ => TokenMintError::InvalidProof,
+    })?;
+
+    // The token program checks the account's mint, but not who owns it.
+    let recipient = spl_token::state::Account::unpack(&token_account.try_borrow_data()?)?.owner;
+    if recipient != claim.recipient {
+        return Err(TokenMintError::RecipientMismatch.into());
+    }
+
+    // Consume the claim, failing if it was already minted.
+    let nullifier = args.nullifier();
+    let (nullifier_address, nullifier_bump) = nullifier.find_program_address(program_id);
+    if *nullifier_account.key != nullifier_address {
+        return Err(TokenMintError::InvalidPdaAddress.into());
+    }
+    if nullifier_account.owner == program_id {
+        return Err(TokenMintError::AlreadyClaimed.into());
+    }
+    // The nullifier address is public, so it may have been funded to block the claim.
+    create_pda(
+        payer,
+        nullifier_account,
+        system_program,
+        0,
+        program_id,
+        &[NULLIFIER_SEED, nullifier.as_bytes(), &[nullifier_bump]],
+    )?;
+
+    let (mint_authority_address, mint_authority_bump) =
+        find_mint_authority_address(program_id, &args.sp1_vkey_hash);
+    if *mint_authority.key != mint_authority_address {
+        return Err(TokenMintError::InvalidPdaAddress.into());
+    }
+    invoke_signed(
+        &spl_token::instruction::mint_to(
+            token_program.key,
+            mint.key,
+            token_account.key,
+            mint_authority.key,
+            &[],
+            claim.amount,
+        )?,
+        &[
+            mint.clone(),
+            token_account.clone(),
+            mint_authority.clone(),
+            token_program.clone(),
+        ],
+        &[&[
+            MINT_AUTHORITY_SEED,
+            &args.sp1_vkey_hash,
+            &[mint_authority_bump],
+        ]],
+    )?;
+
+    msg!("Minted {} tokens to {}", claim.amount, claim.recipient);
+
+    Ok(())
+}
+
+/// Finds the PDA that must be the mint authority of mints accepting proofs of `sp1_vkey_hash`.
+pub fn find_mint_authority_address(program_id: &Pubkey, sp1_vkey_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MINT_AUTHORITY_SEED, sp1_vkey_hash], program_id)
+}
+
+/// Builds the instruction minting the claim proven by `args` to `token_account`.
+pub fn claim(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    args: &ClaimArgs,
+) -> Instruction {
+    let (mint_authority, _) = find_mint_authority_address(program_id, &args.sp1_vkey_hash);
+    let (nullifier, _) = args.nullifier().find_program_address(program_id);
+    Instruction::new_with_borsh(
+        *program_id,
+        args,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(*token_account, false),
+            AccountMeta::new_readonly(mint_authority, false),
+            AccountMeta::new(nullifier, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
@@ -0,0 +1,410 @@
+//! An example Token-2022 transfer hook, allowing transfers only from owners attested with SP1.
+//!
+//! An owner is attested once, by verifying a proof whose public values decode to an
+//! [`Attestation`], e.g. of a compliance check. The attestation is stored at a PDA of the SP1
+//! program vkey hash and the owner, until its expiry slot. The hook doesn't verify anything on
+//! transfer: it only requires that the attestation PDA of the source account's owner exists and
+//! hasn't expired.
+//!
+//! The extra account of the hook, the attestation PDA, is declared in the mint's validation
+//! account by [`AttestationInstruction::InitializeGate`], so clients resolve it with the
+//! `offchain` helpers of `spl-transfer-hook-interface` or `spl-token-2022`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_option::COption,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use sp1_solana::{verify_and_decode, GROTH16_VK_3_0_0_BYTES};
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account, Mint},
+};
+use spl_transfer_hook_interface::{
+    collect_extra_account_metas_signer_seeds, get_extra_account_metas_address,
+    get_extra_account_metas_address_and_bump_seed,
+    instruction::{ExecuteInstruction, TransferHookInstruction},
+};
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// The seed prefix of attestation PDAs, followed by the SP1 program vkey hash and the owner.
+pub const ATTESTATION_SEED: &[u8] = b"attestation";
+
+/// The public values of an attestation proof, also stored in the attestation PDA.
+///
+/// Its Borsh encoding is the output of `sp1_zkvm::io::commit` on the owner's address as a
+/// `[u8; 32]`, followed by the expiry slot as a `u64`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct Attestation {
+    /// The attested owner of token accounts.
+    pub owner: Pubkey,
+    /// The last slot in which the attestation is valid.
+    pub expiry_slot: u64,
+}
+
+impl Attestation {
+    /// The length of the serialized attestation.
+    pub const LEN: usize = 32 + 8;
+}
+
+/// The instructions of the program, besides the transfer hook interface's `Execute`.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum AttestationInstruction {
+    /// Gates the transfers of a mint on attestations proven by `sp1_vkey_hash`, by creating its
+    /// validation account.
+    ///
+    /// Accounts: the payer (signer, writable), the mint, its mint authority (signer), the
+    /// validation account PDA (writable), and the system program.
+    InitializeGate { sp1_vkey_hash: [u8; 32] },
+    /// Verifies an attestation proof, and creates or extends the attestation PDA of its owner.
+    ///
+    /// Accounts: the payer (signer, writable), the attestation PDA (writable), and the system
+    /// program.
+    Attest {
+        /// The vkey hash of the SP1 program that proved the attestation, from `vk.bytes32()`.
+        sp1_vkey_hash: [u8; 32],
+        /// The proof in SP1's standard format, prefixed with the Groth16 vkey hash.
+        proof: Vec<u8>,
+        /// The SP1 public values, encoding an [`Attestation`].
+        sp1_public_inputs: Vec<u8>,
+    },
+}
+
+/// Errors returned by the program, as `ProgramError::Custom(error as u32)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferHookError {
+    /// The instruction data doesn't decode to an instruction of the program.
+    InvalidInstructionData,
+    /// The payer or the mint authority didn't sign the transaction.
+    MissingSignature,
+    /// The signer is not the mint authority of the mint.
+    MintAuthorityMismatch,
+    /// The public values don't decode to an [`Attestation`].
+    InvalidPublicValues,
+    /// The proof doesn't verify.
+    InvalidProof,
+    /// The validation or attestation account is not the expected PDA.
+    InvalidPdaAddress,
+    /// The owner of the source account has no attestation.
+    MissingAttestation,
+    /// The attestation expired.
+    AttestationExpired,
+}
+
+impl From<TransferHookError> for ProgramError {
+    fn from(error: TransferHookError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // The interface's instructions start with an 8-byte discriminator, none of which starts with
+    // the variant index of an `AttestationInstruction`.
+    if let Ok(instruction) = TransferHookInstruction::unpack(instruction_data) {
+        return match instruction {
+            TransferHookInstruction::Execute { amount } => {
+                process_execute(program_id, accounts, instruction_data, amount)
+            }
+            // The extra accounts are fixed by `InitializeGate`.
+            _ => Err(TransferHookError::InvalidInstructionData.into()),
+        };
+    }
+
+    let instruction = AttestationInstruction::try_from_slice(instruction_data)
+        .map_err(|_| TransferHookError::InvalidInstructionData)?;
+    match instruction {
+        AttestationInstruction::InitializeGate { sp1_vkey_hash } => {
+            process_initialize_gate(program_id, accounts, &sp1_vkey_hash)
+        }
+        AttestationInstruction::Attest {
+            sp1_vkey_hash,
+            proof,
+            sp1_public_inputs,
+        } => process_attest(
+            program_id,
+            accounts,
+            &sp1_vkey_hash,
+            &proof,
+            &sp1_public_inputs,
+        ),
+    }
+}
+
+fn process_initialize_gate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sp1_vkey_hash: &[u8; 32],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer = next_account_info(account_info_iter)?;
+    let mint = next_account_info(account_info_iter)?;
+    let mint_authority = next_account_info(account_info_iter)?;
+    let validation_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer || !mint_authority.is_signer {
+        return Err(TransferHookError::MissingSignature.into());
+    }
+    if *mint.owner != spl_token_2022::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let authority = StateWithExtensions::<Mint>::unpack(&mint.try_borrow_data()?)?
+        .base
+        .mint_authority;
+    if authority != COption::Some(*mint_authority.key) {
+        return Err(TransferHookError::MintAuthorityMismatch.into());
+    }
+
+    let (validation_address, bump) =
+        get_extra_account_metas_address_and_bump_seed(mint.key, program_id);
+    if *validation_account.key != validation_address {
+        return Err(TransferHookError::InvalidPdaAddress.into());
+    }
+
+    let extra_account_metas = extra_account_metas(sp1_vkey_hash)?;
+    let space = ExtraAccountMetaList::size_of(extra_account_metas.len())?;
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            validation_account.key,
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            program_id,
+        ),
+        &[
+            payer.clone(),
+            validation_account.clone(),
+            system_program.clone(),
+        ],
+        &[&collect_extra_account_metas_signer_seeds(mint.key, &[bump])],
+    )?;
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut validation_account.try_borrow_mut_data()?,
+        &extra_account_metas,
+    )?;
+
+    Ok(())
+}
+
+fn process_attest(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sp1_vkey_hash: &[u8; 32],
+    proof: &[u8],
+    sp1_public_inputs: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer = next_account_info(account_info_iter)?;
+    let attestation_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(TransferHookError::MissingSignature.into());
+    }
+
+    let mut attestation: Attestation = verify_and_decode(
+        proof,
+        sp1_public_inputs,
+        sp1_vkey_hash,
+        GROTH16_VK_3_0_0_BYTES,
+    )
+    .map_err(|error| match error {
+        sp1_solana::Error::BorshDeserializeError(_) => TransferHookError::InvalidPublicValues,
+        _ => TransferHookError::InvalidProof,
+    })?;
+    if attestation.expiry_slot < Clock::get()?.slot {
+        return Err(TransferHookError::AttestationExpired.into());
+    }
+
+    let (attestation_address, bump) =
+        find_attestation_address(program_id, sp1_vkey_hash, &attestation.owner);
+    if *attestation_account.key != attestation_address {
+        return Err(TransferHookError::InvalidPdaAddress.into());
+    }
+
+    if attestation_account.owner == program_id {
+        // Replaying an older proof must not shorten a newer attestation.
+        let existing = Attestation::try_from_slice(&attestation_account.try_borrow_data()?)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        attestation.expiry_slot = attestation.expiry_slot.max(existing.expiry_slot);
+    } else {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                attestation_account.key,
+                Rent::get()?.minimum_balance(Attestation::LEN),
+                Attestation::LEN as u64,
+                program_id,
+            ),
+            &[
+                payer.clone(),
+                attestation_account.clone(),
+                system_program.clone(),
+            ],
+            &[&[
+                ATTESTATION_SEED,
+                sp1_vkey_hash,
+                attestation.owner.as_ref(),
+                &[bump],
+            ]],
+        )?;
+    }
+    attestation
+        .serialize(&mut &mut attestation_account.try_borrow_mut_data()?[..])
+        .map_err(|_| ProgramError::AccountDataTooSmall)?;
+
+    msg!(
+        "Attested {} until slot {}",
+        attestation.owner,
+        attestation.expiry_slot
+    );
+
+    Ok(())
+}
+
+fn process_execute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let source = next_account_info(account_info_iter)?;
+    let mint = next_account_info(account_info_iter)?;
+    let _destination = next_account_info(account_info_iter)?;
+    let _authority = next_account_info(account_info_iter)?;
+    let validation_account = next_account_info(account_info_iter)?;
+    let attestation_account = next_account_info(account_info_iter)?;
+
+    // Checks that the attestation account is the PDA declared for the mint, i.e. of the gated SP1
+    // program and the owner of the source account.
+    if *validation_account.key != get_extra_account_metas_address(mint.key, program_id)
+        || validation_account.owner != program_id
+    {
+        return Err(TransferHookError::InvalidPdaAddress.into());
+    }
+    ExtraAccountMetaList::check_account_infos::<ExecuteInstruction>(
+        accounts,
+        instruction_data,
+        program_id,
+        &validation_account.try_borrow_data()?,
+    )?;
+
+    if attestation_account.owner != program_id {
+        return Err(TransferHookError::MissingAttestation.into());
+    }
+    let attestation = Attestation::try_from_slice(&attestation_account.try_borrow_data()?)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    if attestation.expiry_slot < Clock::get()?.slot {
+        return Err(TransferHookError::AttestationExpired.into());
+    }
+
+    let source_owner = StateWithExtensions::<Account>::unpack(&source.try_borrow_data()?)?
+        .base
+        .owner;
+    msg!("Transfer of {} from attested {}", amount, source_owner);
+
+    Ok(())
+}
+
+/// The extra accounts of `Execute` for mints gated on `sp1_vkey_hash`: the attestation PDA of the
+/// owner of the source account, read from the token account data.
+pub fn extra_account_metas(
+    sp1_vkey_hash: &[u8; 32],
+) -> Result<Vec<ExtraAccountMeta>, ProgramError> {
+    Ok(vec![ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal {
+                bytes: ATTESTATION_SEED.to_vec(),
+            },
+            Seed::Literal {
+                bytes: sp1_vkey_hash.to_vec(),
+            },
+            // The owner of a token account is stored after its mint.
+            Seed::AccountData {
+                account_index: 0,
+                data_index: 32,
+                length: 32,
+            },
+        ],
+        false,
+        false,
+    )?])
+}
+
+/// Finds the attestation PDA of `owner` for the proofs of `sp1_vkey_hash`.
+pub fn find_attestation_address(
+    program_id: &Pubkey,
+    sp1_vkey_hash: &[u8; 32],
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ATTESTATION_SEED, sp1_vkey_hash, owner.as_ref()],
+        program_id,
+    )
+}
+
+/// Builds an `InitializeGate` instruction.
+pub fn initialize_gate(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    sp1_vkey_hash: [u8; 32],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &AttestationInstruction::InitializeGate { sp1_vkey_hash },
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*mint_authority, true),
+            AccountMeta::new(get_extra_account_metas_address(mint, program_id), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
+
+/// Builds an `Attest` instruction for the attestation of `owner` proven by `proof`.
+pub fn attest(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    owner: &Pubkey,
+    sp1_vkey_hash: [u8; 32],
+    proof: Vec<u8>,
+    sp1_public_inputs: Vec<u8>,
+) -> Instruction {
+    let (attestation, _) = find_attestation_address(program_id, &sp1_vkey_hash, owner);
+    Instruction::new_with_borsh(
+        *program_id,
+        &AttestationInstruction::Attest {
+            sp1_vkey_hash,
+            proof,
+            sp1_public_inputs,
+        },
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(attestation, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
@@ -0,0 +1,39 @@
+//! A standalone SP1 Groth16 verifier program.
+//!
+//! Other programs invoke it through [`sp1_solana::cpi::invoke_verify`] rather than embedding the
+//! verifier themselves. The instruction succeeds if and only if the proof is valid for the given
+//! SP1 program vkey hash and committed values digest. Its interface is defined in
+//! `sp1-solana-interface`.
+
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use sp1_solana::{groth16_public_inputs, verify_proof_raw, CommittedValuesDigest};
+use sp1_solana_interface::VerifyArgs;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let args = VerifyArgs::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    CommittedValuesDigest::from_masked_bytes(args.committed_values_digest)
+        .and_then(|committed_values_digest| {
+            verify_proof_raw(
+                &args.proof,
+                &groth16_public_inputs(&args.sp1_vkey_hash, &committed_values_digest),
+                sp1_solana::GROTH16_VK_3_0_0_BYTES,
+            )
+        })
+        .map_err(|error| {
+            msg!("Verification failed: {}", error);
+            error.into()
+        })
+}
@@ -0,0 +1,219 @@
+//! Byte-exact descriptions of instruction data, for wallets and SDKs encoding it without Rust.
+//!
+//! An instruction implementing [`InstructionLayout`] lists the fields of its encoding, with their
+//! offsets, lengths and encodings. The layout is that of a given instruction, since the offsets of
+//! the fields after a length-prefixed one depend on its length. [`to_markdown`] renders it as a
+//! table, and [`assert_encodes_to`] checks an encoder's output against it field by field.
+//!
+//! The layout is checked against the Borsh encoding of the same value: a layout that skips,
+//! overlaps or misplaces a field fails [`assert_encodes_to`].
+
+use core::{fmt, ops::Range};
+
+use borsh::BorshSerialize;
+
+use crate::VerifyArgs;
+
+/// How the bytes of a field encode its value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// The index of the instruction in its enum, on one byte.
+    Discriminator,
+    /// A little-endian `u32`, such as the length prefix of a vector.
+    U32Le,
+    /// Raw bytes, such as a hash, a proof or the contents of a vector.
+    Bytes,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Discriminator => "u8 discriminator",
+            Self::U32Le => "u32 LE",
+            Self::Bytes => "bytes",
+        })
+    }
+}
+
+/// A field of encoded instruction data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldDesc {
+    /// The path of the field in the instruction, e.g. `groth16_proofs[1].proof`. The length prefix
+    /// of a vector is named after it, with a `.len` suffix.
+    pub name: String,
+    /// The offset of the field in the instruction data.
+    pub offset: usize,
+    /// The length of the field, in bytes.
+    pub len: usize,
+    /// How the bytes of the field encode its value.
+    pub encoding: Encoding,
+}
+
+impl FieldDesc {
+    /// The bytes of the field in the instruction data.
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.len
+    }
+}
+
+/// An instruction whose encoding can be described field by field.
+pub trait InstructionLayout: BorshSerialize {
+    /// The fields of the encoding of `self`, in order.
+    fn layout(&self) -> Vec<FieldDesc>;
+}
+
+/// Lists the fields of an encoding, in order, keeping track of their offsets.
+#[derive(Debug, Default)]
+pub struct LayoutBuilder {
+    fields: Vec<FieldDesc>,
+    offset: usize,
+}
+
+impl LayoutBuilder {
+    /// A builder of a layout starting at offset 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a field of `len` bytes.
+    pub fn field(&mut self, name: impl Into<String>, len: usize, encoding: Encoding) -> &mut Self {
+        self.fields.push(FieldDesc {
+            name: name.into(),
+            offset: self.offset,
+            len,
+            encoding,
+        });
+        self.offset += len;
+        self
+    }
+
+    /// Adds the one-byte discriminator of an enum.
+    pub fn discriminator(&mut self) -> &mut Self {
+        self.field("discriminator", 1, Encoding::Discriminator)
+    }
+
+    /// Adds a little-endian `u32`.
+    pub fn u32(&mut self, name: impl Into<String>) -> &mut Self {
+        self.field(name, 4, Encoding::U32Le)
+    }
+
+    /// Adds `len` raw bytes, such as a fixed-size array.
+    pub fn bytes(&mut self, name: impl Into<String>, len: usize) -> &mut Self {
+        self.field(name, len, Encoding::Bytes)
+    }
+
+    /// Adds a vector of `len` bytes: its `u32` length, then its contents.
+    pub fn vec(&mut self, name: impl Into<String>, len: usize) -> &mut Self {
+        let name = name.into();
+        self.u32(format!("{name}.len")).bytes(name, len)
+    }
+
+    /// Returns the fields added so far.
+    pub fn finish(&mut self) -> Vec<FieldDesc> {
+        core::mem::take(&mut self.fields)
+    }
+}
+
+impl InstructionLayout for VerifyArgs {
+    fn layout(&self) -> Vec<FieldDesc> {
+        let Self {
+            sp1_vkey_hash,
+            committed_values_digest,
+            proof,
+        } = self;
+        LayoutBuilder::new()
+            .bytes("sp1_vkey_hash", sp1_vkey_hash.len())
+            .bytes("committed_values_digest", committed_values_digest.len())
+            .bytes("proof", proof.len())
+            .finish()
+    }
+}
+
+/// Encodes `instruction` and checks that its layout covers the encoding exactly.
+///
+/// Panics if a field is misplaced, or if the fields don't end where the encoding does.
+fn encode_checked<T: InstructionLayout>(instruction: &T) -> (Vec<u8>, Vec<FieldDesc>) {
+    let encoded = borsh::to_vec(instruction).expect("serializing to a vector doesn't fail");
+    let fields = instruction.layout();
+    let mut offset = 0;
+    for field in &fields {
+        assert_eq!(
+            field.offset, offset,
+            "the layout places `{}` at {}, after a field ending at {offset}",
+            field.name, field.offset,
+        );
+        let len = match field.encoding {
+            Encoding::Discriminator => 1,
+            Encoding::U32Le => 4,
+            Encoding::Bytes => field.len,
+        };
+        assert_eq!(
+            field.len, len,
+            "`{}` is {} bytes long, but its encoding takes {len}",
+            field.name, field.len,
+        );
+        offset += field.len;
+    }
+    assert_eq!(
+        offset,
+        encoded.len(),
+        "the layout covers {offset} bytes of an encoding of {}",
+        encoded.len()
+    );
+    (encoded, fields)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Renders the layout of `instruction` as a Markdown table, with the bytes of each field in hex.
+pub fn to_markdown<T: InstructionLayout>(instruction: &T) -> String {
+    let (encoded, fields) = encode_checked(instruction);
+    let mut table = String::from(
+        "| offset | len | field | encoding | bytes |\n\
+         | ------ | --- | ----- | -------- | ----- |\n",
+    );
+    for field in fields {
+        table += &format!(
+            "| {} | {} | {} | {} | {} |\n",
+            field.offset,
+            field.len,
+            field.name,
+            field.encoding,
+            to_hex(&encoded[field.range()]),
+        );
+    }
+    table
+}
+
+/// Checks that `expected`, e.g. the output of a client's encoder, is the encoding of
+/// `instruction`.
+///
+/// Panics with the first field that differs, naming it and showing both encodings of it, or if
+/// `expected` is longer than the encoding.
+#[track_caller]
+pub fn assert_encodes_to<T: InstructionLayout>(instruction: &T, expected: &[u8]) {
+    let (encoded, fields) = encode_checked(instruction);
+    for field in fields {
+        let actual = &encoded[field.range()];
+        match expected.get(field.range()) {
+            Some(bytes) if bytes == actual => {}
+            bytes => panic!(
+                "`{}` ({}, at {}..{}) is encoded as {}, not {}",
+                field.name,
+                field.encoding,
+                field.offset,
+                field.offset + field.len,
+                to_hex(actual),
+                bytes.map_or_else(|| "missing".to_string(), to_hex),
+            ),
+        }
+    }
+    assert!(
+        expected.len() == encoded.len(),
+        "{} bytes are expected, but the instruction is encoded in {}",
+        expected.len(),
+        encoded.len()
+    );
+}
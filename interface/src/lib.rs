@@ -0,0 +1,127 @@
+//! The interface of the standalone SP1 verifier program: its instruction layout and error codes.
+//!
+//! Programs and clients calling the verifier program depend on this crate instead of `sp1-solana`,
+//! so that they don't build the verifier itself or arkworks. The layouts here are stable: the
+//! `layout` tests pin their serialized bytes, and the [`layout`] module describes them field by
+//! field, for clients encoding them in other languages.
+//!
+//! The program has a single instruction, which takes no accounts. Its data is a [`VerifyArgs`],
+//! without a discriminator. It succeeds if and only if the proof is valid for the given SP1 program
+//! vkey hash and committed values digest, and otherwise fails with a [`VerifierError`] code.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+pub mod layout;
+
+/// The instruction data of the standalone verifier program.
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct VerifyArgs {
+    /// The SP1 program vkey hash, from `vk.bytes32()`.
+    pub sp1_vkey_hash: [u8; 32],
+    /// The committed values digest: the SHA-256 hash of the SP1 public values, with its top 3 bits
+    /// cleared.
+    pub committed_values_digest: [u8; 32],
+    /// The raw Groth16 proof, without the 4-byte Groth16 vkey hash prefix.
+    pub proof: [u8; 256],
+}
+
+impl VerifyArgs {
+    /// The length of the encoded instruction data.
+    pub const LEN: usize = 32 + 32 + 256;
+
+    /// Encodes the arguments as instruction data for the verifier program.
+    pub fn to_instruction_data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(Self::LEN);
+        data.extend_from_slice(&self.sp1_vkey_hash);
+        data.extend_from_slice(&self.committed_values_digest);
+        data.extend_from_slice(&self.proof);
+        data
+    }
+}
+
+/// Builds an instruction calling the verifier program deployed at `program_id`.
+pub fn verify_instruction(program_id: &Pubkey, args: &VerifyArgs) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![],
+        data: args.to_instruction_data(),
+    }
+}
+
+/// The errors of the verifier program, returned as `ProgramError::Custom(error as u32)`.
+///
+/// The codes are those of `sp1_solana::Error`, which the program returns unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum VerifierError {
+    G1CompressionError,
+    G2CompressionError,
+    /// The pairing check failed: the proof is invalid for the vkey hash and digest.
+    VerificationError,
+    InvalidPublicInput,
+    SerializationError,
+    DeserializationError,
+    InvalidInstructionData,
+    ArithmeticError,
+    PairingError,
+    InvalidInput,
+    BorshSerializeError,
+    BorshDeserializeError,
+    IoError,
+    Groth16VkeyHashMismatch,
+    InvalidProgramVkeyHash,
+    PublicValuesDigestMismatch,
+    InputTooLarge,
+    ExecutionNotAggregated,
+    NoVkAvailable,
+    /// The proof is neither 256 bytes, raw, nor 260 bytes, prefixed with its selector.
+    InvalidProofLength,
+    /// An input continues past what the verifier reads, e.g. junk appended to the proof.
+    TrailingBytes,
+    /// The proof is valid for none of the program vkey hashes it was checked against.
+    NoVkeyHashMatched,
+    /// The proof is from a known SP1 release whose verification key the program wasn't built with.
+    UnsupportedSp1Version,
+    /// The public values' schema hash is that of no version of the schema they are decoded as.
+    SchemaHashMismatch,
+    /// The length prefix of serialized public values is above the limit of their parser.
+    PublicValuesTooLarge,
+}
+
+impl VerifierError {
+    /// Every error, in the order of their codes.
+    pub const ALL: [Self; 25] = [
+        Self::G1CompressionError,
+        Self::G2CompressionError,
+        Self::VerificationError,
+        Self::InvalidPublicInput,
+        Self::SerializationError,
+        Self::DeserializationError,
+        Self::InvalidInstructionData,
+        Self::ArithmeticError,
+        Self::PairingError,
+        Self::InvalidInput,
+        Self::BorshSerializeError,
+        Self::BorshDeserializeError,
+        Self::IoError,
+        Self::Groth16VkeyHashMismatch,
+        Self::InvalidProgramVkeyHash,
+        Self::PublicValuesDigestMismatch,
+        Self::InputTooLarge,
+        Self::ExecutionNotAggregated,
+        Self::NoVkAvailable,
+        Self::InvalidProofLength,
+        Self::TrailingBytes,
+        Self::NoVkeyHashMatched,
+        Self::UnsupportedSp1Version,
+        Self::SchemaHashMismatch,
+        Self::PublicValuesTooLarge,
+    ];
+
+    /// The error of a `ProgramError::Custom` code returned by the verifier program.
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.get(code as usize).copied()
+    }
+}
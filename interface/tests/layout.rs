@@ -0,0 +1,126 @@
+//! Snapshots of the serialized instruction data and error codes, which deployed programs and
+//! clients rely on. A change here is a breaking change of the verifier program.
+
+use borsh::BorshDeserialize;
+use solana_pubkey::Pubkey;
+use sp1_solana_interface::{
+    layout::{assert_encodes_to, to_markdown, Encoding, FieldDesc, InstructionLayout},
+    verify_instruction, VerifierError, VerifyArgs,
+};
+
+fn args() -> VerifyArgs {
+    VerifyArgs {
+        sp1_vkey_hash: core::array::from_fn(|i| i as u8),
+        committed_values_digest: core::array::from_fn(|i| 0x80 + i as u8),
+        proof: core::array::from_fn(|i| (i as u8).wrapping_mul(7)),
+    }
+}
+
+#[test]
+fn test_verify_args_layout() {
+    let data = args().to_instruction_data();
+    assert_eq!(data.len(), VerifyArgs::LEN);
+    assert_eq!(
+        hex::encode(&data[..64]),
+        "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f\
+         808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f"
+    );
+    assert_eq!(
+        hex::encode(&data[64..96]),
+        "00070e151c232a31383f464d545b626970777e858c939aa1a8afb6bdc4cbd2d9"
+    );
+    assert!(data[64..]
+        .iter()
+        .enumerate()
+        .all(|(i, &byte)| byte == (i as u8).wrapping_mul(7)));
+
+    // The Borsh encoding is the same, so programs can decode it with `try_from_slice`.
+    assert_eq!(borsh::to_vec(&args()).unwrap(), data);
+    assert_eq!(VerifyArgs::try_from_slice(&data).unwrap(), args());
+}
+
+#[test]
+fn test_verify_args_field_layout() {
+    let fields = args().layout();
+    assert_eq!(
+        fields[2],
+        FieldDesc {
+            name: "proof".to_string(),
+            offset: 64,
+            len: 256,
+            encoding: Encoding::Bytes,
+        }
+    );
+    assert_eq!(to_markdown(&args()), include_str!("golden/verify_args.md"));
+    assert_encodes_to(&args(), &args().to_instruction_data());
+
+    // An encoder that swaps the hashes is pointed at the first field it gets wrong.
+    let mut swapped = args().to_instruction_data();
+    swapped[..64].rotate_left(32);
+    let panic = std::panic::catch_unwind(|| assert_encodes_to(&args(), &swapped)).unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert!(
+        message.starts_with("`sp1_vkey_hash` (bytes, at 0..32)"),
+        "{message}"
+    );
+    // So is one that cuts a field short.
+    let data = args().to_instruction_data();
+    let panic = std::panic::catch_unwind(|| assert_encodes_to(&args(), &data[..100])).unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert!(
+        message.starts_with("`proof` (bytes, at 64..320)"),
+        "{message}"
+    );
+    assert!(message.ends_with("not missing"), "{message}");
+}
+
+#[test]
+fn test_verify_instruction() {
+    let program_id = Pubkey::new_from_array([7; 32]);
+    let instruction = verify_instruction(&program_id, &args());
+    assert_eq!(instruction.program_id, program_id);
+    assert!(instruction.accounts.is_empty());
+    assert_eq!(instruction.data, args().to_instruction_data());
+}
+
+#[test]
+fn test_error_codes() {
+    let codes: Vec<(VerifierError, u32)> = VerifierError::ALL
+        .iter()
+        .map(|&error| (error, error as u32))
+        .collect();
+    assert_eq!(
+        codes,
+        [
+            (VerifierError::G1CompressionError, 0),
+            (VerifierError::G2CompressionError, 1),
+            (VerifierError::VerificationError, 2),
+            (VerifierError::InvalidPublicInput, 3),
+            (VerifierError::SerializationError, 4),
+            (VerifierError::DeserializationError, 5),
+            (VerifierError::InvalidInstructionData, 6),
+            (VerifierError::ArithmeticError, 7),
+            (VerifierError::PairingError, 8),
+            (VerifierError::InvalidInput, 9),
+            (VerifierError::BorshSerializeError, 10),
+            (VerifierError::BorshDeserializeError, 11),
+            (VerifierError::IoError, 12),
+            (VerifierError::Groth16VkeyHashMismatch, 13),
+            (VerifierError::InvalidProgramVkeyHash, 14),
+            (VerifierError::PublicValuesDigestMismatch, 15),
+            (VerifierError::InputTooLarge, 16),
+            (VerifierError::ExecutionNotAggregated, 17),
+            (VerifierError::NoVkAvailable, 18),
+            (VerifierError::InvalidProofLength, 19),
+            (VerifierError::TrailingBytes, 20),
+            (VerifierError::NoVkeyHashMatched, 21),
+            (VerifierError::UnsupportedSp1Version, 22),
+            (VerifierError::SchemaHashMismatch, 23),
+            (VerifierError::PublicValuesTooLarge, 24),
+        ]
+    );
+    for (error, code) in codes {
+        assert_eq!(VerifierError::from_code(code), Some(error));
+    }
+    assert_eq!(VerifierError::from_code(25), None);
+}
@@ -0,0 +1,82 @@
+//! Benchmarks of the verifier on the fibonacci proof of `testdata/vectors.json`.
+//!
+//! Run with `cargo bench --bench verify`, or a single group, e.g.
+//! `cargo bench --bench verify -- rejection`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sp1_solana::{
+    precheck_proof, verify_proof, verify_proof_raw, CommittedValuesDigest, GROTH16_VK_3_0_0_BYTES,
+};
+
+/// The fibonacci proof's entry of the committed test vectors.
+fn fibonacci() -> Value {
+    let vectors: Value = serde_json::from_str(include_str!("../testdata/vectors.json")).unwrap();
+    vectors[0].clone()
+}
+
+/// Decodes a `0x`-prefixed hex field of the test vectors.
+fn field(vectors: &Value, name: &str) -> Vec<u8> {
+    hex::decode(vectors[name].as_str().unwrap().trim_start_matches("0x")).unwrap()
+}
+
+/// [`precheck_proof`] against a full verification of the same valid proof.
+fn precheck(c: &mut Criterion) {
+    let vectors = fibonacci();
+    let proof = field(&vectors, "proof");
+    let public_inputs = field(&vectors, "groth16_public_inputs");
+    let vk = GROTH16_VK_3_0_0_BYTES;
+
+    let mut group = c.benchmark_group("precheck");
+    group.bench_function("precheck_proof", |b| {
+        b.iter(|| precheck_proof(black_box(&proof), black_box(&public_inputs), vk).unwrap())
+    });
+    group.bench_function("verify_proof_raw", |b| {
+        b.iter(|| verify_proof_raw(black_box(&proof), black_box(&public_inputs), vk).unwrap())
+    });
+    group.finish();
+}
+
+/// Rejections of submissions failing the cheap checks of [`verify_proof`], with 16 KB of public
+/// values, against the same rejections after hashing the public values and the verifying key,
+/// which `verify_proof` did for every submission before its checks were ordered by cost. The
+/// ordered rejections should be at least 5 times cheaper.
+fn rejection(c: &mut Criterion) {
+    let vectors = fibonacci();
+    let proof = field(&vectors, "proof");
+    let sp1_vkey_hash = vectors["sp1_vkey_hash"].as_str().unwrap();
+    let vk = GROTH16_VK_3_0_0_BYTES;
+    let long_values = vec![0x5a; 16 * 1024];
+    let wrong_selector = [&[0u8; 4][..], &proof[4..]].concat();
+    let non_canonical_hash = format!("0x01{}", &sp1_vkey_hash[4..]);
+    let rejected: [(&[u8], &str); 3] = [
+        (&[], sp1_vkey_hash),
+        (&wrong_selector, sp1_vkey_hash),
+        (&proof, &non_canonical_hash),
+    ];
+
+    let mut group = c.benchmark_group("rejection");
+    group.bench_function("ordered", |b| {
+        b.iter(|| {
+            for (proof, vkey_hash) in rejected {
+                verify_proof(proof, black_box(&long_values), vkey_hash, vk).unwrap_err();
+            }
+        })
+    });
+    group.bench_function("unordered", |b| {
+        b.iter(|| {
+            for (proof, vkey_hash) in rejected {
+                black_box(CommittedValuesDigest::from_public_values(black_box(
+                    &long_values,
+                )));
+                black_box(Sha256::digest(vk));
+                verify_proof(proof, black_box(&long_values), vkey_hash, vk).unwrap_err();
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, precheck, rejection);
+criterion_main!(benches);
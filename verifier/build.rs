@@ -0,0 +1,55 @@
+use sha2::{Digest, Sha256};
+
+/// The SHA-256 hash of each embedded Groth16 verification key. A key file can't be replaced without
+/// updating its hash here, and its selector, `GROTH16_VK_*_HASH`, with the first 4 bytes.
+const VK_PINS: [(&str, &str); 3] = [
+    (
+        "vk/v3.0.0/groth16_vk.bin",
+        "090690902a12d1d02c07a1ad25aa76bded5f6499e12a11ba127669501b553998",
+    ),
+    (
+        "vk/v3.0.0rc4/groth16_vk.bin",
+        "feb5e54e3703b9aecfb0a650545bf1a8cc4b11eba14e48afa89a95dc0bd9c867",
+    ),
+    (
+        "vk/v2.0.0/groth16_vk.bin",
+        "6a2906ac6ec461c8cacd5b3557157f59317bd572e57bc8253975aaf5c5acb488",
+    ),
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    check_vk_pins();
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+/// Fails the build if an embedded verification key doesn't hash to its pinned value.
+fn check_vk_pins() {
+    for (path, pinned) in VK_PINS {
+        println!("cargo:rerun-if-changed={path}");
+        let vk =
+            std::fs::read(path).unwrap_or_else(|error| panic!("failed to read {path}: {error}"));
+        let hash = hex::encode(Sha256::digest(&vk));
+        assert!(
+            hash == pinned,
+            "{path} hashes to {hash}, but its pinned hash is {pinned}: update the pin in build.rs \
+             and the selector in src/lib.rs if the key was meant to change"
+        );
+    }
+}
+
+/// Regenerates the C header of the `ffi` module.
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    cbindgen::Builder::new()
+        .with_src(format!("{crate_dir}/src/ffi.rs"))
+        .with_language(cbindgen::Language::C)
+        .with_include_guard("SP1_SOLANA_H")
+        .with_documentation(true)
+        .generate()
+        .expect("failed to generate the C header")
+        .write_to_file(format!("{crate_dir}/include/sp1_solana.h"));
+}
@@ -0,0 +1,63 @@
+#ifndef SP1_SOLANA_H
+#define SP1_SOLANA_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Returned on success.
+ */
+#define SP1_SOLANA_OK 0
+
+/**
+ * Returned if the verifier panicked.
+ */
+#define SP1_SOLANA_ERROR_PANIC -1
+
+/**
+ * Returned if a required pointer is null.
+ */
+#define SP1_SOLANA_ERROR_NULL_POINTER -2
+
+/**
+ * Verifier errors are returned as their numeric code plus this offset, as the code of the first
+ * error is 0.
+ */
+#define SP1_SOLANA_ERROR_CODE_OFFSET 1
+
+/**
+ * Verifies an SP1 Groth16 proof, like `verify_proof`.
+ *
+ * `proof` is the output of `SP1ProofWithPublicValues::bytes()`, `public_inputs` the SP1 public
+ * values, `vkey_hash` the 32-byte program vkey hash (`vk.bytes32()` decoded from hex) and `vk`
+ * the Groth16 verification key. Returns `SP1_SOLANA_OK` if the proof verifies.
+ *
+ * # Safety
+ *
+ * Each pointer must be valid for reads of its length, `vkey_hash_ptr` of 32 bytes. Pointers with a
+ * length of 0 may be null.
+ */
+int32_t sp1_solana_verify(const uint8_t *proof_ptr,
+                          uintptr_t proof_len,
+                          const uint8_t *public_inputs_ptr,
+                          uintptr_t public_inputs_len,
+                          const uint8_t *vkey_hash_ptr,
+                          const uint8_t *vk_ptr,
+                          uintptr_t vk_len);
+
+/**
+ * Writes the committed values digest of the SP1 public values to the 32 bytes at `out_ptr`,
+ * like `hash_public_inputs`.
+ *
+ * # Safety
+ *
+ * `public_inputs_ptr` must be valid for reads of `public_inputs_len` bytes, or null if it is 0,
+ * and `out_ptr` valid for writes of 32 bytes.
+ */
+int32_t sp1_solana_hash_public_inputs(const uint8_t *public_inputs_ptr,
+                                      uintptr_t public_inputs_len,
+                                      uint8_t *out_ptr);
+
+#endif /* SP1_SOLANA_H */
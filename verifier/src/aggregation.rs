@@ -0,0 +1,103 @@
+//! Decoding of the public values of SP1 aggregation proofs.
+//!
+//! An aggregation program verifies many inner SP1 proofs inside the zkVM and commits, for each of
+//! them, the inner program's vkey and the digest of its public values:
+//!
+//! ```ignore
+//! let executions: Vec<([u32; 8], [u8; 32])> = vkeys
+//!     .iter()
+//!     .zip(&public_values)
+//!     .map(|(vkey, public_values)| (*vkey, Sha256::digest(public_values).into()))
+//!     .collect();
+//! for (vkey, digest) in &executions {
+//!     sp1_zkvm::lib::verify::verify_sp1_proof(vkey, digest);
+//! }
+//! sp1_zkvm::io::commit(&executions);
+//! ```
+//!
+//! The outer Groth16 proof is verified as usual, against the aggregation program's vkey hash. Its
+//! public values are then the bincode encoding of that vector: a little-endian `u64` length,
+//! followed by each vkey as eight little-endian `u32` words and each 32-byte digest.
+
+use crate::{utils::ct_eq, Error};
+
+/// The length of the bincode length prefix.
+const LEN_PREFIX_LEN: usize = 8;
+
+/// The length of one encoded `([u32; 8], [u8; 32])` pair.
+const EXECUTION_LEN: usize = 64;
+
+/// An inner execution whose proof an aggregation proof verified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AggregatedExecution {
+    /// The inner program's vkey, `vk.hash_u32()` with each word big-endian, as returned by
+    /// `vk.hash_bytes()`.
+    pub vkey_hash: [u8; 32],
+    /// The SHA-256 hash of the inner execution's public values.
+    ///
+    /// Unlike the committed values digest of a Groth16 proof, its top bits aren't masked.
+    pub committed_values_digest: [u8; 32],
+}
+
+impl AggregatedExecution {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let mut vkey_hash = [0u8; 32];
+        for (word, le) in vkey_hash
+            .chunks_exact_mut(4)
+            .zip(bytes[..32].chunks_exact(4))
+        {
+            word.copy_from_slice(le);
+            word.reverse();
+        }
+        Self {
+            vkey_hash,
+            committed_values_digest: bytes[32..EXECUTION_LEN].try_into().unwrap(),
+        }
+    }
+}
+
+/// Checks the length prefix of `values` and returns the encoded executions.
+fn encoded_executions(values: &[u8]) -> Result<core::slice::ChunksExact<'_, u8>, Error> {
+    if values.len() < LEN_PREFIX_LEN {
+        return Err(Error::DeserializationError(None));
+    }
+    let (len, executions) = values.split_at(LEN_PREFIX_LEN);
+    let len = u64::from_le_bytes(len.try_into().unwrap());
+    let expected_len = usize::try_from(len)
+        .ok()
+        .and_then(|len| len.checked_mul(EXECUTION_LEN))
+        .ok_or(Error::DeserializationError(None))?;
+    if executions.len() != expected_len {
+        return Err(Error::DeserializationError(None));
+    }
+    Ok(executions.chunks_exact(EXECUTION_LEN))
+}
+
+/// Decodes the public values of an aggregation proof into its inner executions, in the order they
+/// were committed.
+pub fn parse_aggregated_public_values(values: &[u8]) -> Result<Vec<AggregatedExecution>, Error> {
+    Ok(encoded_executions(values)?
+        .map(AggregatedExecution::from_bytes)
+        .collect())
+}
+
+/// Checks that the public values of an aggregation proof include an execution of `expected_vkey`
+/// with public values hashing to `expected_digest`.
+///
+/// This doesn't allocate, for use on-chain once the aggregation proof itself is verified.
+pub fn assert_contains_execution(
+    values: &[u8],
+    expected_vkey: &[u8; 32],
+    expected_digest: &[u8; 32],
+) -> Result<(), Error> {
+    if encoded_executions(values)?.any(|execution| {
+        let execution = AggregatedExecution::from_bytes(execution);
+        // Both are compared, whether the first matches or not.
+        ct_eq(&execution.vkey_hash, expected_vkey)
+            & ct_eq(&execution.committed_values_digest, expected_digest)
+    }) {
+        Ok(())
+    } else {
+        Err(Error::ExecutionNotAggregated)
+    }
+}
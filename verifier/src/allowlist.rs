@@ -0,0 +1,87 @@
+//! Accounts listing the SP1 program vkey hashes a program accepts proofs of.
+//!
+//! Rotating the ELF of an SP1 program changes its vkey hash. A [`ProgramVkeyAllowlist`] lets a
+//! program accept proofs of several versions at once, checked in a single call with
+//! [`crate::verify_proof_any_of`], and its authority add the new version before removing the old
+//! one, without redeploying.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    utils::{check_sp1_vkey_hash, ct_eq, source},
+    verify_proof_any_of, Error,
+};
+
+/// The seed of the allowlist PDA of a program.
+pub const ALLOWLIST_SEED: &[u8] = b"sp1-vkey-allowlist";
+
+/// The program vkey hashes a program accepts proofs of.
+#[derive(Clone, Debug, Default, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct ProgramVkeyAllowlist {
+    /// The only account allowed to add or remove hashes.
+    pub authority: Pubkey,
+    /// The allowed program vkey hashes, from `vk.bytes32()`, without duplicates.
+    pub hashes: Vec<[u8; 32]>,
+}
+
+impl ProgramVkeyAllowlist {
+    /// The size of an allowlist account, with room for `max_hashes` hashes.
+    pub const fn space(max_hashes: usize) -> usize {
+        32 + 4 + max_hashes * 32
+    }
+
+    /// Reads an allowlist from account data, ignoring the unused space after it.
+    pub fn from_account_data(data: &[u8]) -> Result<Self, Error> {
+        Self::deserialize(&mut &data[..])
+            .map_err(|error| Error::BorshDeserializeError(source(error)))
+    }
+
+    /// Writes the allowlist to account data, failing with [`Error::BorshSerializeError`] if it
+    /// doesn't fit.
+    pub fn to_account_data(&self, data: &mut [u8]) -> Result<(), Error> {
+        self.serialize(&mut &mut data[..])
+            .map_err(|error| Error::BorshSerializeError(source(error)))
+    }
+
+    /// Whether proofs of `sp1_vkey_hash` are accepted.
+    pub fn contains(&self, sp1_vkey_hash: &[u8; 32]) -> bool {
+        self.hashes.iter().any(|hash| ct_eq(hash, sp1_vkey_hash))
+    }
+
+    /// Allows `sp1_vkey_hash`, returning whether it wasn't already.
+    ///
+    /// Fails with [`Error::InvalidProgramVkeyHash`] if its first byte isn't zero, as with every
+    /// SP1 program vkey hash.
+    pub fn add(&mut self, sp1_vkey_hash: [u8; 32]) -> Result<bool, Error> {
+        check_sp1_vkey_hash(&sp1_vkey_hash)?;
+        if self.contains(&sp1_vkey_hash) {
+            return Ok(false);
+        }
+        self.hashes.push(sp1_vkey_hash);
+        Ok(true)
+    }
+
+    /// Disallows `sp1_vkey_hash`, returning whether it was allowed.
+    pub fn remove(&mut self, sp1_vkey_hash: &[u8; 32]) -> bool {
+        let len = self.hashes.len();
+        self.hashes.retain(|hash| !ct_eq(hash, sp1_vkey_hash));
+        self.hashes.len() != len
+    }
+
+    /// Verifies a proof with [`verify_proof_any_of`] against the allowed hashes, returning the
+    /// index of the one it was generated for.
+    pub fn verify(
+        &self,
+        proof: &[u8],
+        sp1_public_inputs: &[u8],
+        groth16_vk: &[u8],
+    ) -> Result<usize, Error> {
+        verify_proof_any_of(proof, sp1_public_inputs, &self.hashes, groth16_vk)
+    }
+
+    /// Finds the allowlist PDA of `program_id`.
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ALLOWLIST_SEED], program_id)
+    }
+}
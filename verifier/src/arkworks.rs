@@ -0,0 +1,123 @@
+//! An alternative off-chain verifier, running `ark-groth16` end to end.
+//!
+//! [`verify_proof_raw_ark`] takes the same inputs as [`verify_proof_raw`](crate::verify_proof_raw)
+//! but shares none of its elliptic curve code: the decompressed points are converted to arkworks
+//! types, and the whole verification, including the public input MSM and the pairing check, is
+//! done by `ark_groth16`. Where the two disagree, one of them has a bug, which is what the
+//! differential tests look for.
+//!
+//! Points are validated more strictly than by the `alt_bn128` syscalls, which only check that G2
+//! points are on the curve: here they must also be in the prime-order subgroup. Both reject such
+//! points, as the pairing check fails for them anyway, but with different errors.
+
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger256, PrimeField, Zero};
+use ark_groth16::{Groth16, PreparedVerifyingKey, VerifyingKey};
+
+use crate::{
+    utils::{load_public_inputs_from_bytes, load_raw_proof_from_bytes},
+    Error, Proof, PublicInputs, VerificationKey, VkView,
+};
+
+/// Converts a big-endian integer to a field element, rejecting values above the modulus rather
+/// than reducing them, like the `alt_bn128` syscalls do.
+fn field_from_be<F: PrimeField<BigInt = BigInteger256>>(bytes: &[u8]) -> Option<F> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    F::from_bigint(BigInteger256::new(limbs))
+}
+
+/// Converts an uncompressed big-endian G1 point, all zeros being the point at infinity.
+fn g1_from_be(bytes: &[u8; 64]) -> Result<G1Affine, Error> {
+    let x: Fq = field_from_be(&bytes[..32]).ok_or(Error::InvalidInput)?;
+    let y: Fq = field_from_be(&bytes[32..]).ok_or(Error::InvalidInput)?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::identity());
+    }
+    let point = G1Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidInput);
+    }
+    Ok(point)
+}
+
+/// Converts an uncompressed big-endian G2 point, in the `x.c1 || x.c0 || y.c1 || y.c0` order of
+/// the `alt_bn128` syscalls.
+fn g2_from_be(bytes: &[u8; 128]) -> Result<G2Affine, Error> {
+    let fq2 = |bytes: &[u8]| -> Result<Fq2, Error> {
+        let c1 = field_from_be(&bytes[..32]).ok_or(Error::InvalidInput)?;
+        let c0 = field_from_be(&bytes[32..]).ok_or(Error::InvalidInput)?;
+        Ok(Fq2::new(c0, c1))
+    };
+    let x = fq2(&bytes[..64])?;
+    let y = fq2(&bytes[64..])?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::identity());
+    }
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidInput);
+    }
+    Ok(point)
+}
+
+/// Converts a decompressed verification key into an arkworks one.
+fn prepared_verifying_key(vk: &VerificationKey) -> Result<PreparedVerifyingKey<Bn254>, Error> {
+    let vk = VerifyingKey::<Bn254> {
+        alpha_g1: g1_from_be(&vk.vk_alpha_g1)?,
+        beta_g2: g2_from_be(&vk.vk_beta_g2)?,
+        gamma_g2: g2_from_be(&vk.vk_gamma_g2)?,
+        delta_g2: g2_from_be(&vk.vk_delta_g2)?,
+        gamma_abc_g1: vk
+            .vk_ic
+            .as_slice()
+            .iter()
+            .map(g1_from_be)
+            .collect::<Result<_, _>>()?,
+    };
+    Ok(ark_groth16::prepare_verifying_key(&vk))
+}
+
+/// Verifies a proof like [`verify_proof_raw`](crate::verify_proof_raw), with `ark-groth16`
+/// instead of `groth16-solana`.
+///
+/// The proof is the raw 256-byte form, with pi_a as the prover wrote it: `ark-groth16` checks
+/// `e(A, B) = e(alpha, beta) e(L, gamma) e(C, delta)`, so pi_a isn't negated here. This is meant
+/// for off-chain use only.
+pub fn verify_proof_raw_ark(proof: &[u8], public_inputs: &[u8], vk: &[u8]) -> Result<(), Error> {
+    let proof = load_raw_proof_from_bytes(proof)?;
+    let public_inputs = load_public_inputs_from_bytes(public_inputs)?;
+    let vk = VkView::new(vk)?.to_verification_key()?;
+    verify_ark(&proof, &public_inputs, &vk)
+}
+
+/// The typed counterpart of [`verify_proof_raw_ark`], behind the
+/// [`Arkworks`](crate::backend::Arkworks) backend.
+pub(crate) fn verify_ark<const N: usize>(
+    proof: &Proof,
+    public_inputs: &PublicInputs<N>,
+    vk: &VerificationKey,
+) -> Result<(), Error> {
+    let proof = ark_groth16::Proof::<Bn254> {
+        a: g1_from_be(&proof.pi_a)?,
+        b: g2_from_be(&proof.pi_b)?,
+        c: g1_from_be(&proof.pi_c)?,
+    };
+    let public_inputs = public_inputs
+        .inputs
+        .iter()
+        .map(|input| field_from_be::<Fr>(input).ok_or(Error::InvalidPublicInput))
+        .collect::<Result<Vec<_>, _>>()?;
+    let pvk = prepared_verifying_key(vk)?;
+
+    if public_inputs.len() + 1 != pvk.vk.gamma_abc_g1.len() {
+        return Err(Error::InvalidPublicInput);
+    }
+    match Groth16::<Bn254>::verify_proof(&pvk, &proof, &public_inputs) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(Error::VerificationError),
+        Err(_) => Err(Error::PairingError),
+    }
+}
@@ -0,0 +1,103 @@
+//! Interchangeable implementations of the Groth16 check.
+//!
+//! Every entry point of the crate verifies with [`Groth16Solana`], statically: [`DefaultBackend`]
+//! names it, and costs nothing over calling [`verify`](crate::verify) directly. The other backends
+//! share none of its elliptic curve code, so comparing them on the same inputs, with
+//! [`verify_proof_raw_with`](crate::verify_proof_raw_with), catches a bug in any one of them:
+//!
+//! - [`AltBn128`], with the `solana` feature, runs the public input MSM and the pairing check as
+//!   separate `alt_bn128` operations of `solana-program`, which are the syscalls on Solana.
+//! - [`Arkworks`], with the `ark` feature, runs `ark-groth16` end to end, off-chain.
+//!
+//! All of them agree on which proofs are valid, but not always on why the others are rejected.
+//! The `mock-verifier` feature only applies to [`Groth16Solana`].
+
+use crate::{Error, Proof, PublicInputs, VerificationKey};
+
+/// Verifies an SP1 Groth16 proof against its two public inputs and a decompressed verification
+/// key.
+pub trait Groth16Backend {
+    /// Verifies `proof`, whose pi_a isn't negated, like [`verify`](crate::verify).
+    fn verify(
+        &self,
+        proof: &Proof,
+        public: &PublicInputs<2>,
+        vk: &VerificationKey,
+    ) -> Result<(), Error>;
+}
+
+/// The backend of every entry point of the crate.
+pub type DefaultBackend = Groth16Solana;
+
+/// `groth16-solana`, which calls the `alt_bn128` syscalls on Solana and runs arkworks elsewhere.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Groth16Solana;
+
+impl Groth16Backend for Groth16Solana {
+    fn verify(
+        &self,
+        proof: &Proof,
+        public: &PublicInputs<2>,
+        vk: &VerificationKey,
+    ) -> Result<(), Error> {
+        crate::verify(proof, public, vk)
+    }
+}
+
+/// The `alt_bn128` addition, multiplication and pairing operations of `solana-program`, called
+/// one by one.
+#[cfg(feature = "solana")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AltBn128;
+
+#[cfg(feature = "solana")]
+impl Groth16Backend for AltBn128 {
+    fn verify(
+        &self,
+        proof: &Proof,
+        public: &PublicInputs<2>,
+        vk: &VerificationKey,
+    ) -> Result<(), Error> {
+        #[allow(deprecated)]
+        use solana_program::alt_bn128::prelude::alt_bn128_pairing;
+
+        let prepared_inputs = crate::prepare_public_inputs(public, vk)?;
+        let pi_a = crate::utils::negate_g1(&proof.pi_a)?;
+
+        // e(-A, B) e(L, gamma) e(C, delta) e(alpha, beta) = 1
+        let pairing_input = [
+            &pi_a[..],
+            &proof.pi_b[..],
+            &prepared_inputs[..],
+            &vk.vk_gamma_g2[..],
+            &proof.pi_c[..],
+            &vk.vk_delta_g2[..],
+            &vk.vk_alpha_g1[..],
+            &vk.vk_beta_g2[..],
+        ]
+        .concat();
+        let result = alt_bn128_pairing(&pairing_input).map_err(|_| Error::InvalidInput)?;
+        match result.split_last() {
+            Some((1, rest)) if rest.iter().all(|&byte| byte == 0) => Ok(()),
+            _ => Err(Error::VerificationError),
+        }
+    }
+}
+
+/// `ark-groth16`, like [`verify_proof_raw_ark`](crate::verify_proof_raw_ark). This is meant for
+/// off-chain use only.
+#[cfg(feature = "ark")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Arkworks;
+
+#[cfg(feature = "ark")]
+impl Groth16Backend for Arkworks {
+    fn verify(
+        &self,
+        proof: &Proof,
+        public: &PublicInputs<2>,
+        vk: &VerificationKey,
+    ) -> Result<(), Error> {
+        crate::arkworks::verify_ark(proof, public, vk)
+    }
+}
@@ -0,0 +1,180 @@
+//! Verification of circom Groth16 proofs, in snarkjs's JSON formats.
+//!
+//! circom proofs are checked with the same BN254 pairing as SP1's, but are encoded differently:
+//! points are decimal strings followed by a `1` z coordinate, G2 coordinates list the real part
+//! first where `alt_bn128` expects the imaginary part first, and pi_a isn't negated. The public
+//! inputs are the circuit's public signals, rather than SP1's vkey hash and digest.
+//!
+//! Off-chain, [`verify_circom_proof`] takes the JSON files as-is. On-chain, convert the proof
+//! with [`circom_proof_to_bytes`] and the public signals with [`public_inputs_from_snarkjs`]
+//! off-chain, and pass them to [`verify_groth16_prenegated`](crate::verify_groth16_prenegated)
+//! along with the verification key.
+//!
+//! ```no_run
+//! use sp1_solana::circom::{verification_key_from_snarkjs, verify_circom_proof};
+//!
+//! let vk = verification_key_from_snarkjs(&std::fs::read_to_string("verification_key.json")?)?;
+//! verify_circom_proof(
+//!     &std::fs::read_to_string("proof.json")?,
+//!     &std::fs::read_to_string("public.json")?,
+//!     &vk,
+//! )?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use num_bigint::BigUint;
+use serde_json::Value;
+
+use crate::{
+    utils::{source, BN254_BASE_FIELD_MODULUS, BN254_SCALAR_FIELD_MODULUS},
+    verify_groth16_prenegated, Error, Proof, VerificationKey, PROOF_LEN,
+};
+
+/// Parses a decimal string below `modulus` into a big-endian 32-byte integer.
+fn decode_decimal(value: &Value, modulus: &BigUint) -> Result<[u8; 32], Error> {
+    let value: BigUint = value
+        .as_str()
+        .ok_or(Error::DeserializationError(None))?
+        .parse()
+        .map_err(|error| Error::DeserializationError(source(error)))?;
+    if value >= *modulus {
+        return Err(Error::InvalidInput);
+    }
+    let bytes = value.to_bytes_be();
+    let mut be = [0u8; 32];
+    be[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(be)
+}
+
+fn base_field_modulus() -> BigUint {
+    BigUint::from_bytes_be(&BN254_BASE_FIELD_MODULUS)
+}
+
+fn scalar_field_modulus() -> BigUint {
+    BigUint::from_bytes_be(&BN254_SCALAR_FIELD_MODULUS)
+}
+
+/// Decodes an affine G1 point, `[x, y, "1"]`.
+fn decode_g1(value: &Value) -> Result<[u8; 64], Error> {
+    let p = base_field_modulus();
+    match value.as_array().map(Vec::as_slice) {
+        Some([x, y, z]) if z.as_str() == Some("1") => {
+            let mut point = [0u8; 64];
+            point[..32].copy_from_slice(&decode_decimal(x, &p)?);
+            point[32..].copy_from_slice(&decode_decimal(y, &p)?);
+            Ok(point)
+        }
+        _ => Err(Error::DeserializationError(None)),
+    }
+}
+
+/// Decodes an affine G2 point, `[[x.c0, x.c1], [y.c0, y.c1], ["1", "0"]]`, into the
+/// `x.c1 || x.c0 || y.c1 || y.c0` order of the `alt_bn128` syscalls.
+fn decode_g2(value: &Value) -> Result<[u8; 128], Error> {
+    let p = base_field_modulus();
+    let coordinates = value.as_array().map(Vec::as_slice);
+    let Some([x, y, z]) = coordinates else {
+        return Err(Error::DeserializationError(None));
+    };
+    if z != &serde_json::json!(["1", "0"]) {
+        return Err(Error::DeserializationError(None));
+    }
+    let mut point = [0u8; 128];
+    for (offset, coordinate) in [(0, x), (64, y)] {
+        let Some([c0, c1]) = coordinate.as_array().map(Vec::as_slice) else {
+            return Err(Error::DeserializationError(None));
+        };
+        point[offset..offset + 32].copy_from_slice(&decode_decimal(c1, &p)?);
+        point[offset + 32..offset + 64].copy_from_slice(&decode_decimal(c0, &p)?);
+    }
+    Ok(point)
+}
+
+fn parse(json: &str) -> Result<Value, Error> {
+    serde_json::from_str(json).map_err(|error| Error::DeserializationError(source(error)))
+}
+
+/// Imports a snarkjs `verification_key.json`.
+pub fn verification_key_from_snarkjs(vk_json: &str) -> Result<VerificationKey, Error> {
+    let vk = parse(vk_json)?;
+    if vk["protocol"] != "groth16" || vk["curve"] != "bn128" {
+        return Err(Error::InvalidInput);
+    }
+    let vk_ic = vk["IC"]
+        .as_array()
+        .ok_or(Error::DeserializationError(None))?
+        .iter()
+        .map(decode_g1)
+        .collect::<Result<Vec<_>, _>>()?;
+    let nr_pubinputs = vk["nPublic"]
+        .as_u64()
+        .ok_or(Error::DeserializationError(None))?;
+    if nr_pubinputs + 1 != vk_ic.len() as u64 {
+        return Err(Error::InvalidInput);
+    }
+    Ok(VerificationKey {
+        nr_pubinputs: nr_pubinputs as u32,
+        vk_alpha_g1: decode_g1(&vk["vk_alpha_1"])?,
+        vk_beta_g2: decode_g2(&vk["vk_beta_2"])?,
+        vk_gamma_g2: decode_g2(&vk["vk_gamma_2"])?,
+        vk_delta_g2: decode_g2(&vk["vk_delta_2"])?,
+        vk_ic,
+    })
+}
+
+/// Decodes a snarkjs `proof.json`, with pi_a as snarkjs writes it, i.e. not negated.
+pub fn proof_from_snarkjs(proof_json: &str) -> Result<Proof, Error> {
+    let proof = parse(proof_json)?;
+    if proof["protocol"] != "groth16" {
+        return Err(Error::InvalidInput);
+    }
+    Ok(Proof {
+        pi_a: decode_g1(&proof["pi_a"])?,
+        pi_b: decode_g2(&proof["pi_b"])?,
+        pi_c: decode_g1(&proof["pi_c"])?,
+    })
+}
+
+/// Decodes a snarkjs `public.json` into big-endian scalars.
+pub fn public_inputs_from_snarkjs(public_json: &str) -> Result<Vec<[u8; 32]>, Error> {
+    let r = scalar_field_modulus();
+    parse(public_json)?
+        .as_array()
+        .ok_or(Error::DeserializationError(None))?
+        .iter()
+        .map(|input| decode_decimal(input, &r).map_err(|_| Error::InvalidPublicInput))
+        .collect()
+}
+
+/// Converts a snarkjs `proof.json` to the raw 256-byte form taken by
+/// [`verify_groth16_prenegated`], negating pi_a.
+///
+/// snarkjs checks `e(A, B) = e(alpha, beta) e(L, gamma) e(C, delta)`, while the `alt_bn128`
+/// pairing check takes `-A`. SP1 proofs are negated when they're decompressed; circom proofs are
+/// negated here instead, so they must not go through [`negate_proof_a`](crate::negate_proof_a)
+/// too.
+pub fn circom_proof_to_bytes(proof_json: &str) -> Result<[u8; PROOF_LEN], Error> {
+    Ok(proof_from_snarkjs(proof_json)?.with_negated_a()?.to_bytes())
+}
+
+/// Verifies a circom Groth16 proof off-chain, from its snarkjs `proof.json` and `public.json`.
+///
+/// The circuit may have one or two public signals. For more, call [`verify_groth16_prenegated`]
+/// with the number of public signals as `N`.
+pub fn verify_circom_proof(
+    proof_json: &str,
+    public_json: &str,
+    vk: &VerificationKey,
+) -> Result<(), Error> {
+    let proof = circom_proof_to_bytes(proof_json)?;
+    let public_inputs = public_inputs_from_snarkjs(public_json)?;
+    if public_inputs.len() != vk.nr_pubinputs as usize {
+        return Err(Error::InvalidPublicInput);
+    }
+    // The pairing check is generic over the number of public inputs, a constant.
+    match public_inputs.as_slice() {
+        [input] => verify_groth16_prenegated(&proof, &[*input], vk),
+        [first, second] => verify_groth16_prenegated(&proof, &[*first, *second], vk),
+        _ => Err(Error::InvalidPublicInput),
+    }
+}
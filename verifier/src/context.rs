@@ -0,0 +1,341 @@
+//! A reusable verification setup, pairing a verification key with the SP1 programs it accepts.
+//!
+//! Integrators otherwise thread the proof, the public values, the program vkey hash and the
+//! Groth16 verification key through several calls, and nothing stops them from pairing the wrong
+//! ones. A [`VerificationContext`] is built once from the last two, and policy flags, and then
+//! only takes proofs:
+//!
+//! ```
+//! use sp1_solana::{VerificationContext, VkSource, GROTH16_VK_3_0_0_BYTES, GROTH16_VK_3_0_0_HASH};
+//!
+//! const FIBONACCI_VKEY_HASH: [u8; 32] =
+//!     hex_literal::hex!("007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83");
+//!
+//! static CONTEXT: VerificationContext<'static> = VerificationContext::new(
+//!     VkSource::BytesWithHash {
+//!         vk: GROTH16_VK_3_0_0_BYTES,
+//!         groth16_vkey_hash: GROTH16_VK_3_0_0_HASH,
+//!     },
+//!     &FIBONACCI_VKEY_HASH,
+//! )
+//! .with_strict_lengths(true);
+//! ```
+//!
+//! Building a context is free: it only stores references, so a program can keep it in a `static`.
+
+use groth16_solana::groth16::Groth16Verifyingkey;
+
+use crate::{
+    groth16_public_inputs, groth16_verifying_key, pairing_check, proof_selector,
+    utils::{check_sp1_vkey_hash, ct_eq, groth16_vk_hash, strip_selector},
+    validate_proof_len,
+    versions::selector_mismatch,
+    CommittedValuesDigest, Error, NegatedProof, Proof, PublicInputs, SP1ProofFixtureRef,
+    VerificationKey, VkView, PROOF_WITH_SELECTOR_LEN, SELECTOR_LEN,
+};
+
+/// Looks up Groth16 verification keys by the selector of the proofs generated for them.
+pub trait VkProvider {
+    /// The gnark-compressed verification key whose SHA-256 hash starts with `groth16_vkey_hash`.
+    fn groth16_vk(&self, groth16_vkey_hash: &[u8; SELECTOR_LEN]) -> Option<&[u8]>;
+}
+
+/// The verification keys embedded with the `embedded-vk-*` features, as used by
+/// [`verify_proof_auto`](crate::verify_proof_auto).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EmbeddedVks;
+
+impl VkProvider for EmbeddedVks {
+    fn groth16_vk(&self, groth16_vkey_hash: &[u8; SELECTOR_LEN]) -> Option<&[u8]> {
+        crate::EMBEDDED_VKS
+            .iter()
+            .find(|(hash, _)| ct_eq(hash, groth16_vkey_hash))
+            .map(|(_, vk)| *vk)
+    }
+}
+
+/// Where a [`VerificationContext`] gets its Groth16 verification key.
+#[derive(Clone, Copy)]
+pub enum VkSource<'a> {
+    /// A gnark-compressed key, e.g. [`GROTH16_VK_3_0_0_BYTES`](crate::GROTH16_VK_3_0_0_BYTES).
+    Bytes(&'a [u8]),
+    /// A gnark-compressed key with its precomputed Groth16 vkey hash, e.g.
+    /// [`GROTH16_VK_3_0_0_HASH`](crate::GROTH16_VK_3_0_0_HASH), so that the key isn't hashed to
+    /// check each proof's selector.
+    BytesWithHash {
+        vk: &'a [u8],
+        groth16_vkey_hash: [u8; SELECTOR_LEN],
+    },
+    /// A decompressed key, along with the selector of its proofs, which can't be computed from
+    /// the decompressed points.
+    Parsed {
+        vk: &'a VerificationKey,
+        groth16_vkey_hash: [u8; SELECTOR_LEN],
+    },
+    /// The key named by each proof's selector. Proofs must then carry their selector.
+    Provider(&'a (dyn VkProvider + Sync)),
+}
+
+/// The SP1 programs whose proofs a [`VerificationContext`] accepts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgramVkeys<'a> {
+    /// A single program vkey hash, from `vk.bytes32()`.
+    One(&'a [u8; 32]),
+    /// Any of several program vkey hashes, as with [`verify_proof_any_of`](crate::verify_proof_any_of).
+    AnyOf(&'a [[u8; 32]]),
+}
+
+impl ProgramVkeys<'_> {
+    /// The error of a proof valid for none of the hashes.
+    fn mismatch(&self) -> Error {
+        match self {
+            Self::One(_) => Error::VerificationError,
+            Self::AnyOf(sp1_vkey_hashes) => Error::NoVkeyHashMatched {
+                candidates: sp1_vkey_hashes.len(),
+            },
+        }
+    }
+}
+
+/// A Groth16 verification key and the SP1 programs it accepts proofs of, with the policy applied to
+/// each proof.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Clone, Copy)]
+pub struct VerificationContext<'a> {
+    vk: VkSource<'a>,
+    sp1_vkey_hashes: ProgramVkeys<'a>,
+    strict_lengths: bool,
+    prenegated: bool,
+    #[cfg(feature = "ark")]
+    subgroup_checks: bool,
+}
+
+impl<'a> VerificationContext<'a> {
+    /// A context accepting proofs of the program `sp1_vkey_hash` against `vk`, with the default
+    /// policy of [`verify_proof`](crate::verify_proof).
+    pub const fn new(vk: VkSource<'a>, sp1_vkey_hash: &'a [u8; 32]) -> Self {
+        Self::with_programs(vk, ProgramVkeys::One(sp1_vkey_hash))
+    }
+
+    /// A context accepting proofs of any of `sp1_vkey_hashes`.
+    pub const fn with_programs(vk: VkSource<'a>, sp1_vkey_hashes: ProgramVkeys<'a>) -> Self {
+        Self {
+            vk,
+            sp1_vkey_hashes,
+            strict_lengths: false,
+            prenegated: false,
+            #[cfg(feature = "ark")]
+            subgroup_checks: false,
+        }
+    }
+
+    /// Requires proofs in SP1's 260-byte format, so that their selector is always checked, and a
+    /// verification key that ends where it is parsed, like
+    /// [`verify_proof_raw_exact`](crate::verify_proof_raw_exact).
+    pub const fn with_strict_lengths(mut self, strict_lengths: bool) -> Self {
+        self.strict_lengths = strict_lengths;
+        self
+    }
+
+    /// Takes proofs whose pi_a was already negated with
+    /// [`negate_proof_a`](crate::negate_proof_a), like
+    /// [`verify_proof_raw_prenegated`](crate::verify_proof_raw_prenegated).
+    pub const fn with_prenegated_proofs(mut self, prenegated: bool) -> Self {
+        self.prenegated = prenegated;
+        self
+    }
+
+    /// Runs the pairing check with the [`Arkworks`](crate::backend::Arkworks) backend, which also
+    /// checks that every point is in its prime-order subgroup. This is meant for off-chain use.
+    #[cfg(feature = "ark")]
+    pub const fn with_subgroup_checks(mut self, subgroup_checks: bool) -> Self {
+        self.subgroup_checks = subgroup_checks;
+        self
+    }
+
+    /// The program vkey hashes the context accepts.
+    pub fn sp1_vkey_hashes(&self) -> &'a [[u8; 32]] {
+        match self.sp1_vkey_hashes {
+            ProgramVkeys::One(sp1_vkey_hash) => core::slice::from_ref(sp1_vkey_hash),
+            ProgramVkeys::AnyOf(sp1_vkey_hashes) => sp1_vkey_hashes,
+        }
+    }
+
+    /// Verifies a proof of `sp1_public_values`, returning the program vkey hash it is valid for.
+    ///
+    /// The proof is in SP1's 260-byte format or, unless the lengths are strict or the key comes
+    /// from a provider, the raw 256-byte one. A proof valid for none of the accepted programs
+    /// fails with [`Error::VerificationError`] for a single program, and with
+    /// [`Error::NoVkeyHashMatched`] for several.
+    pub fn verify(&self, proof: &[u8], sp1_public_values: &[u8]) -> Result<&'a [u8; 32], Error> {
+        self.verify_with_digest(
+            proof,
+            &CommittedValuesDigest::from_public_values(sp1_public_values),
+        )
+    }
+
+    /// Verifies a fixture like [`verify_proof_fixture`](crate::verify_proof_fixture), and checks
+    /// that it is a proof of one of the accepted programs, failing with
+    /// [`Error::InvalidProgramVkeyHash`] otherwise.
+    pub fn verify_fixture<'b>(
+        &self,
+        fixture: impl Into<SP1ProofFixtureRef<'b>>,
+    ) -> Result<&'a [u8; 32], Error> {
+        let fixture = fixture.into();
+        let sp1_vkey_hash = self
+            .sp1_vkey_hashes()
+            .iter()
+            .find(|sp1_vkey_hash| ct_eq(*sp1_vkey_hash, fixture.sp1_vkey_hash))
+            .ok_or(Error::InvalidProgramVkeyHash)?;
+        if !fixture.sp1_public_inputs.is_empty()
+            && !ct_eq(
+                CommittedValuesDigest::from_public_values(fixture.sp1_public_inputs).as_bytes(),
+                fixture.committed_values_digest,
+            )
+        {
+            return Err(Error::PublicValuesDigestMismatch);
+        }
+        let committed_values_digest =
+            CommittedValuesDigest::from_masked_bytes(*fixture.committed_values_digest)?;
+
+        let mut proof = [0u8; PROOF_WITH_SELECTOR_LEN];
+        proof[..SELECTOR_LEN].copy_from_slice(fixture.groth16_vkey_hash);
+        proof[SELECTOR_LEN..].copy_from_slice(fixture.proof);
+        // Fixtures hold proofs as SP1 writes them, whatever the policy.
+        Self {
+            sp1_vkey_hashes: ProgramVkeys::One(sp1_vkey_hash),
+            prenegated: false,
+            ..*self
+        }
+        .verify_with_digest(&proof, &committed_values_digest)
+    }
+
+    /// Verifies a proof against a committed values digest.
+    pub fn verify_with_digest(
+        &self,
+        proof: &[u8],
+        committed_values_digest: &CommittedValuesDigest,
+    ) -> Result<&'a [u8; 32], Error> {
+        self.sp1_vkey_hashes()
+            .iter()
+            .try_for_each(check_sp1_vkey_hash)?;
+
+        let (proof, vk) = match self.vk {
+            VkSource::Bytes(vk) => (self.strip_selector(proof, || groth16_vk_hash(vk))?, vk),
+            VkSource::BytesWithHash {
+                vk,
+                groth16_vkey_hash,
+            } => (self.strip_selector(proof, || groth16_vkey_hash)?, vk),
+            VkSource::Provider(provider) => {
+                // A raw proof has no selector to look its key up with.
+                validate_proof_len(proof)?;
+                let vk = provider
+                    .groth16_vk(proof_selector(proof)?)
+                    .ok_or_else(|| selector_mismatch(&proof[..SELECTOR_LEN]))?;
+                (&proof[SELECTOR_LEN..], vk)
+            }
+            VkSource::Parsed {
+                vk,
+                groth16_vkey_hash,
+            } => {
+                let proof = self.load_proof(self.strip_selector(proof, || groth16_vkey_hash)?)?;
+                #[cfg(feature = "ark")]
+                if self.subgroup_checks {
+                    return self.verify_candidates(committed_values_digest, |public_inputs| {
+                        verify_with_subgroup_checks(&proof, public_inputs, vk)
+                    });
+                }
+                let verifying_key = parsed_verifying_key(vk);
+                return self.verify_candidates(committed_values_digest, |public_inputs| {
+                    pairing_check(proof.as_proof_ref(), &public_inputs.inputs, &verifying_key)
+                });
+            }
+        };
+
+        let vk_view = if self.strict_lengths {
+            VkView::new_exact(vk)?
+        } else {
+            VkView::new(vk)?
+        };
+        let proof = self.load_proof(proof)?;
+        #[cfg(feature = "ark")]
+        if self.subgroup_checks {
+            let vk = vk_view.to_verification_key()?;
+            return self.verify_candidates(committed_values_digest, |public_inputs| {
+                verify_with_subgroup_checks(&proof, public_inputs, &vk)
+            });
+        }
+        let verifying_key = groth16_verifying_key(&vk_view)?;
+        self.verify_candidates(committed_values_digest, |public_inputs| {
+            pairing_check(proof.as_proof_ref(), &public_inputs.inputs, &verifying_key)
+        })
+    }
+
+    /// Strips the selector of `proof`, which is required with strict lengths.
+    fn strip_selector<'p>(
+        &self,
+        proof: &'p [u8],
+        groth16_vkey_hash: impl FnOnce() -> [u8; SELECTOR_LEN],
+    ) -> Result<&'p [u8], Error> {
+        if self.strict_lengths {
+            validate_proof_len(proof)?;
+        }
+        strip_selector(proof, groth16_vkey_hash)
+    }
+
+    /// Loads a raw proof, negating pi_a unless it already is.
+    fn load_proof(&self, proof: &[u8]) -> Result<NegatedProof, Error> {
+        if self.prenegated {
+            NegatedProof::from_prenegated_bytes(proof)
+        } else {
+            Proof::from_bytes(proof)?.with_negated_a()
+        }
+    }
+
+    /// Runs `check` on the public inputs of each accepted program, returning the first one it
+    /// passes for.
+    fn verify_candidates(
+        &self,
+        committed_values_digest: &CommittedValuesDigest,
+        mut check: impl FnMut(&PublicInputs<2>) -> Result<(), Error>,
+    ) -> Result<&'a [u8; 32], Error> {
+        for sp1_vkey_hash in self.sp1_vkey_hashes() {
+            let public_inputs = PublicInputs::from_bytes(&groth16_public_inputs(
+                sp1_vkey_hash,
+                committed_values_digest,
+            ))?;
+            match check(&public_inputs) {
+                Ok(()) => return Ok(sp1_vkey_hash),
+                // The proof is well-formed, but for another program.
+                Err(Error::VerificationError) => continue,
+                Err(error) => return Err(error),
+            }
+        }
+        Err(self.sp1_vkey_hashes.mismatch())
+    }
+}
+
+/// Verifies a proof with `ark-groth16`, which checks that every point is in its subgroup.
+#[cfg(feature = "ark")]
+fn verify_with_subgroup_checks(
+    proof: &NegatedProof,
+    public_inputs: &PublicInputs<2>,
+    vk: &VerificationKey,
+) -> Result<(), Error> {
+    // arkworks takes pi_a as the prover wrote it.
+    let proof = proof.proof().clone().with_negated_a()?;
+    crate::arkworks::verify_ark(proof.proof(), public_inputs, vk)
+}
+
+/// Borrows a decompressed key in the form taken by `groth16-solana`.
+fn parsed_verifying_key(vk: &VerificationKey) -> Groth16Verifyingkey<'_> {
+    Groth16Verifyingkey {
+        nr_pubinputs: vk.nr_pubinputs as usize,
+        vk_alpha_g1: vk.vk_alpha_g1,
+        vk_beta_g2: vk.vk_beta_g2,
+        vk_gamme_g2: vk.vk_gamma_g2,
+        vk_delta_g2: vk.vk_delta_g2,
+        vk_ic: vk.vk_ic.as_slice(),
+    }
+}
@@ -0,0 +1,28 @@
+//! Helpers for calling a standalone SP1 verifier program.
+//!
+//! Instead of embedding the verifier and its verification key, a program can invoke a single
+//! deployed verifier program, which verifies a raw Groth16 proof against its own embedded key. The
+//! program fails the instruction if the proof is invalid.
+//!
+//! Its instruction layout and error codes live in `sp1-solana-interface`, which is re-exported
+//! here, for callers that don't need the verifier itself.
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke, pubkey::Pubkey,
+};
+pub use sp1_solana_interface::{verify_instruction, VerifierError, VerifyArgs};
+
+/// Invokes the verifier program deployed at `program_id`.
+///
+/// `accounts` must contain the verifier program's account.
+///
+/// `program_id` must be pinned by the caller, e.g. as a constant, and never taken from an account
+/// passed to the instruction without checking it: any program put in the verifier's place can
+/// succeed without checking the proof.
+pub fn invoke_verify(
+    program_id: &Pubkey,
+    args: &VerifyArgs,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    invoke(&verify_instruction(program_id, args), accounts)
+}
@@ -0,0 +1,114 @@
+//! Explains why a proof fails verification.
+//!
+//! [`verify_proof_fixture`](crate::verify_proof_fixture) stops at the first failed check, and a
+//! failed pairing check doesn't say which input was wrong. [`diagnose_proof`] runs every check
+//! instead and reports each mismatch with both sides, for use off-chain before submitting a proof.
+
+use crate::{
+    groth16_public_inputs, hash_public_inputs,
+    utils::{ct_eq, sha256},
+    verify_proof_raw, CommittedValuesDigest, Error, SP1ProofFixtureRef,
+};
+
+/// A check of [`diagnose_proof`] that the proof fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofMismatch {
+    /// The proof was generated for another Groth16 verification key.
+    Groth16VkeyHash { proof: [u8; 4], vk: [u8; 4] },
+    /// The proof is of another SP1 program.
+    Sp1VkeyHash { proof: [u8; 32], expected: [u8; 32] },
+    /// The public values don't hash to the committed values digest of the proof, its second
+    /// Groth16 public input.
+    CommittedValuesDigest {
+        proof: [u8; 32],
+        recomputed: [u8; 32],
+    },
+    /// The pairing check fails for the proof's own vkey hash and digest, so the proof itself is
+    /// corrupt, or was generated for another verification key with the same selector.
+    InvalidProof(Error),
+}
+
+impl core::fmt::Display for ProofMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Groth16VkeyHash { proof, vk } => write!(
+                f,
+                "Groth16 vkey hash mismatch: the proof's selector is 0x{}, the verification key's \
+                 hash starts with 0x{}",
+                hex::encode(proof),
+                hex::encode(vk)
+            ),
+            Self::Sp1VkeyHash { proof, expected } => write!(
+                f,
+                "program vkey hash mismatch: the proof is for 0x{}, expected 0x{}",
+                hex::encode(proof),
+                hex::encode(expected)
+            ),
+            Self::CommittedValuesDigest { proof, recomputed } => write!(
+                f,
+                "committed values digest mismatch: the proof commits to 0x{}, the public values \
+                 hash to 0x{}",
+                hex::encode(proof),
+                hex::encode(recomputed)
+            ),
+            Self::InvalidProof(error) => write!(f, "the proof doesn't verify: {error}"),
+        }
+    }
+}
+
+/// Checks a fixture against `groth16_vk` and the program vkey hash a verifier expects, returning
+/// every check it fails.
+///
+/// The checks are those of [`verify_proof_fixture`](crate::verify_proof_fixture), plus the
+/// comparison with `expected_sp1_vkey_hash`. The digest is only recomputed if the fixture carries
+/// the public values. The pairing check runs even if other checks fail, against the vkey hash and
+/// digest stored in the fixture, so that a valid proof of the wrong program or values is told
+/// apart from a corrupt one.
+pub fn diagnose_proof<'a>(
+    fixture: impl Into<SP1ProofFixtureRef<'a>>,
+    expected_sp1_vkey_hash: &[u8; 32],
+    groth16_vk: &[u8],
+) -> Result<(), Vec<ProofMismatch>> {
+    let fixture = fixture.into();
+    let mut mismatches = Vec::new();
+
+    let vk_hash: [u8; 4] = sha256(groth16_vk)[..4].try_into().unwrap();
+    if !ct_eq(&vk_hash, fixture.groth16_vkey_hash) {
+        mismatches.push(ProofMismatch::Groth16VkeyHash {
+            proof: *fixture.groth16_vkey_hash,
+            vk: vk_hash,
+        });
+    }
+    if !ct_eq(fixture.sp1_vkey_hash, expected_sp1_vkey_hash) {
+        mismatches.push(ProofMismatch::Sp1VkeyHash {
+            proof: *fixture.sp1_vkey_hash,
+            expected: *expected_sp1_vkey_hash,
+        });
+    }
+    if !fixture.sp1_public_inputs.is_empty() {
+        let recomputed = hash_public_inputs(fixture.sp1_public_inputs);
+        if !ct_eq(&recomputed, fixture.committed_values_digest) {
+            mismatches.push(ProofMismatch::CommittedValuesDigest {
+                proof: *fixture.committed_values_digest,
+                recomputed,
+            });
+        }
+    }
+    let verified = CommittedValuesDigest::from_masked_bytes(*fixture.committed_values_digest)
+        .and_then(|committed_values_digest| {
+            verify_proof_raw(
+                fixture.proof,
+                &groth16_public_inputs(fixture.sp1_vkey_hash, &committed_values_digest),
+                groth16_vk,
+            )
+        });
+    if let Err(error) = verified {
+        mismatches.push(ProofMismatch::InvalidProof(error));
+    }
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
+    }
+}
@@ -0,0 +1,115 @@
+//! The committed values digest, the second Groth16 public input of SP1 proofs.
+
+use std::ops::Deref;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    utils::{sha256, sha256v},
+    Error,
+};
+
+/// The mask of the first byte of a committed values digest.
+///
+/// The digest is a scalar of BN254, whose modulus is 254 bits long, so SP1 zeroes the top 3 bits
+/// of the SHA-256 hash of the public values. The same happens in the SP1 Ethereum verifier contract.
+const FIRST_BYTE_MASK: u8 = 0x1F;
+
+/// The committed values digest of SP1 public values: their SHA-256 hash, with the top 3 bits
+/// zeroed.
+///
+/// The digest can only be built by hashing public values, or from bytes that are already masked,
+/// so that an unmasked SHA-256 hash can't reach the pairing check, where it would fail without
+/// explanation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CommittedValuesDigest([u8; 32]);
+
+impl CommittedValuesDigest {
+    /// Hashes SP1 public values into their digest.
+    pub fn from_public_values(public_values: &[u8]) -> Self {
+        Self::from_sha256(sha256(public_values))
+    }
+
+    /// Hashes SP1 public values committed under `domain`, which separates them from the same bytes
+    /// committed by programs of other protocols.
+    ///
+    /// The public values of the SP1 program must start with the domain tag: the length of `domain`
+    /// as a little-endian `u64`, then `domain`, e.g. committed with
+    /// `sp1_zkvm::io::commit(&domain.to_vec())`. `public_values` are the bytes committed after the
+    /// tag, and the digest is that of the tag followed by them.
+    pub fn from_domain_public_values(domain: &[u8], public_values: &[u8]) -> Self {
+        Self::from_sha256(sha256v(&[
+            &(domain.len() as u64).to_le_bytes(),
+            domain,
+            public_values,
+        ]))
+    }
+
+    /// Masks the SHA-256 hash of the public values.
+    pub(crate) fn from_sha256(mut hash: [u8; 32]) -> Self {
+        hash[0] &= FIRST_BYTE_MASK;
+        Self(hash)
+    }
+
+    /// Wraps a digest, failing with [`Error::InvalidPublicInput`] if any of its top 3 bits is set.
+    pub fn from_masked_bytes(bytes: [u8; 32]) -> Result<Self, Error> {
+        if bytes[0] & !FIRST_BYTE_MASK != 0 {
+            return Err(Error::InvalidPublicInput);
+        }
+        Ok(Self(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl TryFrom<[u8; 32]> for CommittedValuesDigest {
+    type Error = Error;
+
+    fn try_from(bytes: [u8; 32]) -> Result<Self, Error> {
+        Self::from_masked_bytes(bytes)
+    }
+}
+
+impl From<CommittedValuesDigest> for [u8; 32] {
+    fn from(digest: CommittedValuesDigest) -> Self {
+        digest.0
+    }
+}
+
+impl Deref for CommittedValuesDigest {
+    type Target = [u8; 32];
+
+    fn deref(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for CommittedValuesDigest {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl BorshSerialize for CommittedValuesDigest {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+/// Deserializing checks the mask, like [`CommittedValuesDigest::from_masked_bytes`].
+impl BorshDeserialize for CommittedValuesDigest {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        Self::from_masked_bytes(<[u8; 32]>::deserialize_reader(reader)?).map_err(|_| {
+            borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                "committed values digest with its top 3 bits set",
+            )
+        })
+    }
+}
@@ -0,0 +1,223 @@
+//! A stable serialization of [`Error`], for off-chain services returning errors to their callers.
+//!
+//! An error serializes as its code, its name and the details of its variant, e.g.
+//! `{"code":19,"name":"InvalidProofLength","detail":{"expected_one_of":[256],"got":260}}`. The
+//! detail is `null` for variants without any, and `{"source":"..."}` for errors with a source.
+//!
+//! Codes and names are append-only: a variant keeps its code and name forever, and new variants
+//! take the next code. Clients classify errors by code, and can deserialize them back into an
+//! [`Error`] of the same build. Details that borrow static data, such as the supported SP1
+//! releases, round-trip if they are ones this build produces, and are otherwise replaced by empty
+//! values; sources come back as their message.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    utils::source, versions::KNOWN_SP1_VERSIONS, Error, InputSection, PROOF_LEN,
+    PROOF_WITH_SELECTOR_LEN, SUPPORTED_SP1_VERSIONS,
+};
+
+/// The proof lengths of the [`Error::InvalidProofLength`] errors the crate returns.
+const EXPECTED_PROOF_LENS: [&[usize]; 3] = [
+    &[PROOF_WITH_SELECTOR_LEN],
+    &[PROOF_LEN],
+    &[PROOF_LEN, PROOF_WITH_SELECTOR_LEN],
+];
+
+#[derive(Serialize, Deserialize)]
+struct ErrorJson {
+    code: u32,
+    name: String,
+    detail: Option<Detail>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Detail {
+    ProofLength {
+        expected_one_of: Vec<usize>,
+        got: usize,
+    },
+    TrailingBytes {
+        section: String,
+        extra: usize,
+    },
+    Candidates {
+        candidates: usize,
+    },
+    Sp1Version {
+        detected: String,
+        supported: Vec<String>,
+    },
+    Source {
+        source: String,
+    },
+    Limit {
+        len: usize,
+        max: usize,
+    },
+}
+
+/// The message of the source of a deserialized error.
+#[derive(Debug)]
+struct SourceMessage(String);
+
+impl core::fmt::Display for SourceMessage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SourceMessage {}
+
+fn section_name(section: InputSection) -> &'static str {
+    match section {
+        InputSection::Proof => "proof",
+        InputSection::PublicInputs => "public_inputs",
+        InputSection::Vk => "vk",
+        InputSection::Fixture => "fixture",
+        InputSection::PublicValues => "public_values",
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let detail = match self {
+            Self::DeserializationError(source)
+            | Self::BorshSerializeError(source)
+            | Self::BorshDeserializeError(source)
+            | Self::IoError(source) => source.as_ref().map(|source| Detail::Source {
+                source: source.to_string(),
+            }),
+            Self::InvalidProofLength {
+                expected_one_of,
+                got,
+            } => Some(Detail::ProofLength {
+                expected_one_of: expected_one_of.to_vec(),
+                got: *got,
+            }),
+            Self::TrailingBytes { section, extra } => Some(Detail::TrailingBytes {
+                section: section_name(*section).to_string(),
+                extra: *extra,
+            }),
+            Self::NoVkeyHashMatched { candidates } => Some(Detail::Candidates {
+                candidates: *candidates,
+            }),
+            Self::UnsupportedSp1Version {
+                detected,
+                supported,
+            } => Some(Detail::Sp1Version {
+                detected: detected.to_string(),
+                supported: supported
+                    .iter()
+                    .map(|version| version.to_string())
+                    .collect(),
+            }),
+            Self::PublicValuesTooLarge { len, max } => Some(Detail::Limit {
+                len: *len,
+                max: *max,
+            }),
+            _ => None,
+        };
+        ErrorJson {
+            code: self.code(),
+            name: self.name().to_string(),
+            detail,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Error {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ErrorJson { code, name, detail } = ErrorJson::deserialize(deserializer)?;
+        let error = from_json(code, detail).map_err(de::Error::custom)?;
+        if error.name() != name {
+            return Err(de::Error::custom(format_args!(
+                "the error code {code} is {}, not {name}",
+                error.name()
+            )));
+        }
+        Ok(error)
+    }
+}
+
+fn from_json(code: u32, detail: Option<Detail>) -> Result<Error, String> {
+    let unexpected = || format!("unexpected detail for the error code {code}");
+    let source_of = |detail: Option<Detail>| match detail {
+        None => Ok(None),
+        Some(Detail::Source { source: message }) => Ok(source(SourceMessage(message))),
+        Some(_) => Err(unexpected()),
+    };
+
+    let error = match (code, detail) {
+        (0, None) => Error::G1CompressionError,
+        (1, None) => Error::G2CompressionError,
+        (2, None) => Error::VerificationError,
+        (3, None) => Error::InvalidPublicInput,
+        (4, None) => Error::SerializationError,
+        (5, detail) => Error::DeserializationError(source_of(detail)?),
+        (6, None) => Error::InvalidInstructionData,
+        (7, None) => Error::ArithmeticError,
+        (8, None) => Error::PairingError,
+        (9, None) => Error::InvalidInput,
+        (10, detail) => Error::BorshSerializeError(source_of(detail)?),
+        (11, detail) => Error::BorshDeserializeError(source_of(detail)?),
+        (12, detail) => Error::IoError(source_of(detail)?),
+        (13, None) => Error::Groth16VkeyHashMismatch,
+        (14, None) => Error::InvalidProgramVkeyHash,
+        (15, None) => Error::PublicValuesDigestMismatch,
+        (16, None) => Error::InputTooLarge,
+        (17, None) => Error::ExecutionNotAggregated,
+        (18, None) => Error::NoVkAvailable,
+        (
+            19,
+            Some(Detail::ProofLength {
+                expected_one_of,
+                got,
+            }),
+        ) => Error::InvalidProofLength {
+            expected_one_of: EXPECTED_PROOF_LENS
+                .into_iter()
+                .find(|lens| **lens == expected_one_of[..])
+                .unwrap_or(&[]),
+            got,
+        },
+        (20, Some(Detail::TrailingBytes { section, extra })) => Error::TrailingBytes {
+            section: [
+                InputSection::Proof,
+                InputSection::PublicInputs,
+                InputSection::Vk,
+                InputSection::Fixture,
+                InputSection::PublicValues,
+            ]
+            .into_iter()
+            .find(|known| section_name(*known) == section)
+            .ok_or_else(|| format!("unknown input section {section}"))?,
+            extra,
+        },
+        (21, Some(Detail::Candidates { candidates })) => Error::NoVkeyHashMatched { candidates },
+        (
+            22,
+            Some(Detail::Sp1Version {
+                detected,
+                supported,
+            }),
+        ) => Error::UnsupportedSp1Version {
+            detected: KNOWN_SP1_VERSIONS
+                .iter()
+                .find(|known| known.version == detected)
+                .map_or("unknown", |known| known.version),
+            supported: if SUPPORTED_SP1_VERSIONS.iter().eq(supported.iter()) {
+                SUPPORTED_SP1_VERSIONS
+            } else {
+                &[]
+            },
+        },
+        (23, None) => Error::SchemaHashMismatch,
+        (24, Some(Detail::Limit { len, max })) => Error::PublicValuesTooLarge { len, max },
+        (0..=24, _) => return Err(unexpected()),
+        _ => return Err(format!("unknown error code {code}")),
+    };
+    Ok(error)
+}
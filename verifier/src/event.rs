@@ -0,0 +1,63 @@
+//! Verification events, for off-chain indexers.
+//!
+//! [`emit_verification_event`] logs a [`VerificationEvent`] with `sol_log_data`, which shows up in
+//! the transaction logs as a `Program data: <base64>` line. [`parse_verification_events`] decodes
+//! those lines back.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Prefixes every verification event, i.e. `sha256("sp1-solana:verification-event")[..8]`.
+pub const VERIFICATION_EVENT_DISCRIMINATOR: [u8; 8] =
+    [0x8b, 0xfd, 0xcf, 0x8e, 0x82, 0x84, 0xe4, 0xad];
+
+/// The prefix of the log lines written by `sol_log_data`.
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Records that a proof was verified for an SP1 program.
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct VerificationEvent {
+    pub sp1_vkey_hash: [u8; 32],
+    pub committed_values_digest: [u8; 32],
+}
+
+impl VerificationEvent {
+    /// The length of the logged data, including the discriminator.
+    pub const LEN: usize = 8 + 32 + 32;
+
+    /// Encodes the event as the discriminator followed by the Borsh-serialized event.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[..8].copy_from_slice(&VERIFICATION_EVENT_DISCRIMINATOR);
+        bytes[8..40].copy_from_slice(&self.sp1_vkey_hash);
+        bytes[40..].copy_from_slice(&self.committed_values_digest);
+        bytes
+    }
+
+    /// Decodes an event encoded by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let payload = bytes.strip_prefix(&VERIFICATION_EVENT_DISCRIMINATOR)?;
+        Self::try_from_slice(payload).ok()
+    }
+}
+
+/// Logs a verification event. Call it once the proof has been verified.
+pub fn emit_verification_event(sp1_vkey_hash: &[u8; 32], committed_values_digest: &[u8; 32]) {
+    let event = VerificationEvent {
+        sp1_vkey_hash: *sp1_vkey_hash,
+        committed_values_digest: *committed_values_digest,
+    };
+    solana_program::log::sol_log_data(&[&event.to_bytes()]);
+}
+
+/// Decodes the verification events from the log messages of a transaction.
+///
+/// Any program can log data that looks like a verification event, so indexers must check which
+/// program emitted an event before trusting it.
+pub fn parse_verification_events(logs: &[String]) -> Vec<VerificationEvent> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix(PROGRAM_DATA_PREFIX))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .filter_map(|bytes| VerificationEvent::from_bytes(&bytes))
+        .collect()
+}
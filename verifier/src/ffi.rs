@@ -0,0 +1,118 @@
+//! A C interface to the verifier, for services written in other languages.
+//!
+//! The header is generated into `include/sp1_solana.h` by `cbindgen` when building with the `ffi`
+//! feature. Link against the library built as a `staticlib` or `cdylib`, e.g. with
+//! `cargo rustc --release --features ffi --crate-type staticlib`.
+//!
+//! Panics are caught at the boundary and reported as [`SP1_SOLANA_ERROR_PANIC`], so that no
+//! unwinding crosses into the caller.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::{
+    hash_public_inputs, utils::sha256, verify_proof_with_digest, CommittedValuesDigest, Error,
+};
+
+/// Returned on success.
+pub const SP1_SOLANA_OK: i32 = 0;
+
+/// Returned if the verifier panicked.
+pub const SP1_SOLANA_ERROR_PANIC: i32 = -1;
+
+/// Returned if a required pointer is null.
+pub const SP1_SOLANA_ERROR_NULL_POINTER: i32 = -2;
+
+/// Verifier errors are returned as their numeric code plus this offset, as the code of the first
+/// error is 0.
+pub const SP1_SOLANA_ERROR_CODE_OFFSET: i32 = 1;
+
+fn error_code(error: Error) -> i32 {
+    SP1_SOLANA_ERROR_CODE_OFFSET + error.code() as i32
+}
+
+/// Borrows `len` bytes at `ptr`, which may only be null if `len` is 0.
+///
+/// # Safety
+///
+/// Unless null, `ptr` must be valid for reads of `len` bytes for `'a`.
+unsafe fn slice<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (ptr.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(std::slice::from_raw_parts(ptr, len)),
+    }
+}
+
+/// Runs `f`, turning a panic into [`SP1_SOLANA_ERROR_PANIC`].
+fn catch_panic(f: impl FnOnce() -> i32) -> i32 {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(SP1_SOLANA_ERROR_PANIC)
+}
+
+/// Verifies an SP1 Groth16 proof, like `verify_proof`.
+///
+/// `proof` is the output of `SP1ProofWithPublicValues::bytes()`, `public_inputs` the SP1 public
+/// values, `vkey_hash` the 32-byte program vkey hash (`vk.bytes32()` decoded from hex) and `vk`
+/// the Groth16 verification key. Returns `SP1_SOLANA_OK` if the proof verifies.
+///
+/// # Safety
+///
+/// Each pointer must be valid for reads of its length, `vkey_hash_ptr` of 32 bytes. Pointers with a
+/// length of 0 may be null.
+#[no_mangle]
+pub unsafe extern "C" fn sp1_solana_verify(
+    proof_ptr: *const u8,
+    proof_len: usize,
+    public_inputs_ptr: *const u8,
+    public_inputs_len: usize,
+    vkey_hash_ptr: *const u8,
+    vk_ptr: *const u8,
+    vk_len: usize,
+) -> i32 {
+    catch_panic(|| {
+        let (Some(proof), Some(public_inputs), Some(vkey_hash), Some(vk)) = (
+            slice(proof_ptr, proof_len),
+            slice(public_inputs_ptr, public_inputs_len),
+            slice(vkey_hash_ptr, 32),
+            slice(vk_ptr, vk_len),
+        ) else {
+            return SP1_SOLANA_ERROR_NULL_POINTER;
+        };
+        let groth16_vk_hash: [u8; 4] = sha256(vk)[..4].try_into().unwrap();
+        match verify_proof_with_digest(
+            proof,
+            &CommittedValuesDigest::from_public_values(public_inputs),
+            vkey_hash.try_into().unwrap(),
+            vk,
+            &groth16_vk_hash,
+        ) {
+            Ok(()) => SP1_SOLANA_OK,
+            Err(error) => error_code(error),
+        }
+    })
+}
+
+/// Writes the committed values digest of the SP1 public values to the 32 bytes at `out_ptr`,
+/// like `hash_public_inputs`.
+///
+/// # Safety
+///
+/// `public_inputs_ptr` must be valid for reads of `public_inputs_len` bytes, or null if it is 0,
+/// and `out_ptr` valid for writes of 32 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sp1_solana_hash_public_inputs(
+    public_inputs_ptr: *const u8,
+    public_inputs_len: usize,
+    out_ptr: *mut u8,
+) -> i32 {
+    catch_panic(|| {
+        let Some(public_inputs) = slice(public_inputs_ptr, public_inputs_len) else {
+            return SP1_SOLANA_ERROR_NULL_POINTER;
+        };
+        if out_ptr.is_null() {
+            return SP1_SOLANA_ERROR_NULL_POINTER;
+        }
+        let digest = hash_public_inputs(public_inputs);
+        std::ptr::copy_nonoverlapping(digest.as_ptr(), out_ptr, digest.len());
+        SP1_SOLANA_OK
+    })
+}
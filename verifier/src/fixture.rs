@@ -0,0 +1,327 @@
+//! A self-contained, Borsh-serializable SP1 Groth16 proof.
+//!
+//! [`SP1ProofFixture`] bundles everything needed to verify a proof: the raw proof, its Groth16
+//! vkey hash selector, the SP1 program vkey hash and the committed values digest, optionally along
+//! with the public values themselves. Its fixed-size fields come first, so that
+//! [`SP1ProofFixtureRef`] can read a fixture stored in account data without copying it.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    groth16_public_inputs, hash_public_inputs,
+    utils::{check_sp1_vkey_hash, check_trailing_bytes, ct_eq, groth16_vk_hash, source},
+    validate_proof_len, verify_proof_raw, CommittedValuesDigest, Error, InputSection,
+    Sp1PublicValues, MAX_SP1_PUBLIC_VALUES_LEN, PROOF_LEN, PROOF_WITH_SELECTOR_LEN, SELECTOR_LEN,
+};
+
+/// The length of a serialized fixture with no public values: the selector, the raw proof, the
+/// SP1 vkey hash, the committed values digest and the length prefix of the public values.
+pub const FIXTURE_MIN_LEN: usize = PROOF_WITH_SELECTOR_LEN + 32 + 32 + 4;
+
+/// An SP1 Groth16 proof with everything needed to verify it.
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct SP1ProofFixture {
+    /// The first 4 bytes of the SHA-256 hash of the Groth16 verification key.
+    pub groth16_vkey_hash: [u8; SELECTOR_LEN],
+    /// The raw Groth16 proof.
+    pub proof: [u8; PROOF_LEN],
+    /// The SP1 program vkey hash, from `vk.bytes32()`.
+    pub sp1_vkey_hash: [u8; 32],
+    /// The output of [`hash_public_inputs`] on the SP1 public values.
+    pub committed_values_digest: [u8; 32],
+    /// The SP1 public values, or empty if only their digest is kept.
+    pub sp1_public_inputs: Sp1PublicValues,
+}
+
+impl SP1ProofFixture {
+    /// Builds a fixture from a proof in SP1's standard format, i.e. the output of
+    /// `SP1ProofWithPublicValues::bytes()`, and the SP1 public values.
+    ///
+    /// A raw 256-byte proof lacks the selector the fixture records, so any other length than 260
+    /// bytes fails with [`Error::InvalidProofLength`].
+    #[cfg_attr(
+        all(feature = "tracing", not(target_os = "solana")),
+        tracing::instrument(
+            name = "sp1_solana::fixture_from_components",
+            level = "debug",
+            skip_all,
+            fields(
+                selector = %crate::trace::selector(proof),
+                public_values_len = sp1_public_inputs.len(),
+            ),
+            err(Display),
+        )
+    )]
+    pub fn from_components(
+        proof: &[u8],
+        sp1_public_inputs: &[u8],
+        sp1_vkey_hash: &[u8; 32],
+    ) -> Result<Self, Error> {
+        validate_proof_len(proof)?;
+        let (groth16_vkey_hash, proof) = proof.split_at(SELECTOR_LEN);
+        let sp1_public_inputs = Sp1PublicValues::from(sp1_public_inputs);
+        Ok(Self {
+            groth16_vkey_hash: groth16_vkey_hash.try_into().unwrap(),
+            proof: proof.try_into().unwrap(),
+            sp1_vkey_hash: *sp1_vkey_hash,
+            committed_values_digest: sp1_public_inputs.digest().to_bytes(),
+            sp1_public_inputs,
+        })
+    }
+
+    /// Builds a fake fixture of `sp1_public_inputs`, whose proof is all zeros.
+    ///
+    /// The fixture is structurally valid, with the v3.0.0 Groth16 vkey hash and the digest of the
+    /// public values, so it passes verification with the test-only `mock-verifier` feature, and
+    /// only with it.
+    pub fn mock(sp1_vkey_hash: &[u8; 32], sp1_public_inputs: &[u8]) -> Self {
+        let sp1_public_inputs = Sp1PublicValues::from(sp1_public_inputs);
+        Self {
+            groth16_vkey_hash: crate::GROTH16_VK_3_0_0_HASH,
+            proof: [0; PROOF_LEN],
+            sp1_vkey_hash: *sp1_vkey_hash,
+            committed_values_digest: sp1_public_inputs.digest().to_bytes(),
+            sp1_public_inputs,
+        }
+    }
+
+    /// Splits the public values off the fixture, e.g. to submit the proof on-chain with its digest
+    /// alone while keeping the values elsewhere.
+    ///
+    /// Returns the fixture without public values, and the values, or `None` if the fixture has
+    /// none. Reattach them with [`Self::with_public_values`].
+    pub fn strip_public_values(&self) -> (SP1ProofFixture, Option<Vec<u8>>) {
+        let values = (!self.sp1_public_inputs.is_empty()).then(|| self.sp1_public_inputs.to_vec());
+        let stripped = Self {
+            sp1_public_inputs: Sp1PublicValues::default(),
+            ..self.clone()
+        };
+        (stripped, values)
+    }
+
+    /// Attaches `values` to the fixture as its public values, replacing any it has.
+    ///
+    /// Fails with [`Error::PublicValuesDigestMismatch`] if the values don't hash to the fixture's
+    /// committed values digest, e.g. because they are those of another proof.
+    pub fn with_public_values(self, values: Vec<u8>) -> Result<SP1ProofFixture, Error> {
+        let sp1_public_inputs = Sp1PublicValues::from(values);
+        if !ct_eq(
+            &sp1_public_inputs.digest().to_bytes(),
+            &self.committed_values_digest,
+        ) {
+            return Err(Error::PublicValuesDigestMismatch);
+        }
+        Ok(Self {
+            sp1_public_inputs,
+            ..self
+        })
+    }
+
+    /// Serializes the fixture with Borsh.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(self).expect("serializing to a Vec can't fail")
+    }
+
+    /// Deserializes a fixture serialized with [`Self::to_bytes`].
+    ///
+    /// Public values longer than [`MAX_SP1_PUBLIC_VALUES_LEN`] fail with
+    /// [`Error::PublicValuesTooLarge`], before they are copied.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::try_from_slice(bytes).map_err(Error::from_borsh)
+    }
+
+    /// Like [`Self::from_bytes`], with `max_public_inputs_len` as the limit on the length of the
+    /// public values instead of [`MAX_SP1_PUBLIC_VALUES_LEN`].
+    ///
+    /// Programs decoding fixtures from instruction data use this to bound what they accept, and
+    /// off-chain services to accept longer values.
+    pub fn from_bytes_with_limit(
+        bytes: &[u8],
+        max_public_inputs_len: usize,
+    ) -> Result<Self, Error> {
+        let fixture = SP1ProofFixtureRef::from_bytes_with_limit(bytes, max_public_inputs_len)
+            .map_err(|error| match error {
+                Error::PublicValuesTooLarge { .. } => error,
+                _ => Error::BorshDeserializeError(source(error)),
+            })?;
+        if fixture.serialized_len() != bytes.len() {
+            return Err(Error::BorshDeserializeError(None));
+        }
+        Ok(fixture.into())
+    }
+
+    /// Loads a fixture saved with [`Self::save`].
+    #[cfg(all(feature = "std", not(target_os = "solana")))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "sp1_solana::fixture_load",
+            skip_all,
+            fields(path = %path.as_ref().display()),
+            err(Display),
+        )
+    )]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Saves the fixture to a file.
+    #[cfg(all(feature = "std", not(target_os = "solana")))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "sp1_solana::fixture_save",
+            skip_all,
+            fields(path = %path.as_ref().display()),
+            err(Display),
+        )
+    )]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), Error> {
+        Ok(std::fs::write(path, self.to_bytes())?)
+    }
+}
+
+/// A borrowed view of a serialized [`SP1ProofFixture`], e.g. in account data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SP1ProofFixtureRef<'a> {
+    pub groth16_vkey_hash: &'a [u8; SELECTOR_LEN],
+    pub proof: &'a [u8; PROOF_LEN],
+    pub sp1_vkey_hash: &'a [u8; 32],
+    pub committed_values_digest: &'a [u8; 32],
+    pub sp1_public_inputs: &'a [u8],
+}
+
+impl<'a> SP1ProofFixtureRef<'a> {
+    /// The length of the fixed-size fields, plus the length prefix of the public values.
+    const HEADER_LEN: usize = FIXTURE_MIN_LEN;
+
+    /// Reads a serialized fixture from the start of `bytes`, ignoring any trailing bytes, e.g. the
+    /// rest of an account. See [`Self::from_bytes_exact`] to reject them.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        Self::from_bytes_with_limit(bytes, usize::MAX)
+    }
+
+    /// Like [`Self::from_bytes`], but fails with [`Error::TrailingBytes`] if `bytes` continues past
+    /// the fixture.
+    pub fn from_bytes_exact(bytes: &'a [u8]) -> Result<Self, Error> {
+        let fixture = Self::from_bytes(bytes)?;
+        check_trailing_bytes(InputSection::Fixture, bytes.len(), fixture.serialized_len())?;
+        Ok(fixture)
+    }
+
+    /// Like [`Self::from_bytes`], but fails with [`Error::PublicValuesTooLarge`] if the length
+    /// prefix of the public values exceeds `max_public_inputs_len`.
+    pub fn from_bytes_with_limit(
+        bytes: &'a [u8],
+        max_public_inputs_len: usize,
+    ) -> Result<Self, Error> {
+        let header = bytes.get(..Self::HEADER_LEN).ok_or(Error::InvalidInput)?;
+        let (header, len) = header.split_at(Self::HEADER_LEN - 4);
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        if len > max_public_inputs_len {
+            return Err(Error::PublicValuesTooLarge {
+                len,
+                max: max_public_inputs_len,
+            });
+        }
+        let sp1_public_inputs = bytes
+            .get(Self::HEADER_LEN..)
+            .and_then(|values| values.get(..len))
+            .ok_or(Error::InvalidInput)?;
+        Ok(Self {
+            groth16_vkey_hash: header[..SELECTOR_LEN].try_into().unwrap(),
+            proof: header[SELECTOR_LEN..PROOF_WITH_SELECTOR_LEN]
+                .try_into()
+                .unwrap(),
+            sp1_vkey_hash: header[PROOF_WITH_SELECTOR_LEN..PROOF_WITH_SELECTOR_LEN + 32]
+                .try_into()
+                .unwrap(),
+            committed_values_digest: header[PROOF_WITH_SELECTOR_LEN + 32..].try_into().unwrap(),
+            sp1_public_inputs,
+        })
+    }
+
+    /// The serialized length of the fixture.
+    pub fn serialized_len(&self) -> usize {
+        Self::HEADER_LEN + self.sp1_public_inputs.len()
+    }
+}
+
+impl<'a> From<&'a SP1ProofFixture> for SP1ProofFixtureRef<'a> {
+    fn from(fixture: &'a SP1ProofFixture) -> Self {
+        Self {
+            groth16_vkey_hash: &fixture.groth16_vkey_hash,
+            proof: &fixture.proof,
+            sp1_vkey_hash: &fixture.sp1_vkey_hash,
+            committed_values_digest: &fixture.committed_values_digest,
+            sp1_public_inputs: fixture.sp1_public_inputs.as_slice(),
+        }
+    }
+}
+
+impl From<SP1ProofFixtureRef<'_>> for SP1ProofFixture {
+    fn from(fixture: SP1ProofFixtureRef<'_>) -> Self {
+        Self {
+            groth16_vkey_hash: *fixture.groth16_vkey_hash,
+            proof: *fixture.proof,
+            sp1_vkey_hash: *fixture.sp1_vkey_hash,
+            committed_values_digest: *fixture.committed_values_digest,
+            sp1_public_inputs: fixture.sp1_public_inputs.into(),
+        }
+    }
+}
+
+/// Verifies a fixture against `groth16_vk`.
+///
+/// The proof is verified for the SP1 program vkey hash stored in the fixture itself, so it can be
+/// a proof of any program: use [`verify_proof_fixture_for_program`] to accept a single one. If the
+/// fixture carries the public values, they must hash to its committed values digest.
+pub fn verify_proof_fixture<'a>(
+    fixture: impl Into<SP1ProofFixtureRef<'a>>,
+    groth16_vk: &[u8],
+) -> Result<(), Error> {
+    let fixture = fixture.into();
+
+    if !ct_eq(&groth16_vk_hash(groth16_vk), fixture.groth16_vkey_hash) {
+        return Err(crate::versions::selector_mismatch(
+            fixture.groth16_vkey_hash,
+        ));
+    }
+    if !fixture.sp1_public_inputs.is_empty()
+        && !ct_eq(
+            &hash_public_inputs(fixture.sp1_public_inputs),
+            fixture.committed_values_digest,
+        )
+    {
+        return Err(Error::PublicValuesDigestMismatch);
+    }
+    check_sp1_vkey_hash(fixture.sp1_vkey_hash)?;
+    let committed_values_digest =
+        CommittedValuesDigest::from_masked_bytes(*fixture.committed_values_digest)?;
+
+    verify_proof_raw(
+        fixture.proof,
+        &groth16_public_inputs(fixture.sp1_vkey_hash, &committed_values_digest),
+        groth16_vk,
+    )
+}
+
+/// Verifies a fixture against `groth16_vk` like [`verify_proof_fixture`], and checks that it is a
+/// proof of the program `expected_sp1_vkey_hash`, failing with [`Error::InvalidProgramVkeyHash`]
+/// otherwise.
+///
+/// The public inputs only carry the last 31 bytes of the program vkey hash, so both hashes are
+/// compared in full, and `expected_sp1_vkey_hash` must have the zero first byte of `vk.bytes32()`.
+pub fn verify_proof_fixture_for_program<'a>(
+    fixture: impl Into<SP1ProofFixtureRef<'a>>,
+    expected_sp1_vkey_hash: &[u8; 32],
+    groth16_vk: &[u8],
+) -> Result<(), Error> {
+    let fixture = fixture.into();
+
+    check_sp1_vkey_hash(expected_sp1_vkey_hash)?;
+    if !ct_eq(fixture.sp1_vkey_hash, expected_sp1_vkey_hash) {
+        return Err(Error::InvalidProgramVkeyHash);
+    }
+    verify_proof_fixture(fixture, groth16_vk)
+}
@@ -0,0 +1,144 @@
+//! Incremental SHA-256 hashing of SP1 public values.
+//!
+//! Public values that are too large for a single transaction can be absorbed chunk by chunk across
+//! several instructions. The running state is Borsh-serializable, so it can be stored in account
+//! data between instructions and finalized into the committed values digest at the end.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use sha2::digest::generic_array::GenericArray;
+
+use crate::CommittedValuesDigest;
+
+const BLOCK_LEN: usize = 64;
+
+/// The SHA-256 initial hash values.
+pub(crate) const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// A running SHA-256 hash that can round-trip through account data.
+#[derive(BorshSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct Sha256State {
+    state: [u32; 8],
+    buffer: [u8; BLOCK_LEN],
+    buffer_len: u8,
+    total_len: u64,
+}
+
+impl Sha256State {
+    /// The length of a Borsh-serialized `Sha256State`.
+    pub const LEN: usize = 8 * 4 + BLOCK_LEN + 1 + 8;
+
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL_STATE,
+            buffer: [0u8; BLOCK_LEN],
+            buffer_len: 0,
+            total_len: 0,
+        }
+    }
+
+    /// Absorbs the next chunk of data into the hash.
+    pub fn absorb(&mut self, mut chunk: &[u8]) {
+        self.total_len += chunk.len() as u64;
+
+        // Top up a partially filled block first.
+        let buffered = self.buffer_len as usize;
+        if buffered > 0 {
+            let take = (BLOCK_LEN - buffered).min(chunk.len());
+            self.buffer[buffered..buffered + take].copy_from_slice(&chunk[..take]);
+            self.buffer_len += take as u8;
+            chunk = &chunk[take..];
+
+            if (self.buffer_len as usize) < BLOCK_LEN {
+                return;
+            }
+            compress(&mut self.state, &self.buffer);
+            self.buffer_len = 0;
+        }
+
+        let mut blocks = chunk.chunks_exact(BLOCK_LEN);
+        for block in &mut blocks {
+            compress(&mut self.state, block.try_into().unwrap());
+        }
+
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_len = remainder.len() as u8;
+    }
+
+    /// Finishes the hash, returning the plain SHA-256 digest of everything absorbed.
+    pub fn finalize(mut self) -> [u8; 32] {
+        let len = self.buffer_len as usize;
+        let bit_len = self.total_len * 8;
+
+        // Append the 0x80 terminator, then zero padding and the big-endian bit length.
+        self.buffer[len] = 0x80;
+        self.buffer[len + 1..].fill(0);
+        if len + 1 > BLOCK_LEN - 8 {
+            compress(&mut self.state, &self.buffer);
+            self.buffer = [0u8; BLOCK_LEN];
+        }
+        self.buffer[BLOCK_LEN - 8..].copy_from_slice(&bit_len.to_be_bytes());
+        compress(&mut self.state, &self.buffer);
+
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    /// Finishes the hash, returning the committed values digest.
+    ///
+    /// This matches [`CommittedValuesDigest::from_public_values`] on the concatenation of every
+    /// absorbed chunk.
+    pub fn finalize_masked(self) -> CommittedValuesDigest {
+        CommittedValuesDigest::from_sha256(self.finalize())
+    }
+}
+
+impl BorshDeserialize for Sha256State {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let state = <[u32; 8]>::deserialize_reader(reader)?;
+        let buffer = <[u8; BLOCK_LEN]>::deserialize_reader(reader)?;
+        let buffer_len = u8::deserialize_reader(reader)?;
+        let total_len = u64::deserialize_reader(reader)?;
+
+        // A full block is always compressed immediately, so the buffer is never full.
+        if buffer_len as usize >= BLOCK_LEN {
+            return Err(borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                "invalid SHA-256 buffer length",
+            ));
+        }
+        // The buffer holds what follows the last full block, so a state whose lengths disagree
+        // would finalize to the hash of other data.
+        if total_len % BLOCK_LEN as u64 != buffer_len as u64 {
+            return Err(borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                "SHA-256 total length inconsistent with its buffer length",
+            ));
+        }
+
+        Ok(Self {
+            state,
+            buffer,
+            buffer_len,
+            total_len,
+        })
+    }
+}
+
+impl Default for Sha256State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn compress(state: &mut [u32; 8], block: &[u8; BLOCK_LEN]) {
+    sha2::compress256(
+        state,
+        core::slice::from_ref(GenericArray::from_slice(block)),
+    );
+}
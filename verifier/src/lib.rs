@@ -2,6 +2,11 @@
 //!
 //! This crate contains utilities for verifying SP1 Groth16 proofs on Solana.
 //!
+//! Point decompression, the public input MSM and the pairing check are delegated to
+//! `groth16-solana`, which calls Solana's `alt_bn128` syscalls when compiled for
+//! `target_os = "solana"` and falls back to an arkworks implementation of the same
+//! operations everywhere else.
+//!
 //! # Example
 //! ```no_run
 //! use sp1_sdk::proof::SP1ProofWithPublicValues;
@@ -21,31 +26,491 @@
 //!
 //! verify_proof(&proof_bytes, &sp1_public_inputs, &vkey_hash, &GROTH16_VK_2_0_0_BYTES).unwrap();
 //! ```
+//!
+//! # Mock verification
+//!
+//! The test-only `mock-verifier` feature replaces the pairing check with the structural checks
+//! alone, so that programs can be developed against fake proofs such as
+//! [`SP1ProofFixture::mock`]. The Groth16 vkey hash selector and the committed values digest are
+//! still checked, so the public values are handled as with real proofs. **A mock verifier accepts
+//! any well-formed proof.** Enable the `mainnet` feature in release builds: combining it with
+//! `mock-verifier` is a compile error.
 
+use backend::Groth16Backend;
 use groth16_solana::groth16::Groth16Verifyingkey;
-use sha2::{Digest, Sha256};
 
-#[cfg(test)]
+#[cfg(all(feature = "mock-verifier", feature = "mainnet"))]
+compile_error!("the mock-verifier feature accepts any proof, and can't be enabled with mainnet");
+
+#[cfg(all(
+    feature = "embedded-vk",
+    not(any(
+        feature = "embedded-vk-v3-0-0",
+        feature = "embedded-vk-v3-0-0-rc4",
+        feature = "embedded-vk-v2-0-0"
+    ))
+))]
+compile_error!(
+    "the embedded-vk feature embeds no verification key on its own: enable one of \
+     embedded-vk-v3-0-0, embedded-vk-v3-0-0-rc4 or embedded-vk-v2-0-0, or pass your own key to \
+     verify_proof"
+);
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
 mod test;
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test_circuit;
+
+pub mod aggregation;
+#[cfg(feature = "solana")]
+pub mod allowlist;
+pub mod backend;
+#[cfg(feature = "solana")]
+pub mod cpi;
+#[cfg(feature = "solana")]
+pub mod event;
+#[cfg(feature = "solana")]
+pub mod record;
+#[cfg(feature = "solana")]
+pub mod router;
+#[cfg(feature = "solana")]
+pub mod upgrade_authority;
+#[cfg(feature = "solana")]
+pub mod values_account;
+pub mod vectors;
+#[cfg(feature = "solana")]
+pub mod vk_account;
+
+#[cfg(feature = "ark")]
+mod arkworks;
+#[cfg(feature = "ark")]
+pub use arkworks::verify_proof_raw_ark;
+
+#[cfg(feature = "circom")]
+pub mod circom;
+
+mod context;
+pub use context::{EmbeddedVks, ProgramVkeys, VerificationContext, VkProvider, VkSource};
+
+mod diagnose;
+pub use diagnose::{diagnose_proof, ProofMismatch};
+
+#[cfg(feature = "serde")]
+mod error_json;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+mod fixture;
+pub use fixture::{
+    verify_proof_fixture, verify_proof_fixture_for_program, SP1ProofFixture, SP1ProofFixtureRef,
+    FIXTURE_MIN_LEN,
+};
+
+mod hasher;
+pub use hasher::Sha256State;
+
+mod lint;
+pub use lint::{FixtureLint, ProofPoint};
+
+pub mod nullifier;
+#[cfg(feature = "solana")]
+pub use nullifier::NULLIFIER_SEED;
+pub use nullifier::{Nullifier, NULLIFIER_DOMAIN};
+
+mod digest;
+pub use digest::CommittedValuesDigest;
+
+mod precheck;
+pub use precheck::{precheck_proof, ProofSummary};
+
+mod prepared;
+#[cfg(feature = "solana")]
+pub use prepared::prepare_public_inputs;
+pub use prepared::verify_proof_with_prepared_inputs;
+
+mod proof_bytes;
+use proof_bytes::check_selector;
+pub use proof_bytes::ProofBytes;
+
+mod public_values;
+pub use public_values::{PublicValuesReader, Sp1PublicValues, MAX_SP1_PUBLIC_VALUES_LEN};
+
+mod schema;
+#[doc(hidden)]
+pub use schema::required_fields;
+pub use schema::{
+    schema_hash, schema_hashes, SchemaReader, Sp1PublicValuesDecode, TrailingBytesPolicy,
+    SCHEMA_HASH_LEN,
+};
 
 mod utils;
+#[cfg(not(all(feature = "std", not(target_os = "solana"))))]
+pub use utils::NoSource;
+#[cfg(feature = "anchor")]
+pub use utils::ANCHOR_ERROR_CODE_OFFSET;
 use utils::*;
+pub use utils::{
+    convert_endianness, decode_sp1_vkey_hash, groth16_public_inputs, groth16_public_values,
+    hash_public_inputs, hash_public_inputs_domain, validate_proof_len, validate_public_inputs_len,
+    validate_raw_proof_len, validate_vk_len, Error, ErrorSource, InputSection, NegatedProof, Proof,
+    ProofRef, PublicInputs, VerificationKey, VkView, GROTH16_PUBLIC_INPUTS_LEN, GROTH16_VK_MIN_LEN,
+    PROOF_LEN, PROOF_WITH_SELECTOR_LEN, SELECTOR_LEN,
+};
+
+mod versions;
+pub use versions::{
+    known_sp1_version, KnownSp1Version, KNOWN_SP1_VERSIONS, SUPPORTED_SP1_VERSIONS,
+};
+
+#[cfg(all(
+    feature = "metrics",
+    not(target_os = "solana"),
+    not(target_arch = "wasm32")
+))]
+mod metrics;
+#[cfg(all(
+    feature = "metrics",
+    not(target_os = "solana"),
+    not(target_arch = "wasm32")
+))]
+pub use metrics::{verify_proof_timed, VerifyMetrics};
+
+mod observe;
+use observe::{observe, Observer, Step};
+
+#[cfg(all(feature = "tracing", not(target_os = "solana")))]
+mod trace;
+
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "wasm")]
+pub use wasm::{hash_public_inputs_js, verify_proof_js};
+
+/// The typed verification API: a [`VerificationContext`] and what it takes and returns.
+pub mod prelude {
+    pub use crate::{
+        CommittedValuesDigest, EmbeddedVks, Error, ProgramVkeys, SP1ProofFixture,
+        SP1ProofFixtureRef, VerificationContext, VkProvider, VkSource,
+    };
+}
 
 /// Groth16 verification keys for different SP1 versions.
+///
+/// Each is embedded with its `embedded-vk-*` feature, all of which are enabled by default.
+#[cfg(feature = "embedded-vk-v3-0-0")]
 pub const GROTH16_VK_3_0_0_BYTES: &[u8] = include_bytes!("../vk/v3.0.0/groth16_vk.bin");
+#[cfg(feature = "embedded-vk-v3-0-0-rc4")]
 pub const GROTH16_VK_3_0_0_RC4_BYTES: &[u8] = include_bytes!("../vk/v3.0.0rc4/groth16_vk.bin");
+#[cfg(feature = "embedded-vk-v2-0-0")]
 pub const GROTH16_VK_2_0_0_BYTES: &[u8] = include_bytes!("../vk/v2.0.0/groth16_vk.bin");
 
+/// The first 4 bytes of the SHA-256 hash of each Groth16 verification key.
+///
+/// SP1 prepends these bytes to every Groth16 proof, so checking them against a constant avoids
+/// hashing the whole verification key on-chain.
+pub const GROTH16_VK_3_0_0_HASH: [u8; 4] = [0x09, 0x06, 0x90, 0x90];
+pub const GROTH16_VK_3_0_0_RC4_HASH: [u8; 4] = [0xfe, 0xb5, 0xe5, 0x4e];
+pub const GROTH16_VK_2_0_0_HASH: [u8; 4] = [0x6a, 0x29, 0x06, 0xac];
+
+/// An SP1 release whose Groth16 verification key is embedded in the crate.
+///
+/// Only the versions whose `embedded-vk-*` feature is enabled are variants.
+#[cfg(feature = "embedded-vk")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Groth16VkVersion {
+    #[cfg(feature = "embedded-vk-v3-0-0")]
+    V3_0_0,
+    #[cfg(feature = "embedded-vk-v3-0-0-rc4")]
+    V3_0_0Rc4,
+    #[cfg(feature = "embedded-vk-v2-0-0")]
+    V2_0_0,
+}
+
+#[cfg(feature = "embedded-vk")]
+impl Groth16VkVersion {
+    /// The SP1 release, e.g. `v3.0.0`.
+    pub fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "embedded-vk-v3-0-0")]
+            Self::V3_0_0 => "v3.0.0",
+            #[cfg(feature = "embedded-vk-v3-0-0-rc4")]
+            Self::V3_0_0Rc4 => "v3.0.0rc4",
+            #[cfg(feature = "embedded-vk-v2-0-0")]
+            Self::V2_0_0 => "v2.0.0",
+        }
+    }
+
+    /// The Groth16 verification key.
+    pub fn groth16_vk(self) -> &'static [u8] {
+        match self {
+            #[cfg(feature = "embedded-vk-v3-0-0")]
+            Self::V3_0_0 => GROTH16_VK_3_0_0_BYTES,
+            #[cfg(feature = "embedded-vk-v3-0-0-rc4")]
+            Self::V3_0_0Rc4 => GROTH16_VK_3_0_0_RC4_BYTES,
+            #[cfg(feature = "embedded-vk-v2-0-0")]
+            Self::V2_0_0 => GROTH16_VK_2_0_0_BYTES,
+        }
+    }
+
+    /// The 4-byte selector prefixed to proofs for the key.
+    pub fn groth16_vkey_hash(self) -> [u8; 4] {
+        match self {
+            #[cfg(feature = "embedded-vk-v3-0-0")]
+            Self::V3_0_0 => GROTH16_VK_3_0_0_HASH,
+            #[cfg(feature = "embedded-vk-v3-0-0-rc4")]
+            Self::V3_0_0Rc4 => GROTH16_VK_3_0_0_RC4_HASH,
+            #[cfg(feature = "embedded-vk-v2-0-0")]
+            Self::V2_0_0 => GROTH16_VK_2_0_0_HASH,
+        }
+    }
+
+    /// The embedded version whose selector is `groth16_vkey_hash`, if any.
+    pub fn from_groth16_vkey_hash(groth16_vkey_hash: &[u8; 4]) -> Option<Self> {
+        available_vk_versions()
+            .iter()
+            .copied()
+            .find(|version| ct_eq(&version.groth16_vkey_hash(), groth16_vkey_hash))
+    }
+}
+
+/// The versions whose verification key is embedded, newest first.
+#[cfg(feature = "embedded-vk")]
+pub fn available_vk_versions() -> &'static [Groth16VkVersion] {
+    &[
+        #[cfg(feature = "embedded-vk-v3-0-0")]
+        Groth16VkVersion::V3_0_0,
+        #[cfg(feature = "embedded-vk-v3-0-0-rc4")]
+        Groth16VkVersion::V3_0_0Rc4,
+        #[cfg(feature = "embedded-vk-v2-0-0")]
+        Groth16VkVersion::V2_0_0,
+    ]
+}
+
+/// The embedded verification keys, by selector.
+const EMBEDDED_VKS: &[([u8; 4], &[u8])] = &[
+    #[cfg(feature = "embedded-vk-v3-0-0")]
+    (GROTH16_VK_3_0_0_HASH, GROTH16_VK_3_0_0_BYTES),
+    #[cfg(feature = "embedded-vk-v3-0-0-rc4")]
+    (GROTH16_VK_3_0_0_RC4_HASH, GROTH16_VK_3_0_0_RC4_BYTES),
+    #[cfg(feature = "embedded-vk-v2-0-0")]
+    (GROTH16_VK_2_0_0_HASH, GROTH16_VK_2_0_0_BYTES),
+];
+
 /// Verifies a proof using raw bytes, without any checks.
 ///
 /// The public inputs are the vkey hash and the commited values digest, concatenated.
 /// The proof is a decompressed G1 element, followed by a decompressed G2 element, followed by a
-/// decompressed G1 element.
-pub fn verify_proof_raw(proof: &[u8], public_inputs: &[u8], vk: &[u8]) -> Result<(), Error> {
-    let proof = load_proof_from_bytes(proof)?;
-    let vk = load_groth16_verifying_key_from_bytes(vk)?;
-    let public_inputs = load_public_inputs_from_bytes(public_inputs)?;
+/// decompressed G1 element. A proof in SP1's standard format, prefixed with its selector, is also
+/// accepted: the selector is checked against `vk`, then stripped. Any other length fails with
+/// [`Error::InvalidProofLength`].
+pub fn verify_proof_raw(
+    proof: impl Into<ProofBytes<'_>>,
+    public_inputs: &[u8],
+    vk: &[u8],
+) -> Result<(), Error> {
+    let proof = proof.into();
+    let proof = proof.strip_selector(|| groth16_vk_hash(vk))?;
+    verify_proof_raw_observed(proof, public_inputs, vk, &mut ())
+}
 
+/// [`verify_proof_raw`] on a raw proof, whose selector, if it had one, was checked, with its
+/// stages reported to `observer`.
+fn verify_proof_raw_observed(
+    proof: &[u8; PROOF_LEN],
+    public_inputs: &[u8],
+    vk: &[u8],
+    observer: &mut impl Observer,
+) -> Result<(), Error> {
+    verify_groth16(
+        ProofRef::from_bytes(proof)?,
+        ProofForm::Raw,
+        public_inputs,
+        vk,
+        observer,
+    )
+}
+
+/// Like [`verify_proof_raw`], with the Groth16 check run by `backend`.
+///
+/// [`verify_proof_raw`] always runs [`backend::DefaultBackend`], without going through this
+/// function. This one fully decompresses the verification key before any check, so it is meant for
+/// comparing backends off-chain, e.g. `verify_proof_raw_with(&backend::Arkworks, ...)`.
+pub fn verify_proof_raw_with<B: Groth16Backend + ?Sized>(
+    backend: &B,
+    proof: &[u8],
+    public_inputs: &[u8],
+    vk: &[u8],
+) -> Result<(), Error> {
+    let proof = Proof::from_bytes(strip_selector(proof, || groth16_vk_hash(vk))?)?;
+    let public_inputs = PublicInputs::from_bytes(public_inputs)?;
+    let vk = VkView::new(vk)?.to_verification_key()?;
+    backend.verify(&proof, &public_inputs, &vk)
+}
+
+/// Like [`verify_proof_raw`], but fails with [`Error::TrailingBytes`] if an input continues past
+/// what its parser reads: a proof longer than 256 bytes that isn't a 260-byte proof with its
+/// selector, public inputs longer than 63 bytes, or a verifying key followed by other bytes.
+///
+/// This catches serialization bugs in clients, such as junk appended to an instruction's data.
+pub fn verify_proof_raw_exact(proof: &[u8], public_inputs: &[u8], vk: &[u8]) -> Result<(), Error> {
+    let proof_len = if proof.len() < PROOF_WITH_SELECTOR_LEN {
+        PROOF_LEN
+    } else {
+        PROOF_WITH_SELECTOR_LEN
+    };
+    check_trailing_bytes(InputSection::Proof, proof.len(), proof_len)?;
+    check_trailing_bytes(
+        InputSection::PublicInputs,
+        public_inputs.len(),
+        GROTH16_PUBLIC_INPUTS_LEN,
+    )?;
+    VkView::new_exact(vk)?;
+    verify_proof_raw(proof, public_inputs, vk)
+}
+
+/// Verifies a proof whose pi_a was already negated with [`negate_proof_a`].
+///
+/// This skips the on-chain negation of pi_a. Passing a proof that was not pre-negated (or
+/// pre-negating a proof and passing it to [`verify_proof_raw`]) fails the pairing check.
+pub fn verify_proof_raw_prenegated(
+    proof: &[u8],
+    public_inputs: &[u8],
+    vk: &[u8],
+) -> Result<(), Error> {
+    let proof = strip_selector(proof, || groth16_vk_hash(vk))?;
+    verify_groth16(
+        ProofRef::from_bytes(proof)?,
+        ProofForm::Prenegated,
+        public_inputs,
+        vk,
+        &mut (),
+    )
+}
+
+/// Verifies a raw 256-byte proof stored at `offset` in `data`, typically account data, without
+/// copying it.
+///
+/// The only copy made is of the negated pi_a. Use [`verify_proof_raw_prenegated`] on the same slice
+/// to avoid that too.
+pub fn verify_proof_from_slice(
+    data: &[u8],
+    offset: usize,
+    public_inputs: &[u8; GROTH16_PUBLIC_INPUTS_LEN],
+    vk: &[u8],
+) -> Result<(), Error> {
+    let proof = ProofRef::from_bytes(data.get(offset..).ok_or(Error::InvalidInput)?)?;
+    verify_groth16(proof, ProofForm::Raw, public_inputs, vk, &mut ())
+}
+
+/// Negates pi_a of a raw 256-byte proof, for use with [`verify_proof_raw_prenegated`].
+///
+/// This is meant to be done client-side, when building the instruction data.
+pub fn negate_proof_a(proof: &[u8]) -> Result<[u8; PROOF_LEN], Error> {
+    Ok(load_proof_from_bytes(proof)?.to_bytes())
+}
+
+/// Whether the pi_a of a proof passed to [`verify_groth16`] is still to be negated.
+#[derive(Clone, Copy)]
+enum ProofForm {
+    /// As generated: pi_a is negated once every cheaper check has passed.
+    Raw,
+    /// Negated with [`negate_proof_a`].
+    Prenegated,
+}
+
+/// Runs the Groth16 pairing check on a proof.
+///
+/// The structural checks of the verifying key and public inputs, including that the key takes as
+/// many public inputs as are passed, run before pi_a is negated and before any point of the key is
+/// decompressed.
+#[cfg_attr(
+    all(feature = "tracing", not(target_os = "solana")),
+    tracing::instrument(
+        name = "sp1_solana::verify_groth16",
+        level = "debug",
+        skip_all,
+        fields(parse_us = tracing::field::Empty, verify_us = tracing::field::Empty),
+        err(Display),
+    )
+)]
+fn verify_groth16(
+    proof: ProofRef,
+    form: ProofForm,
+    public_inputs: &[u8],
+    vk: &[u8],
+    observer: &mut impl Observer,
+) -> Result<(), Error> {
+    #[cfg(all(feature = "tracing", not(target_os = "solana")))]
+    let stage = trace::Stage::start();
+    // Only a structural pass over the VK happens here. Its points are decompressed below, once
+    // the cheaper checks have passed.
+    let vk_view = observe(observer, Step::VkParse, || VkView::new(vk))?;
+    let public_inputs = observe(observer, Step::PublicInputs, || {
+        PublicInputs::from_bytes(public_inputs)
+    })?;
+    check_public_inputs(&public_inputs.inputs)?;
+    // The pairing check would fail the same way, but only after the key is decompressed.
+    if vk_view.nr_pubinputs() as usize != public_inputs.inputs.len() {
+        return Err(Error::InvalidPublicInput);
+    }
+
+    let pi_a = match form {
+        ProofForm::Raw => Some(observe(observer, Step::ProofParse, || {
+            negate_g1(proof.pi_a)
+        })?),
+        ProofForm::Prenegated => None,
+    };
+    let proof = ProofRef {
+        pi_a: pi_a.as_ref().unwrap_or(proof.pi_a),
+        ..proof
+    };
+
+    let vk = observe(observer, Step::VkParse, || groth16_verifying_key(&vk_view))?;
+    #[cfg(all(feature = "tracing", not(target_os = "solana")))]
+    stage.record("parse_us");
+
+    #[cfg(all(feature = "tracing", not(target_os = "solana")))]
+    let stage = trace::Stage::start();
+    let result = observe(observer, Step::Pairing, || {
+        pairing_check(proof, &public_inputs.inputs, &vk)
+    });
+    #[cfg(all(feature = "tracing", not(target_os = "solana")))]
+    stage.record("verify_us");
+    result
+}
+
+/// Verifies an SP1 Groth16 proof against its two public inputs and a decompressed verification
+/// key.
+///
+/// This is the typed counterpart of [`verify_proof_raw`], for callers that already hold parsed
+/// values, e.g. from [`Proof::from_bytes`], [`PublicInputs::from_bytes`] and
+/// [`VkView::to_verification_key`]. pi_a is negated here: a proof whose pi_a is already negated is
+/// a [`NegatedProof`], verified with [`verify_negated`].
+pub fn verify(
+    proof: &Proof,
+    public_inputs: &PublicInputs<2>,
+    vk: &VerificationKey,
+) -> Result<(), Error> {
+    verify_generic(proof, public_inputs, vk)
+}
+
+/// Like [`verify`], with any number of public inputs. `N` must be one less than the number of IC
+/// points of `vk`, which isn't capped: keys with more points than SP1's keep them on the heap.
+pub fn verify_generic<const N: usize>(
+    proof: &Proof,
+    public_inputs: &PublicInputs<N>,
+    vk: &VerificationKey,
+) -> Result<(), Error> {
+    verify_negated(&proof.clone().with_negated_a()?, public_inputs, vk)
+}
+
+/// Like [`verify_generic`], for a proof whose pi_a is already negated.
+pub fn verify_negated<const N: usize>(
+    proof: &NegatedProof,
+    public_inputs: &PublicInputs<N>,
+    vk: &VerificationKey,
+) -> Result<(), Error> {
     let vk = Groth16Verifyingkey {
         nr_pubinputs: vk.nr_pubinputs as usize,
         vk_alpha_g1: vk.vk_alpha_g1,
@@ -54,25 +519,95 @@ pub fn verify_proof_raw(proof: &[u8], public_inputs: &[u8], vk: &[u8]) -> Result
         vk_delta_g2: vk.vk_delta_g2,
         vk_ic: vk.vk_ic.as_slice(),
     };
+    pairing_check(proof.as_proof_ref(), &public_inputs.inputs, &vk)
+}
+
+/// Verifies a raw Groth16 proof against a decompressed verification key, with any number of
+/// public inputs.
+///
+/// This is the byte-level entry point for Groth16 proofs that don't come from SP1, such as circom
+/// proofs converted with [`circom::circom_proof_to_bytes`]. The proof is the raw 256-byte form
+/// with pi_a already negated, and each public input is a big-endian scalar below the BN254 scalar
+/// field modulus. `N` must be one less than the number of IC points of `vk`.
+pub fn verify_groth16_prenegated<const N: usize>(
+    proof: &[u8],
+    public_inputs: &[[u8; 32]; N],
+    vk: &VerificationKey,
+) -> Result<(), Error> {
+    verify_negated(
+        &NegatedProof::from_prenegated_bytes(proof)?,
+        &PublicInputs {
+            inputs: *public_inputs,
+        },
+        vk,
+    )
+}
+
+/// Decompresses the points of a verifying key into the form taken by `groth16-solana`.
+fn groth16_verifying_key<'a>(vk_view: &'a VkView) -> Result<Groth16Verifyingkey<'a>, Error> {
+    Ok(Groth16Verifyingkey {
+        nr_pubinputs: vk_view.nr_pubinputs() as usize,
+        vk_alpha_g1: *vk_view.alpha_g1()?,
+        vk_beta_g2: *vk_view.beta_g2()?,
+        vk_gamme_g2: *vk_view.gamma_g2()?,
+        vk_delta_g2: *vk_view.delta_g2()?,
+        vk_ic: vk_view.ic()?,
+    })
+}
 
-    let mut verifier = groth16_solana::groth16::Groth16Verifier::new(
-        &proof.pi_a,
-        &proof.pi_b,
-        &proof.pi_c,
-        &public_inputs.inputs,
-        &vk,
+fn pairing_check<const N: usize>(
+    proof: ProofRef,
+    public_inputs: &[[u8; 32]; N],
+    vk: &Groth16Verifyingkey,
+) -> Result<(), Error> {
+    check_public_inputs(public_inputs)?;
+    // The constructor only fails on a number of public inputs that doesn't match the IC points.
+    let verifier = groth16_solana::groth16::Groth16Verifier::new(
+        proof.pi_a,
+        proof.pi_b,
+        proof.pi_c,
+        public_inputs,
+        vk,
     )
-    .map_err(|_| Error::VerificationError)?;
+    .map_err(|_| Error::InvalidPublicInput)?;
 
-    if verifier.verify().map_err(|_| Error::VerificationError)? {
-        println!("Verification successful.");
+    // The mock verifier keeps the structural checks of the verifier's constructor, on the lengths
+    // of the inputs and the range of the public inputs, and skips the pairing.
+    #[cfg(feature = "mock-verifier")]
+    {
+        drop(verifier);
         Ok(())
+    }
+    #[cfg(not(feature = "mock-verifier"))]
+    {
+        let mut verifier = verifier;
+        match verifier.verify() {
+            Ok(true) => Ok(()),
+            _ => Err(classify_pairing_failure(proof)),
+        }
+    }
+}
+
+/// Tells a malformed proof, with a point off the curve that the pairing syscall rejects, apart from
+/// a well-formed but wrong one. This is only paid for by proofs that fail.
+#[cfg(not(feature = "mock-verifier"))]
+#[cold]
+fn classify_pairing_failure(proof: ProofRef) -> Error {
+    if is_g1_on_curve(proof.pi_a) && is_g2_on_curve(proof.pi_b) && is_g1_on_curve(proof.pi_c) {
+        Error::VerificationError
     } else {
-        println!("Verification failed.");
-        Err(Error::VerificationError)
+        Error::InvalidInput
     }
 }
 
+/// Returns the Groth16 selector of a proof in SP1's standard format: the first 4 bytes of the
+/// SHA-256 hash of the verification key it was generated for, e.g. [`GROTH16_VK_3_0_0_HASH`].
+pub fn proof_selector(proof: &[u8]) -> Result<&[u8; SELECTOR_LEN], Error> {
+    proof
+        .first_chunk::<SELECTOR_LEN>()
+        .ok_or(Error::InvalidInput)
+}
+
 /// Verifies a proof generated by [`SP1ProofWithPublicValues`].
 ///
 /// The proof is expected to be from this method on `SP1ProofWithPublicValues`:
@@ -81,31 +616,316 @@ pub fn verify_proof_raw(proof: &[u8], public_inputs: &[u8], vk: &[u8]) -> Result
 /// https://docs.rs/sp1-sdk/latest/sp1_sdk/struct.SP1PublicValues.html#method.as_slice
 /// The vkey hash is derived from running `vk.bytes32()` on the program's vkey.
 /// https://docs.rs/sp1-sdk/latest/sp1_sdk/trait.HashableKey.html#method.bytes32
+///
+/// A raw 256-byte proof, without the selector, is verified as is. Any other length than 256 or 260
+/// bytes fails with [`Error::InvalidProofLength`]. The proof can be passed in any of the shapes of
+/// [`ProofBytes`], e.g. `&[u8]`, `&Vec<u8>`, `&[u8; 256]` or `Vec<u8>`.
+///
+/// # Check order
+///
+/// The checks run from the cheapest to the most expensive, and the first one to fail is returned,
+/// so that an invalid submission costs as little as possible. This order is guaranteed:
+///
+/// 1. the length of the proof;
+/// 2. its selector, against the SHA-256 hash of `groth16_vk`, which isn't computed for a raw proof;
+/// 3. the decoding of `sp1_vkey_hash`;
+/// 4. the hashing of the public values into the committed values digest, and the building of the
+///    Groth16 public inputs;
+/// 5. the structural pass over `groth16_vk`, and the number of public inputs it takes;
+/// 6. the negation of pi_a;
+/// 7. the decompression of the points of `groth16_vk`, then the pairing check.
+///
+/// No elliptic curve arithmetic runs before step 6.
 #[inline]
 pub fn verify_proof(
-    proof: &[u8],
+    proof: impl Into<ProofBytes<'_>>,
+    sp1_public_inputs: &[u8],
+    sp1_vkey_hash: &str,
+    groth16_vk: &[u8],
+) -> Result<(), Error> {
+    verify_proof_bytes(&proof.into(), sp1_public_inputs, sp1_vkey_hash, groth16_vk)
+}
+
+/// [`verify_proof`], on the converted proof.
+#[cfg_attr(
+    all(feature = "tracing", not(target_os = "solana")),
+    tracing::instrument(
+        name = "sp1_solana::verify_proof",
+        skip_all,
+        fields(
+            selector = %trace::selector(proof.as_slice()),
+            sp1_vkey_hash = sp1_vkey_hash,
+            public_values_len = sp1_public_inputs.len(),
+        ),
+        err(Display),
+    )
+)]
+fn verify_proof_bytes(
+    proof: &ProofBytes,
+    sp1_public_inputs: &[u8],
+    sp1_vkey_hash: &str,
+    groth16_vk: &[u8],
+) -> Result<(), Error> {
+    let proof = proof.split()?;
+    let sp1_vkey_hash = || decode_sp1_vkey_hash(sp1_vkey_hash);
+    verify_proof_observed(proof, sp1_public_inputs, sp1_vkey_hash, groth16_vk, &mut ())
+}
+
+/// [`verify_proof`] on a proof split into its selector and raw proof, with its stages reported to
+/// `observer`. The program vkey hash is decoded by `sp1_vkey_hash` once the selector is checked.
+pub(crate) fn verify_proof_observed(
+    proof: (Option<&[u8; SELECTOR_LEN]>, &[u8; PROOF_LEN]),
+    sp1_public_inputs: &[u8],
+    sp1_vkey_hash: impl FnOnce() -> Result<[u8; 32], Error>,
+    groth16_vk: &[u8],
+    observer: &mut impl Observer,
+) -> Result<(), Error> {
+    // The proof is checked before the public values are hashed, in the order documented on
+    // `verify_proof`.
+    let proof = check_selector(proof, || {
+        observe(observer, Step::VkParse, || groth16_vk_hash(groth16_vk))
+    })?;
+    let sp1_vkey_hash = sp1_vkey_hash()?;
+    let committed_values_digest = observe(observer, Step::PublicInputs, || {
+        CommittedValuesDigest::from_public_values(sp1_public_inputs)
+    });
+
+    verify_proof_with_digest_observed(
+        proof,
+        &committed_values_digest,
+        &sp1_vkey_hash,
+        groth16_vk,
+        observer,
+    )
+}
+
+/// Verifies a proof like [`verify_proof`], whose SP1 program committed its public values under
+/// `domain`, as described in [`CommittedValuesDigest::from_domain_public_values`].
+///
+/// `sp1_public_inputs` are the public values after the domain tag. Without a domain, this is
+/// [`verify_proof`].
+pub fn verify_proof_with_domain(
+    proof: impl Into<ProofBytes<'_>>,
+    sp1_public_inputs: &[u8],
+    sp1_vkey_hash: &str,
+    groth16_vk: &[u8],
+    domain: Option<&[u8]>,
+) -> Result<(), Error> {
+    let Some(domain) = domain else {
+        return verify_proof(proof, sp1_public_inputs, sp1_vkey_hash, groth16_vk);
+    };
+    let proof = proof.into();
+    let proof = proof.strip_selector(|| groth16_vk_hash(groth16_vk))?;
+    let sp1_vkey_hash = decode_sp1_vkey_hash(sp1_vkey_hash)?;
+    verify_proof_with_digest_observed(
+        proof,
+        &CommittedValuesDigest::from_domain_public_values(domain, sp1_public_inputs),
+        &sp1_vkey_hash,
+        groth16_vk,
+        &mut (),
+    )
+}
+
+/// Verifies a proof like [`verify_proof`], against the embedded verification key named by its
+/// selector.
+///
+/// This fails with [`Error::NoVkAvailable`] if no key is embedded, with
+/// [`Error::UnsupportedSp1Version`] if the proof's selector is that of a known SP1 release whose
+/// key isn't embedded, and with [`Error::Groth16VkeyHashMismatch`] for any other selector.
+pub fn verify_proof_auto(
+    proof: impl Into<ProofBytes<'_>>,
     sp1_public_inputs: &[u8],
     sp1_vkey_hash: &str,
+) -> Result<(), Error> {
+    let proof = proof.into();
+    validate_proof_len(proof.as_slice())?;
+    let (selector, proof) = proof.split()?;
+    let selector = selector.ok_or(Error::InvalidInput)?;
+    // The key is picked by the selector, which is thus checked.
+    let (_, groth16_vk) = match EMBEDDED_VKS {
+        [] => return Err(Error::NoVkAvailable),
+        vks => vks
+            .iter()
+            .find(|(hash, _)| ct_eq(hash, selector))
+            .ok_or_else(|| versions::selector_mismatch(selector))?,
+    };
+    let sp1_vkey_hash = decode_sp1_vkey_hash(sp1_vkey_hash)?;
+
+    verify_proof_with_digest_observed(
+        proof,
+        &CommittedValuesDigest::from_public_values(sp1_public_inputs),
+        &sp1_vkey_hash,
+        groth16_vk,
+        &mut (),
+    )
+}
+
+/// Verifies a proof like [`verify_proof`], against any of `allowed_vkey_hashes`, e.g. the vkey
+/// hashes of the successive versions of an SP1 program, and returns the index of the first one the
+/// proof is valid for.
+///
+/// The public values are hashed, and the proof and verification key parsed, only once, but each
+/// candidate tried costs a pairing check: list the most likely hashes first. This fails with
+/// [`Error::NoVkeyHashMatched`] if the proof is valid for none of them, and with the error of any
+/// other check, e.g. [`Error::InvalidProgramVkeyHash`] if a hash isn't one of an SP1 program.
+pub fn verify_proof_any_of(
+    proof: impl Into<ProofBytes<'_>>,
+    sp1_public_inputs: &[u8],
+    allowed_vkey_hashes: &[[u8; 32]],
     groth16_vk: &[u8],
+) -> Result<usize, Error> {
+    allowed_vkey_hashes
+        .iter()
+        .try_for_each(check_sp1_vkey_hash)?;
+    let proof = proof.into();
+    let proof = load_proof_from_bytes(proof.strip_selector(|| groth16_vk_hash(groth16_vk))?)?;
+    let vk_view = VkView::new(groth16_vk)?;
+    let vk = groth16_verifying_key(&vk_view)?;
+    let committed_values_digest = CommittedValuesDigest::from_public_values(sp1_public_inputs);
+
+    for (index, sp1_vkey_hash) in allowed_vkey_hashes.iter().enumerate() {
+        let public_inputs = PublicInputs::from_bytes(&groth16_public_inputs(
+            sp1_vkey_hash,
+            &committed_values_digest,
+        ))?;
+        match pairing_check(proof.as_proof_ref(), &public_inputs.inputs, &vk) {
+            Ok(()) => return Ok(index),
+            // The proof is well-formed, but for another program.
+            Err(Error::VerificationError) => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    Err(Error::NoVkeyHashMatched {
+        candidates: allowed_vkey_hashes.len(),
+    })
+}
+
+/// Verifies a proof against a precomputed committed values digest.
+///
+/// This is the cheap counterpart of [`verify_proof`]: the program vkey hash is passed as raw bytes
+/// (e.g. embedded with `hex_literal::hex!`), the Groth16 vkey hash is one of the precomputed
+/// constants such as [`GROTH16_VK_3_0_0_HASH`], and the committed values digest is built from the
+/// SP1 public values with [`CommittedValuesDigest::from_public_values`], or checked with
+/// [`CommittedValuesDigest::from_masked_bytes`] when received as bytes.
+///
+/// As with [`verify_proof`], the proof may also be raw, without the selector.
+pub fn verify_proof_with_digest(
+    proof: impl Into<ProofBytes<'_>>,
+    committed_values_digest: &CommittedValuesDigest,
+    sp1_vkey_hash: &[u8; 32],
+    groth16_vk: &[u8],
+    groth16_vk_hash: &[u8; 4],
 ) -> Result<(), Error> {
-    // Hash the vk and get the first 4 bytes.
-    let groth16_vk_hash: [u8; 4] = Sha256::digest(groth16_vk)[..4].try_into().unwrap();
+    verify_proof_with_digest_bytes(
+        &proof.into(),
+        committed_values_digest,
+        sp1_vkey_hash,
+        groth16_vk,
+        || *groth16_vk_hash,
+    )
+}
 
+/// [`verify_proof_with_digest`], with the Groth16 vkey hash computed by `groth16_vk_hash` only if
+/// the proof has a selector to check.
+fn verify_proof_with_digest_bytes(
+    proof: &ProofBytes,
+    committed_values_digest: &CommittedValuesDigest,
+    sp1_vkey_hash: &[u8; 32],
+    groth16_vk: &[u8],
+    groth16_vk_hash: impl FnOnce() -> [u8; SELECTOR_LEN],
+) -> Result<(), Error> {
     // Check to make sure that this proof was generated by the groth16 proving key corresponding to
     // the given groth16_vk.
     //
     // SP1 prepends the raw Groth16 proof with the first 4 bytes of the groth16 vkey to
     // faciliate this check.
-    if groth16_vk_hash != proof[..4] {
-        return Err(Error::Groth16VkeyHashMismatch);
-    }
-
-    let sp1_vkey_hash = decode_sp1_vkey_hash(sp1_vkey_hash)?;
+    // A raw proof, without the selector, is verified as is.
+    let proof = proof.strip_selector(groth16_vk_hash)?;
+    check_sp1_vkey_hash(sp1_vkey_hash)?;
+    verify_proof_with_digest_observed(
+        proof,
+        committed_values_digest,
+        sp1_vkey_hash,
+        groth16_vk,
+        &mut (),
+    )
+}
 
+/// [`verify_proof_with_digest`] on a raw proof, whose selector, if it had one, was checked, and a
+/// checked program vkey hash, with its stages reported to `observer`.
+#[cfg_attr(
+    all(feature = "tracing", not(target_os = "solana")),
+    tracing::instrument(
+        name = "sp1_solana::verify_proof_with_digest",
+        skip_all,
+        fields(
+            sp1_vkey_hash = %hex::encode(sp1_vkey_hash),
+            committed_values_digest = %hex::encode(committed_values_digest.as_bytes()),
+        ),
+        err(Display),
+    )
+)]
+fn verify_proof_with_digest_observed(
+    proof: &[u8; PROOF_LEN],
+    committed_values_digest: &CommittedValuesDigest,
+    sp1_vkey_hash: &[u8; 32],
+    groth16_vk: &[u8],
+    observer: &mut impl Observer,
+) -> Result<(), Error> {
     // Verify the proof.
-    verify_proof_raw(
-        &proof[4..],
-        &groth16_public_values(&sp1_vkey_hash, sp1_public_inputs),
+    let public_inputs = observe(observer, Step::PublicInputs, || {
+        groth16_public_inputs(sp1_vkey_hash, committed_values_digest)
+    });
+    verify_proof_raw_observed(proof, &public_inputs, groth16_vk, observer)
+}
+
+/// Verifies a proof, then returns its public values decoded as `T`.
+///
+/// The public values must be the Borsh encoding of `T`, with no trailing bytes. For fixed-size
+/// fields such as integers, arrays and `Pubkey`s, this matches what the SP1 program writes with
+/// `sp1_zkvm::io::commit`. Values that don't decode fail with [`Error::BorshDeserializeError`]
+/// before the proof is verified.
+pub fn verify_and_decode<T: borsh::BorshDeserialize>(
+    proof: impl Into<ProofBytes<'_>>,
+    sp1_public_inputs: &[u8],
+    sp1_vkey_hash: &[u8; 32],
+    groth16_vk: &[u8],
+) -> Result<T, Error> {
+    let values = T::try_from_slice(sp1_public_inputs)
+        .map_err(|error| Error::BorshDeserializeError(source(error)))?;
+
+    verify_proof_with_digest_bytes(
+        &proof.into(),
+        &CommittedValuesDigest::from_public_values(sp1_public_inputs),
+        sp1_vkey_hash,
         groth16_vk,
-    )
+        || groth16_vk_hash(groth16_vk),
+    )?;
+
+    Ok(values)
+}
+
+/// Verifies a proof whose public values start with a schema hash of `T`, then returns them decoded
+/// as the version of `T` it names.
+///
+/// See [`Sp1PublicValuesDecode::decode_with_schema_hash`]. Values of another schema fail with
+/// [`Error::SchemaHashMismatch`], and values that don't decode fail, before the proof is verified.
+pub fn verify_and_decode_with_schema<T: Sp1PublicValuesDecode>(
+    proof: impl Into<ProofBytes<'_>>,
+    sp1_public_inputs: &[u8],
+    sp1_vkey_hash: &[u8; 32],
+    groth16_vk: &[u8],
+    trailing: TrailingBytesPolicy,
+) -> Result<T, Error> {
+    let values = T::decode_with_schema_hash(sp1_public_inputs, trailing)?;
+
+    verify_proof_with_digest_bytes(
+        &proof.into(),
+        &CommittedValuesDigest::from_public_values(sp1_public_inputs),
+        sp1_vkey_hash,
+        groth16_vk,
+        || groth16_vk_hash(groth16_vk),
+    )?;
+
+    Ok(values)
 }
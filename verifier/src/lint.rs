@@ -0,0 +1,168 @@
+//! Cheap well-formedness checks of fixtures, for services accepting them from untrusted sources.
+//!
+//! [`SP1ProofFixture::is_well_formed`] rejects obvious garbage before a fixture is queued for
+//! verification, and reports every problem at once, so that an API can return them all to the
+//! uploader. It runs no elliptic curve arithmetic: a well-formed fixture can still fail
+//! verification.
+
+use crate::{
+    hash_public_inputs, known_sp1_version, utils::ct_eq, CommittedValuesDigest, SP1ProofFixture,
+    SP1ProofFixtureRef, MAX_SP1_PUBLIC_VALUES_LEN, SELECTOR_LEN,
+};
+
+/// A point of a Groth16 proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProofPoint {
+    A,
+    B,
+    C,
+}
+
+impl ProofPoint {
+    /// The offset of the point in a raw proof.
+    fn offset(self) -> usize {
+        match self {
+            Self::A => 0,
+            Self::B => 64,
+            Self::C => 192,
+        }
+    }
+}
+
+impl core::fmt::Display for ProofPoint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::A => "pi_a",
+            Self::B => "pi_b",
+            Self::C => "pi_c",
+        })
+    }
+}
+
+/// A check of [`SP1ProofFixture::is_well_formed`] that a fixture fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FixtureLint {
+    /// The selector is the Groth16 vkey hash of no SP1 release the crate knows of.
+    UnknownSelector { selector: [u8; SELECTOR_LEN] },
+    /// The first byte of a point's x coordinate has gnark's compression or infinity flags set,
+    /// which the uncompressed points of a raw proof never have, e.g. because a compressed proof
+    /// was passed as a raw one.
+    PointFlags { point: ProofPoint, flags: u8 },
+    /// The SP1 program vkey hash doesn't start with a zero byte, as `vk.bytes32()` does.
+    NonCanonicalSp1VkeyHash,
+    /// The committed values digest has any of its top 3 bits set, so it isn't an SP1 digest.
+    NonCanonicalCommittedValuesDigest,
+    /// The public values are longer than the limit they are checked against.
+    PublicValuesTooLong { len: usize, max: usize },
+    /// The public values don't hash to the committed values digest.
+    CommittedValuesDigestMismatch {
+        stored: [u8; 32],
+        recomputed: [u8; 32],
+    },
+}
+
+impl core::fmt::Display for FixtureLint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnknownSelector { selector } => write!(
+                f,
+                "the selector 0x{} is that of no known SP1 release",
+                hex::encode(selector)
+            ),
+            Self::PointFlags { point, flags } => write!(
+                f,
+                "{point} has the gnark flags {flags:#04b}, which an uncompressed point doesn't have"
+            ),
+            Self::NonCanonicalSp1VkeyHash => {
+                f.write_str("the program vkey hash doesn't start with a zero byte")
+            }
+            Self::NonCanonicalCommittedValuesDigest => {
+                f.write_str("the committed values digest has its top 3 bits set")
+            }
+            Self::PublicValuesTooLong { len, max } => write!(
+                f,
+                "the public values are {len} bytes long, above the limit of {max}"
+            ),
+            Self::CommittedValuesDigestMismatch { stored, recomputed } => write!(
+                f,
+                "the public values hash to 0x{}, but the committed values digest is 0x{}",
+                hex::encode(recomputed),
+                hex::encode(stored)
+            ),
+        }
+    }
+}
+
+impl SP1ProofFixture {
+    /// The limit of [`Self::is_well_formed`] on the length of the public values: that of the
+    /// deserializers, [`MAX_SP1_PUBLIC_VALUES_LEN`].
+    pub const MAX_PUBLIC_VALUES_LEN: usize = MAX_SP1_PUBLIC_VALUES_LEN;
+
+    /// Runs cheap checks of the fixture's structure, returning every one it fails.
+    ///
+    /// This checks that the selector is that of a known SP1 release, that no proof point has
+    /// gnark's compression flags, that both public inputs are canonical, and that the public
+    /// values, if any, are at most [`Self::MAX_PUBLIC_VALUES_LEN`] bytes long and hash to the
+    /// committed values digest. Oversized public values aren't hashed.
+    pub fn is_well_formed(&self) -> Result<(), Vec<FixtureLint>> {
+        SP1ProofFixtureRef::from(self).is_well_formed_with_limit(Self::MAX_PUBLIC_VALUES_LEN)
+    }
+
+    /// Like [`Self::is_well_formed`], with another limit on the length of the public values.
+    pub fn is_well_formed_with_limit(
+        &self,
+        max_public_inputs_len: usize,
+    ) -> Result<(), Vec<FixtureLint>> {
+        SP1ProofFixtureRef::from(self).is_well_formed_with_limit(max_public_inputs_len)
+    }
+}
+
+impl SP1ProofFixtureRef<'_> {
+    /// See [`SP1ProofFixture::is_well_formed_with_limit`].
+    pub fn is_well_formed_with_limit(
+        &self,
+        max_public_inputs_len: usize,
+    ) -> Result<(), Vec<FixtureLint>> {
+        let mut lints = Vec::new();
+
+        if known_sp1_version(self.groth16_vkey_hash).is_none() {
+            lints.push(FixtureLint::UnknownSelector {
+                selector: *self.groth16_vkey_hash,
+            });
+        }
+        for point in [ProofPoint::A, ProofPoint::B, ProofPoint::C] {
+            let flags = self.proof[point.offset()] >> 6;
+            if flags != 0 {
+                lints.push(FixtureLint::PointFlags { point, flags });
+            }
+        }
+        if self.sp1_vkey_hash[0] != 0 {
+            lints.push(FixtureLint::NonCanonicalSp1VkeyHash);
+        }
+        if CommittedValuesDigest::from_masked_bytes(*self.committed_values_digest).is_err() {
+            lints.push(FixtureLint::NonCanonicalCommittedValuesDigest);
+        }
+
+        let len = self.sp1_public_inputs.len();
+        if len > max_public_inputs_len {
+            lints.push(FixtureLint::PublicValuesTooLong {
+                len,
+                max: max_public_inputs_len,
+            });
+        } else if len > 0 {
+            let recomputed = hash_public_inputs(self.sp1_public_inputs);
+            if !ct_eq(&recomputed, self.committed_values_digest) {
+                lints.push(FixtureLint::CommittedValuesDigestMismatch {
+                    stored: *self.committed_values_digest,
+                    recomputed,
+                });
+            }
+        }
+
+        if lints.is_empty() {
+            Ok(())
+        } else {
+            Err(lints)
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Timing of the stages of a verification, with the `metrics` feature.
+//!
+//! None of it is built for Solana or for `wasm32-unknown-unknown`, which has no clock. Allocation
+//! counts aren't reported: counting them takes a global allocator, which a library can't install.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    observe::{Observer, Step},
+    proof_bytes::split_proof,
+    utils::decode_sp1_vkey_hash,
+    verify_proof_observed, Error,
+};
+
+/// How long each stage of [`verify_proof_timed`] took, and the outcome of the verification.
+///
+/// A stage that didn't run, because an earlier one failed, took [`Duration::ZERO`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyMetrics {
+    /// Hashing the verifying key for its selector, and parsing and decompressing it.
+    pub vk_parse: Duration,
+    /// Loading the proof and negating pi_a.
+    pub proof_parse: Duration,
+    /// Hashing the public values into the committed values digest, and loading the Groth16 public
+    /// inputs.
+    pub public_inputs: Duration,
+    /// The public input MSM and the pairing check.
+    pub pairing: Duration,
+    /// The whole verification, including the checks between stages. It is at least the sum of
+    /// the stages.
+    pub total: Duration,
+    /// What [`verify_proof`](crate::verify_proof) returns on the same arguments.
+    pub outcome: Result<(), Error>,
+}
+
+/// Accumulates the time spent in each [`Step`].
+#[derive(Default)]
+struct Timer {
+    started: Option<Instant>,
+    elapsed: [Duration; 4],
+}
+
+impl Observer for Timer {
+    fn enter(&mut self, _step: Step) {
+        self.started = Some(Instant::now());
+    }
+
+    fn exit(&mut self, step: Step) {
+        if let Some(started) = self.started.take() {
+            self.elapsed[step as usize] += started.elapsed();
+        }
+    }
+}
+
+/// Verifies a proof like [`verify_proof`](crate::verify_proof), timing each stage.
+///
+/// This only fails if `sp1_vkey_hash` can't be decoded. Whether the proof verified is
+/// [`VerifyMetrics::outcome`].
+pub fn verify_proof_timed(
+    proof: &[u8],
+    sp1_public_inputs: &[u8],
+    sp1_vkey_hash: &str,
+    groth16_vk: &[u8],
+) -> Result<VerifyMetrics, Error> {
+    let sp1_vkey_hash = decode_sp1_vkey_hash(sp1_vkey_hash)?;
+
+    let mut timer = Timer::default();
+    let started = Instant::now();
+    let outcome = split_proof(proof).and_then(|proof| {
+        verify_proof_observed(
+            proof,
+            sp1_public_inputs,
+            || Ok(sp1_vkey_hash),
+            groth16_vk,
+            &mut timer,
+        )
+    });
+    let total = started.elapsed();
+
+    Ok(VerifyMetrics {
+        vk_parse: timer.elapsed[Step::VkParse as usize],
+        proof_parse: timer.elapsed[Step::ProofParse as usize],
+        public_inputs: timer.elapsed[Step::PublicInputs as usize],
+        pairing: timer.elapsed[Step::Pairing as usize],
+        total,
+        outcome,
+    })
+}
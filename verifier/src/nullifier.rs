@@ -0,0 +1,105 @@
+//! Nullifiers for proofs that must be consumed at most once.
+//!
+//! A program that spends a proof, e.g. to release a withdrawal, derives its [`Nullifier`] and
+//! creates the nullifier PDA with [`create_pda`]. Creating it a second time fails, so a replayed
+//! proof is rejected.
+
+use crate::utils::sha256v;
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "solana")]
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+/// Separates nullifier hashes from any other SHA-256 hash over the same data.
+pub const NULLIFIER_DOMAIN: &[u8] = b"sp1-solana:nullifier:v1";
+
+/// The seed prefix of nullifier PDAs.
+#[cfg(feature = "solana")]
+pub const NULLIFIER_SEED: &[u8] = b"sp1-nullifier";
+
+/// A domain-separated hash identifying one use of a proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, BorshDeserialize, BorshSerialize)]
+pub struct Nullifier(pub [u8; 32]);
+
+impl Nullifier {
+    /// Derives the nullifier of a proof.
+    ///
+    /// The `salt` is chosen by the application and is typically extracted from the public values,
+    /// e.g. a withdrawal id, so that a single program can nullify distinct uses independently.
+    pub fn new(sp1_vkey_hash: &[u8; 32], committed_values_digest: &[u8; 32], salt: &[u8]) -> Self {
+        Self(sha256v(&[
+            NULLIFIER_DOMAIN,
+            sp1_vkey_hash,
+            committed_values_digest,
+            salt,
+        ]))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Finds the nullifier PDA of `program_id`.
+    #[cfg(feature = "solana")]
+    pub fn find_program_address(&self, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[NULLIFIER_SEED, &self.0], program_id)
+    }
+}
+
+/// Creates the PDA `account` with `space` bytes of data, owned by `owner` and rent-exempt at the
+/// expense of `payer`, signing for it with `signer_seeds`.
+///
+/// The address of a PDA derived from public data, such as a nullifier, is known in advance, and
+/// anyone can transfer lamports to it, after which `system_instruction::create_account` fails
+/// with `AccountAlreadyInUse`. A pre-funded account is instead topped up to the rent-exempt
+/// minimum, then allocated and assigned, so that lamports sent to the address can't block its
+/// creation. An account that was already created, with data or another owner, still fails.
+#[cfg(feature = "solana")]
+pub fn create_pda<'a>(
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                rent_exempt_minimum,
+                space as u64,
+                owner,
+            ),
+            &[payer.clone(), account.clone(), system_program.clone()],
+            &[signer_seeds],
+        );
+    }
+
+    let shortfall = rent_exempt_minimum.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, shortfall),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, owner),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )
+}
@@ -0,0 +1,37 @@
+//! Hooks into the stages of a verification, for off-chain observability such as
+//! `verify_proof_timed`.
+//!
+//! The public entry points pass `()`, which observes nothing and compiles away, so the on-chain
+//! verifier is unchanged.
+
+/// A stage of verification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Step {
+    /// Hashing the verifying key for its selector, and parsing and decompressing it.
+    VkParse,
+    /// Loading the proof and negating pi_a.
+    ProofParse,
+    /// Hashing the public values, and loading the Groth16 public inputs.
+    PublicInputs,
+    /// The public input MSM and the pairing check.
+    Pairing,
+}
+
+/// Notified as each [`Step`] starts and ends. A step can run in several parts, each reported
+/// separately.
+pub(crate) trait Observer {
+    fn enter(&mut self, _step: Step) {}
+
+    fn exit(&mut self, _step: Step) {}
+}
+
+impl Observer for () {}
+
+/// Runs `f` as `step`, notifying `observer`.
+#[inline(always)]
+pub(crate) fn observe<T>(observer: &mut impl Observer, step: Step, f: impl FnOnce() -> T) -> T {
+    observer.enter(step);
+    let result = f();
+    observer.exit(step);
+    result
+}
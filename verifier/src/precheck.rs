@@ -0,0 +1,75 @@
+//! A cheap structural filter for proofs, run before the pairing check.
+//!
+//! [`precheck_proof`] is meant for the intake of services that verify or submit proofs, e.g. a
+//! relayer that pays for each on-chain attempt: it rejects malformed submissions for a small
+//! fraction of the cost of [`verify_proof_raw`](crate::verify_proof_raw).
+
+use crate::{
+    utils::{
+        check_public_inputs, groth16_vk_hash, is_g1_on_curve, is_g2_on_curve,
+        load_public_inputs_from_bytes, load_raw_proof_from_bytes, strip_selector,
+    },
+    CommittedValuesDigest, Error, VkView,
+};
+
+/// What a proof claims, as read by [`precheck_proof`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofSummary {
+    /// The Groth16 vkey hash of the verification key, which the proof's selector matches.
+    pub selector: [u8; 4],
+    /// The SP1 program vkey hash, the first public input.
+    pub sp1_vkey_hash: [u8; 32],
+    /// The committed values digest, the second public input.
+    pub committed_values_digest: CommittedValuesDigest,
+}
+
+/// Runs the structural checks of [`verify_proof_raw`](crate::verify_proof_raw) on the same inputs,
+/// without any pairing or point decompression, and returns what the proof claims.
+///
+/// In order, this checks:
+///
+/// - the length of the proof and, if it has one, its selector against the key's hash,
+/// - the lengths of the verification key and of the public inputs,
+/// - the gnark flags of every compressed point of the key, and that it has two public inputs,
+/// - that both public inputs are scalars, and that the committed values digest is masked,
+/// - that pi_a, pi_b and pi_c are on the curve, with canonical coordinates.
+///
+/// A proof failing any of these checks fails verification too, so this never accepts a proof the
+/// verifier rejects for its structure. It still accepts proofs that fail the pairing check,
+/// including those with a pi_b outside the prime-order subgroup of G2. The points of the key
+/// aren't decompressed, so a key that fails to decompress can pass: the key is typically a
+/// constant of the verifier rather than part of the submission.
+///
+/// The public inputs are the 63 bytes taken by `verify_proof_raw`, e.g. built by
+/// [`groth16_public_inputs`](crate::groth16_public_inputs). The `verify` bench compares its cost
+/// with a verification's: `cargo bench --bench verify -- precheck`.
+pub fn precheck_proof(
+    proof: &[u8],
+    public_inputs: &[u8],
+    vk: &[u8],
+) -> Result<ProofSummary, Error> {
+    let selector = groth16_vk_hash(vk);
+    let proof = load_raw_proof_from_bytes(strip_selector(proof, || selector)?)?;
+    let vk_view = VkView::new(vk)?;
+    let public_inputs = load_public_inputs_from_bytes(public_inputs)?;
+
+    vk_view.check_compressed_flags()?;
+    if vk_view.nr_pubinputs() != 2 {
+        return Err(Error::InvalidPublicInput);
+    }
+    check_public_inputs(&public_inputs.inputs)?;
+    let [sp1_vkey_hash, committed_values_digest] = public_inputs.inputs;
+    let committed_values_digest =
+        CommittedValuesDigest::from_masked_bytes(committed_values_digest)?;
+
+    if !is_g1_on_curve(&proof.pi_a) || !is_g2_on_curve(&proof.pi_b) || !is_g1_on_curve(&proof.pi_c)
+    {
+        return Err(Error::InvalidInput);
+    }
+
+    Ok(ProofSummary {
+        selector,
+        sp1_vkey_hash,
+        committed_values_digest,
+    })
+}
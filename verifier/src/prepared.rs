@@ -0,0 +1,71 @@
+//! Verification against a precomputed public input MSM.
+//!
+//! The Groth16 check starts by preparing the public inputs into `ic[0] + sum(input_i * ic[i + 1])`,
+//! a G1 point that only depends on the public inputs and the verification key. A program verifying
+//! many proofs of the same statement, e.g. re-verifying it during a dispute window, can compute
+//! it once with [`prepare_public_inputs`], keep it in an account, and verify each proof with
+//! [`verify_proof_with_prepared_inputs`], which skips the MSM.
+//!
+//! The prepared point replaces the public inputs in the statement: a proof verified against it
+//! proves whatever inputs it was prepared from, and nothing else checks that these are the ones
+//! the caller has in mind. It must come from a trusted computation, e.g. an account the program
+//! wrote itself, and never from the same untrusted source as the proof.
+
+use groth16_solana::groth16::Groth16Verifyingkey;
+
+use crate::{pairing_check, Error, Proof, VerificationKey};
+#[cfg(feature = "solana")]
+use crate::{utils::check_public_inputs, PublicInputs};
+
+/// Computes the prepared public inputs of `vk`, serialized as the `alt_bn128` syscalls take G1
+/// points, for [`verify_proof_with_prepared_inputs`].
+///
+/// This runs the `alt_bn128` operations of `solana-program`, which are the syscalls on Solana.
+#[cfg(feature = "solana")]
+pub fn prepare_public_inputs(
+    public_inputs: &PublicInputs<2>,
+    vk: &VerificationKey,
+) -> Result<[u8; 64], Error> {
+    #[allow(deprecated)]
+    use solana_program::alt_bn128::prelude::{alt_bn128_addition, alt_bn128_multiplication};
+
+    check_public_inputs(&public_inputs.inputs)?;
+    let ic = vk.vk_ic.as_slice();
+    if ic.len() != public_inputs.inputs.len() + 1 {
+        return Err(Error::InvalidPublicInput);
+    }
+
+    // ic[0] + the sum of input_i * ic[i + 1].
+    let mut prepared_inputs = ic[0];
+    for (input, point) in public_inputs.inputs.iter().zip(&ic[1..]) {
+        let product = alt_bn128_multiplication(&[&point[..], &input[..]].concat())
+            .map_err(|_| Error::InvalidInput)?;
+        let sum = alt_bn128_addition(&[&prepared_inputs[..], &product[..]].concat())
+            .map_err(|_| Error::InvalidInput)?;
+        prepared_inputs.copy_from_slice(&sum);
+    }
+    Ok(prepared_inputs)
+}
+
+/// Verifies a proof, whose pi_a isn't negated, against public inputs prepared by
+/// [`prepare_public_inputs`].
+///
+/// See the [module documentation](self): `prepared_inputs` is trusted to be those of the claimed
+/// public inputs. A point off the curve fails like a wrong one, with [`Error::VerificationError`].
+pub fn verify_proof_with_prepared_inputs(
+    proof: &Proof,
+    prepared_inputs: &[u8; 64],
+    vk: &VerificationKey,
+) -> Result<(), Error> {
+    let proof = proof.clone().with_negated_a()?;
+    // Without public inputs, `groth16-solana` takes the single IC point as the prepared inputs.
+    let vk = Groth16Verifyingkey {
+        nr_pubinputs: 0,
+        vk_alpha_g1: vk.vk_alpha_g1,
+        vk_beta_g2: vk.vk_beta_g2,
+        vk_gamme_g2: vk.vk_gamma_g2,
+        vk_delta_g2: vk.vk_delta_g2,
+        vk_ic: core::slice::from_ref(prepared_inputs),
+    };
+    pairing_check(proof.as_proof_ref(), &[], &vk)
+}
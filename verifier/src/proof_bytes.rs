@@ -0,0 +1,148 @@
+//! The bytes of a Groth16 proof, raw or in SP1's standard format, borrowed or owned.
+
+use crate::{
+    utils::ct_eq, versions::selector_mismatch, Error, PROOF_LEN, PROOF_WITH_SELECTOR_LEN,
+    SELECTOR_LEN,
+};
+
+/// The bytes of a proof, in any of the shapes the crate's APIs take: a raw 256-byte proof, as in
+/// [`SP1ProofFixture`](crate::SP1ProofFixture), a 260-byte proof prefixed with its selector, as
+/// output by `SP1ProofWithPublicValues::bytes()`, or bytes of unchecked length, e.g. from
+/// instruction data.
+///
+/// The verification entry points, such as [`verify_proof`](crate::verify_proof), take
+/// `impl Into<ProofBytes>`, and normalize the proof with [`ProofBytes::split`] before anything
+/// else: any length other than [`PROOF_LEN`] and [`PROOF_WITH_SELECTOR_LEN`] fails with
+/// [`Error::InvalidProofLength`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofBytes<'a> {
+    /// A raw proof: pi_a, pi_b and pi_c, uncompressed.
+    Raw256(&'a [u8; PROOF_LEN]),
+    /// A proof in SP1's standard format: the selector, then the raw proof.
+    WithSelector(&'a [u8; PROOF_WITH_SELECTOR_LEN]),
+    /// Owned bytes, raw or with the selector, whose length is checked when they are normalized.
+    Owned(Vec<u8>),
+}
+
+impl ProofBytes<'_> {
+    /// The bytes as they were passed, with the selector if they have one.
+    pub fn as_slice(&self) -> &[u8] {
+        match self {
+            Self::Raw256(proof) => *proof,
+            Self::WithSelector(proof) => *proof,
+            Self::Owned(proof) => proof,
+        }
+    }
+
+    /// Normalizes the proof into its selector, if it has one, and the raw 256-byte proof.
+    pub fn split(&self) -> Result<(Option<&[u8; SELECTOR_LEN]>, &[u8; PROOF_LEN]), Error> {
+        match self {
+            Self::Raw256(proof) => Ok((None, proof)),
+            Self::WithSelector(proof) => Ok(split_selector(proof)),
+            Self::Owned(proof) => split_proof(proof),
+        }
+    }
+
+    /// The raw 256-byte proof, without the selector.
+    pub fn raw(&self) -> Result<&[u8; PROOF_LEN], Error> {
+        Ok(self.split()?.1)
+    }
+
+    /// The selector of a proof in SP1's standard format, or `None` for a raw proof.
+    pub fn selector(&self) -> Result<Option<&[u8; SELECTOR_LEN]>, Error> {
+        Ok(self.split()?.0)
+    }
+
+    /// Copies borrowed bytes, so that the proof outlives them.
+    pub fn into_owned(self) -> ProofBytes<'static> {
+        match self {
+            Self::Owned(proof) => ProofBytes::Owned(proof),
+            proof => ProofBytes::Owned(proof.as_slice().to_vec()),
+        }
+    }
+
+    /// The raw proof, after checking that the selector of a proof in SP1's standard format is
+    /// `groth16_vk_hash`, which is only computed for such proofs.
+    pub(crate) fn strip_selector(
+        &self,
+        groth16_vk_hash: impl FnOnce() -> [u8; SELECTOR_LEN],
+    ) -> Result<&[u8; PROOF_LEN], Error> {
+        check_selector(self.split()?, groth16_vk_hash)
+    }
+}
+
+/// Splits `proof` into its selector, if it has one, and the raw proof, failing with
+/// [`Error::InvalidProofLength`] unless it is [`PROOF_LEN`] or [`PROOF_WITH_SELECTOR_LEN`] bytes
+/// long.
+pub(crate) fn split_proof(
+    proof: &[u8],
+) -> Result<(Option<&[u8; SELECTOR_LEN]>, &[u8; PROOF_LEN]), Error> {
+    if let Ok(proof) = proof.try_into() {
+        return Ok((None, proof));
+    }
+    match proof.try_into() {
+        Ok(proof) => Ok(split_selector(proof)),
+        Err(_) => Err(Error::InvalidProofLength {
+            expected_one_of: &[PROOF_LEN, PROOF_WITH_SELECTOR_LEN],
+            got: proof.len(),
+        }),
+    }
+}
+
+fn split_selector(
+    proof: &[u8; PROOF_WITH_SELECTOR_LEN],
+) -> (Option<&[u8; SELECTOR_LEN]>, &[u8; PROOF_LEN]) {
+    let (selector, proof) = proof.split_first_chunk::<SELECTOR_LEN>().unwrap();
+    (Some(selector), proof.try_into().unwrap())
+}
+
+/// Returns the raw proof of a split proof, whose selector, if any, must be `groth16_vk_hash`.
+pub(crate) fn check_selector<'a>(
+    (selector, proof): (Option<&[u8; SELECTOR_LEN]>, &'a [u8; PROOF_LEN]),
+    groth16_vk_hash: impl FnOnce() -> [u8; SELECTOR_LEN],
+) -> Result<&'a [u8; PROOF_LEN], Error> {
+    if let Some(selector) = selector {
+        if !ct_eq(&groth16_vk_hash(), selector) {
+            return Err(selector_mismatch(selector));
+        }
+    }
+    Ok(proof)
+}
+
+/// Borrows a raw proof or a proof with its selector without copying it. Slices of any other
+/// length are copied, and fail when normalized.
+impl<'a> From<&'a [u8]> for ProofBytes<'a> {
+    fn from(proof: &'a [u8]) -> Self {
+        if let Ok(proof) = proof.try_into() {
+            return Self::Raw256(proof);
+        }
+        match proof.try_into() {
+            Ok(proof) => Self::WithSelector(proof),
+            Err(_) => Self::Owned(proof.to_vec()),
+        }
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u8; N]> for ProofBytes<'a> {
+    fn from(proof: &'a [u8; N]) -> Self {
+        Self::from(&proof[..])
+    }
+}
+
+impl<'a> From<&'a Vec<u8>> for ProofBytes<'a> {
+    fn from(proof: &'a Vec<u8>) -> Self {
+        Self::from(proof.as_slice())
+    }
+}
+
+impl From<Vec<u8>> for ProofBytes<'_> {
+    fn from(proof: Vec<u8>) -> Self {
+        Self::Owned(proof)
+    }
+}
+
+impl<'a> From<&'a ProofBytes<'_>> for ProofBytes<'a> {
+    fn from(proof: &'a ProofBytes<'_>) -> Self {
+        Self::from(proof.as_slice())
+    }
+}
@@ -0,0 +1,162 @@
+//! SP1 public values, with their committed values digest computed once.
+
+use std::{ops::Deref, sync::OnceLock};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{CommittedValuesDigest, Error};
+
+/// The longest public values the crate deserializes by default: 32 KiB, the default heap of a
+/// Solana program, which the values are copied into on-chain.
+///
+/// Longer length prefixes fail with [`Error::PublicValuesTooLarge`] before anything is allocated.
+/// Off-chain parsers accept longer values with [`Sp1PublicValues::deserialize_with_limit`] and
+/// [`SP1ProofFixture::from_bytes_with_limit`](crate::SP1ProofFixture::from_bytes_with_limit).
+pub const MAX_SP1_PUBLIC_VALUES_LEN: usize = 32 * 1024;
+
+/// The public values of an SP1 proof: the bytes the program committed to, e.g. with
+/// `sp1_zkvm::io::commit`.
+///
+/// The committed values digest is computed on the first call to [`Self::digest`], and cached.
+/// The bytes can't be modified in place, so that the cache stays valid: convert them back with
+/// [`Self::into_vec`] instead. Values serialize with Borsh as a plain `Vec<u8>`.
+#[derive(Clone, Default)]
+pub struct Sp1PublicValues {
+    bytes: Vec<u8>,
+    digest: OnceLock<CommittedValuesDigest>,
+}
+
+impl Sp1PublicValues {
+    /// The committed values digest of the public values.
+    pub fn digest(&self) -> CommittedValuesDigest {
+        *self
+            .digest
+            .get_or_init(|| CommittedValuesDigest::from_public_values(&self.bytes))
+    }
+
+    /// A reader decoding the public values in the order they were committed.
+    pub fn reader(&self) -> PublicValuesReader<'_> {
+        PublicValuesReader {
+            remaining: &self.bytes,
+        }
+    }
+
+    /// The public values, as committed.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The public values, e.g. to modify them.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Deserializes Borsh-encoded values like their [`BorshDeserialize`] implementation, with
+    /// another limit than [`MAX_SP1_PUBLIC_VALUES_LEN`] on their length.
+    ///
+    /// A longer length prefix fails with an IO error carrying [`Error::PublicValuesTooLarge`],
+    /// which [`Error::from_borsh`] extracts.
+    pub fn deserialize_with_limit<R: borsh::io::Read>(
+        reader: &mut R,
+        max: usize,
+    ) -> borsh::io::Result<Self> {
+        let len = u32::deserialize_reader(reader)? as usize;
+        if len > max {
+            return Err(borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                Error::PublicValuesTooLarge { len, max },
+            ));
+        }
+        let mut bytes = vec![0; len];
+        reader.read_exact(&mut bytes)?;
+        Ok(bytes.into())
+    }
+}
+
+impl From<Vec<u8>> for Sp1PublicValues {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            digest: OnceLock::new(),
+        }
+    }
+}
+
+impl From<&[u8]> for Sp1PublicValues {
+    fn from(bytes: &[u8]) -> Self {
+        bytes.to_vec().into()
+    }
+}
+
+impl From<Sp1PublicValues> for Vec<u8> {
+    fn from(values: Sp1PublicValues) -> Self {
+        values.bytes
+    }
+}
+
+impl Deref for Sp1PublicValues {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl AsRef<[u8]> for Sp1PublicValues {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl PartialEq for Sp1PublicValues {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl Eq for Sp1PublicValues {}
+
+impl std::fmt::Debug for Sp1PublicValues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Sp1PublicValues").field(&self.bytes).finish()
+    }
+}
+
+impl BorshSerialize for Sp1PublicValues {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.bytes.serialize(writer)
+    }
+}
+
+/// Values longer than [`MAX_SP1_PUBLIC_VALUES_LEN`] fail with [`Error::PublicValuesTooLarge`].
+impl BorshDeserialize for Sp1PublicValues {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        Self::deserialize_with_limit(reader, MAX_SP1_PUBLIC_VALUES_LEN)
+    }
+}
+
+/// Decodes public values one at a time, like the reader of sp1-sdk's `SP1PublicValues`, but with
+/// Borsh. Each value is read where the previous one ended.
+#[derive(Clone, Copy, Debug)]
+pub struct PublicValuesReader<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> PublicValuesReader<'a> {
+    /// A reader of the public values `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { remaining: bytes }
+    }
+
+    /// Decodes the next value, failing with [`Error::BorshDeserializeError`] if the remaining
+    /// bytes don't start with one, or with the error a nested value fails with, such as
+    /// [`Error::PublicValuesTooLarge`].
+    pub fn read<T: BorshDeserialize>(&mut self) -> Result<T, Error> {
+        T::deserialize(&mut self.remaining).map_err(Error::from_borsh)
+    }
+
+    /// The bytes that haven't been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.remaining
+    }
+}
@@ -0,0 +1,52 @@
+//! On-chain records of verified proofs.
+//!
+//! A program that verifies a proof can store a [`VerificationRecord`] at the PDA derived from the
+//! SP1 program vkey hash and the committed values digest. Other instructions and programs then
+//! check whether an execution has been proven by deriving the same address and deserializing the
+//! account, without verifying the proof again.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+/// The seed prefix of verification record PDAs.
+pub const VERIFICATION_RECORD_SEED: &[u8] = b"sp1-verification";
+
+/// The record written to the verification record PDA once a proof has been verified.
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct VerificationRecord {
+    /// The slot in which the proof was verified.
+    pub slot: u64,
+    /// The committed values digest of the proof.
+    pub committed_values_digest: [u8; 32],
+    /// The account that submitted the proof.
+    pub submitter: Pubkey,
+}
+
+impl VerificationRecord {
+    /// The length of the serialized record.
+    pub const LEN: usize = 8 + 32 + 32;
+}
+
+/// The seeds of the verification record PDA, without the bump.
+pub fn verification_record_seeds<'a>(
+    sp1_vkey_hash: &'a [u8; 32],
+    committed_values_digest: &'a [u8; 32],
+) -> [&'a [u8]; 3] {
+    [
+        VERIFICATION_RECORD_SEED,
+        sp1_vkey_hash,
+        committed_values_digest,
+    ]
+}
+
+/// Finds the verification record PDA of `program_id` for the given proof.
+pub fn find_verification_record_address(
+    program_id: &Pubkey,
+    sp1_vkey_hash: &[u8; 32],
+    committed_values_digest: &[u8; 32],
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &verification_record_seeds(sp1_vkey_hash, committed_values_digest),
+        program_id,
+    )
+}
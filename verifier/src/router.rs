@@ -0,0 +1,91 @@
+//! The registry of a verifier router, which dispatches proofs by their Groth16 selector.
+//!
+//! Every SP1 Groth16 proof starts with a 4-byte selector, the first bytes of the SHA-256 hash of
+//! the verification key it was generated for (see [`crate::proof_selector`]). A router keeps a
+//! [`VerifierRegistry`] mapping selectors to a [`RouteTarget`], and verifies each proof with the
+//! target of its selector. Supporting a new SP1 release is then a matter of registering a route,
+//! rather than upgrading every program that verifies proofs.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    utils::{ct_eq, source},
+    Error,
+};
+
+/// The seed of the registry PDA of a router.
+pub const REGISTRY_SEED: &[u8] = b"sp1-verifier-registry";
+
+/// The number of routes a registry has room for.
+pub const MAX_ROUTES: usize = 16;
+
+/// Where the proofs of a selector are verified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub enum RouteTarget {
+    /// A verifier program invoked with [`crate::cpi::invoke_verify`], e.g. the standalone
+    /// verifier program.
+    Program(Pubkey),
+    /// An account holding the verification key, in the format of [`crate::vk_account`].
+    VkAccount(Pubkey),
+}
+
+impl RouteTarget {
+    /// The account that must be passed to verify a proof with this target.
+    pub fn address(&self) -> &Pubkey {
+        match self {
+            RouteTarget::Program(address) | RouteTarget::VkAccount(address) => address,
+        }
+    }
+}
+
+/// A registered selector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct Route {
+    pub selector: [u8; 4],
+    pub target: RouteTarget,
+}
+
+impl Route {
+    /// The length of a serialized route.
+    pub const LEN: usize = 4 + 1 + 32;
+}
+
+/// The registry of a router.
+#[derive(Clone, Debug, Default, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct VerifierRegistry {
+    /// The only account allowed to add or remove routes.
+    pub admin: Pubkey,
+    /// The registered routes, with distinct selectors.
+    pub routes: Vec<Route>,
+}
+
+impl VerifierRegistry {
+    /// The size of a registry account, with room for [`MAX_ROUTES`] routes.
+    pub const LEN: usize = 32 + 4 + MAX_ROUTES * Route::LEN;
+
+    /// Reads a registry from account data, ignoring the unused space after it.
+    pub fn from_account_data(data: &[u8]) -> Result<Self, Error> {
+        Self::deserialize(&mut &data[..])
+            .map_err(|error| Error::BorshDeserializeError(source(error)))
+    }
+
+    /// Writes the registry to account data.
+    pub fn to_account_data(&self, data: &mut [u8]) -> Result<(), Error> {
+        self.serialize(&mut &mut data[..])
+            .map_err(|error| Error::BorshSerializeError(source(error)))
+    }
+
+    /// The target of `selector`, if it is registered.
+    pub fn target(&self, selector: &[u8; 4]) -> Option<&RouteTarget> {
+        self.routes
+            .iter()
+            .find(|route| ct_eq(&route.selector, selector))
+            .map(|route| &route.target)
+    }
+
+    /// Finds the registry PDA of the router `program_id`.
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REGISTRY_SEED], program_id)
+    }
+}
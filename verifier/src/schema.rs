@@ -0,0 +1,416 @@
+//! Versioned decoding of public values, for programs whose committed fields grow over time.
+//!
+//! A struct declared with [`sp1_public_values_schema!`](crate::sp1_public_values_schema) decodes
+//! the values its program commits, field by field, with Borsh. New fields are appended at the end
+//! and marked `#[sp1(default)]`: payloads committed by an older version of the program end before
+//! them, and decode with their `Default` value.
+//!
+//! Each version of the struct, i.e. each prefix of its fields that includes every required one, has
+//! a schema hash, derived from the names and types of its fields. A program can commit
+//! [`SCHEMA_HASH`](Sp1PublicValuesDecode::SCHEMA_HASH) before its values, making them
+//! self-describing: [`Sp1PublicValuesDecode::decode_with_schema_hash`] then decodes exactly the
+//! fields of the version it names, and rejects a payload of any other schema with
+//! [`Error::SchemaHashMismatch`]. A verifier must therefore be upgraded before the programs it
+//! verifies start committing new fields.
+
+use borsh::BorshDeserialize;
+
+use crate::{hasher::INITIAL_STATE, utils::ct_eq, Error, InputSection, PublicValuesReader};
+
+/// The length of a schema hash.
+pub const SCHEMA_HASH_LEN: usize = 32;
+
+/// Hashed before the fields of a schema, to keep schema hashes apart from other SHA-256 hashes.
+const SCHEMA_DOMAIN: &[u8] = b"sp1-solana:public-values-schema:v1\n";
+
+/// What to do with bytes left after the last field of a payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrailingBytesPolicy {
+    /// Fail with [`Error::TrailingBytes`], e.g. to reject values committed by a newer program.
+    Strict,
+    /// Ignore them, e.g. to read the fields a verifier knows of from a newer program's values.
+    Lenient,
+}
+
+/// Public values that decode, with Borsh, into `Self`, one field after the other.
+///
+/// This is implemented by [`sp1_public_values_schema!`](crate::sp1_public_values_schema), which
+/// also derives the schema hashes. A manual implementation must list its fields in the same format
+/// as the macro, and read them in that order in [`Self::decode_fields`].
+pub trait Sp1PublicValuesDecode: Sized {
+    /// The fields of `Self`, in the order they are committed: one `name: type` line each.
+    const SCHEMA: &'static str;
+    /// The number of leading fields that every payload has. The others were appended later.
+    const REQUIRED_FIELDS: usize;
+    /// The [`schema_hash`] of each prefix of [`Self::SCHEMA`]: the first of its first field, the
+    /// last of all of them.
+    const SCHEMA_HASHES: &'static [[u8; SCHEMA_HASH_LEN]];
+    /// The schema hash of the current version, with every field.
+    const SCHEMA_HASH: [u8; SCHEMA_HASH_LEN] = Self::SCHEMA_HASHES[Self::SCHEMA_HASHES.len() - 1];
+
+    /// Reads the fields of `Self` from `fields`, in order.
+    fn decode_fields(fields: &mut SchemaReader<'_>) -> Result<Self, Error>;
+
+    /// Decodes public values without a schema hash. Trailing fields missing from `values` take
+    /// their default value.
+    fn decode(values: &[u8], trailing: TrailingBytesPolicy) -> Result<Self, Error> {
+        decode_with(values, None, trailing)
+    }
+
+    /// Decodes public values that start with the schema hash of a version of `Self`, and have the
+    /// fields of that version.
+    ///
+    /// Fails with [`Error::SchemaHashMismatch`] if the values don't start with one of the schema
+    /// hashes of `Self`.
+    fn decode_with_schema_hash(
+        values: &[u8],
+        trailing: TrailingBytesPolicy,
+    ) -> Result<Self, Error> {
+        if values.len() < SCHEMA_HASH_LEN {
+            return Err(Error::SchemaHashMismatch);
+        }
+        let (schema_hash, values) = values.split_at(SCHEMA_HASH_LEN);
+        let fields = Self::schema_version(schema_hash.try_into().unwrap())
+            .ok_or(Error::SchemaHashMismatch)?;
+        decode_with(values, Some(fields), trailing)
+    }
+
+    /// The number of fields of the version of `Self` whose schema hash is `schema_hash`, if any.
+    fn schema_version(schema_hash: &[u8; SCHEMA_HASH_LEN]) -> Option<usize> {
+        Self::SCHEMA_HASHES
+            .iter()
+            .position(|hash| ct_eq(hash, schema_hash))
+            .map(|index| index + 1)
+            .filter(|&fields| fields >= Self::REQUIRED_FIELDS)
+    }
+}
+
+fn decode_with<T: Sp1PublicValuesDecode>(
+    values: &[u8],
+    fields: Option<usize>,
+    trailing: TrailingBytesPolicy,
+) -> Result<T, Error> {
+    let mut reader = SchemaReader {
+        reader: PublicValuesReader::new(values),
+        read: 0,
+        fields,
+    };
+    let decoded = T::decode_fields(&mut reader)?;
+
+    let extra = reader.reader.remaining().len();
+    if trailing == TrailingBytesPolicy::Strict && extra > 0 {
+        return Err(Error::TrailingBytes {
+            section: InputSection::PublicValues,
+            extra,
+        });
+    }
+    Ok(decoded)
+}
+
+/// Reads the fields of an [`Sp1PublicValuesDecode`] type, knowing which of them the payload has.
+#[derive(Debug)]
+pub struct SchemaReader<'a> {
+    reader: PublicValuesReader<'a>,
+    /// The number of fields read so far, including defaulted ones.
+    read: usize,
+    /// The number of fields of the payload's version, when it has a schema hash.
+    fields: Option<usize>,
+}
+
+impl SchemaReader<'_> {
+    /// Reads the next field, which every payload has.
+    pub fn field<T: BorshDeserialize>(&mut self) -> Result<T, Error> {
+        self.read += 1;
+        self.reader.read()
+    }
+
+    /// Reads the next field, or returns its default value if the payload was committed before it
+    /// was appended: either its schema hash names an older version, or, without one, it has no
+    /// bytes left.
+    pub fn appended_field<T: BorshDeserialize + Default>(&mut self) -> Result<T, Error> {
+        let present = match self.fields {
+            Some(fields) => self.read < fields,
+            None => !self.reader.remaining().is_empty(),
+        };
+        self.read += 1;
+        if present {
+            self.reader.read()
+        } else {
+            Ok(T::default())
+        }
+    }
+}
+
+/// The schema hash of the first `fields` fields of `schema`, in the format of
+/// [`Sp1PublicValuesDecode::SCHEMA`].
+///
+/// This is the SHA-256 hash of a domain separator, then of the first `fields` lines of `schema`
+/// without their spaces and tabs, so that the hash doesn't depend on how the compiler formats
+/// types. Being a `const fn`, it is computed at compile time.
+pub const fn schema_hash(schema: &str, fields: usize) -> [u8; SCHEMA_HASH_LEN] {
+    let schema = schema.as_bytes();
+    let mut hasher = ConstSha256::new();
+    let mut index = 0;
+    while index < SCHEMA_DOMAIN.len() {
+        hasher = hasher.update(SCHEMA_DOMAIN[index]);
+        index += 1;
+    }
+
+    let mut lines = 0;
+    index = 0;
+    while index < schema.len() && lines < fields {
+        let byte = schema[index];
+        if byte != b' ' && byte != b'\t' && byte != b'\r' {
+            hasher = hasher.update(byte);
+        }
+        if byte == b'\n' {
+            lines += 1;
+        }
+        index += 1;
+    }
+    assert!(lines == fields, "the schema has fewer fields than hashed");
+    hasher.finalize()
+}
+
+/// The schema hashes of every prefix of `schema`, which has `N` fields, as in
+/// [`Sp1PublicValuesDecode::SCHEMA_HASHES`].
+pub const fn schema_hashes<const N: usize>(schema: &str) -> [[u8; SCHEMA_HASH_LEN]; N] {
+    let mut hashes = [[0; SCHEMA_HASH_LEN]; N];
+    let mut index = 0;
+    while index < N {
+        hashes[index] = schema_hash(schema, index + 1);
+        index += 1;
+    }
+    hashes
+}
+
+/// The number of leading fields that aren't `#[sp1(default)]`, for the schema macro. Fails to
+/// compile if a required field follows a default one.
+#[doc(hidden)]
+pub const fn required_fields(defaults: &[bool]) -> usize {
+    let mut required = 0;
+    while required < defaults.len() && !defaults[required] {
+        required += 1;
+    }
+    let mut index = required;
+    while index < defaults.len() {
+        assert!(
+            defaults[index],
+            "fields after an #[sp1(default)] field must be #[sp1(default)] too"
+        );
+        index += 1;
+    }
+    required
+}
+
+/// Declares a struct of public values, and implements [`Sp1PublicValuesDecode`] for it.
+///
+/// Fields are decoded in declaration order. Fields appended after the first version of the
+/// program are marked `#[sp1(default)]`, must come last, and must implement `Default`. Field
+/// attributes other than doc comments and `#[sp1(default)]` aren't supported.
+///
+/// ```
+/// use sp1_solana::{sp1_public_values_schema, Sp1PublicValuesDecode, TrailingBytesPolicy};
+///
+/// sp1_public_values_schema! {
+///     #[derive(Debug, PartialEq)]
+///     pub struct FibonacciValues {
+///         pub n: u32,
+///         pub a: u32,
+///         pub b: u32,
+///         /// Committed since the second version of the program.
+///         #[sp1(default)]
+///         pub steps: u64,
+///     }
+/// }
+///
+/// // Values committed by the first version of the program.
+/// let values = [20u32, 6765, 3027].map(u32::to_le_bytes).concat();
+/// assert_eq!(
+///     FibonacciValues::decode(&values, TrailingBytesPolicy::Strict).unwrap(),
+///     FibonacciValues { n: 20, a: 6765, b: 3027, steps: 0 }
+/// );
+/// ```
+#[macro_export]
+macro_rules! sp1_public_values_schema {
+    (@read $fields:ident) => {
+        $fields.field()?
+    };
+    (@read $fields:ident default) => {
+        $fields.appended_field()?
+    };
+    (@is_default) => {
+        false
+    };
+    (@is_default default) => {
+        true
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[doc = $field_doc:literal])*
+                $(#[sp1($default:ident)])?
+                $field_vis:vis $field:ident: $ty:ty
+            ),+ $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $(
+                $(#[doc = $field_doc])*
+                $field_vis $field: $ty,
+            )+
+        }
+
+        const _: () = {
+            const FIELDS: usize = [$(stringify!($field)),+].len();
+            const SCHEMA: &str = concat!($(stringify!($field), ": ", stringify!($ty), "\n"),+);
+            const SCHEMA_HASHES: [[u8; $crate::SCHEMA_HASH_LEN]; FIELDS] =
+                $crate::schema_hashes::<FIELDS>(SCHEMA);
+            const REQUIRED_FIELDS: usize = $crate::required_fields(&[
+                $($crate::sp1_public_values_schema!(@is_default $($default)?)),+
+            ]);
+
+            impl $crate::Sp1PublicValuesDecode for $name {
+                const SCHEMA: &'static str = SCHEMA;
+                const REQUIRED_FIELDS: usize = REQUIRED_FIELDS;
+                const SCHEMA_HASHES: &'static [[u8; $crate::SCHEMA_HASH_LEN]] = &SCHEMA_HASHES;
+
+                fn decode_fields(
+                    fields: &mut $crate::SchemaReader<'_>,
+                ) -> ::core::result::Result<Self, $crate::Error> {
+                    ::core::result::Result::Ok(Self {
+                        $($field: $crate::sp1_public_values_schema!(@read fields $($default)?),)+
+                    })
+                }
+            }
+        };
+    };
+}
+
+/// The SHA-256 round constants.
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// A SHA-256 hasher usable in `const fn`s, which `sha2` isn't, fed one byte at a time.
+struct ConstSha256 {
+    state: [u32; 8],
+    block: [u8; 64],
+    block_len: usize,
+    total_len: u64,
+}
+
+impl ConstSha256 {
+    const fn new() -> Self {
+        Self {
+            state: INITIAL_STATE,
+            block: [0; 64],
+            block_len: 0,
+            total_len: 0,
+        }
+    }
+
+    const fn update(mut self, byte: u8) -> Self {
+        self.total_len += 1;
+        self.push(byte)
+    }
+
+    const fn push(mut self, byte: u8) -> Self {
+        self.block[self.block_len] = byte;
+        self.block_len += 1;
+        if self.block_len == 64 {
+            self.state = compress(self.state, &self.block);
+            self.block_len = 0;
+        }
+        self
+    }
+
+    const fn finalize(mut self) -> [u8; 32] {
+        let bit_len = (self.total_len * 8).to_be_bytes();
+        // The 0x80 terminator, zero padding up to the last 8 bytes of a block, and the bit length.
+        self = self.push(0x80);
+        while self.block_len != 56 {
+            self = self.push(0);
+        }
+        let mut index = 0;
+        while index < 8 {
+            self = self.push(bit_len[index]);
+            index += 1;
+        }
+
+        let mut digest = [0; 32];
+        index = 0;
+        while index < 32 {
+            digest[index] = self.state[index / 4].to_be_bytes()[index % 4];
+            index += 1;
+        }
+        digest
+    }
+}
+
+const fn compress(state: [u32; 8], block: &[u8; 64]) -> [u32; 8] {
+    let mut schedule = [0u32; 64];
+    let mut index = 0;
+    while index < 16 {
+        schedule[index] = u32::from_be_bytes([
+            block[4 * index],
+            block[4 * index + 1],
+            block[4 * index + 2],
+            block[4 * index + 3],
+        ]);
+        index += 1;
+    }
+    while index < 64 {
+        let w15 = schedule[index - 15];
+        let w2 = schedule[index - 2];
+        let s0 = w15.rotate_right(7) ^ w15.rotate_right(18) ^ (w15 >> 3);
+        let s1 = w2.rotate_right(17) ^ w2.rotate_right(19) ^ (w2 >> 10);
+        schedule[index] = schedule[index - 16]
+            .wrapping_add(s0)
+            .wrapping_add(schedule[index - 7])
+            .wrapping_add(s1);
+        index += 1;
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    index = 0;
+    while index < 64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let choice = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(choice)
+            .wrapping_add(ROUND_CONSTANTS[index])
+            .wrapping_add(schedule[index]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let majority = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(majority);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+        index += 1;
+    }
+
+    let mut next = state;
+    let words = [a, b, c, d, e, f, g, h];
+    index = 0;
+    while index < 8 {
+        next[index] = next[index].wrapping_add(words[index]);
+        index += 1;
+    }
+    next
+}
@@ -1,7 +1,16 @@
+//! Tests marked with the `sp1-fixtures` feature verify the SP1 proof committed in `../proofs`.
+//! The others run on a fresh clone, with proofs of [`crate::test_circuit`] where they need one.
+
+#[cfg(feature = "sp1-fixtures")]
 use num_bigint::BigUint;
+#[cfg(feature = "sp1-fixtures")]
 use num_traits::Num;
+#[cfg(feature = "sp1-fixtures")]
 use sp1_sdk::SP1ProofWithPublicValues;
 
+use crate::test_circuit::ark_compressed_to_gnark_compressed;
+
+#[cfg(feature = "sp1-fixtures")]
 #[test]
 fn test_verify_from_sp1() {
     use crate::{verify_proof, GROTH16_VK_3_0_0_BYTES};
@@ -40,6 +49,7 @@ fn test_verify_from_sp1() {
     .is_ok());
 }
 
+#[cfg(feature = "sp1-fixtures")]
 #[test]
 fn test_hash_public_inputs_() {
     use crate::utils::hash_public_inputs;
@@ -75,3 +85,2679 @@ fn test_decode_sp1_vkey_hash() {
         hex_literal::hex!("0054c0e58911dd8b993c6d8f249aa50a2e523114ec4b7ef9dd355c5f6bfbf3ce")
     );
 }
+
+/// Hashes, selectors and digests are compared with `ct_eq`, never with `==`, in every source file
+/// but the tests.
+#[test]
+fn test_constant_time_comparisons() {
+    use crate::utils::ct_eq;
+
+    assert!(ct_eq(&[1, 2, 3], &[1, 2, 3]));
+    assert!(ct_eq(&[], &[]));
+    assert!(!ct_eq(&[1, 2, 3], &[0, 2, 3]));
+    assert!(!ct_eq(&[1, 2, 3], &[1, 2, 4]));
+    assert!(!ct_eq(&[1, 2, 3], &[1, 2]));
+
+    // Comparisons of a single byte, of lengths and of counts.
+    const ALLOWED: [&str; 3] = ["[0] != 0", ".len() != ", "lines == fields"];
+    let mut comparisons = Vec::new();
+    for entry in std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/src")).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        if !name.ends_with(".rs") || name.starts_with("test") {
+            continue;
+        }
+        let source = std::fs::read_to_string(&path).unwrap();
+        for (index, line) in source.lines().enumerate() {
+            let code = line.split("//").next().unwrap().to_lowercase();
+            if (code.contains("==") || code.contains("!="))
+                && ["hash", "selector", "digest"]
+                    .iter()
+                    .any(|word| code.contains(word))
+                && !ALLOWED.iter().any(|allowed| code.contains(allowed))
+            {
+                comparisons.push(format!("{name}:{}: {}", index + 1, line.trim()));
+            }
+        }
+    }
+    assert!(
+        comparisons.is_empty(),
+        "compare these with ct_eq: {comparisons:#?}"
+    );
+}
+
+#[test]
+fn test_groth16_vk_hash_constants() {
+    use crate::*;
+    use sha2::{Digest, Sha256};
+
+    for (vk, hash) in [
+        (GROTH16_VK_3_0_0_BYTES, GROTH16_VK_3_0_0_HASH),
+        (GROTH16_VK_3_0_0_RC4_BYTES, GROTH16_VK_3_0_0_RC4_HASH),
+        (GROTH16_VK_2_0_0_BYTES, GROTH16_VK_2_0_0_HASH),
+    ] {
+        assert_eq!(Sha256::digest(vk)[..4], hash);
+    }
+}
+
+/// The vectors of the fibonacci proof, against the committed `testdata/vectors.json`.
+#[cfg(feature = "embedded-vk-v3-0-0")]
+#[test]
+fn test_vectors() {
+    use crate::{
+        decode_sp1_vkey_hash,
+        vectors::{to_json, TestVectors},
+        verify_proof_raw, Error, GROTH16_VK_3_0_0_BYTES, GROTH16_VK_3_0_0_HASH,
+    };
+    use num_bigint::BigUint;
+
+    let proof_json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string("../proofs/fibonacci_proof.json").unwrap())
+            .unwrap();
+    let field = |name: &str| proof_json[name].as_str().unwrap().to_string();
+    let decode = |name: &str| hex::decode(field(name).strip_prefix("0x").unwrap()).unwrap();
+    let vectors = TestVectors::new(
+        "fibonacci",
+        &decode_sp1_vkey_hash(&field("vkey_hash")).unwrap(),
+        &decode("public_values"),
+        &decode("proof"),
+    )
+    .unwrap();
+    assert_eq!(
+        to_json(&[vectors.clone()]),
+        include_str!("../testdata/vectors.json"),
+        "regenerate testdata/vectors.json with `cargo run --bin vectors` in example/script"
+    );
+
+    // The vectors are those the verifier checks, and match the public inputs the prover reported.
+    assert_eq!(vectors.groth16_selector, GROTH16_VK_3_0_0_HASH);
+    verify_proof_raw(
+        &vectors.proof,
+        &vectors.groth16_public_inputs,
+        GROTH16_VK_3_0_0_BYTES,
+    )
+    .unwrap();
+    assert_eq!(
+        vectors.committed_values_digest[0],
+        vectors.public_values_sha256[0] & 0x1f
+    );
+    assert_eq!(
+        vectors.committed_values_digest[1..],
+        vectors.public_values_sha256[1..]
+    );
+    for (scalar, decimal) in [
+        (&vectors.vkey_hash_scalar, &proof_json["public_inputs"][0]),
+        (
+            &vectors.committed_values_digest,
+            &proof_json["public_inputs"][1],
+        ),
+    ] {
+        assert_eq!(
+            BigUint::from_bytes_be(scalar).to_string(),
+            decimal.as_str().unwrap()
+        );
+    }
+
+    // A non-canonical vkey hash or a raw proof without its selector has no vectors.
+    let mut sp1_vkey_hash = vectors.sp1_vkey_hash;
+    sp1_vkey_hash[0] = 1;
+    assert_eq!(
+        TestVectors::new(
+            "bad",
+            &sp1_vkey_hash,
+            &vectors.public_values,
+            &vectors.proof
+        ),
+        Err(Error::InvalidProgramVkeyHash)
+    );
+    assert!(TestVectors::new(
+        "bad",
+        &vectors.sp1_vkey_hash,
+        &vectors.public_values,
+        &vectors.proof[4..]
+    )
+    .is_err());
+}
+
+#[test]
+fn test_embedded_vk_versions() {
+    use crate::{available_vk_versions, verify_proof_auto, Error, Groth16VkVersion};
+    use sha2::{Digest, Sha256};
+
+    assert_eq!(
+        available_vk_versions(),
+        [
+            Groth16VkVersion::V3_0_0,
+            Groth16VkVersion::V3_0_0Rc4,
+            Groth16VkVersion::V2_0_0
+        ]
+    );
+    for &version in available_vk_versions() {
+        assert_eq!(
+            Sha256::digest(version.groth16_vk())[..4],
+            version.groth16_vkey_hash()
+        );
+        assert_eq!(
+            Groth16VkVersion::from_groth16_vkey_hash(&version.groth16_vkey_hash()),
+            Some(version)
+        );
+    }
+    assert_eq!(Groth16VkVersion::V3_0_0Rc4.name(), "v3.0.0rc4");
+    assert_eq!(Groth16VkVersion::from_groth16_vkey_hash(&[0; 4]), None);
+
+    // A proof for a key that isn't embedded.
+    let vkey_hash = "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83";
+    assert_eq!(
+        verify_proof_auto(&[0; 260], &[], vkey_hash),
+        Err(Error::Groth16VkeyHashMismatch)
+    );
+    assert_eq!(
+        verify_proof_auto(&[0; 3], &[], vkey_hash),
+        Err(Error::InvalidProofLength {
+            expected_one_of: &[PROOF_WITH_SELECTOR_LEN],
+            got: 3
+        })
+    );
+}
+
+#[test]
+fn test_unsupported_sp1_version() {
+    use crate::{
+        available_vk_versions, known_sp1_version, verify_proof_raw,
+        versions::{selector_mismatch_in, KnownSp1Version},
+        Error, GROTH16_VK_2_0_0_HASH, GROTH16_VK_3_0_0_BYTES, GROTH16_VK_3_0_0_HASH,
+        KNOWN_SP1_VERSIONS, SUPPORTED_SP1_VERSIONS,
+    };
+
+    // The table agrees with the embedded keys, which are all compiled in by default.
+    assert_eq!(SUPPORTED_SP1_VERSIONS, ["v3.0.0", "v3.0.0rc4", "v2.0.0"]);
+    for &version in available_vk_versions() {
+        let known = known_sp1_version(&version.groth16_vkey_hash()).unwrap();
+        assert_eq!(known.version, version.name());
+        assert!(known.embedded);
+    }
+    assert_eq!(KNOWN_SP1_VERSIONS.len(), available_vk_versions().len());
+    assert_eq!(known_sp1_version(&[0; 4]), None);
+
+    // A v2 proof, received while only v3 support is compiled in.
+    static V3_ONLY: &[KnownSp1Version] = &[
+        KnownSp1Version {
+            groth16_vkey_hash: GROTH16_VK_3_0_0_HASH,
+            version: "v3.0.0",
+            embedded: true,
+        },
+        KnownSp1Version {
+            groth16_vkey_hash: GROTH16_VK_2_0_0_HASH,
+            version: "v2.0.0",
+            embedded: false,
+        },
+    ];
+    let error = selector_mismatch_in(V3_ONLY, &["v3.0.0"], &GROTH16_VK_2_0_0_HASH);
+    assert!(matches!(
+        error,
+        Error::UnsupportedSp1Version {
+            detected: "v2.0.0",
+            supported: ["v3.0.0"]
+        }
+    ));
+    assert_eq!(
+        error.to_string(),
+        "The proof is from SP1 v2.0.0, whose Groth16 verification key isn't compiled in; \
+         supported versions: [\"v3.0.0\"]"
+    );
+    // Unknown selectors, and those of compiled-in keys, are plain mismatches.
+    for selector in [[0; 4], GROTH16_VK_3_0_0_HASH] {
+        assert_eq!(
+            selector_mismatch_in(V3_ONLY, &["v3.0.0"], &selector),
+            Error::Groth16VkeyHashMismatch
+        );
+    }
+
+    // With every key compiled in, a v2 proof checked against the v3 key is a mismatch too.
+    let mut v2_proof = [0u8; 260];
+    v2_proof[..4].copy_from_slice(&GROTH16_VK_2_0_0_HASH);
+    assert_eq!(
+        verify_proof_raw(&v2_proof, &[0; 63], GROTH16_VK_3_0_0_BYTES),
+        Err(Error::Groth16VkeyHashMismatch)
+    );
+}
+
+/// The same, in a build that embeds the v3.0.0 key but not the v2.0.0 one.
+#[cfg(all(feature = "embedded-vk-v3-0-0", not(feature = "embedded-vk-v2-0-0")))]
+#[test]
+fn test_unsupported_sp1_version_v3_only() {
+    use crate::{
+        verify_proof_auto, verify_proof_raw, Error, GROTH16_VK_2_0_0_HASH, GROTH16_VK_3_0_0_BYTES,
+    };
+
+    let mut v2_proof = [0u8; 260];
+    v2_proof[..4].copy_from_slice(&GROTH16_VK_2_0_0_HASH);
+    let vkey_hash = "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83";
+    let unsupported = |result| {
+        matches!(
+            result,
+            Err(Error::UnsupportedSp1Version {
+                detected: "v2.0.0",
+                ..
+            })
+        )
+    };
+    assert!(unsupported(verify_proof_auto(&v2_proof, &[], vkey_hash)));
+    assert!(unsupported(verify_proof_raw(
+        &v2_proof,
+        &[0; 63],
+        GROTH16_VK_3_0_0_BYTES
+    )));
+}
+
+/// Proofs only verify under the key they were generated for, even though the keys of SP1 releases
+/// take the same public inputs and proofs of all of them are as long. Each proof is checked against
+/// each embedded key and the key of the test circuit: off the diagonal, the selector check rejects
+/// it, and when the selector is stripped or forged to match, the pairing check rejects it. No cell
+/// panics.
+#[cfg(feature = "embedded-vk-v3-0-0")]
+#[test]
+fn test_cross_version_matrix() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use crate::{
+        available_vk_versions, decode_sp1_vkey_hash, groth16_public_inputs, test_circuit::prove,
+        utils::sha256, verify_proof, verify_proof_raw, CommittedValuesDigest, Error,
+    };
+
+    struct Fixture {
+        name: &'static str,
+        /// The proof in SP1's standard format.
+        proof: Vec<u8>,
+        sp1_vkey_hash: [u8; 32],
+        public_values: Vec<u8>,
+        /// The key the proof was generated for.
+        vk: Vec<u8>,
+    }
+
+    let mut keys: Vec<(&str, Vec<u8>)> = available_vk_versions()
+        .iter()
+        .map(|version| (version.name(), version.groth16_vk().to_vec()))
+        .collect();
+
+    let proof_json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string("../proofs/fibonacci_proof.json").unwrap())
+            .unwrap();
+    let field = |name: &str| proof_json[name].as_str().unwrap().to_string();
+    let decode = |name: &str| hex::decode(field(name).strip_prefix("0x").unwrap()).unwrap();
+    let mut fixtures = vec![Fixture {
+        name: "fibonacci",
+        proof: decode("proof"),
+        sp1_vkey_hash: decode_sp1_vkey_hash(&field("vkey_hash")).unwrap(),
+        public_values: decode("public_values"),
+        vk: crate::GROTH16_VK_3_0_0_BYTES.to_vec(),
+    }];
+
+    // A proof of the test circuit, which takes the same public inputs as SP1's.
+    let mut sp1_vkey_hash = [7; 32];
+    sp1_vkey_hash[0] = 0;
+    let public_values = b"cross-version".to_vec();
+    let committed_values_digest = CommittedValuesDigest::from_public_values(&public_values);
+    let test_proof = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    fixtures.push(Fixture {
+        name: "test circuit",
+        proof: [&sha256(&test_proof.vk)[..4], &test_proof.proof].concat(),
+        sp1_vkey_hash,
+        public_values,
+        vk: test_proof.vk.clone(),
+    });
+    keys.push(("test circuit", test_proof.vk));
+
+    let run = |cell: &str, verify: &dyn Fn() -> Result<(), Error>| {
+        catch_unwind(AssertUnwindSafe(verify)).unwrap_or_else(|_| panic!("{cell} panicked"))
+    };
+    for fixture in &fixtures {
+        let vkey_hash = format!("0x{}", hex::encode(fixture.sp1_vkey_hash));
+        let public_inputs = groth16_public_inputs(
+            &fixture.sp1_vkey_hash,
+            &CommittedValuesDigest::from_public_values(&fixture.public_values),
+        );
+        let raw_proof = &fixture.proof[4..];
+
+        for (key_name, vk) in &keys {
+            let cell = format!("{} under {key_name}", fixture.name);
+            let diagonal = *vk == fixture.vk;
+            let rejected = |error| if diagonal { Ok(()) } else { Err(error) };
+
+            // The selector names another key.
+            assert_eq!(
+                run(&cell, &|| verify_proof(
+                    &fixture.proof,
+                    &fixture.public_values,
+                    &vkey_hash,
+                    vk
+                )),
+                rejected(Error::Groth16VkeyHashMismatch),
+                "{cell}"
+            );
+
+            // Without the selector, only the pairing check is left.
+            assert_eq!(
+                run(&cell, &|| verify_proof_raw(raw_proof, &public_inputs, vk)),
+                rejected(Error::VerificationError),
+                "{cell}, raw"
+            );
+
+            // With the selector of the key.
+            let forged = [&sha256(vk)[..4], raw_proof].concat();
+            assert_eq!(
+                run(&cell, &|| verify_proof(
+                    &forged,
+                    &fixture.public_values,
+                    &vkey_hash,
+                    vk
+                )),
+                rejected(Error::VerificationError),
+                "{cell}, forged selector"
+            );
+        }
+    }
+
+    // Every fixture has its key on the diagonal.
+    for fixture in &fixtures {
+        assert_eq!(
+            keys.iter().filter(|(_, vk)| *vk == fixture.vk).count(),
+            1,
+            "{}",
+            fixture.name
+        );
+    }
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_verify_proof_auto() {
+    use crate::{verify_proof_auto, Error, GROTH16_VK_2_0_0_HASH, PROOF_WITH_SELECTOR_LEN};
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+    let public_values = sp1_proof_with_public_values.public_values.to_vec();
+    let vkey_hash = "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83";
+
+    // The v3.0.0 key is picked from the proof's selector.
+    assert!(verify_proof_auto(&proof, &public_values, vkey_hash).is_ok());
+
+    // A proof claiming to be for another embedded key is checked against that key.
+    let mut v2_proof = proof.clone();
+    v2_proof[..4].copy_from_slice(&GROTH16_VK_2_0_0_HASH);
+    assert_eq!(
+        verify_proof_auto(&v2_proof, &public_values, vkey_hash),
+        Err(Error::VerificationError)
+    );
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_verify_with_digest() {
+    use crate::{
+        decode_sp1_vkey_hash, verify_proof_with_digest, CommittedValuesDigest,
+        GROTH16_VK_3_0_0_BYTES, GROTH16_VK_3_0_0_HASH,
+    };
+
+    let sp1_proof_with_public_values_file = "../proofs/fibonacci_proof.bin";
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load(&sp1_proof_with_public_values_file).unwrap();
+
+    let proof_bytes = sp1_proof_with_public_values.bytes();
+    let committed_values_digest = CommittedValuesDigest::from_public_values(
+        sp1_proof_with_public_values.public_values.as_slice(),
+    );
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+
+    assert!(verify_proof_with_digest(
+        &proof_bytes,
+        &committed_values_digest,
+        &sp1_vkey_hash,
+        GROTH16_VK_3_0_0_BYTES,
+        &GROTH16_VK_3_0_0_HASH,
+    )
+    .is_ok());
+
+    // Tampering with the digest must cause the pairing check to fail.
+    let mut tampered_digest = committed_values_digest.to_bytes();
+    tampered_digest[31] ^= 1;
+    let tampered_digest = CommittedValuesDigest::from_masked_bytes(tampered_digest).unwrap();
+    assert!(verify_proof_with_digest(
+        &proof_bytes,
+        &tampered_digest,
+        &sp1_vkey_hash,
+        GROTH16_VK_3_0_0_BYTES,
+        &GROTH16_VK_3_0_0_HASH,
+    )
+    .is_err());
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_verify_prenegated() {
+    use crate::{
+        decode_sp1_vkey_hash, groth16_public_values, negate_proof_a, verify_proof_raw,
+        verify_proof_raw_prenegated, GROTH16_VK_3_0_0_BYTES,
+    };
+
+    let sp1_proof_with_public_values_file = "../proofs/fibonacci_proof.bin";
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load(&sp1_proof_with_public_values_file).unwrap();
+
+    let proof_bytes = sp1_proof_with_public_values.bytes();
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let public_inputs = groth16_public_values(
+        &sp1_vkey_hash,
+        &sp1_proof_with_public_values.public_values.to_vec(),
+    );
+
+    let prenegated_proof = negate_proof_a(&proof_bytes[4..]).unwrap();
+    assert!(
+        verify_proof_raw_prenegated(&prenegated_proof, &public_inputs, GROTH16_VK_3_0_0_BYTES)
+            .is_ok()
+    );
+
+    // The two paths are mutually exclusive.
+    assert!(verify_proof_raw(&prenegated_proof, &public_inputs, GROTH16_VK_3_0_0_BYTES).is_err());
+    assert!(
+        verify_proof_raw_prenegated(&proof_bytes[4..], &public_inputs, GROTH16_VK_3_0_0_BYTES)
+            .is_err()
+    );
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_verify_from_slice() {
+    use crate::{
+        decode_sp1_vkey_hash, groth16_public_inputs, verify_proof_from_slice,
+        CommittedValuesDigest, Error, GROTH16_VK_3_0_0_BYTES,
+    };
+
+    let sp1_proof_with_public_values_file = "../proofs/fibonacci_proof.bin";
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load(&sp1_proof_with_public_values_file).unwrap();
+
+    let proof_bytes = sp1_proof_with_public_values.bytes();
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let public_inputs = groth16_public_inputs(
+        &sp1_vkey_hash,
+        &CommittedValuesDigest::from_public_values(
+            sp1_proof_with_public_values.public_values.as_slice(),
+        ),
+    );
+
+    // Embed the raw proof in a larger buffer, as it would be in account data.
+    let offset = 100;
+    let mut data = vec![0u8; 1024];
+    data[offset..offset + 256].copy_from_slice(&proof_bytes[4..]);
+
+    assert!(verify_proof_from_slice(&data, offset, &public_inputs, GROTH16_VK_3_0_0_BYTES).is_ok());
+    assert!(
+        verify_proof_from_slice(&data, offset + 1, &public_inputs, GROTH16_VK_3_0_0_BYTES).is_err()
+    );
+
+    // Offsets that leave less than 256 bytes are rejected instead of panicking.
+    assert!(matches!(
+        verify_proof_from_slice(&data, 1024 - 255, &public_inputs, GROTH16_VK_3_0_0_BYTES),
+        Err(Error::InvalidInput)
+    ));
+    assert!(matches!(
+        verify_proof_from_slice(&data, 2048, &public_inputs, GROTH16_VK_3_0_0_BYTES),
+        Err(Error::InvalidInput)
+    ));
+}
+
+/// Counts heap allocations made by the current thread.
+mod counting_allocator {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    /// Returns the number of allocations performed by the current thread while running `f`.
+    pub fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+        let before = ALLOCATIONS.with(Cell::get);
+        let result = f();
+        let after = ALLOCATIONS.with(Cell::get);
+        (result, after - before)
+    }
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_loading_is_allocation_free() {
+    use crate::utils::{load_proof_from_bytes, load_public_inputs_from_bytes};
+    use crate::{
+        decode_sp1_vkey_hash, groth16_verifying_key, hash_public_inputs, pairing_check,
+        verify_proof, verify_proof_raw, VkView, GROTH16_VK_3_0_0_BYTES,
+    };
+    use counting_allocator::count_allocations;
+
+    let sp1_proof_with_public_values_file = "../proofs/fibonacci_proof.bin";
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load(&sp1_proof_with_public_values_file).unwrap();
+    let proof_bytes = sp1_proof_with_public_values.bytes();
+    let sp1_public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+
+    // The pairing itself is delegated to `groth16-solana`, whose off-chain fallback allocates, so
+    // this covers everything on the `verify_proof` path that runs before it.
+    let (public_inputs, allocations) = count_allocations(|| {
+        let sp1_vkey_hash = decode_sp1_vkey_hash(
+            "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83",
+        )
+        .unwrap();
+        let digest = hash_public_inputs(&sp1_public_inputs);
+
+        let mut public_inputs = [0u8; 63];
+        public_inputs[..31].copy_from_slice(&sp1_vkey_hash[1..]);
+        public_inputs[31..].copy_from_slice(&digest);
+
+        load_proof_from_bytes(&proof_bytes[4..]).unwrap();
+        let vk_view = VkView::new(GROTH16_VK_3_0_0_BYTES).unwrap();
+        groth16_verifying_key(&vk_view).unwrap();
+        load_public_inputs_from_bytes(&public_inputs).unwrap();
+        public_inputs
+    });
+    assert_eq!(allocations, 0);
+
+    // The whole verification allocates only what the pairing itself does.
+    let proof = load_proof_from_bytes(&proof_bytes[4..]).unwrap();
+    let vk_view = VkView::new(GROTH16_VK_3_0_0_BYTES).unwrap();
+    let vk = groth16_verifying_key(&vk_view).unwrap();
+    let inputs = load_public_inputs_from_bytes(&public_inputs).unwrap();
+    let (result, pairing_allocations) =
+        count_allocations(|| pairing_check(proof.as_proof_ref(), &inputs.inputs, &vk));
+    result.unwrap();
+    let (result, allocations) = count_allocations(|| {
+        verify_proof_raw(&proof_bytes, &public_inputs, GROTH16_VK_3_0_0_BYTES)
+    });
+    result.unwrap();
+    assert_eq!(allocations, pairing_allocations);
+    let (result, allocations) = count_allocations(|| {
+        verify_proof(
+            &proof_bytes,
+            &sp1_public_inputs,
+            "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83",
+            GROTH16_VK_3_0_0_BYTES,
+        )
+    });
+    result.unwrap();
+    assert_eq!(allocations, pairing_allocations);
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_size_constants() {
+    use crate::{
+        validate_proof_len, validate_public_inputs_len, validate_raw_proof_len, validate_vk_len,
+        verify_proof, Error, SP1ProofFixture, FIXTURE_MIN_LEN, GROTH16_PUBLIC_INPUTS_LEN,
+        GROTH16_VK_3_0_0_BYTES, GROTH16_VK_MIN_LEN, PROOF_LEN, PROOF_WITH_SELECTOR_LEN,
+        SELECTOR_LEN,
+    };
+
+    assert_eq!(SELECTOR_LEN, 4);
+    assert_eq!(PROOF_LEN, 256);
+    assert_eq!(PROOF_WITH_SELECTOR_LEN, 260);
+    assert_eq!(GROTH16_PUBLIC_INPUTS_LEN, 63);
+    assert_eq!(GROTH16_VK_MIN_LEN, 328);
+    assert_eq!(
+        FIXTURE_MIN_LEN,
+        SP1ProofFixture::mock(&[0; 32], &[]).to_bytes().len()
+    );
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+    validate_proof_len(&proof).unwrap();
+    validate_raw_proof_len(&proof[SELECTOR_LEN..]).unwrap();
+    validate_vk_len(GROTH16_VK_3_0_0_BYTES).unwrap();
+    validate_public_inputs_len(&[0; GROTH16_PUBLIC_INPUTS_LEN]).unwrap();
+
+    assert!(matches!(
+        validate_proof_len(&proof[1..]),
+        Err(Error::InvalidProofLength {
+            expected_one_of: &[PROOF_WITH_SELECTOR_LEN],
+            got: PROOF_LEN
+        })
+    ));
+    assert!(matches!(
+        validate_raw_proof_len(&proof),
+        Err(Error::InvalidProofLength {
+            expected_one_of: &[PROOF_LEN],
+            got: PROOF_WITH_SELECTOR_LEN
+        })
+    ));
+    assert_eq!(
+        validate_vk_len(&GROTH16_VK_3_0_0_BYTES[..GROTH16_VK_MIN_LEN - 1]),
+        Err(Error::InvalidInput)
+    );
+    assert_eq!(
+        validate_public_inputs_len(&[0; GROTH16_PUBLIC_INPUTS_LEN + 1]),
+        Err(Error::InvalidPublicInput)
+    );
+
+    // Trailing bytes after a proof are rejected, rather than ignored.
+    let mut padded = proof.clone();
+    padded.push(0);
+    assert_eq!(
+        verify_proof(
+            &padded,
+            sp1_proof_with_public_values.public_values.as_slice(),
+            "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83",
+            GROTH16_VK_3_0_0_BYTES,
+        ),
+        Err(Error::InvalidProofLength {
+            expected_one_of: &[PROOF_LEN, PROOF_WITH_SELECTOR_LEN],
+            got: PROOF_WITH_SELECTOR_LEN + 1
+        })
+    );
+}
+
+#[test]
+fn test_vk_view() {
+    use crate::{VkView, GROTH16_VK_3_0_0_BYTES};
+
+    let vk_view = VkView::new(GROTH16_VK_3_0_0_BYTES).unwrap();
+    let vk = vk_view.to_verification_key().unwrap();
+    assert_eq!(vk.vk_ic.as_slice().len(), 3);
+
+    // Accessing an element again returns the memoized point.
+    assert_eq!(vk_view.alpha_g1().unwrap(), &vk.vk_alpha_g1);
+    assert_eq!(vk_view.ic().unwrap(), vk.vk_ic.as_slice());
+
+    // A truncated verifying key is rejected by the structural pass.
+    assert!(VkView::new(&GROTH16_VK_3_0_0_BYTES[..290]).is_err());
+    assert!(VkView::new(&GROTH16_VK_3_0_0_BYTES[..380]).is_err());
+}
+
+#[test]
+fn test_vk_view_exact() {
+    use crate::{available_vk_versions, Error, InputSection, VkView};
+
+    // Every embedded key is consumed whole, whichever commitment key section it ends with.
+    for &version in available_vk_versions() {
+        let vk = version.groth16_vk();
+        assert!(VkView::new_exact(vk).is_ok());
+        for extra in 1..=3 {
+            let padded = [vk, &vec![0; extra]].concat();
+            assert!(VkView::new(&padded).is_ok());
+            assert!(matches!(
+                VkView::new_exact(&padded),
+                Err(Error::TrailingBytes {
+                    section: InputSection::Vk,
+                    extra: e
+                }) if e == extra
+            ));
+        }
+    }
+}
+
+#[test]
+fn test_hash_public_inputs_vector() {
+    use crate::hash_public_inputs;
+
+    // Pinned so that the sha2 and `sol_sha256` syscall implementations can be checked against the
+    // same value: SHA-256("abc") with its top 3 bits cleared.
+    assert_eq!(
+        hash_public_inputs(b"abc"),
+        hex_literal::hex!("1a7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+}
+
+#[test]
+fn test_hash_public_inputs_domain_vector() {
+    use crate::{hash_public_inputs, hash_public_inputs_domain, CommittedValuesDigest};
+
+    // Pinned for guest-side implementations: SHA-256 of the little-endian u64 length of the domain,
+    // the domain and "abc", with its top 3 bits cleared.
+    let domain = b"sp1-solana:test:v1";
+    assert_eq!(
+        hash_public_inputs_domain(domain, b"abc"),
+        hex_literal::hex!("18f1dbb01d03dbedc172ac4c5fe409b8102710e651d950beb78ac6587553c423")
+    );
+    assert_eq!(
+        hash_public_inputs_domain(b"", b"abc"),
+        hex_literal::hex!("13652e4ce938bb9965f62c3ca4d8f69301a6c85b1e86eac67e291152d3c0e3dd")
+    );
+
+    // It is the plain digest of the public values with the tag committed first, and differs from
+    // the digest without it.
+    let tagged = [&(domain.len() as u64).to_le_bytes()[..], domain, b"abc"].concat();
+    assert_eq!(
+        hash_public_inputs_domain(domain, b"abc"),
+        hash_public_inputs(&tagged)
+    );
+    assert_ne!(
+        hash_public_inputs_domain(domain, b"abc"),
+        hash_public_inputs(b"abc")
+    );
+    assert_eq!(
+        CommittedValuesDigest::from_domain_public_values(domain, b"abc").to_bytes(),
+        hash_public_inputs_domain(domain, b"abc")
+    );
+}
+
+#[test]
+fn test_committed_values_digest() {
+    use crate::{hash_public_inputs, utils::sha256, CommittedValuesDigest, Error};
+    use borsh::BorshDeserialize;
+
+    // Hashing masks the top 3 bits of SHA-256("abc"), which starts with 0xba, and only those.
+    let digest = CommittedValuesDigest::from_public_values(b"abc");
+    assert_eq!(digest.as_bytes()[0], 0x1a);
+    assert_eq!(digest.as_bytes()[1..], sha256(b"abc")[1..]);
+    assert_eq!(digest.to_bytes(), hash_public_inputs(b"abc"));
+
+    // Masked bytes are taken as is, up to the largest masked first byte.
+    assert_eq!(
+        CommittedValuesDigest::from_masked_bytes(digest.to_bytes()),
+        Ok(digest)
+    );
+    let mut largest = [0xff; 32];
+    largest[0] = 0x1f;
+    assert_eq!(
+        CommittedValuesDigest::from_masked_bytes(largest).map(<[u8; 32]>::from),
+        Ok(largest)
+    );
+
+    // Bytes with any of the top 3 bits set are rejected, e.g. an unmasked SHA-256 hash.
+    for bit in 5..8 {
+        let mut bytes = digest.to_bytes();
+        bytes[0] |= 1 << bit;
+        assert_eq!(
+            CommittedValuesDigest::from_masked_bytes(bytes),
+            Err(Error::InvalidPublicInput)
+        );
+        assert_eq!(
+            CommittedValuesDigest::try_from(bytes),
+            Err(Error::InvalidPublicInput)
+        );
+        assert!(CommittedValuesDigest::try_from_slice(&bytes).is_err());
+    }
+    assert_eq!(
+        CommittedValuesDigest::from_masked_bytes(sha256(b"abc")),
+        Err(Error::InvalidPublicInput)
+    );
+
+    // Conversions keep the bytes, and Borsh serializes them alone.
+    assert_eq!(
+        CommittedValuesDigest::try_from(digest.to_bytes()),
+        Ok(digest)
+    );
+    assert_eq!(<[u8; 32]>::from(digest), *digest);
+    assert_eq!(digest.as_ref(), &digest.as_bytes()[..]);
+    let serialized = borsh::to_vec(&digest).unwrap();
+    assert_eq!(serialized, digest.to_bytes());
+    assert_eq!(
+        CommittedValuesDigest::try_from_slice(&serialized).unwrap(),
+        digest
+    );
+}
+
+#[test]
+fn test_incremental_hash() {
+    use crate::{hash_public_inputs, Sha256State};
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use sha2::{Digest, Sha256};
+
+    let data: Vec<u8> = (0..10_000u32).map(|i| (i * 31 % 251) as u8).collect();
+
+    for chunk_size in [1, 7, 55, 56, 63, 64, 65, 1000, 10_000] {
+        let mut state = Sha256State::new();
+        for chunk in data.chunks(chunk_size) {
+            // Round-trip the state through Borsh between chunks, as if stored in an account.
+            let mut account_data = Vec::new();
+            state.serialize(&mut account_data).unwrap();
+            assert_eq!(account_data.len(), Sha256State::LEN);
+            state = Sha256State::try_from_slice(&account_data).unwrap();
+
+            state.absorb(chunk);
+        }
+        assert_eq!(
+            state.clone().finalize(),
+            <[u8; 32]>::from(Sha256::digest(&data))
+        );
+        assert_eq!(
+            state.finalize_masked().to_bytes(),
+            hash_public_inputs(&data)
+        );
+    }
+
+    for len in 0..130 {
+        let mut state = Sha256State::new();
+        state.absorb(&data[..len]);
+        assert_eq!(
+            state.finalize(),
+            <[u8; 32]>::from(Sha256::digest(&data[..len]))
+        );
+    }
+}
+
+#[test]
+fn test_incremental_hash_inconsistent_state() {
+    use crate::Sha256State;
+    use borsh::{io::ErrorKind, BorshDeserialize, BorshSerialize};
+
+    let mut state = Sha256State::new();
+    state.absorb(&[0x5a; 70]);
+    let mut account_data = Vec::new();
+    state.serialize(&mut account_data).unwrap();
+    assert_eq!(Sha256State::try_from_slice(&account_data).unwrap(), state);
+
+    // The buffer length, after the state words and the buffer, then the total length.
+    let buffer_len = 8 * 4 + 64;
+    let total_len = buffer_len + 1;
+    for (offset, value) in [
+        (total_len, 71),
+        (total_len, 6),
+        (total_len, 134),
+        (buffer_len, 7),
+        (buffer_len, 64),
+    ] {
+        let mut tampered = account_data.clone();
+        tampered[offset] = value;
+        assert_eq!(
+            Sha256State::try_from_slice(&tampered).unwrap_err().kind(),
+            ErrorKind::InvalidData,
+            "byte {offset} set to {value}"
+        );
+    }
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_proof_fixture() {
+    use crate::{
+        decode_sp1_vkey_hash, verify_proof_fixture, verify_proof_fixture_for_program, Error,
+        SP1ProofFixture, SP1ProofFixtureRef, GROTH16_VK_2_0_0_BYTES, GROTH16_VK_3_0_0_BYTES,
+    };
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let fixture = SP1ProofFixture::from_components(
+        &sp1_proof_with_public_values.bytes(),
+        sp1_proof_with_public_values.public_values.as_slice(),
+        &sp1_vkey_hash,
+    )
+    .unwrap();
+
+    // The borrowed view reads the serialized fixture as-is, ignoring trailing bytes.
+    let mut bytes = fixture.to_bytes();
+    assert_eq!(SP1ProofFixture::from_bytes(&bytes).unwrap(), fixture);
+    bytes.extend_from_slice(&[0xff; 16]);
+    let fixture_ref = SP1ProofFixtureRef::from_bytes(&bytes).unwrap();
+    assert_eq!(fixture_ref, SP1ProofFixtureRef::from(&fixture));
+    assert_eq!(fixture_ref.serialized_len(), bytes.len() - 16);
+    assert!(matches!(
+        SP1ProofFixtureRef::from_bytes(&bytes[..300]),
+        Err(Error::InvalidInput)
+    ));
+
+    // The bounded parsers reject public values over the limit, and the owned one trailing bytes.
+    let len = fixture.sp1_public_inputs.len();
+    assert_eq!(
+        SP1ProofFixture::from_bytes_with_limit(&bytes[..bytes.len() - 16], len).unwrap(),
+        fixture
+    );
+    assert!(matches!(
+        SP1ProofFixture::from_bytes_with_limit(&bytes, len),
+        Err(Error::BorshDeserializeError(_))
+    ));
+    assert!(matches!(
+        SP1ProofFixture::from_bytes_with_limit(&bytes[..300], len),
+        Err(Error::BorshDeserializeError(_))
+    ));
+    assert!(matches!(
+        SP1ProofFixtureRef::from_bytes_with_limit(&bytes, len - 1),
+        Err(Error::PublicValuesTooLarge { len: l, max }) if l == len && max == len - 1
+    ));
+    assert!(matches!(
+        SP1ProofFixture::from_bytes_with_limit(&bytes, len - 1),
+        Err(Error::PublicValuesTooLarge { len: l, max }) if l == len && max == len - 1
+    ));
+
+    assert!(verify_proof_fixture(&fixture, GROTH16_VK_3_0_0_BYTES).is_ok());
+    assert!(verify_proof_fixture(fixture_ref, GROTH16_VK_3_0_0_BYTES).is_ok());
+    assert!(
+        verify_proof_fixture_for_program(&fixture, &sp1_vkey_hash, GROTH16_VK_3_0_0_BYTES).is_ok()
+    );
+    let mut other_vkey_hash = sp1_vkey_hash;
+    other_vkey_hash[31] ^= 1;
+    assert!(matches!(
+        verify_proof_fixture_for_program(&fixture, &other_vkey_hash, GROTH16_VK_3_0_0_BYTES),
+        Err(Error::InvalidProgramVkeyHash)
+    ));
+    assert!(matches!(
+        verify_proof_fixture(&fixture, GROTH16_VK_2_0_0_BYTES),
+        Err(Error::Groth16VkeyHashMismatch)
+    ));
+
+    let mut tampered = fixture.clone();
+    let mut values = fixture.sp1_public_inputs.to_vec();
+    values[0] ^= 1;
+    tampered.sp1_public_inputs = values.into();
+    assert!(matches!(
+        verify_proof_fixture(&tampered, GROTH16_VK_3_0_0_BYTES),
+        Err(Error::PublicValuesDigestMismatch)
+    ));
+
+    // Without the public values, the digest alone is verified.
+    let mut stripped = fixture.clone();
+    stripped.sp1_public_inputs = Default::default();
+    assert!(verify_proof_fixture(&stripped, GROTH16_VK_3_0_0_BYTES).is_ok());
+}
+
+#[test]
+fn test_mock_fixture() {
+    use crate::{
+        decode_sp1_vkey_hash, hash_public_inputs, verify_proof, verify_proof_fixture, Error,
+        SP1ProofFixture, GROTH16_VK_2_0_0_BYTES, GROTH16_VK_3_0_0_BYTES,
+    };
+
+    let sp1_vkey_hash = "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83";
+    let sp1_public_inputs = hex::decode("140000006d1a0000d30b0000").unwrap();
+    let fixture = SP1ProofFixture::mock(
+        &decode_sp1_vkey_hash(sp1_vkey_hash).unwrap(),
+        &sp1_public_inputs,
+    );
+    assert_eq!(
+        fixture.committed_values_digest,
+        hash_public_inputs(&sp1_public_inputs)
+    );
+    let mut proof = fixture.groth16_vkey_hash.to_vec();
+    proof.extend_from_slice(&fixture.proof);
+
+    let result = verify_proof_fixture(&fixture, GROTH16_VK_3_0_0_BYTES);
+    if cfg!(feature = "mock-verifier") {
+        // The mock fixture passes, but the public values and the Groth16 vkey are still checked.
+        result.unwrap();
+        verify_proof(
+            &proof,
+            &sp1_public_inputs,
+            sp1_vkey_hash,
+            GROTH16_VK_3_0_0_BYTES,
+        )
+        .unwrap();
+        let mut tampered = fixture.clone();
+        let mut values = fixture.sp1_public_inputs.to_vec();
+        values[0] ^= 1;
+        tampered.sp1_public_inputs = values.into();
+        assert_eq!(
+            verify_proof_fixture(&tampered, GROTH16_VK_3_0_0_BYTES),
+            Err(Error::PublicValuesDigestMismatch)
+        );
+        assert_eq!(
+            verify_proof_fixture(&fixture, GROTH16_VK_2_0_0_BYTES),
+            Err(Error::Groth16VkeyHashMismatch)
+        );
+        assert!(verify_proof(
+            &proof[..259],
+            &sp1_public_inputs,
+            sp1_vkey_hash,
+            GROTH16_VK_3_0_0_BYTES
+        )
+        .is_err());
+    } else {
+        // The real verifier rejects it.
+        assert_eq!(result, Err(Error::VerificationError));
+        assert_eq!(
+            verify_proof(
+                &proof,
+                &sp1_public_inputs,
+                sp1_vkey_hash,
+                GROTH16_VK_3_0_0_BYTES
+            ),
+            Err(Error::VerificationError)
+        );
+    }
+}
+
+#[test]
+fn test_proof_bytes() {
+    use crate::{Error, ProofBytes, GROTH16_VK_3_0_0_HASH, PROOF_LEN, PROOF_WITH_SELECTOR_LEN};
+
+    let raw: [u8; PROOF_LEN] = std::array::from_fn(|i| i as u8);
+    let with_selector = [&GROTH16_VK_3_0_0_HASH[..], &raw].concat();
+    let with_selector_array: [u8; PROOF_WITH_SELECTOR_LEN] =
+        with_selector.clone().try_into().unwrap();
+
+    // Borrowed proofs of either length are borrowed as such, whatever type they're passed as.
+    let raw_vec = raw.to_vec();
+    assert_eq!(ProofBytes::from(&raw), ProofBytes::Raw256(&raw));
+    assert_eq!(ProofBytes::from(&raw[..]), ProofBytes::Raw256(&raw));
+    assert_eq!(ProofBytes::from(&raw_vec), ProofBytes::Raw256(&raw));
+    assert_eq!(
+        ProofBytes::from(&with_selector_array),
+        ProofBytes::WithSelector(&with_selector_array)
+    );
+    assert_eq!(
+        ProofBytes::from(&with_selector),
+        ProofBytes::WithSelector(&with_selector_array)
+    );
+    assert_eq!(
+        ProofBytes::from(raw_vec.clone()),
+        ProofBytes::Owned(raw_vec.clone())
+    );
+    assert_eq!(
+        ProofBytes::from(&ProofBytes::Owned(with_selector.clone())),
+        ProofBytes::WithSelector(&with_selector_array)
+    );
+
+    // Every variant normalizes to the raw proof, and its selector if it has one.
+    let cases = [
+        (ProofBytes::Raw256(&raw), None),
+        (ProofBytes::Owned(raw_vec.clone()), None),
+        (
+            ProofBytes::WithSelector(&with_selector_array),
+            Some(&GROTH16_VK_3_0_0_HASH),
+        ),
+        (
+            ProofBytes::Owned(with_selector.clone()),
+            Some(&GROTH16_VK_3_0_0_HASH),
+        ),
+    ];
+    for (proof, selector) in cases {
+        assert_eq!(proof.split(), Ok((selector, &raw)));
+        assert_eq!(proof.raw(), Ok(&raw));
+        assert_eq!(proof.selector(), Ok(selector));
+        let expected_len = if selector.is_some() {
+            PROOF_WITH_SELECTOR_LEN
+        } else {
+            PROOF_LEN
+        };
+        assert_eq!(proof.as_slice().len(), expected_len);
+        let owned = proof.clone().into_owned();
+        assert_eq!(owned, ProofBytes::Owned(proof.as_slice().to_vec()));
+        assert_eq!(owned.split(), proof.split());
+    }
+
+    // Other lengths are copied as they are, and fail when normalized.
+    for len in [0, 4, 255, 257, 259, 261, 512] {
+        let bytes = vec![0; len];
+        let proof = ProofBytes::from(&bytes[..]);
+        assert_eq!(proof, ProofBytes::Owned(bytes.clone()));
+        assert!(matches!(
+            proof.split(),
+            Err(Error::InvalidProofLength {
+                expected_one_of: &[PROOF_LEN, PROOF_WITH_SELECTOR_LEN],
+                got,
+            }) if got == len
+        ));
+        assert!(proof.raw().is_err());
+        assert!(proof.selector().is_err());
+    }
+}
+
+#[test]
+fn test_public_values_len_limit() {
+    use crate::{
+        Error, SP1ProofFixture, Sp1PublicValues, FIXTURE_MIN_LEN, MAX_SP1_PUBLIC_VALUES_LEN,
+    };
+    use borsh::BorshDeserialize;
+
+    // A length prefix of 10 MB followed by a few bytes fails on the length, not the truncation.
+    const FORGED_LEN: usize = 10 << 20;
+    fn too_large<T>(result: Result<T, Error>) -> bool {
+        matches!(
+            result,
+            Err(Error::PublicValuesTooLarge { len, max })
+                if len == FORGED_LEN && max == MAX_SP1_PUBLIC_VALUES_LEN
+        )
+    }
+    let mut values = (FORGED_LEN as u32).to_le_bytes().to_vec();
+    values.extend_from_slice(&[0; 8]);
+    assert!(too_large(
+        Sp1PublicValues::try_from_slice(&values).map_err(Error::from_borsh)
+    ));
+
+    let fixture = SP1ProofFixture::mock(&[1; 32], &[1, 2, 3]);
+    let mut bytes = borsh::to_vec(&fixture).unwrap();
+    bytes.truncate(FIXTURE_MIN_LEN - 4);
+    bytes.extend_from_slice(&values);
+    assert!(too_large(SP1ProofFixture::from_bytes(&bytes)));
+
+    // Values at the limit are accepted, and a larger limit accepts longer ones.
+    let at_limit = borsh::to_vec(&vec![7u8; MAX_SP1_PUBLIC_VALUES_LEN]).unwrap();
+    assert_eq!(
+        Sp1PublicValues::try_from_slice(&at_limit).unwrap().len(),
+        MAX_SP1_PUBLIC_VALUES_LEN
+    );
+    let over_limit = borsh::to_vec(&vec![7u8; MAX_SP1_PUBLIC_VALUES_LEN + 1]).unwrap();
+    assert!(matches!(
+        Sp1PublicValues::try_from_slice(&over_limit).map_err(Error::from_borsh),
+        Err(Error::PublicValuesTooLarge { .. })
+    ));
+    let values = Sp1PublicValues::deserialize_with_limit(
+        &mut &over_limit[..],
+        2 * MAX_SP1_PUBLIC_VALUES_LEN,
+    )
+    .unwrap();
+    assert_eq!(values.len(), MAX_SP1_PUBLIC_VALUES_LEN + 1);
+
+    // Other failures are still deserialization errors.
+    assert!(matches!(
+        Sp1PublicValues::try_from_slice(&[3, 0, 0, 0, 1]).map_err(Error::from_borsh),
+        Err(Error::BorshDeserializeError(_))
+    ));
+}
+
+#[test]
+fn test_fixture_strip_public_values() {
+    use crate::{hash_public_inputs, Error, SP1ProofFixture};
+
+    let sp1_public_inputs = hex::decode("140000006d1a0000d30b0000").unwrap();
+    let fixture = SP1ProofFixture::mock(&[1; 32], &sp1_public_inputs);
+
+    // Stripping keeps the digest, and reattaching the values restores the fixture.
+    let (stripped, values) = fixture.strip_public_values();
+    assert!(stripped.sp1_public_inputs.is_empty());
+    assert_eq!(
+        stripped.committed_values_digest,
+        fixture.committed_values_digest
+    );
+    assert_eq!(values.as_deref(), Some(&sp1_public_inputs[..]));
+    assert_eq!(stripped.strip_public_values(), (stripped.clone(), None));
+    let reattached = stripped
+        .clone()
+        .with_public_values(values.unwrap())
+        .unwrap();
+    assert_eq!(reattached, fixture);
+    assert_eq!(
+        reattached
+            .clone()
+            .with_public_values(sp1_public_inputs.clone()),
+        Ok(fixture.clone())
+    );
+
+    // Tampered values, or those of another proof, don't match the digest.
+    let mut tampered = sp1_public_inputs.clone();
+    tampered[0] ^= 1;
+    assert_eq!(
+        stripped.clone().with_public_values(tampered.clone()),
+        Err(Error::PublicValuesDigestMismatch)
+    );
+    assert_eq!(
+        fixture.clone().with_public_values(tampered),
+        Err(Error::PublicValuesDigestMismatch)
+    );
+    assert_eq!(
+        stripped.clone().with_public_values(vec![]),
+        Err(Error::PublicValuesDigestMismatch)
+    );
+
+    // A fixture that never had values commits to the empty ones, and only takes those.
+    let empty = SP1ProofFixture::mock(&[1; 32], &[]);
+    assert_eq!(empty.committed_values_digest, hash_public_inputs(&[]));
+    assert_eq!(empty.strip_public_values(), (empty.clone(), None));
+    assert_eq!(
+        empty.clone().with_public_values(sp1_public_inputs),
+        Err(Error::PublicValuesDigestMismatch)
+    );
+    assert_eq!(empty.clone().with_public_values(vec![]), Ok(empty));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_error_sources() {
+    use crate::{Error, SP1ProofFixture};
+    use std::error::Error as _;
+
+    // A fixture that can't be read keeps its IO error.
+    let path = "../proofs/missing_fixture.bin";
+    let error = SP1ProofFixture::load(path).unwrap_err();
+    assert_eq!(error, Error::IoError(None));
+    assert_eq!(
+        error.source().unwrap().to_string(),
+        std::fs::read(path).unwrap_err().to_string()
+    );
+
+    // So does one that doesn't decode.
+    let error = SP1ProofFixture::from_bytes(&[1, 2, 3]).unwrap_err();
+    assert!(matches!(error, Error::BorshDeserializeError(Some(_))));
+    assert!(error.source().is_some());
+
+    // Sources don't change the code of an error.
+    assert_eq!(error.code(), Error::BorshDeserializeError(None).code());
+    assert_eq!(Error::G1CompressionError.code(), 0);
+    assert_eq!(Error::IoError(None).code(), 12);
+    assert_eq!(Error::ExecutionNotAggregated.code(), 17);
+    assert_eq!(Error::NoVkAvailable.code(), 18);
+    assert_eq!(
+        Error::InvalidProofLength {
+            expected_one_of: &[],
+            got: 0
+        }
+        .code(),
+        19
+    );
+}
+
+#[test]
+fn test_error_equality() {
+    use crate::{Error, InputSection};
+
+    // Errors with fields are equal only with the same fields.
+    let trailing = |extra| Error::TrailingBytes {
+        section: InputSection::Vk,
+        extra,
+    };
+    assert_eq!(trailing(1), trailing(1));
+    assert_ne!(trailing(1), trailing(2));
+    assert_ne!(
+        trailing(1),
+        Error::TrailingBytes {
+            section: InputSection::Proof,
+            extra: 1
+        }
+    );
+    assert!(trailing(1).same_kind(&trailing(2)));
+    assert_ne!(
+        Error::InvalidProofLength {
+            expected_one_of: &[256, 260],
+            got: 3
+        },
+        Error::InvalidProofLength {
+            expected_one_of: &[260],
+            got: 3
+        }
+    );
+    assert_ne!(
+        Error::NoVkeyHashMatched { candidates: 1 },
+        Error::NoVkeyHashMatched { candidates: 2 }
+    );
+    assert_ne!(
+        Error::PublicValuesTooLarge { len: 2, max: 1 },
+        Error::PublicValuesTooLarge { len: 3, max: 1 }
+    );
+
+    // Sources are ignored.
+    assert_eq!(
+        Error::from_borsh(borsh::io::Error::new(
+            borsh::io::ErrorKind::InvalidData,
+            "truncated"
+        )),
+        Error::BorshDeserializeError(None)
+    );
+
+    // Different variants differ, whatever their fields.
+    assert_ne!(Error::VerificationError, Error::InvalidInput);
+    assert!(!Error::IoError(None).same_kind(&Error::BorshDeserializeError(None)));
+    assert!(!trailing(1).same_kind(&Error::NoVkeyHashMatched { candidates: 1 }));
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_diagnose_proof() {
+    use crate::{
+        decode_sp1_vkey_hash, diagnose_proof, hash_public_inputs, ProofMismatch, SP1ProofFixture,
+        GROTH16_VK_2_0_0_BYTES, GROTH16_VK_2_0_0_HASH, GROTH16_VK_3_0_0_BYTES,
+        GROTH16_VK_3_0_0_HASH,
+    };
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let fixture = SP1ProofFixture::from_components(
+        &sp1_proof_with_public_values.bytes(),
+        sp1_proof_with_public_values.public_values.as_slice(),
+        &sp1_vkey_hash,
+    )
+    .unwrap();
+    assert_eq!(
+        diagnose_proof(&fixture, &sp1_vkey_hash, GROTH16_VK_3_0_0_BYTES),
+        Ok(())
+    );
+
+    // A mismatched verification key fails both the selector and the pairing check.
+    let mismatches = diagnose_proof(&fixture, &sp1_vkey_hash, GROTH16_VK_2_0_0_BYTES).unwrap_err();
+    assert_eq!(
+        mismatches[0],
+        ProofMismatch::Groth16VkeyHash {
+            proof: GROTH16_VK_3_0_0_HASH,
+            vk: GROTH16_VK_2_0_0_HASH,
+        }
+    );
+    assert!(matches!(mismatches[1], ProofMismatch::InvalidProof(_)));
+    assert_eq!(mismatches.len(), 2);
+
+    // A valid proof of another program only fails the vkey hash check.
+    let other_vkey_hash = [1; 32];
+    assert_eq!(
+        diagnose_proof(&fixture, &other_vkey_hash, GROTH16_VK_3_0_0_BYTES),
+        Err(vec![ProofMismatch::Sp1VkeyHash {
+            proof: sp1_vkey_hash,
+            expected: other_vkey_hash,
+        }])
+    );
+
+    let mut tampered = fixture.clone();
+    let mut values = fixture.sp1_public_inputs.to_vec();
+    values[0] ^= 1;
+    tampered.sp1_public_inputs = values.into();
+    assert_eq!(
+        diagnose_proof(&tampered, &sp1_vkey_hash, GROTH16_VK_3_0_0_BYTES),
+        Err(vec![ProofMismatch::CommittedValuesDigest {
+            proof: fixture.committed_values_digest,
+            recomputed: hash_public_inputs(&tampered.sp1_public_inputs),
+        }])
+    );
+    assert!(mismatches[0].to_string().contains("0x09069090"));
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_verify_and_decode() {
+    use crate::{decode_sp1_vkey_hash, verify_and_decode, Error, GROTH16_VK_3_0_0_BYTES};
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+    let sp1_public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+
+    let (n, a, b) = verify_and_decode::<(u32, u32, u32)>(
+        &proof,
+        &sp1_public_inputs,
+        &sp1_vkey_hash,
+        GROTH16_VK_3_0_0_BYTES,
+    )
+    .unwrap();
+    assert_eq!((n, a, b), (20, 6765, 3027));
+
+    // Trailing bytes are rejected, as are values too short for the type.
+    assert_eq!(
+        verify_and_decode::<(u32, u32)>(
+            &proof,
+            &sp1_public_inputs,
+            &sp1_vkey_hash,
+            GROTH16_VK_3_0_0_BYTES
+        ),
+        Err(Error::BorshDeserializeError(None))
+    );
+    assert_eq!(
+        verify_and_decode::<([u8; 32], u64)>(
+            &proof,
+            &sp1_public_inputs,
+            &sp1_vkey_hash,
+            GROTH16_VK_3_0_0_BYTES
+        ),
+        Err(Error::BorshDeserializeError(None))
+    );
+
+    // Values that decode but weren't proven fail verification.
+    let mut tampered = sp1_public_inputs.clone();
+    tampered[0] ^= 1;
+    assert_eq!(
+        verify_and_decode::<(u32, u32, u32)>(
+            &proof,
+            &tampered,
+            &sp1_vkey_hash,
+            GROTH16_VK_3_0_0_BYTES
+        ),
+        Err(Error::VerificationError)
+    );
+}
+
+#[test]
+fn test_fixture_lints() {
+    use crate::{hash_public_inputs, FixtureLint, ProofPoint, SP1ProofFixture};
+
+    let mut sp1_vkey_hash = [7; 32];
+    sp1_vkey_hash[0] = 0;
+    let valid = SP1ProofFixture::mock(&sp1_vkey_hash, b"public values");
+    assert_eq!(valid.is_well_formed(), Ok(()));
+    // Without public values, only the digest itself is checked.
+    let mut digest_only = valid.clone();
+    digest_only.sp1_public_inputs = Default::default();
+    assert_eq!(digest_only.is_well_formed(), Ok(()));
+
+    let long_values = vec![0; SP1ProofFixture::MAX_PUBLIC_VALUES_LEN + 1];
+    #[allow(clippy::type_complexity)]
+    let corpus: &[(&str, fn(&mut SP1ProofFixture), &[FixtureLint])] = &[
+        (
+            "unknown selector",
+            |fixture| fixture.groth16_vkey_hash = [1, 2, 3, 4],
+            &[FixtureLint::UnknownSelector {
+                selector: [1, 2, 3, 4],
+            }],
+        ),
+        (
+            "compressed pi_a",
+            |fixture| fixture.proof[0] = 0b1000_0000,
+            &[FixtureLint::PointFlags {
+                point: ProofPoint::A,
+                flags: 0b10,
+            }],
+        ),
+        (
+            "pi_b at infinity",
+            |fixture| fixture.proof[64] = 0b0100_0000,
+            &[FixtureLint::PointFlags {
+                point: ProofPoint::B,
+                flags: 0b01,
+            }],
+        ),
+        (
+            "compressed pi_c",
+            |fixture| fixture.proof[192] = 0b1100_0001,
+            &[FixtureLint::PointFlags {
+                point: ProofPoint::C,
+                flags: 0b11,
+            }],
+        ),
+        (
+            "flags outside the x coordinates",
+            |fixture| {
+                fixture.proof[32] = 0xff;
+                fixture.proof[255] = 0xff;
+            },
+            &[],
+        ),
+        (
+            "program vkey hash without its zero byte",
+            |fixture| fixture.sp1_vkey_hash[0] = 1,
+            &[FixtureLint::NonCanonicalSp1VkeyHash],
+        ),
+        (
+            "unmasked digest, without public values",
+            |fixture| {
+                fixture.sp1_public_inputs = Default::default();
+                fixture.committed_values_digest[0] |= 0b0010_0000;
+            },
+            &[FixtureLint::NonCanonicalCommittedValuesDigest],
+        ),
+        (
+            "tampered public values",
+            |fixture| fixture.sp1_public_inputs = b"other values".to_vec().into(),
+            &[FixtureLint::CommittedValuesDigestMismatch {
+                stored: hash_public_inputs(b"public values"),
+                recomputed: hash_public_inputs(b"other values"),
+            }],
+        ),
+    ];
+    for (name, tamper, lints) in corpus {
+        let mut fixture = valid.clone();
+        tamper(&mut fixture);
+        let expected = if lints.is_empty() {
+            Ok(())
+        } else {
+            Err(lints.to_vec())
+        };
+        assert_eq!(fixture.is_well_formed(), expected, "{name}");
+    }
+
+    // Oversized public values are reported without being hashed, against a configurable limit.
+    let mut oversized = valid.clone();
+    oversized.sp1_public_inputs = long_values.into();
+    assert_eq!(
+        oversized.is_well_formed(),
+        Err(vec![FixtureLint::PublicValuesTooLong {
+            len: SP1ProofFixture::MAX_PUBLIC_VALUES_LEN + 1,
+            max: SP1ProofFixture::MAX_PUBLIC_VALUES_LEN,
+        }])
+    );
+    assert_eq!(
+        valid.is_well_formed_with_limit(4),
+        Err(vec![FixtureLint::PublicValuesTooLong { len: 13, max: 4 }])
+    );
+
+    // Every problem is reported at once.
+    let mut garbage = valid.clone();
+    garbage.groth16_vkey_hash = [0; 4];
+    garbage.proof = [0xff; 256];
+    garbage.sp1_vkey_hash = [0xff; 32];
+    garbage.committed_values_digest = [0xff; 32];
+    let lints = garbage.is_well_formed().unwrap_err();
+    assert_eq!(
+        lints,
+        [
+            FixtureLint::UnknownSelector { selector: [0; 4] },
+            FixtureLint::PointFlags {
+                point: ProofPoint::A,
+                flags: 0b11
+            },
+            FixtureLint::PointFlags {
+                point: ProofPoint::B,
+                flags: 0b11
+            },
+            FixtureLint::PointFlags {
+                point: ProofPoint::C,
+                flags: 0b11
+            },
+            FixtureLint::NonCanonicalSp1VkeyHash,
+            FixtureLint::NonCanonicalCommittedValuesDigest,
+            FixtureLint::CommittedValuesDigestMismatch {
+                stored: [0xff; 32],
+                recomputed: hash_public_inputs(b"public values"),
+            },
+        ]
+    );
+    assert_eq!(
+        lints[1].to_string(),
+        "pi_a has the gnark flags 0b11, which an uncompressed point doesn't have"
+    );
+}
+
+#[test]
+fn test_public_values_schema() {
+    use crate::{
+        schema_hash, sp1_public_values_schema,
+        utils::sha256,
+        Error, InputSection, Sp1PublicValuesDecode,
+        TrailingBytesPolicy::{Lenient, Strict},
+    };
+
+    sp1_public_values_schema! {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct FibonacciV1 {
+            n: u32,
+            a: u32,
+            b: u32,
+        }
+    }
+    sp1_public_values_schema! {
+        /// The values of a later version of the program, which appended two fields.
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct FibonacciV2 {
+            n: u32,
+            a: u32,
+            b: u32,
+            /// The number of steps run.
+            #[sp1(default)]
+            steps: u64,
+            #[sp1(default)]
+            tag: [u8; 32],
+        }
+    }
+
+    // The schema hashes cover the names and types of the fields, whitespace aside, and the
+    // versions of a schema are its prefixes.
+    assert_eq!(
+        FibonacciV1::SCHEMA_HASH,
+        sha256(b"sp1-solana:public-values-schema:v1\nn:u32\na:u32\nb:u32\n")
+    );
+    assert_eq!(
+        FibonacciV2::SCHEMA_HASH,
+        sha256(
+            b"sp1-solana:public-values-schema:v1\nn:u32\na:u32\nb:u32\nsteps:u64\ntag:[u8;32]\n"
+        )
+    );
+    assert_eq!(FibonacciV2::SCHEMA_HASHES.len(), 5);
+    assert_eq!(FibonacciV2::SCHEMA_HASHES[2], FibonacciV1::SCHEMA_HASH);
+    assert_eq!(
+        (FibonacciV1::REQUIRED_FIELDS, FibonacciV2::REQUIRED_FIELDS),
+        (3, 3)
+    );
+    assert_eq!(
+        schema_hash("n : u32\n\ta:u32 \r\n", 2),
+        schema_hash("n:u32\na:u32\n", 2)
+    );
+    assert_ne!(schema_hash("n:u32\n", 1), schema_hash("n:u64\n", 1));
+    assert_ne!(schema_hash("n:u32\n", 1), schema_hash("m:u32\n", 1));
+
+    let v1_values = [20u32, 6765, 3027].map(u32::to_le_bytes).concat();
+    let v2_values = [&v1_values[..], &30u64.to_le_bytes(), &[7; 32]].concat();
+    let v1 = FibonacciV1 {
+        n: 20,
+        a: 6765,
+        b: 3027,
+    };
+    let v2 = FibonacciV2 {
+        n: 20,
+        a: 6765,
+        b: 3027,
+        steps: 30,
+        tag: [7; 32],
+    };
+
+    // Old payloads decode with a newer struct, whose appended fields take their default value.
+    assert_eq!(FibonacciV1::decode(&v1_values, Strict), Ok(v1));
+    assert_eq!(
+        FibonacciV2::decode(&v1_values, Strict),
+        Ok(FibonacciV2 {
+            steps: 0,
+            tag: [0; 32],
+            ..v2
+        })
+    );
+    assert_eq!(FibonacciV2::decode(&v2_values, Strict), Ok(v2));
+    // Newer payloads decode with an older struct only if their trailing bytes may be ignored.
+    assert_eq!(
+        FibonacciV1::decode(&v2_values, Strict),
+        Err(Error::TrailingBytes {
+            section: InputSection::PublicValues,
+            extra: 40
+        })
+    );
+    assert_eq!(FibonacciV1::decode(&v2_values, Lenient), Ok(v1));
+    // Required fields, and appended fields that were started, must be complete.
+    for len in [8, 16] {
+        assert_eq!(
+            FibonacciV2::decode(&v2_values[..len], Lenient),
+            Err(Error::BorshDeserializeError(None)),
+            "{len}"
+        );
+    }
+
+    // With a schema hash, each version decodes exactly its own fields.
+    let with_hash = |hash: [u8; 32], values: &[u8]| [&hash[..], values].concat();
+    let v1_payload = with_hash(FibonacciV1::SCHEMA_HASH, &v1_values);
+    let v2_payload = with_hash(FibonacciV2::SCHEMA_HASH, &v2_values);
+    assert_eq!(
+        FibonacciV1::decode_with_schema_hash(&v1_payload, Strict),
+        Ok(v1)
+    );
+    assert_eq!(
+        FibonacciV2::decode_with_schema_hash(&v1_payload, Strict),
+        Ok(FibonacciV2 {
+            steps: 0,
+            tag: [0; 32],
+            ..v2
+        })
+    );
+    assert_eq!(
+        FibonacciV2::decode_with_schema_hash(&v2_payload, Strict),
+        Ok(v2)
+    );
+    let v2_steps_only = with_hash(FibonacciV2::SCHEMA_HASHES[3], &v2_values[..20]);
+    assert_eq!(
+        FibonacciV2::decode_with_schema_hash(&v2_steps_only, Strict),
+        Ok(FibonacciV2 { tag: [0; 32], ..v2 })
+    );
+    // The hash, rather than the length of the values, says which fields they have.
+    assert_eq!(
+        FibonacciV2::decode_with_schema_hash(
+            &with_hash(FibonacciV2::SCHEMA_HASH, &v1_values),
+            Strict
+        ),
+        Err(Error::BorshDeserializeError(None))
+    );
+    assert_eq!(
+        FibonacciV2::decode_with_schema_hash(
+            &with_hash(FibonacciV1::SCHEMA_HASH, &v2_values),
+            Strict
+        ),
+        Err(Error::TrailingBytes {
+            section: InputSection::PublicValues,
+            extra: 40
+        })
+    );
+
+    // Payloads of another schema, of a version without every required field, or without a hash,
+    // are rejected.
+    for payload in [
+        with_hash(FibonacciV2::SCHEMA_HASH, &v2_values),
+        with_hash([0; 32], &v1_values),
+        v1_values.clone(),
+    ] {
+        assert_eq!(
+            FibonacciV1::decode_with_schema_hash(&payload, Lenient),
+            Err(Error::SchemaHashMismatch)
+        );
+    }
+    assert_eq!(
+        FibonacciV2::decode_with_schema_hash(
+            &with_hash(FibonacciV2::SCHEMA_HASHES[1], &v1_values[..8]),
+            Lenient
+        ),
+        Err(Error::SchemaHashMismatch)
+    );
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_sp1_public_values() {
+    use crate::{
+        decode_sp1_vkey_hash, hash_public_inputs, Error, SP1ProofFixture, Sp1PublicValues,
+    };
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let bytes = sp1_proof_with_public_values.public_values.to_vec();
+    let values = Sp1PublicValues::from(bytes.clone());
+    assert_eq!(values.as_slice(), &bytes[..]);
+
+    // The values read back in the order the program committed them.
+    let mut reader = values.reader();
+    let n: u32 = reader.read().unwrap();
+    let a: u32 = reader.read().unwrap();
+    let b: u32 = reader.read().unwrap();
+    assert_eq!((n, a, b), (20, 6765, 3027));
+    assert!(reader.remaining().is_empty());
+    assert!(matches!(
+        reader.read::<u32>(),
+        Err(Error::BorshDeserializeError(_))
+    ));
+
+    // The digest is computed once, and is that of the raw bytes.
+    assert_eq!(values.digest().to_bytes(), hash_public_inputs(&bytes));
+    assert_eq!(values.digest().to_bytes(), hash_public_inputs(&bytes));
+    assert_eq!(values.clone().digest(), values.digest());
+
+    // They serialize as the plain bytes, so fixtures serialized before keep deserializing.
+    assert_eq!(
+        borsh::to_vec(&values).unwrap(),
+        borsh::to_vec(&bytes).unwrap()
+    );
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let fixture = SP1ProofFixture::from_components(
+        &sp1_proof_with_public_values.bytes(),
+        &bytes,
+        &sp1_vkey_hash,
+    )
+    .unwrap();
+    assert_eq!(fixture.sp1_public_inputs, values);
+    assert_eq!(fixture.committed_values_digest, values.digest().to_bytes());
+    assert_eq!(
+        SP1ProofFixture::from_bytes(&fixture.to_bytes()).unwrap(),
+        fixture
+    );
+}
+
+#[test]
+fn test_nullifier() {
+    use crate::{hash_public_inputs, Nullifier, NULLIFIER_DOMAIN};
+    use sha2::{Digest, Sha256};
+
+    let sp1_vkey_hash = [1u8; 32];
+    let digest_a = hash_public_inputs(b"a");
+    let digest_b = hash_public_inputs(b"b");
+
+    let nullifier = Nullifier::new(&sp1_vkey_hash, &digest_a, b"salt");
+    let expected: [u8; 32] = Sha256::new()
+        .chain_update(NULLIFIER_DOMAIN)
+        .chain_update(sp1_vkey_hash)
+        .chain_update(digest_a)
+        .chain_update(b"salt")
+        .finalize()
+        .into();
+    assert_eq!(nullifier.as_bytes(), &expected);
+    assert_eq!(
+        nullifier,
+        Nullifier::new(&sp1_vkey_hash, &digest_a, b"salt")
+    );
+
+    // Distinct proofs, or distinct salts for the same proof, give distinct nullifiers.
+    assert_ne!(
+        nullifier,
+        Nullifier::new(&sp1_vkey_hash, &digest_b, b"salt")
+    );
+    assert_ne!(
+        nullifier,
+        Nullifier::new(&sp1_vkey_hash, &digest_a, b"pepper")
+    );
+    assert_ne!(nullifier, Nullifier::new(&[2u8; 32], &digest_a, b"salt"));
+}
+
+#[test]
+fn test_convert_endianness() {
+    use crate::convert_endianness;
+
+    let bytes: [u8; 64] = core::array::from_fn(|i| i as u8);
+
+    // A single 32 byte chunk is fully reversed.
+    let input: [u8; 32] = bytes[..32].try_into().unwrap();
+    let expected: [u8; 32] = core::array::from_fn(|i| 31 - i as u8);
+    assert_eq!(convert_endianness::<32, 32>(&input), expected);
+
+    // Two 32 byte chunks are each reversed in place.
+    let expected: [u8; 64] = core::array::from_fn(|i| if i < 32 { 31 - i } else { 95 - i } as u8);
+    assert_eq!(convert_endianness::<32, 64>(&bytes), expected);
+
+    // A single 64 byte chunk is fully reversed.
+    let expected: [u8; 64] = core::array::from_fn(|i| 63 - i as u8);
+    assert_eq!(convert_endianness::<64, 64>(&bytes), expected);
+
+    // The conversion can run at compile time.
+    const CONVERTED: [u8; 4] = convert_endianness::<2, 4>(&[1, 2, 3, 4]);
+    assert_eq!(CONVERTED, [2, 1, 4, 3]);
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_negate_g1_matches_arkworks() {
+    use crate::utils::{load_raw_proof_from_bytes, negate_g1_be};
+    use crate::{VkView, GROTH16_VK_3_0_0_BYTES};
+    use ark_bn254::{Fq, Fr, G1Affine, G1Projective};
+    use ark_ec::{CurveGroup, Group};
+    use ark_ff::{BigInteger, PrimeField};
+
+    fn to_be_bytes(point: &G1Affine) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&point.x.into_bigint().to_bytes_be());
+        bytes[32..].copy_from_slice(&point.y.into_bigint().to_bytes_be());
+        bytes
+    }
+
+    fn ark_negate(bytes: &[u8; 64]) -> [u8; 64] {
+        let x = Fq::from_be_bytes_mod_order(&bytes[..32]);
+        let y = Fq::from_be_bytes_mod_order(&bytes[32..]);
+        to_be_bytes(&G1Affine::new_unchecked(x, -y))
+    }
+
+    // Pseudo-random multiples of the generator.
+    for k in 1..64u64 {
+        let scalar = Fr::from(k.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        let point = (G1Projective::generator() * scalar).into_affine();
+        assert_eq!(negate_g1_be(&to_be_bytes(&point)), to_be_bytes(&-point));
+    }
+
+    // Every G1 point in the verifying key and the fibonacci proof.
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let proof = load_raw_proof_from_bytes(&sp1_proof_with_public_values.bytes()[4..]).unwrap();
+    let vk = VkView::new(GROTH16_VK_3_0_0_BYTES)
+        .unwrap()
+        .to_verification_key()
+        .unwrap();
+    for point in [vk.vk_alpha_g1, proof.pi_a, proof.pi_c]
+        .iter()
+        .chain(vk.vk_ic.as_slice())
+    {
+        assert_eq!(negate_g1_be(point), ark_negate(point));
+    }
+
+    // Coordinates that need reducing, and a zero y coordinate.
+    let mut bytes = [0xffu8; 64];
+    assert_eq!(negate_g1_be(&bytes), ark_negate(&bytes));
+    bytes[32..].fill(0);
+    assert_eq!(negate_g1_be(&bytes), ark_negate(&bytes));
+}
+
+proptest::proptest! {
+    #[test]
+    fn test_gnark_g1_conversion_round_trip(scalar in proptest::prelude::any::<[u8; 32]>()) {
+        use crate::utils::{decompress_g1, gnark_compressed_x_to_ark_compressed_x};
+        use ark_bn254::{Fr, G1Projective};
+        use ark_ec::{CurveGroup, Group};
+        use ark_ff::{BigInteger, PrimeField};
+        use ark_serialize::CanonicalSerialize;
+
+        let point =
+            (G1Projective::generator() * Fr::from_le_bytes_mod_order(&scalar)).into_affine();
+        let mut ark = Vec::new();
+        point.serialize_compressed(&mut ark).unwrap();
+        let gnark = ark_compressed_to_gnark_compressed::<32>(&ark);
+        let converted = gnark_compressed_x_to_ark_compressed_x(&gnark).unwrap();
+        proptest::prop_assert_eq!(converted.as_slice(), ark.as_slice());
+
+        // gnark flags y as the larger of y and -y by comparing them as integers.
+        proptest::prop_assume!(!point.infinity);
+        let largest = point.y.into_bigint() > (-point.y).into_bigint();
+        proptest::prop_assert_eq!(gnark[0] >> 6 == 0b11, largest);
+        let decompressed = decompress_g1(&gnark).unwrap();
+        let x = point.x.into_bigint().to_bytes_be();
+        let y = point.y.into_bigint().to_bytes_be();
+        proptest::prop_assert_eq!(&decompressed[..32], x.as_slice());
+        proptest::prop_assert_eq!(&decompressed[32..], y.as_slice());
+    }
+
+    #[test]
+    fn test_gnark_g2_conversion_round_trip(scalar in proptest::prelude::any::<[u8; 32]>()) {
+        use crate::utils::{decompress_g2, gnark_compressed_x_to_ark_compressed_x};
+        use ark_bn254::{Fr, G2Projective};
+        use ark_ec::{CurveGroup, Group};
+        use ark_ff::{BigInteger, PrimeField};
+        use ark_serialize::CanonicalSerialize;
+
+        let point =
+            (G2Projective::generator() * Fr::from_le_bytes_mod_order(&scalar)).into_affine();
+        let mut ark = Vec::new();
+        point.serialize_compressed(&mut ark).unwrap();
+        let gnark = ark_compressed_to_gnark_compressed::<64>(&ark);
+        let converted = gnark_compressed_x_to_ark_compressed_x(&gnark).unwrap();
+        proptest::prop_assert_eq!(converted.as_slice(), ark.as_slice());
+
+        // For G2, gnark compares the c1 components first, then the c0 ones.
+        proptest::prop_assume!(!point.infinity);
+        let neg_y = -point.y;
+        let largest = (point.y.c1.into_bigint(), point.y.c0.into_bigint())
+            > (neg_y.c1.into_bigint(), neg_y.c0.into_bigint());
+        proptest::prop_assert_eq!(gnark[0] >> 6 == 0b11, largest);
+        let decompressed = decompress_g2(&gnark).unwrap();
+        let expected = [point.x.c1, point.x.c0, point.y.c1, point.y.c0]
+            .iter()
+            .flat_map(|c| c.into_bigint().to_bytes_be())
+            .collect::<Vec<_>>();
+        proptest::prop_assert_eq!(decompressed.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn test_gnark_conversion_arbitrary_bytes(
+        bytes in proptest::collection::vec(proptest::prelude::any::<u8>(), 64),
+    ) {
+        use crate::utils::{
+            decompress_g1, decompress_g2, gnark_compressed_x_to_ark_compressed_x,
+            gnark_flag_to_ark_flag,
+        };
+
+        let g1: [u8; 32] = bytes[..32].try_into().unwrap();
+        let g2: [u8; 64] = bytes.as_slice().try_into().unwrap();
+        let flag = bytes[0] >> 6;
+        // Whether the bytes are an encoding of the point at infinity, the flag alone.
+        let is_infinity = |point: &[u8]| {
+            point[0] == 0b01 << 6 && point[1..].iter().all(|&byte| byte == 0)
+        };
+
+        // Only uncompressed points, flagged 0b00, are rejected by the flag conversion.
+        proptest::prop_assert_eq!(gnark_flag_to_ark_flag(bytes[0]).is_err(), flag == 0b00);
+        match gnark_compressed_x_to_ark_compressed_x(&g1) {
+            Ok(ark) => {
+                proptest::prop_assert!(flag == 0b10 || flag == 0b11 || is_infinity(&g1));
+                proptest::prop_assert_eq!(ark_compressed_to_gnark_compressed::<32>(&ark), g1);
+            }
+            Err(_) => proptest::prop_assert!(flag == 0b00 || (flag == 0b01 && !is_infinity(&g1))),
+        }
+        match gnark_compressed_x_to_ark_compressed_x(&g2) {
+            Ok(ark) => {
+                proptest::prop_assert!(flag == 0b10 || flag == 0b11 || is_infinity(&g2));
+                proptest::prop_assert_eq!(ark_compressed_to_gnark_compressed::<64>(&ark), g2);
+            }
+            Err(_) => proptest::prop_assert!(flag == 0b00 || (flag == 0b01 && !is_infinity(&g2))),
+        }
+
+        // Decompression never panics, whatever the bytes.
+        let _ = decompress_g1(&g1);
+        let _ = decompress_g2(&g2);
+    }
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_single_byte_corruptions_fail_cleanly() {
+    use crate::{
+        decode_sp1_vkey_hash, groth16_public_values, verify_proof_raw, Error,
+        GROTH16_VK_3_0_0_BYTES,
+    };
+    use std::{
+        ops::Range,
+        panic::{catch_unwind, AssertUnwindSafe},
+    };
+
+    #[derive(Debug)]
+    enum Outcome {
+        Verified,
+        Rejected(Error),
+        Panicked,
+    }
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes()[4..].to_vec();
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let public_inputs = groth16_public_values(
+        &sp1_vkey_hash,
+        sp1_proof_with_public_values.public_values.as_slice(),
+    );
+    let vk = GROTH16_VK_3_0_0_BYTES.to_vec();
+
+    // Flips one bit of each byte of `artifact` in turn, cycling through the bit positions, and
+    // returns the positions whose outcome isn't a clean rejection.
+    let mutate = |artifact: usize| {
+        let mut inputs = [proof.clone(), public_inputs.clone(), vk.clone()];
+        let mut failures = Vec::new();
+        for position in 0..inputs[artifact].len() {
+            inputs[artifact][position] ^= 1 << (position % 8);
+            let [proof, public_inputs, vk] = &inputs;
+            let outcome = match catch_unwind(AssertUnwindSafe(|| {
+                verify_proof_raw(proof, public_inputs, vk)
+            })) {
+                Ok(Ok(())) => Outcome::Verified,
+                Ok(Err(error)) => Outcome::Rejected(error),
+                Err(_) => Outcome::Panicked,
+            };
+            if !matches!(outcome, Outcome::Rejected(_)) {
+                failures.push((position, outcome));
+            }
+            inputs[artifact][position] ^= 1 << (position % 8);
+        }
+        failures
+    };
+    // Every failure must be a verification within the allowlisted ranges.
+    let check = |name: &str, failures: Vec<(usize, Outcome)>, allowed: &[Range<usize>]| {
+        let unexpected = failures
+            .iter()
+            .filter(|(position, outcome)| {
+                !matches!(outcome, Outcome::Verified)
+                    || !allowed.iter().any(|range| range.contains(position))
+            })
+            .collect::<Vec<_>>();
+        assert!(unexpected.is_empty(), "{name}: {unexpected:?}");
+    };
+
+    verify_proof_raw(&proof, &public_inputs, &vk).unwrap();
+    check("proof", mutate(0), &[]);
+    check("public inputs", mutate(1), &[]);
+    // The G1 beta and delta points aren't used by the verifier, and neither is anything after the
+    // (empty) list of committed wires.
+    check("vk", mutate(2), &[32..64, 192..224, 392..vk.len()]);
+}
+
+#[test]
+fn test_gnark_infinity_encoding() {
+    use crate::utils::{decompress_g1, decompress_g2};
+    use crate::Error;
+
+    // The point at infinity is its flag alone.
+    let mut g1 = [0u8; 32];
+    g1[0] = 0b01 << 6;
+    assert!(decompress_g1(&g1).is_ok());
+    let mut g2 = [0u8; 64];
+    g2[0] = 0b01 << 6;
+    assert!(decompress_g2(&g2).is_ok());
+
+    // Any other bit set is rejected, though arkworks would ignore it.
+    for bit in (0..256).filter(|&bit| bit % 8 > 1 || bit >= 8) {
+        let mut g1 = g1;
+        g1[bit / 8] |= 0x80 >> (bit % 8);
+        assert_eq!(decompress_g1(&g1), Err(Error::InvalidInput));
+    }
+    let mut g2 = g2;
+    g2[63] = 1;
+    assert_eq!(decompress_g2(&g2), Err(Error::InvalidInput));
+}
+
+#[cfg(all(feature = "tracing", feature = "sp1-fixtures"))]
+#[test]
+fn test_tracing_spans() {
+    use crate::{verify_proof, GROTH16_VK_3_0_0_BYTES};
+    use std::sync::{Arc, Mutex};
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Event, Subscriber,
+    };
+    use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+
+    /// Every field recorded, as `(span, field, value)`. Fields of events are prefixed with `event.`.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<(String, String, String)>>>);
+
+    struct Fields<'a>(&'a str, &'a str, &'a Capture);
+
+    impl Visit for Fields<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.2 .0.lock().unwrap().push((
+                self.0.to_string(),
+                format!("{}{}", self.1, field.name()),
+                format!("{value:?}"),
+            ));
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Capture {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+            attrs.record(&mut Fields(attrs.metadata().name(), "", self));
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+            let span = ctx.span(id).unwrap();
+            values.record(&mut Fields(span.name(), "", self));
+        }
+
+        fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+            let span = ctx.event_span(event).map(|span| span.name()).unwrap_or("");
+            event.record(&mut Fields(span, "event.", self));
+        }
+    }
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let proof = sp1_proof_with_public_values.bytes();
+    let sp1_public_inputs = sp1_proof_with_public_values.public_values.to_vec();
+    let sp1_vkey_hash = "0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83";
+
+    let capture = |sp1_public_inputs: &[u8]| {
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+        let result = tracing::subscriber::with_default(subscriber, || {
+            verify_proof(
+                &proof,
+                sp1_public_inputs,
+                sp1_vkey_hash,
+                GROTH16_VK_3_0_0_BYTES,
+            )
+        });
+        let fields = capture.0.lock().unwrap().clone();
+        (result, fields)
+    };
+    let value = |fields: &[(String, String, String)], span: &str, field: &str| {
+        fields
+            .iter()
+            .find(|(s, f, _)| s == span && f == field)
+            .map(|(_, _, value)| value.clone())
+    };
+
+    let (result, fields) = capture(&sp1_public_inputs);
+    result.unwrap();
+    let span = "sp1_solana::verify_proof";
+    assert_eq!(value(&fields, span, "selector").unwrap(), "09069090");
+    assert_eq!(
+        value(&fields, span, "sp1_vkey_hash").unwrap(),
+        format!("{sp1_vkey_hash:?}")
+    );
+    assert_eq!(value(&fields, span, "public_values_len").unwrap(), "12");
+    assert!(value(
+        &fields,
+        "sp1_solana::verify_proof_with_digest",
+        "committed_values_digest"
+    )
+    .is_some());
+    assert_eq!(
+        value(&fields, "sp1_solana::vk_view", "len").unwrap(),
+        GROTH16_VK_3_0_0_BYTES.len().to_string()
+    );
+    for field in ["parse_us", "verify_us"] {
+        let duration = value(&fields, "sp1_solana::verify_groth16", field).unwrap();
+        duration.parse::<u64>().unwrap();
+    }
+    assert!(fields.iter().all(|(_, field, _)| field != "event.error"));
+
+    // A failing verification emits its error in each span it fails.
+    let mut tampered = sp1_public_inputs.clone();
+    tampered[0] ^= 1;
+    let (result, fields) = capture(&tampered);
+    assert!(result.is_err());
+    for span in [
+        "sp1_solana::verify_proof",
+        "sp1_solana::verify_proof_with_digest",
+        "sp1_solana::verify_groth16",
+    ] {
+        assert_eq!(
+            value(&fields, span, "event.error").unwrap(),
+            "Verification error"
+        );
+    }
+    assert!(value(&fields, "sp1_solana::verify_groth16", "verify_us").is_some());
+}
+
+#[cfg(feature = "circom")]
+#[test]
+fn test_verify_circom_proof() {
+    use crate::{
+        circom::{
+            circom_proof_to_bytes, proof_from_snarkjs, public_inputs_from_snarkjs,
+            verification_key_from_snarkjs, verify_circom_proof,
+        },
+        verify_groth16_prenegated, Error,
+    };
+
+    let vk =
+        verification_key_from_snarkjs(include_str!("../testdata/circom/verification_key.json"))
+            .unwrap();
+    let proof_json = include_str!("../testdata/circom/proof.json");
+    let public_json = include_str!("../testdata/circom/public.json");
+    verify_circom_proof(proof_json, public_json, &vk).unwrap();
+
+    // The byte-level path taken on-chain agrees.
+    let proof = circom_proof_to_bytes(proof_json).unwrap();
+    let public_inputs = public_inputs_from_snarkjs(public_json).unwrap();
+    assert_eq!(public_inputs.len(), 1);
+    verify_groth16_prenegated(&proof, &[public_inputs[0]], &vk).unwrap();
+
+    // 3 * 11 isn't 34.
+    assert!(verify_circom_proof(proof_json, r#"["34"]"#, &vk).is_err());
+    assert_eq!(
+        verify_circom_proof(proof_json, r#"["33", "1"]"#, &vk),
+        Err(Error::InvalidPublicInput)
+    );
+
+    // pi_a as snarkjs writes it must be negated first.
+    let unnegated = proof_from_snarkjs(proof_json).unwrap().to_bytes();
+    assert!(verify_groth16_prenegated(&unnegated, &[public_inputs[0]], &vk).is_err());
+}
+
+#[cfg(feature = "sp1-fixtures")]
+#[test]
+fn test_backends_agree() {
+    use crate::{
+        decode_sp1_vkey_hash, groth16_public_values, negate_proof_a,
+        test_circuit::compiled_backends, verify_proof_raw, verify_proof_raw_with,
+        GROTH16_VK_3_0_0_BYTES,
+    };
+    use ark_bn254::{Fq2, G2Affine};
+    use ark_ff::{BigInteger, PrimeField};
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let proof: [u8; 256] = sp1_proof_with_public_values.bytes()[4..]
+        .try_into()
+        .unwrap();
+    let sp1_vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let public_inputs = groth16_public_values(
+        &sp1_vkey_hash,
+        &sp1_proof_with_public_values.public_values.to_vec(),
+    );
+
+    let add_modulus = |bytes: &[u8], modulus: &str| -> Vec<u8> {
+        let value = BigUint::from_bytes_be(bytes) + BigUint::from_str_radix(modulus, 10).unwrap();
+        let bytes = value.to_bytes_be();
+        assert!(bytes.len() <= 32);
+        [vec![0; 32 - bytes.len()], bytes].concat()
+    };
+    let p = "21888242871839275222246405745257275088696311157297823662689037894645226208583";
+    let r = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+    // (name, proof, public inputs)
+    let mut corpus = vec![("valid".to_string(), proof.to_vec(), public_inputs.clone())];
+    for i in (0..256).step_by(5) {
+        let mut mutated = proof;
+        mutated[i] ^= 1 << (i % 8);
+        corpus.push((
+            format!("bit flip at {i}"),
+            mutated.to_vec(),
+            public_inputs.clone(),
+        ));
+    }
+    for i in (0..63).step_by(9) {
+        let mut mutated = public_inputs.clone();
+        mutated[i] ^= 1;
+        corpus.push((format!("public input flip at {i}"), proof.to_vec(), mutated));
+    }
+    for (name, offset) in [("pi_a.x + p", 0), ("pi_a.y + p", 32), ("pi_c.x + p", 192)] {
+        let mut mutated = proof;
+        mutated[offset..offset + 32].copy_from_slice(&add_modulus(&proof[offset..offset + 32], p));
+        corpus.push((name.to_string(), mutated.to_vec(), public_inputs.clone()));
+    }
+    let mut digest_plus_r = public_inputs.clone();
+    digest_plus_r[31..].copy_from_slice(&add_modulus(&public_inputs[31..], r));
+    corpus.push(("digest + r".to_string(), proof.to_vec(), digest_plus_r));
+    corpus.push((
+        "pre-negated pi_a".to_string(),
+        negate_proof_a(&proof).unwrap().to_vec(),
+        public_inputs.clone(),
+    ));
+    let mut swapped = proof;
+    swapped[..64].copy_from_slice(&proof[192..]);
+    swapped[192..].copy_from_slice(&proof[..64]);
+    corpus.push((
+        "pi_a and pi_c swapped".to_string(),
+        swapped.to_vec(),
+        public_inputs.clone(),
+    ));
+    let mut identity_a = proof;
+    identity_a[..64].fill(0);
+    corpus.push((
+        "pi_a at infinity".to_string(),
+        identity_a.to_vec(),
+        public_inputs.clone(),
+    ));
+
+    // A point on the G2 curve but outside the prime-order subgroup.
+    let off_subgroup = (1u64..)
+        .filter_map(|x| G2Affine::get_point_from_x_unchecked(Fq2::from(x), true))
+        .find(|point| !point.is_in_correct_subgroup_assuming_on_curve())
+        .unwrap();
+    let mut mutated = proof;
+    for (offset, coordinate) in [
+        (64, off_subgroup.x.c1),
+        (96, off_subgroup.x.c0),
+        (128, off_subgroup.y.c1),
+        (160, off_subgroup.y.c0),
+    ] {
+        mutated[offset..offset + 32].copy_from_slice(&coordinate.into_bigint().to_bytes_be());
+    }
+    corpus.push((
+        "pi_b outside the subgroup".to_string(),
+        mutated.to_vec(),
+        public_inputs,
+    ));
+
+    let backends = compiled_backends();
+    for (name, proof, public_inputs) in &corpus {
+        let groth16_solana = verify_proof_raw(proof, public_inputs, GROTH16_VK_3_0_0_BYTES);
+        assert_eq!(groth16_solana.is_ok(), name == "valid", "{name}");
+        for (backend_name, backend) in &backends {
+            let result =
+                verify_proof_raw_with(*backend, proof, public_inputs, GROTH16_VK_3_0_0_BYTES);
+            assert_eq!(
+                groth16_solana.is_ok(),
+                result.is_ok(),
+                "{name}: groth16-solana returned {groth16_solana:?}, {backend_name} {result:?}"
+            );
+        }
+        #[cfg(feature = "ark")]
+        {
+            let ark = crate::verify_proof_raw_ark(proof, public_inputs, GROTH16_VK_3_0_0_BYTES);
+            assert_eq!(
+                groth16_solana.is_ok(),
+                ark.is_ok(),
+                "{name}: groth16-solana returned {groth16_solana:?}, ark-groth16 {ark:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_aggregated_public_values() {
+    use crate::{
+        aggregation::{assert_contains_execution, parse_aggregated_public_values},
+        utils::sha256,
+        Error,
+    };
+
+    // Two fibonacci executions, encoded as bincode encodes the committed
+    // `Vec<([u32; 8], [u8; 32])>`.
+    let vkey: [u32; 8] = [
+        0x0f3d_a0f7,
+        0x1c5e_6b21,
+        0x2a8d_0c46,
+        0x3b1f_5e92,
+        0x4c0a_7d13,
+        0x5d94_e8b5,
+        0x6e27_f13c,
+        0x7f60_294a,
+    ];
+    let vkey_hash: Vec<u8> = vkey.iter().flat_map(|word| word.to_be_bytes()).collect();
+    let digests = [
+        sha256(&hex::decode("140000006d1a0000d30b0000").unwrap()),
+        sha256(&hex::decode("0a0000003700000059000000").unwrap()),
+    ];
+    let mut values = 2u64.to_le_bytes().to_vec();
+    for digest in &digests {
+        values.extend(vkey.iter().flat_map(|word| word.to_le_bytes()));
+        values.extend_from_slice(digest);
+    }
+
+    let executions = parse_aggregated_public_values(&values).unwrap();
+    assert_eq!(executions.len(), 2);
+    for (execution, digest) in executions.iter().zip(&digests) {
+        assert_eq!(execution.vkey_hash.as_slice(), vkey_hash);
+        assert_eq!(execution.committed_values_digest, *digest);
+    }
+
+    let vkey_hash: [u8; 32] = vkey_hash.try_into().unwrap();
+    assert_contains_execution(&values, &vkey_hash, &digests[1]).unwrap();
+    assert_eq!(
+        assert_contains_execution(&values, &vkey_hash, &[0; 32]),
+        Err(Error::ExecutionNotAggregated)
+    );
+    let mut other_vkey = vkey_hash;
+    other_vkey[0] ^= 1;
+    assert_eq!(
+        assert_contains_execution(&values, &other_vkey, &digests[0]),
+        Err(Error::ExecutionNotAggregated)
+    );
+
+    // The length prefix must match the executions exactly.
+    assert_eq!(
+        parse_aggregated_public_values(&values[..values.len() - 1]),
+        Err(Error::DeserializationError(None))
+    );
+    let mut overlong = values.clone();
+    overlong[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(
+        parse_aggregated_public_values(&overlong),
+        Err(Error::DeserializationError(None))
+    );
+    assert_eq!(
+        parse_aggregated_public_values(&0u64.to_le_bytes()),
+        Ok(vec![])
+    );
+    assert_eq!(
+        parse_aggregated_public_values(&[0; 7]),
+        Err(Error::DeserializationError(None))
+    );
+}
+
+#[cfg(feature = "solana")]
+#[test]
+fn test_program_vkey_allowlist() {
+    use crate::{allowlist::ProgramVkeyAllowlist, Error};
+    use solana_program::pubkey::Pubkey;
+
+    let vkey_hash = |seed: u8| {
+        let mut hash = [seed; 32];
+        hash[0] = 0;
+        hash
+    };
+    let (old, new) = (vkey_hash(1), vkey_hash(2));
+    let mut allowlist = ProgramVkeyAllowlist {
+        authority: Pubkey::new_unique(),
+        hashes: Vec::new(),
+    };
+    assert_eq!(allowlist.add(old), Ok(true));
+    assert_eq!(allowlist.add(new), Ok(true));
+    assert_eq!(allowlist.add(old), Ok(false));
+    assert_eq!(allowlist.add([1; 32]), Err(Error::InvalidProgramVkeyHash));
+    assert_eq!(allowlist.hashes, [old, new]);
+
+    assert!(allowlist.remove(&old));
+    assert!(!allowlist.remove(&old));
+    assert!(!allowlist.contains(&old));
+    assert!(allowlist.contains(&new));
+
+    // The account has room for the hashes, and reads back ignoring its unused space.
+    let mut data = vec![0; ProgramVkeyAllowlist::space(2)];
+    allowlist.to_account_data(&mut data).unwrap();
+    assert_eq!(
+        ProgramVkeyAllowlist::from_account_data(&data).unwrap(),
+        allowlist
+    );
+    allowlist.add(old).unwrap();
+    assert!(allowlist.to_account_data(&mut data).is_ok());
+    allowlist.add(vkey_hash(3)).unwrap();
+    assert!(matches!(
+        allowlist.to_account_data(&mut data),
+        Err(Error::BorshSerializeError(_))
+    ));
+}
+
+#[cfg(feature = "solana")]
+#[test]
+fn test_interface_error_codes() {
+    use crate::{cpi::VerifierError, Error, InputSection};
+    use solana_program::program_error::ProgramError;
+
+    // The interface crate mirrors the codes of `Error`, which the verifier program returns.
+    let errors = [
+        (Error::G1CompressionError, VerifierError::G1CompressionError),
+        (Error::G2CompressionError, VerifierError::G2CompressionError),
+        (Error::VerificationError, VerifierError::VerificationError),
+        (Error::InvalidPublicInput, VerifierError::InvalidPublicInput),
+        (Error::SerializationError, VerifierError::SerializationError),
+        (
+            Error::DeserializationError(None),
+            VerifierError::DeserializationError,
+        ),
+        (
+            Error::InvalidInstructionData,
+            VerifierError::InvalidInstructionData,
+        ),
+        (Error::ArithmeticError, VerifierError::ArithmeticError),
+        (Error::PairingError, VerifierError::PairingError),
+        (Error::InvalidInput, VerifierError::InvalidInput),
+        (
+            Error::BorshSerializeError(None),
+            VerifierError::BorshSerializeError,
+        ),
+        (
+            Error::BorshDeserializeError(None),
+            VerifierError::BorshDeserializeError,
+        ),
+        (Error::IoError(None), VerifierError::IoError),
+        (
+            Error::Groth16VkeyHashMismatch,
+            VerifierError::Groth16VkeyHashMismatch,
+        ),
+        (
+            Error::InvalidProgramVkeyHash,
+            VerifierError::InvalidProgramVkeyHash,
+        ),
+        (
+            Error::PublicValuesDigestMismatch,
+            VerifierError::PublicValuesDigestMismatch,
+        ),
+        (Error::InputTooLarge, VerifierError::InputTooLarge),
+        (
+            Error::ExecutionNotAggregated,
+            VerifierError::ExecutionNotAggregated,
+        ),
+        (Error::NoVkAvailable, VerifierError::NoVkAvailable),
+        (
+            Error::InvalidProofLength {
+                expected_one_of: &[],
+                got: 0,
+            },
+            VerifierError::InvalidProofLength,
+        ),
+        (
+            Error::TrailingBytes {
+                section: InputSection::Proof,
+                extra: 0,
+            },
+            VerifierError::TrailingBytes,
+        ),
+        (
+            Error::NoVkeyHashMatched { candidates: 0 },
+            VerifierError::NoVkeyHashMatched,
+        ),
+        (
+            Error::UnsupportedSp1Version {
+                detected: "",
+                supported: &[],
+            },
+            VerifierError::UnsupportedSp1Version,
+        ),
+        (Error::SchemaHashMismatch, VerifierError::SchemaHashMismatch),
+        (
+            Error::PublicValuesTooLarge { len: 0, max: 0 },
+            VerifierError::PublicValuesTooLarge,
+        ),
+    ];
+    assert_eq!(errors.len(), VerifierError::ALL.len());
+    for (error, interface_error) in errors {
+        assert_eq!(
+            ProgramError::from(error),
+            ProgramError::Custom(interface_error as u32)
+        );
+    }
+}
+
+/// The JSON of every error, against the snapshot in `testdata/errors.jsonl`. Codes and names are
+/// append-only: a new variant adds a line, and existing lines never change.
+#[cfg(all(
+    feature = "serde",
+    feature = "embedded-vk-v3-0-0",
+    feature = "embedded-vk-v3-0-0-rc4",
+    feature = "embedded-vk-v2-0-0"
+))]
+#[test]
+fn test_error_json() {
+    use crate::{utils::source, Error, InputSection, SUPPORTED_SP1_VERSIONS};
+
+    let errors = [
+        Error::G1CompressionError,
+        Error::G2CompressionError,
+        Error::VerificationError,
+        Error::InvalidPublicInput,
+        Error::SerializationError,
+        Error::DeserializationError(None),
+        Error::InvalidInstructionData,
+        Error::ArithmeticError,
+        Error::PairingError,
+        Error::InvalidInput,
+        Error::BorshSerializeError(None),
+        Error::BorshDeserializeError(None),
+        Error::IoError(source(std::io::Error::other("disk full"))),
+        Error::Groth16VkeyHashMismatch,
+        Error::InvalidProgramVkeyHash,
+        Error::PublicValuesDigestMismatch,
+        Error::InputTooLarge,
+        Error::ExecutionNotAggregated,
+        Error::NoVkAvailable,
+        Error::InvalidProofLength {
+            expected_one_of: &[256, 260],
+            got: 12,
+        },
+        Error::TrailingBytes {
+            section: InputSection::PublicInputs,
+            extra: 1,
+        },
+        Error::NoVkeyHashMatched { candidates: 2 },
+        Error::UnsupportedSp1Version {
+            detected: "v2.0.0",
+            supported: SUPPORTED_SP1_VERSIONS,
+        },
+        Error::SchemaHashMismatch,
+        Error::PublicValuesTooLarge {
+            len: 40_000,
+            max: 32 * 1024,
+        },
+    ];
+    let snapshot = include_str!("../testdata/errors.jsonl");
+    assert_eq!(snapshot.lines().count(), errors.len());
+
+    for (code, (error, expected)) in errors.iter().zip(snapshot.lines()).enumerate() {
+        assert_eq!(error.code() as usize, code);
+        let json = serde_json::to_string(error).unwrap();
+        assert_eq!(json, expected);
+
+        // Errors round-trip, details and source messages included.
+        let deserialized: Error = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, *error);
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+    }
+
+    // Unknown codes, names that aren't those of their code and unexpected details are rejected.
+    for json in [
+        r#"{"code":25,"name":"SchemaHashMismatch","detail":null}"#,
+        r#"{"code":24,"name":"PublicValuesTooLarge","detail":null}"#,
+        r#"{"code":13,"name":"VerificationError","detail":null}"#,
+        r#"{"code":0,"name":"G1CompressionError","detail":{"candidates":1}}"#,
+        r#"{"code":21,"name":"NoVkeyHashMatched","detail":null}"#,
+        r#"{"code":20,"name":"TrailingBytes","detail":{"section":"stack","extra":1}}"#,
+    ] {
+        assert!(serde_json::from_str::<Error>(json).is_err(), "{json}");
+    }
+    // Details this build doesn't produce are dropped rather than rejected.
+    let error: Error = serde_json::from_str(
+        r#"{"code":22,"name":"UnsupportedSp1Version","detail":{"detected":"v9.0.0","supported":[]}}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        error,
+        Error::UnsupportedSp1Version {
+            detected: "unknown",
+            supported: []
+        }
+    ));
+}
@@ -0,0 +1,1778 @@
+//! A tiny Groth16 circuit, proven with `ark-groth16`, whose verifying key and proof are serialized
+//! in the gnark format SP1 uses.
+//!
+//! The setup and the proofs are deterministic, so these tests exercise the whole verifier without
+//! the SP1 artifacts of `../proofs`.
+
+use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::{Groth16, ProvingKey, VerifyingKey};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+
+use crate::backend::Groth16Backend;
+
+/// Converts an arkworks compressed point back to gnark's encoding: big-endian, with gnark's flags.
+pub(crate) fn ark_compressed_to_gnark_compressed<const N: usize>(ark: &[u8]) -> [u8; N] {
+    let mut gnark: [u8; N] = ark.try_into().unwrap();
+    gnark.reverse();
+    let flag = match gnark[0] >> 6 {
+        0b00 => 0b10,
+        0b10 => 0b11,
+        0b01 => 0b01,
+        flag => panic!("arkworks wrote the flag {flag:#b}"),
+    };
+    gnark[0] = gnark[0] & 0b0011_1111 | flag << 6;
+    gnark
+}
+
+/// A circuit with one witness per public input, constrained to be equal to it.
+///
+/// Each public input appears in a constraint, so that its IC point is not zero and the proof is
+/// bound to its value.
+#[derive(Clone)]
+struct EchoCircuit {
+    inputs: Vec<Fr>,
+}
+
+impl ConstraintSynthesizer<Fr> for EchoCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        for input in self.inputs {
+            let public = cs.new_input_variable(|| Ok(input))?;
+            let witness = cs.new_witness_variable(|| Ok(input))?;
+            cs.enforce_constraint(lc!() + witness, lc!() + Variable::One, lc!() + public)?;
+        }
+        Ok(())
+    }
+}
+
+/// A proof of [`EchoCircuit`], in the formats taken by the verifier.
+pub(crate) struct TestProof {
+    /// The gnark-compressed verifying key.
+    pub(crate) vk: Vec<u8>,
+    /// The raw 256-byte proof, with pi_a not negated.
+    pub(crate) proof: [u8; 256],
+}
+
+/// Runs a deterministic setup of [`EchoCircuit`] for `inputs.len()` public inputs, and proves it
+/// for `inputs`, which must be big-endian scalars below the BN254 scalar field modulus.
+pub(crate) fn prove(inputs: &[[u8; 32]]) -> TestProof {
+    let circuit = EchoCircuit {
+        inputs: inputs
+            .iter()
+            .map(|input| {
+                let scalar = Fr::from_be_bytes_mod_order(input);
+                assert_eq!(to_be_bytes(scalar), *input, "input above the modulus");
+                scalar
+            })
+            .collect(),
+    };
+    let rng = &mut ark_std::test_rng();
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), rng).unwrap();
+    let proof = Groth16::<Bn254>::prove(&pk, circuit, rng).unwrap();
+
+    let mut proof_bytes = [0u8; 256];
+    proof_bytes[..64].copy_from_slice(&g1_uncompressed(&proof.a));
+    proof_bytes[64..192].copy_from_slice(&g2_uncompressed(&proof.b));
+    proof_bytes[192..].copy_from_slice(&g1_uncompressed(&proof.c));
+    TestProof {
+        vk: gnark_vk(&pk, &vk),
+        proof: proof_bytes,
+    }
+}
+
+/// Serializes `vk` in gnark's layout. Its G1 beta and delta, which gnark keeps but the verifier
+/// ignores, come from `pk`.
+fn gnark_vk(pk: &ProvingKey<Bn254>, vk: &VerifyingKey<Bn254>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend(g1_compressed(&vk.alpha_g1));
+    bytes.extend(g1_compressed(&pk.beta_g1));
+    bytes.extend(g2_compressed(&vk.beta_g2));
+    bytes.extend(g2_compressed(&vk.gamma_g2));
+    bytes.extend(g1_compressed(&pk.delta_g1));
+    bytes.extend(g2_compressed(&vk.delta_g2));
+    bytes.extend((vk.gamma_abc_g1.len() as u32).to_be_bytes());
+    for ic in &vk.gamma_abc_g1 {
+        bytes.extend(g1_compressed(ic));
+    }
+    // No committed wires, and no Pedersen commitment keys.
+    bytes.extend(0u32.to_be_bytes());
+    bytes.extend(0u32.to_be_bytes());
+    bytes
+}
+
+fn g1_compressed(point: &G1Affine) -> [u8; 32] {
+    let mut ark = Vec::new();
+    point.serialize_compressed(&mut ark).unwrap();
+    ark_compressed_to_gnark_compressed(&ark)
+}
+
+fn g2_compressed(point: &G2Affine) -> [u8; 64] {
+    let mut ark = Vec::new();
+    point.serialize_compressed(&mut ark).unwrap();
+    ark_compressed_to_gnark_compressed(&ark)
+}
+
+fn to_be_bytes(field: impl PrimeField) -> [u8; 32] {
+    field.into_bigint().to_bytes_be().try_into().unwrap()
+}
+
+pub(crate) fn g1_uncompressed(point: &G1Affine) -> [u8; 64] {
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(&to_be_bytes(point.x));
+    bytes[32..].copy_from_slice(&to_be_bytes(point.y));
+    bytes
+}
+
+pub(crate) fn g2_uncompressed(point: &G2Affine) -> [u8; 128] {
+    let mut bytes = [0u8; 128];
+    bytes[..32].copy_from_slice(&to_be_bytes(point.x.c1));
+    bytes[32..64].copy_from_slice(&to_be_bytes(point.x.c0));
+    bytes[64..96].copy_from_slice(&to_be_bytes(point.y.c1));
+    bytes[96..].copy_from_slice(&to_be_bytes(point.y.c0));
+    bytes
+}
+
+const SP1_PUBLIC_VALUES: &[u8] = b"public values";
+
+/// An SP1-shaped program vkey hash and committed values digest.
+fn sp1_inputs() -> ([u8; 32], crate::CommittedValuesDigest) {
+    let mut sp1_vkey_hash = crate::utils::sha256(b"test circuit");
+    sp1_vkey_hash[0] = 0;
+    (
+        sp1_vkey_hash,
+        crate::CommittedValuesDigest::from_public_values(SP1_PUBLIC_VALUES),
+    )
+}
+
+#[test]
+fn test_circuit_verify_proof_raw() {
+    use crate::{
+        groth16_public_inputs, negate_proof_a, verify_proof_raw, verify_proof_raw_prenegated, Error,
+    };
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let public_inputs = groth16_public_inputs(&sp1_vkey_hash, &committed_values_digest);
+
+    assert!(verify_proof_raw(&proof, &public_inputs, &vk).is_ok());
+    let prenegated = negate_proof_a(&proof).unwrap();
+    assert!(verify_proof_raw_prenegated(&prenegated, &public_inputs, &vk).is_ok());
+    assert!(verify_proof_raw(&prenegated, &public_inputs, &vk).is_err());
+
+    // Each public input is bound by the proof.
+    for index in [30, 62] {
+        let mut tampered = public_inputs;
+        tampered[index] ^= 1;
+        assert_eq!(
+            verify_proof_raw(&proof, &tampered, &vk),
+            Err(Error::VerificationError)
+        );
+    }
+    let other = prove(&[*committed_values_digest, sp1_vkey_hash]);
+    assert!(verify_proof_raw(&other.proof, &public_inputs, &vk).is_err());
+}
+
+#[test]
+fn test_circuit_verify_sp1_proof() {
+    use crate::{
+        utils::sha256, verify_proof, verify_proof_fixture, verify_proof_with_digest, Error,
+        SP1ProofFixture,
+    };
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let groth16_vk_hash: [u8; 4] = sha256(&vk)[..4].try_into().unwrap();
+    let sp1_proof = [&groth16_vk_hash[..], &proof].concat();
+
+    assert!(verify_proof(
+        &sp1_proof,
+        SP1_PUBLIC_VALUES,
+        &format!("0x{}", hex::encode(sp1_vkey_hash)),
+        &vk
+    )
+    .is_ok());
+    assert!(verify_proof_with_digest(
+        &sp1_proof,
+        &committed_values_digest,
+        &sp1_vkey_hash,
+        &vk,
+        &groth16_vk_hash
+    )
+    .is_ok());
+    assert_eq!(
+        verify_proof(
+            &sp1_proof,
+            b"other values",
+            &format!("0x{}", hex::encode(sp1_vkey_hash)),
+            &vk
+        ),
+        Err(Error::VerificationError)
+    );
+    assert_eq!(
+        verify_proof(
+            &sp1_proof,
+            SP1_PUBLIC_VALUES,
+            &format!("0x{}", hex::encode(sp1_vkey_hash)),
+            crate::GROTH16_VK_3_0_0_BYTES
+        ),
+        Err(Error::Groth16VkeyHashMismatch)
+    );
+
+    let fixture =
+        SP1ProofFixture::from_components(&sp1_proof, SP1_PUBLIC_VALUES, &sp1_vkey_hash).unwrap();
+    assert!(verify_proof_fixture(&fixture, &vk).is_ok());
+}
+
+#[test]
+fn test_circuit_compressed_vk() {
+    use crate::VkView;
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, .. } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let vk_view = VkView::new(&vk).unwrap();
+    assert_eq!(vk_view.nr_pubinputs(), 2);
+
+    // The decompressed points match arkworks' own setup.
+    let rng = &mut ark_std::test_rng();
+    let circuit = EchoCircuit {
+        inputs: vec![Fr::from(0u8); 2],
+    };
+    let (_, ark_vk) = Groth16::<Bn254>::circuit_specific_setup(circuit, rng).unwrap();
+    let decompressed = vk_view.to_verification_key().unwrap();
+    assert_eq!(decompressed.vk_alpha_g1, g1_uncompressed(&ark_vk.alpha_g1));
+    assert_eq!(decompressed.vk_beta_g2, g2_uncompressed(&ark_vk.beta_g2));
+    assert_eq!(decompressed.vk_gamma_g2, g2_uncompressed(&ark_vk.gamma_g2));
+    assert_eq!(decompressed.vk_delta_g2, g2_uncompressed(&ark_vk.delta_g2));
+    let ic: Vec<_> = ark_vk.gamma_abc_g1.iter().map(g1_uncompressed).collect();
+    assert_eq!(decompressed.vk_ic.as_slice(), ic);
+}
+
+#[test]
+fn test_circuit_generic_n() {
+    use crate::{negate_proof_a, verify_groth16_prenegated, Error, VkView};
+
+    let TestProof { vk, proof } = prove(&[scalar(1)]);
+    let vk = VkView::new(&vk).unwrap().to_verification_key().unwrap();
+    let proof = negate_proof_a(&proof).unwrap();
+    assert!(verify_groth16_prenegated(&proof, &[scalar(1)], &vk).is_ok());
+    assert_eq!(
+        verify_groth16_prenegated(&proof, &[scalar(2)], &vk),
+        Err(Error::VerificationError)
+    );
+
+    let TestProof { vk, proof } = prove(&[scalar(1), scalar(2)]);
+    let vk = VkView::new(&vk).unwrap().to_verification_key().unwrap();
+    let proof = negate_proof_a(&proof).unwrap();
+    assert!(verify_groth16_prenegated(&proof, &[scalar(1), scalar(2)], &vk).is_ok());
+    assert!(verify_groth16_prenegated(&proof, &[scalar(2), scalar(1)], &vk).is_err());
+    // `N` must match the number of public inputs of the key.
+    assert!(verify_groth16_prenegated(&proof, &[scalar(1)], &vk).is_err());
+
+    // Keys with more IC points than SP1's are accepted too.
+    let inputs = [scalar(1), scalar(2), scalar(3), scalar(4), scalar(5)];
+    let TestProof { vk, proof } = prove(&inputs);
+    let vk_view = VkView::new(&vk).unwrap();
+    assert_eq!(vk_view.ic().unwrap().len(), inputs.len() + 1);
+    let vk = vk_view.to_verification_key().unwrap();
+    let proof = negate_proof_a(&proof).unwrap();
+    assert!(verify_groth16_prenegated(&proof, &inputs, &vk).is_ok());
+    let mut wrong_inputs = inputs;
+    wrong_inputs[4] = scalar(6);
+    assert_eq!(
+        verify_groth16_prenegated(&proof, &wrong_inputs, &vk),
+        Err(Error::VerificationError)
+    );
+    assert!(verify_groth16_prenegated(&proof, &inputs[..3].try_into().unwrap(), &vk).is_err());
+}
+
+#[test]
+fn test_circuit_error_variants() {
+    use crate::{
+        groth16_public_inputs, negate_proof_a, utils::sha256, verify_groth16_prenegated,
+        verify_proof, verify_proof_fixture, verify_proof_raw, verify_proof_with_digest, Error,
+        SP1ProofFixture, VkView, GROTH16_VK_MIN_LEN, PROOF_LEN, PROOF_WITH_SELECTOR_LEN,
+    };
+    use num_bigint::BigUint;
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let selector: [u8; 4] = sha256(&vk)[..4].try_into().unwrap();
+    let sp1_proof = [&selector[..], &proof].concat();
+    let vkey_hash = format!("0x{}", hex::encode(sp1_vkey_hash));
+    let public_inputs = groth16_public_inputs(&sp1_vkey_hash, &committed_values_digest);
+    let verify = |proof: &[u8], public_values: &[u8], vkey_hash: &str| {
+        verify_proof(proof, public_values, vkey_hash, &vk)
+    };
+    // A fixture carrying only its digest, with the top bits of the digest set.
+    let mut unmasked_fixture =
+        SP1ProofFixture::from_components(&sp1_proof, SP1_PUBLIC_VALUES, &sp1_vkey_hash).unwrap();
+    unmasked_fixture.sp1_public_inputs = Vec::new().into();
+    unmasked_fixture.committed_values_digest[0] |= 0xe0;
+    // Overwrites the raw proof with `bytes` at `offset`, keeping the selector.
+    let with_bytes = |offset: usize, bytes: &[u8]| {
+        let mut tampered = sp1_proof.clone();
+        tampered[4 + offset..4 + offset + bytes.len()].copy_from_slice(bytes);
+        tampered
+    };
+    // Flips the lowest bit of the last coordinate of the point ending at `end`, moving it off the
+    // curve.
+    let off_curve = |end: usize| {
+        let mut tampered = sp1_proof.clone();
+        tampered[4 + end - 1] ^= 1;
+        tampered
+    };
+    // Valid points of another proof.
+    let other = prove(&[*committed_values_digest, sp1_vkey_hash]).proof;
+    let mut other_vkey_hash = sha256(b"other program");
+    other_vkey_hash[0] = 0;
+
+    // A gnark-compressed x coordinate with no point on the curve.
+    let p = BigUint::from_bytes_be(&crate::utils::BN254_BASE_FIELD_MODULUS);
+    let no_point_x = (1u32..)
+        .map(BigUint::from)
+        .find(|x| (x * x * x + 3u32).modpow(&((&p - 1u32) / 2u32), &p) != BigUint::from(1u32))
+        .unwrap();
+    let mut invalid_vk = vk.clone();
+    invalid_vk[..32].fill(0);
+    let x = no_point_x.to_bytes_be();
+    invalid_vk[32 - x.len()..32].copy_from_slice(&x);
+    invalid_vk[0] |= 0b10 << 6;
+
+    let one_input = prove(&[scalar(1)]);
+    let vk_key = VkView::new(&vk).unwrap().to_verification_key().unwrap();
+    let prenegated = negate_proof_a(&proof).unwrap();
+
+    let cases = [
+        (
+            "wrong Groth16 selector",
+            verify(
+                &[&[0u8; 4][..], &proof].concat(),
+                SP1_PUBLIC_VALUES,
+                &vkey_hash,
+            ),
+            Error::Groth16VkeyHashMismatch,
+        ),
+        (
+            "wrong program vkey hash",
+            verify(
+                &sp1_proof,
+                SP1_PUBLIC_VALUES,
+                &format!("0x{}", hex::encode(other_vkey_hash)),
+            ),
+            Error::VerificationError,
+        ),
+        (
+            "program vkey hash without 0x",
+            verify(&sp1_proof, SP1_PUBLIC_VALUES, &vkey_hash[2..]),
+            Error::InvalidProgramVkeyHash,
+        ),
+        (
+            "truncated program vkey hash",
+            verify(&sp1_proof, SP1_PUBLIC_VALUES, "0"),
+            Error::InvalidProgramVkeyHash,
+        ),
+        (
+            "program vkey hash with a nonzero first byte",
+            verify(
+                &sp1_proof,
+                SP1_PUBLIC_VALUES,
+                &vkey_hash.replacen("00", "01", 1),
+            ),
+            Error::InvalidProgramVkeyHash,
+        ),
+        (
+            "tampered committed values",
+            verify(&sp1_proof, b"public valueS", &vkey_hash),
+            Error::VerificationError,
+        ),
+        (
+            "tampered pi_a",
+            verify(&with_bytes(0, &other[..64]), SP1_PUBLIC_VALUES, &vkey_hash),
+            Error::VerificationError,
+        ),
+        (
+            "tampered pi_b",
+            verify(
+                &with_bytes(64, &other[64..192]),
+                SP1_PUBLIC_VALUES,
+                &vkey_hash,
+            ),
+            Error::VerificationError,
+        ),
+        (
+            "tampered pi_c",
+            verify(
+                &with_bytes(192, &other[192..]),
+                SP1_PUBLIC_VALUES,
+                &vkey_hash,
+            ),
+            Error::VerificationError,
+        ),
+        (
+            "pre-negated pi_a",
+            verify_proof_raw(&prenegated, &public_inputs, &vk),
+            Error::VerificationError,
+        ),
+        (
+            "pi_a off the curve",
+            verify(&off_curve(64), SP1_PUBLIC_VALUES, &vkey_hash),
+            Error::InvalidInput,
+        ),
+        (
+            "pi_b off the curve",
+            verify(&off_curve(192), SP1_PUBLIC_VALUES, &vkey_hash),
+            Error::InvalidInput,
+        ),
+        (
+            "pi_c off the curve",
+            verify(&off_curve(256), SP1_PUBLIC_VALUES, &vkey_hash),
+            Error::InvalidInput,
+        ),
+        (
+            "non-canonical pi_a coordinate",
+            verify(
+                &with_bytes(0, &crate::utils::BN254_BASE_FIELD_MODULUS),
+                SP1_PUBLIC_VALUES,
+                &vkey_hash,
+            ),
+            Error::InvalidInput,
+        ),
+        (
+            "non-canonical pi_c coordinate",
+            verify(
+                &with_bytes(192, &crate::utils::BN254_BASE_FIELD_MODULUS),
+                SP1_PUBLIC_VALUES,
+                &vkey_hash,
+            ),
+            Error::InvalidInput,
+        ),
+        (
+            "truncated proof",
+            verify(&sp1_proof[..259], SP1_PUBLIC_VALUES, &vkey_hash),
+            Error::InvalidProofLength {
+                expected_one_of: &[PROOF_LEN, PROOF_WITH_SELECTOR_LEN],
+                got: 259,
+            },
+        ),
+        (
+            "truncated verification key",
+            verify_proof_raw(&proof, &public_inputs, &vk[..GROTH16_VK_MIN_LEN - 1]),
+            Error::InvalidInput,
+        ),
+        (
+            "verification key point off the curve",
+            verify_proof_raw(&proof, &public_inputs, &invalid_vk),
+            Error::G1CompressionError,
+        ),
+        (
+            "verification key with another number of public inputs",
+            verify_proof_raw(&proof, &public_inputs, &one_input.vk),
+            Error::InvalidPublicInput,
+        ),
+        (
+            "non-canonical committed values digest",
+            crate::CommittedValuesDigest::from_masked_bytes(
+                crate::utils::BN254_SCALAR_FIELD_MODULUS,
+            )
+            .map(drop),
+            Error::InvalidPublicInput,
+        ),
+        (
+            "fixture with an unmasked committed values digest",
+            verify_proof_fixture(&unmasked_fixture, &vk),
+            Error::InvalidPublicInput,
+        ),
+        (
+            "non-canonical public input",
+            verify_groth16_prenegated(
+                &prenegated,
+                &[sp1_vkey_hash, crate::utils::BN254_SCALAR_FIELD_MODULUS],
+                &vk_key,
+            ),
+            Error::InvalidPublicInput,
+        ),
+    ];
+    for (name, result, expected) in cases {
+        assert_eq!(result, Err(expected), "{name}");
+    }
+}
+
+#[test]
+fn test_circuit_typed_entry_points() {
+    use crate::{
+        groth16_public_inputs, negate_proof_a, verify, verify_generic, verify_negated,
+        verify_proof_raw, verify_proof_raw_prenegated, Error, NegatedProof, Proof, PublicInputs,
+        VkView, PROOF_LEN,
+    };
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let public_inputs = groth16_public_inputs(&sp1_vkey_hash, &committed_values_digest);
+
+    // The typed and byte entry points agree.
+    let parsed_proof = Proof::from_bytes(&proof).unwrap();
+    let parsed_inputs = PublicInputs::from_bytes(&public_inputs).unwrap();
+    let parsed_vk = VkView::new(&vk).unwrap().to_verification_key().unwrap();
+    assert_eq!(
+        parsed_inputs.inputs,
+        [sp1_vkey_hash, *committed_values_digest]
+    );
+    assert!(verify(&parsed_proof, &parsed_inputs, &parsed_vk).is_ok());
+    assert!(verify_proof_raw(&proof, &public_inputs, &vk).is_ok());
+
+    let negated = parsed_proof.clone().with_negated_a().unwrap();
+    assert_eq!(negated.to_bytes(), negate_proof_a(&proof).unwrap());
+    assert!(verify_negated(&negated, &parsed_inputs, &parsed_vk).is_ok());
+    assert!(verify_proof_raw_prenegated(&negated.to_bytes(), &public_inputs, &vk).is_ok());
+    assert_eq!(
+        NegatedProof::from_prenegated_bytes(&negated.to_bytes()).unwrap(),
+        negated
+    );
+
+    // Negating twice, by passing the bytes of a negated proof as a raw one, or the other way
+    // around, fails the pairing check.
+    assert_eq!(
+        negated.proof().clone().with_negated_a().unwrap().proof(),
+        &parsed_proof
+    );
+    let twice = Proof::from_bytes(&negated.to_bytes()).unwrap();
+    assert_eq!(
+        verify(&twice, &parsed_inputs, &parsed_vk),
+        Err(Error::VerificationError)
+    );
+    let never = NegatedProof::from_prenegated_bytes(&proof).unwrap();
+    assert_eq!(
+        verify_negated(&never, &parsed_inputs, &parsed_vk),
+        Err(Error::VerificationError)
+    );
+
+    // Other numbers of public inputs.
+    let TestProof { vk, proof } = prove(&[scalar(1)]);
+    let vk = VkView::new(&vk).unwrap().to_verification_key().unwrap();
+    let proof = Proof::from_bytes(&proof).unwrap();
+    let inputs = PublicInputs {
+        inputs: [scalar(1)],
+    };
+    assert!(verify_generic(&proof, &inputs, &vk).is_ok());
+    assert_eq!(
+        verify(&proof, &parsed_inputs, &vk),
+        Err(Error::InvalidPublicInput)
+    );
+    assert_eq!(
+        Proof::from_bytes(&[0; 255]),
+        Err(Error::InvalidProofLength {
+            expected_one_of: &[PROOF_LEN],
+            got: 255
+        })
+    );
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+fn test_circuit_verify_proof_timed() {
+    use crate::{utils::sha256, verify_proof_timed, Error};
+    use std::time::{Duration, Instant};
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let groth16_vk_hash: [u8; 4] = sha256(&vk)[..4].try_into().unwrap();
+    let sp1_proof = [&groth16_vk_hash[..], &proof].concat();
+    let sp1_vkey_hash = format!("0x{}", hex::encode(sp1_vkey_hash));
+
+    let started = Instant::now();
+    let metrics = verify_proof_timed(&sp1_proof, SP1_PUBLIC_VALUES, &sp1_vkey_hash, &vk).unwrap();
+    let wall = started.elapsed();
+    assert_eq!(metrics.outcome, Ok(()));
+
+    // Each stage ran, within the total, itself within the wall clock time of the call. The
+    // pairing check dominates, so the total is most of that time.
+    for stage in [
+        metrics.vk_parse,
+        metrics.proof_parse,
+        metrics.public_inputs,
+        metrics.pairing,
+    ] {
+        assert!(stage > Duration::ZERO, "{metrics:?}");
+    }
+    let stages = metrics.vk_parse + metrics.proof_parse + metrics.public_inputs + metrics.pairing;
+    assert!(stages <= metrics.total, "{metrics:?}");
+    assert!(metrics.total <= wall, "{metrics:?} in {wall:?}");
+    assert!(metrics.total >= wall / 2, "{metrics:?} in {wall:?}");
+
+    // A failure is an outcome, and the stages after it don't run.
+    let metrics =
+        verify_proof_timed(&sp1_proof, SP1_PUBLIC_VALUES, &sp1_vkey_hash, &vk[1..]).unwrap();
+    assert_eq!(metrics.outcome, Err(Error::Groth16VkeyHashMismatch));
+    assert_eq!(metrics.proof_parse, Duration::ZERO);
+    assert_eq!(metrics.pairing, Duration::ZERO);
+    let metrics = verify_proof_timed(&sp1_proof, b"other values", &sp1_vkey_hash, &vk).unwrap();
+    assert_eq!(metrics.outcome, Err(Error::VerificationError));
+    assert!(metrics.pairing > Duration::ZERO);
+
+    // Only an undecodable program vkey hash fails the call itself.
+    assert_eq!(
+        verify_proof_timed(&sp1_proof, SP1_PUBLIC_VALUES, "0x00", &vk),
+        Err(Error::InvalidProgramVkeyHash)
+    );
+}
+
+#[test]
+fn test_circuit_proof_lengths() {
+    use crate::{
+        groth16_public_inputs, utils::sha256, verify_proof, verify_proof_raw,
+        verify_proof_with_digest, Error, ProofBytes, SP1ProofFixture, PROOF_LEN,
+        PROOF_WITH_SELECTOR_LEN,
+    };
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let groth16_vk_hash: [u8; 4] = sha256(&vk)[..4].try_into().unwrap();
+    let sp1_proof = [&groth16_vk_hash[..], &proof].concat();
+    let vkey_hash = format!("0x{}", hex::encode(sp1_vkey_hash));
+    let public_inputs = groth16_public_inputs(&sp1_vkey_hash, &committed_values_digest);
+
+    // Whichever form of the proof is passed, every entry point verifies it.
+    for proof in [&proof[..], &sp1_proof] {
+        assert_eq!(
+            verify_proof(proof, SP1_PUBLIC_VALUES, &vkey_hash, &vk),
+            Ok(())
+        );
+        assert_eq!(
+            verify_proof_with_digest(
+                proof,
+                &committed_values_digest,
+                &sp1_vkey_hash,
+                &vk,
+                &groth16_vk_hash
+            ),
+            Ok(())
+        );
+        assert_eq!(verify_proof_raw(proof, &public_inputs, &vk), Ok(()));
+    }
+
+    // As do the other shapes of `ProofBytes`: arrays, vectors, owned or borrowed.
+    let sp1_proof_array: [u8; PROOF_WITH_SELECTOR_LEN] = sp1_proof.clone().try_into().unwrap();
+    assert_eq!(
+        verify_proof(&proof, SP1_PUBLIC_VALUES, &vkey_hash, &vk),
+        Ok(())
+    );
+    assert_eq!(
+        verify_proof(&sp1_proof_array, SP1_PUBLIC_VALUES, &vkey_hash, &vk),
+        Ok(())
+    );
+    assert_eq!(
+        verify_proof(sp1_proof.clone(), SP1_PUBLIC_VALUES, &vkey_hash, &vk),
+        Ok(())
+    );
+    assert_eq!(
+        verify_proof_raw(ProofBytes::Owned(proof.to_vec()), &public_inputs, &vk),
+        Ok(())
+    );
+    assert_eq!(
+        verify_proof_raw(
+            &ProofBytes::WithSelector(&sp1_proof_array),
+            &public_inputs,
+            &vk
+        ),
+        Ok(())
+    );
+
+    // The selector of a 260-byte proof is still checked.
+    let mut other_selector = sp1_proof.clone();
+    other_selector[0] ^= 1;
+    assert_eq!(
+        verify_proof_raw(&other_selector, &public_inputs, &vk),
+        Err(Error::Groth16VkeyHashMismatch)
+    );
+    assert_eq!(
+        verify_proof(&other_selector, SP1_PUBLIC_VALUES, &vkey_hash, &vk),
+        Err(Error::Groth16VkeyHashMismatch)
+    );
+
+    // Truncated or padded proofs of either form fail with their length.
+    let padded = [&sp1_proof[..], &[0]].concat();
+    for proof in [&proof[..255], &sp1_proof[..259], &padded] {
+        let expected = |error: Result<(), Error>| {
+            matches!(
+                error,
+                Err(Error::InvalidProofLength {
+                    expected_one_of: &[PROOF_LEN, PROOF_WITH_SELECTOR_LEN],
+                    got,
+                }) if got == proof.len()
+            )
+        };
+        assert!(expected(verify_proof(
+            proof,
+            SP1_PUBLIC_VALUES,
+            &vkey_hash,
+            &vk
+        )));
+        assert!(expected(verify_proof_with_digest(
+            proof,
+            &committed_values_digest,
+            &sp1_vkey_hash,
+            &vk,
+            &groth16_vk_hash
+        )));
+        assert!(expected(verify_proof_raw(proof, &public_inputs, &vk)));
+        assert!(expected(verify_proof_raw(
+            proof.to_vec(),
+            &public_inputs,
+            &vk
+        )));
+    }
+
+    // Fixtures record the selector, so only take 260-byte proofs.
+    assert!(
+        SP1ProofFixture::from_components(&sp1_proof, SP1_PUBLIC_VALUES, &sp1_vkey_hash).is_ok()
+    );
+    for proof in [&proof[..255], &proof[..], &sp1_proof[..259], &padded] {
+        assert!(matches!(
+            SP1ProofFixture::from_components(proof, SP1_PUBLIC_VALUES, &sp1_vkey_hash),
+            Err(Error::InvalidProofLength {
+                expected_one_of: &[PROOF_WITH_SELECTOR_LEN],
+                got,
+            }) if got == proof.len()
+        ));
+    }
+
+    // The message points at the selector, the usual culprit.
+    let error = verify_proof_raw(&proof[..255], &public_inputs, &vk).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid proof length: expected one of [256, 260] bytes, got 255. Proofs from \
+         SP1ProofWithPublicValues::bytes() are 260 bytes: a 4-byte Groth16 vkey hash selector, \
+         then the 256-byte raw proof"
+    );
+}
+
+#[test]
+fn test_circuit_trailing_bytes() {
+    use crate::{
+        groth16_public_inputs, utils::sha256, verify_proof_raw, verify_proof_raw_exact, Error,
+        InputSection, SP1ProofFixture, SP1ProofFixtureRef, VkView,
+    };
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let sp1_proof = [&sha256(&vk)[..4], &proof].concat();
+    let public_inputs = groth16_public_inputs(&sp1_vkey_hash, &committed_values_digest);
+    let trailing = |section, error| matches!(error, Err(Error::TrailingBytes { section: s, extra: 1 }) if s == section);
+
+    for proof in [&proof[..], &sp1_proof] {
+        assert_eq!(verify_proof_raw_exact(proof, &public_inputs, &vk), Ok(()));
+    }
+
+    // One extra byte after any section is rejected.
+    for padded in [[&proof[..], &[0]].concat(), [&sp1_proof[..], &[0]].concat()] {
+        assert!(trailing(
+            InputSection::Proof,
+            verify_proof_raw_exact(&padded, &public_inputs, &vk)
+        ));
+    }
+    let padded_inputs = [&public_inputs[..], &[0]].concat();
+    assert!(trailing(
+        InputSection::PublicInputs,
+        verify_proof_raw_exact(&proof, &padded_inputs, &vk)
+    ));
+    let padded_vk = [&vk[..], &[0]].concat();
+    assert!(trailing(
+        InputSection::Vk,
+        verify_proof_raw_exact(&proof, &public_inputs, &padded_vk)
+    ));
+    assert!(trailing(
+        InputSection::Vk,
+        VkView::new_exact(&padded_vk).map(drop)
+    ));
+
+    // The lax entry point ignores the bytes after the verifying key.
+    assert_eq!(verify_proof_raw(&proof, &public_inputs, &padded_vk), Ok(()));
+
+    // Keys without their commitment keys are complete, and a truncated count of them is trailing.
+    assert!(VkView::new_exact(&vk[..vk.len() - 4]).is_ok());
+    for extra in 1..=3 {
+        assert_eq!(
+            VkView::new_exact(&vk[..vk.len() - 4 + extra]).map(drop),
+            Err(Error::TrailingBytes {
+                section: InputSection::Vk,
+                extra
+            })
+        );
+    }
+
+    let fixture =
+        SP1ProofFixture::from_components(&sp1_proof, SP1_PUBLIC_VALUES, &sp1_vkey_hash).unwrap();
+    let bytes = fixture.to_bytes();
+    assert_eq!(
+        SP1ProofFixtureRef::from_bytes_exact(&bytes),
+        Ok(SP1ProofFixtureRef::from(&fixture))
+    );
+    let padded_fixture = [&bytes[..], &[0]].concat();
+    assert!(SP1ProofFixtureRef::from_bytes(&padded_fixture).is_ok());
+    assert!(trailing(
+        InputSection::Fixture,
+        SP1ProofFixtureRef::from_bytes_exact(&padded_fixture).map(drop)
+    ));
+
+    let error = verify_proof_raw_exact(&proof, &padded_inputs, &vk).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Trailing bytes after the public inputs: 1 bytes"
+    );
+}
+
+#[test]
+fn test_circuit_verify_proof_any_of() {
+    use crate::{utils::sha256, verify_proof_any_of, Error};
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let sp1_proof = [&sha256(&vk)[..4], &proof].concat();
+    let other_vkey_hash = |seed: &[u8]| {
+        let mut hash = sha256(seed);
+        hash[0] = 0;
+        hash
+    };
+    let (first, second) = (other_vkey_hash(b"v1"), other_vkey_hash(b"v2"));
+
+    // The index of the matching hash is returned, wherever it is in the list.
+    for (allowed, index) in [
+        ([sp1_vkey_hash, first, second], 0),
+        ([first, sp1_vkey_hash, second], 1),
+        ([first, second, sp1_vkey_hash], 2),
+    ] {
+        for proof in [&proof[..], &sp1_proof] {
+            assert_eq!(
+                verify_proof_any_of(proof, SP1_PUBLIC_VALUES, &allowed, &vk),
+                Ok(index)
+            );
+        }
+    }
+
+    let allowed = [first, second, other_vkey_hash(b"v3")];
+    assert!(matches!(
+        verify_proof_any_of(&proof, SP1_PUBLIC_VALUES, &allowed, &vk),
+        Err(Error::NoVkeyHashMatched { candidates: 3 })
+    ));
+    assert!(matches!(
+        verify_proof_any_of(&proof, SP1_PUBLIC_VALUES, &[], &vk),
+        Err(Error::NoVkeyHashMatched { candidates: 0 })
+    ));
+    assert_eq!(
+        verify_proof_any_of(&proof, b"other public values", &[sp1_vkey_hash], &vk),
+        Err(Error::NoVkeyHashMatched { candidates: 1 })
+    );
+
+    // Checks that don't depend on the candidate fail the whole call.
+    let mut unprefixed = first;
+    unprefixed[0] = 1;
+    assert_eq!(
+        verify_proof_any_of(&proof, SP1_PUBLIC_VALUES, &[sp1_vkey_hash, unprefixed], &vk),
+        Err(Error::InvalidProgramVkeyHash)
+    );
+    let mut other_selector = sp1_proof.clone();
+    other_selector[0] ^= 1;
+    assert_eq!(
+        verify_proof_any_of(&other_selector, SP1_PUBLIC_VALUES, &[sp1_vkey_hash], &vk),
+        Err(Error::Groth16VkeyHashMismatch)
+    );
+
+    let error = verify_proof_any_of(&proof, SP1_PUBLIC_VALUES, &allowed, &vk).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "The proof is valid for none of the 3 allowed program vkey hashes"
+    );
+}
+
+/// Every backend compiled in, by name.
+pub(crate) fn compiled_backends() -> Vec<(&'static str, &'static dyn Groth16Backend)> {
+    vec![
+        ("groth16-solana", &crate::backend::Groth16Solana),
+        #[cfg(feature = "solana")]
+        ("alt_bn128", &crate::backend::AltBn128),
+        #[cfg(feature = "ark")]
+        ("arkworks", &crate::backend::Arkworks),
+    ]
+}
+
+#[test]
+fn test_circuit_backends_agree() {
+    use crate::{
+        groth16_public_inputs, negate_proof_a, utils::sha256, verify_proof_raw,
+        verify_proof_raw_with, Error,
+    };
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let public_inputs = groth16_public_inputs(&sp1_vkey_hash, &committed_values_digest);
+    let other = prove(&[*committed_values_digest, sp1_vkey_hash]);
+
+    // (name, proof, public inputs, verifying key)
+    let mut corpus = vec![
+        (
+            "valid".to_string(),
+            proof.to_vec(),
+            public_inputs,
+            vk.clone(),
+        ),
+        (
+            "with selector".to_string(),
+            [&sha256(&vk)[..4], &proof].concat(),
+            public_inputs,
+            vk.clone(),
+        ),
+        (
+            "pre-negated pi_a".to_string(),
+            negate_proof_a(&proof).unwrap().to_vec(),
+            public_inputs,
+            vk.clone(),
+        ),
+        (
+            "proof of another statement".to_string(),
+            other.proof.to_vec(),
+            public_inputs,
+            vk.clone(),
+        ),
+        (
+            "other verifying key".to_string(),
+            proof.to_vec(),
+            public_inputs,
+            other.vk,
+        ),
+    ];
+    for i in (0..256).step_by(7) {
+        let mut mutated = proof;
+        mutated[i] ^= 1 << (i % 8);
+        corpus.push((
+            format!("bit flip at {i}"),
+            mutated.to_vec(),
+            public_inputs,
+            vk.clone(),
+        ));
+    }
+    for i in (0..63).step_by(9) {
+        let mut mutated = public_inputs;
+        mutated[i] ^= 1;
+        corpus.push((
+            format!("public input flip at {i}"),
+            proof.to_vec(),
+            mutated,
+            vk.clone(),
+        ));
+    }
+    let mut identity_a = proof;
+    identity_a[..64].fill(0);
+    corpus.push((
+        "pi_a at infinity".to_string(),
+        identity_a.to_vec(),
+        public_inputs,
+        vk.clone(),
+    ));
+
+    let backends = compiled_backends();
+    for (name, proof, public_inputs, vk) in &corpus {
+        let expected = verify_proof_raw(proof, public_inputs, vk);
+        assert_eq!(
+            expected.is_ok(),
+            matches!(name.as_str(), "valid" | "with selector"),
+            "{name}: {expected:?}"
+        );
+        for (backend_name, backend) in &backends {
+            let result = verify_proof_raw_with(*backend, proof, public_inputs, vk);
+            assert_eq!(
+                result.is_ok(),
+                expected.is_ok(),
+                "{name}: groth16-solana returned {expected:?}, {backend_name} {result:?}"
+            );
+        }
+    }
+
+    // The default backend is what `verify_proof_raw` runs, down to the error.
+    let mut tampered = public_inputs;
+    tampered[62] ^= 1;
+    assert_eq!(
+        verify_proof_raw_with(
+            &crate::backend::DefaultBackend::default(),
+            &proof,
+            &tampered,
+            &vk
+        ),
+        Err(Error::VerificationError)
+    );
+}
+
+#[test]
+fn test_circuit_precheck_proof() {
+    use crate::{
+        groth16_public_inputs, precheck_proof, utils::sha256, verify_proof_raw, Error, ProofSummary,
+    };
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let selector: [u8; 4] = sha256(&vk)[..4].try_into().unwrap();
+    let sp1_proof = [&selector[..], &proof].concat();
+    let public_inputs = groth16_public_inputs(&sp1_vkey_hash, &committed_values_digest);
+
+    let summary = ProofSummary {
+        selector,
+        sp1_vkey_hash,
+        committed_values_digest,
+    };
+    for proof in [&proof[..], &sp1_proof] {
+        assert_eq!(precheck_proof(proof, &public_inputs, &vk), Ok(summary));
+    }
+    // A proof for other public inputs passes: only the pairing check tells.
+    let mut tampered = public_inputs;
+    tampered[62] ^= 1;
+    assert!(precheck_proof(&proof, &tampered, &vk).is_ok());
+
+    let mut other_selector = sp1_proof.clone();
+    other_selector[0] ^= 1;
+    let mut off_curve = proof;
+    off_curve[255] ^= 1;
+    let mut unmasked = public_inputs;
+    unmasked[31] |= 0x20;
+    let mut above_modulus = public_inputs;
+    above_modulus[31..].fill(0xff);
+    let mut uncompressed_vk = vk.clone();
+    uncompressed_vk[0] &= 0b0011_1111;
+    let one_input_vk = prove(&[scalar(1)]).vk;
+    let cases = [
+        (
+            "proof length",
+            precheck_proof(&proof[1..], &public_inputs, &vk),
+            Error::InvalidProofLength {
+                expected_one_of: &[256, 260],
+                got: 255,
+            },
+        ),
+        (
+            "selector",
+            precheck_proof(&other_selector, &public_inputs, &vk),
+            Error::Groth16VkeyHashMismatch,
+        ),
+        (
+            "public inputs length",
+            precheck_proof(&proof, &public_inputs[1..], &vk),
+            Error::InvalidPublicInput,
+        ),
+        (
+            "vk length",
+            precheck_proof(&proof, &public_inputs, &vk[..100]),
+            Error::InvalidInput,
+        ),
+        (
+            "vk flags",
+            precheck_proof(&proof, &public_inputs, &uncompressed_vk),
+            Error::InvalidInput,
+        ),
+        (
+            "number of public inputs",
+            precheck_proof(&proof, &public_inputs, &one_input_vk),
+            Error::InvalidPublicInput,
+        ),
+        (
+            "scalar above the modulus",
+            precheck_proof(&proof, &above_modulus, &vk),
+            Error::InvalidPublicInput,
+        ),
+        (
+            "unmasked digest",
+            precheck_proof(&proof, &unmasked, &vk),
+            Error::InvalidPublicInput,
+        ),
+        (
+            "pi_c off the curve",
+            precheck_proof(&off_curve, &public_inputs, &vk),
+            Error::InvalidInput,
+        ),
+    ];
+    for (name, result, expected) in cases {
+        assert_eq!(result.map(drop), Err(expected), "{name}");
+    }
+    // The verifier rejects each of them too.
+    assert!(verify_proof_raw(&off_curve, &public_inputs, &vk).is_err());
+    assert!(verify_proof_raw(&proof, &public_inputs, &uncompressed_vk).is_err());
+}
+
+/// `verify_proof` as it ran before its checks were ordered by cost: both hashes first, then the
+/// proof length and selector, then the negation of pi_a before the verifying key was parsed.
+fn verify_proof_unordered(
+    proof: &[u8],
+    sp1_public_inputs: &[u8],
+    sp1_vkey_hash: &str,
+    groth16_vk: &[u8],
+) -> Result<(), crate::Error> {
+    use crate::{
+        groth16_public_inputs, groth16_verifying_key, pairing_check,
+        utils::{check_sp1_vkey_hash, groth16_vk_hash, load_proof_from_bytes, strip_selector},
+        CommittedValuesDigest, PublicInputs, VkView,
+    };
+
+    let sp1_vkey_hash = crate::decode_sp1_vkey_hash(sp1_vkey_hash)?;
+    let groth16_vk_hash = groth16_vk_hash(groth16_vk);
+    let committed_values_digest = CommittedValuesDigest::from_public_values(sp1_public_inputs);
+    let proof = strip_selector(proof, || groth16_vk_hash)?;
+    check_sp1_vkey_hash(&sp1_vkey_hash)?;
+    let public_inputs = groth16_public_inputs(&sp1_vkey_hash, &committed_values_digest);
+    let proof = load_proof_from_bytes(proof)?;
+    let vk_view = VkView::new(groth16_vk)?;
+    let public_inputs = PublicInputs::from_bytes(&public_inputs)?;
+    let vk = groth16_verifying_key(&vk_view)?;
+    pairing_check(proof.as_proof_ref(), &public_inputs.inputs, &vk)
+}
+
+#[test]
+fn test_circuit_check_order() {
+    use crate::{utils::sha256, verify_proof};
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let sp1_proof = [&sha256(&vk)[..4], &proof].concat();
+    let vkey_hash = format!("0x{}", hex::encode(sp1_vkey_hash));
+    let one_input_vk = prove(&[sp1_vkey_hash]).vk;
+    let mut off_curve = sp1_proof.clone();
+    off_curve[4 + 63] ^= 1;
+    let mut non_canonical = sp1_proof.clone();
+    non_canonical[4..36].copy_from_slice(&crate::utils::BN254_BASE_FIELD_MODULUS);
+    let mut tampered_vk = vk.clone();
+    tampered_vk[0] ^= 0b11 << 6;
+    let wrong_selector = [&[0u8; 4][..], &proof].concat();
+    let long_values = vec![0x5a; 16 * 1024];
+    let non_canonical_hash = format!("0x01{}", &vkey_hash[4..]);
+
+    // Each submission is valid, or fails one or more checks, cheap and expensive alike.
+    let corpus: [(&str, &[u8], &[u8], &str, &[u8]); 16] = [
+        ("valid", &sp1_proof, SP1_PUBLIC_VALUES, &vkey_hash, &vk),
+        ("valid raw", &proof, SP1_PUBLIC_VALUES, &vkey_hash, &vk),
+        (
+            "truncated",
+            &sp1_proof[..259],
+            SP1_PUBLIC_VALUES,
+            &vkey_hash,
+            &vk,
+        ),
+        ("empty", &[], &long_values, &vkey_hash, &vk),
+        (
+            "wrong selector",
+            &wrong_selector,
+            &long_values,
+            &vkey_hash,
+            &vk,
+        ),
+        ("invalid hex", &sp1_proof, SP1_PUBLIC_VALUES, "0xzz", &vk),
+        (
+            "non-canonical hash",
+            &sp1_proof,
+            &long_values,
+            &non_canonical_hash,
+            &vk,
+        ),
+        ("tampered values", &sp1_proof, &long_values, &vkey_hash, &vk),
+        (
+            "pi_a off the curve",
+            &off_curve,
+            SP1_PUBLIC_VALUES,
+            &vkey_hash,
+            &vk,
+        ),
+        (
+            "non-canonical pi_a",
+            &non_canonical,
+            SP1_PUBLIC_VALUES,
+            &vkey_hash,
+            &vk,
+        ),
+        (
+            "one-input key",
+            &proof,
+            SP1_PUBLIC_VALUES,
+            &vkey_hash,
+            &one_input_vk,
+        ),
+        (
+            "tampered key",
+            &proof,
+            SP1_PUBLIC_VALUES,
+            &vkey_hash,
+            &tampered_vk,
+        ),
+        (
+            "truncated key",
+            &proof,
+            SP1_PUBLIC_VALUES,
+            &vkey_hash,
+            &vk[..100],
+        ),
+        (
+            "truncated, invalid hex",
+            &proof[..255],
+            SP1_PUBLIC_VALUES,
+            "0xzz",
+            &vk,
+        ),
+        (
+            "selector, one-input key",
+            &sp1_proof,
+            SP1_PUBLIC_VALUES,
+            &vkey_hash,
+            &one_input_vk,
+        ),
+        (
+            "pi_a, one-input key",
+            &non_canonical[4..],
+            SP1_PUBLIC_VALUES,
+            &vkey_hash,
+            &one_input_vk,
+        ),
+    ];
+
+    // The new order makes the same decisions as the old one.
+    for (name, proof, public_values, vkey_hash, vk) in corpus {
+        let ordered = verify_proof(proof, public_values, vkey_hash, vk);
+        let unordered = verify_proof_unordered(proof, public_values, vkey_hash, vk);
+        assert_eq!(
+            ordered.is_ok(),
+            unordered.is_ok(),
+            "{name}: {ordered:?} against {unordered:?}"
+        );
+        assert_eq!(ordered.is_ok(), name.starts_with("valid"), "{name}");
+    }
+
+    // The first failing check is the cheapest one.
+    assert!(matches!(
+        verify_proof(&proof[..255], SP1_PUBLIC_VALUES, "0xzz", &vk),
+        Err(crate::Error::InvalidProofLength { got: 255, .. })
+    ));
+    assert_eq!(
+        verify_proof(
+            &non_canonical[4..],
+            SP1_PUBLIC_VALUES,
+            &vkey_hash,
+            &one_input_vk
+        ),
+        Err(crate::Error::InvalidPublicInput)
+    );
+}
+
+#[test]
+fn test_circuit_hash_comparisons() {
+    use crate::{
+        known_sp1_version, utils::sha256, verify_proof_fixture, verify_proof_fixture_for_program,
+        Error, SP1ProofFixture, GROTH16_VK_3_0_0_HASH,
+    };
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let sp1_proof = [&sha256(&vk)[..4], &proof].concat();
+    let fixture =
+        SP1ProofFixture::from_components(&sp1_proof, SP1_PUBLIC_VALUES, &sp1_vkey_hash).unwrap();
+    verify_proof_fixture_for_program(&fixture, &sp1_vkey_hash, &vk).unwrap();
+
+    // A hash differing in its first or its last byte is rejected alike.
+    for index in [0, 3] {
+        let mut tampered = fixture.clone();
+        tampered.groth16_vkey_hash[index] ^= 1;
+        assert_eq!(
+            verify_proof_fixture(&tampered, &vk),
+            Err(Error::Groth16VkeyHashMismatch)
+        );
+        let mut selector = GROTH16_VK_3_0_0_HASH;
+        selector[index] ^= 1;
+        assert!(known_sp1_version(&selector).is_none());
+    }
+    for index in [0, 31] {
+        let mut tampered = fixture.clone();
+        tampered.sp1_vkey_hash[index] ^= 1;
+        assert_eq!(
+            verify_proof_fixture_for_program(&tampered, &sp1_vkey_hash, &vk),
+            Err(Error::InvalidProgramVkeyHash)
+        );
+        let mut tampered = fixture.clone();
+        tampered.committed_values_digest[index] ^= 1;
+        assert_eq!(
+            verify_proof_fixture(&tampered, &vk),
+            Err(Error::PublicValuesDigestMismatch)
+        );
+    }
+    // A selector of another length matches no release.
+    assert!(known_sp1_version(&GROTH16_VK_3_0_0_HASH).is_some());
+    assert!(known_sp1_version(&GROTH16_VK_3_0_0_HASH[..3]).is_none());
+}
+
+proptest::proptest! {
+    #![proptest_config(proptest::prelude::ProptestConfig::with_cases(32))]
+
+    /// Whatever the corruption, a proof the precheck accepts only fails verification at the
+    /// pairing, and a proof it rejects fails verification.
+    #[test]
+    fn test_circuit_precheck_agrees_with_verifier(
+        proof_flips in proptest::collection::vec((0..260usize, 0..8u8), 0..3),
+        input_flips in proptest::collection::vec((0..63usize, 0..8u8), 0..2),
+        vk_flag_flips in proptest::collection::vec((0..9usize, 6..8u8), 0..2),
+        with_selector in proptest::prelude::any::<bool>(),
+    ) {
+        use std::sync::OnceLock;
+
+        use crate::{
+            groth16_public_inputs, precheck_proof, utils::sha256, verify_proof_raw, Error,
+        };
+
+        static VALID: OnceLock<(Vec<u8>, [u8; 63], Vec<u8>)> = OnceLock::new();
+        let (sp1_proof, public_inputs, vk) = VALID.get_or_init(|| {
+            let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+            let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+            (
+                [&sha256(&vk)[..4], &proof].concat(),
+                groth16_public_inputs(&sp1_vkey_hash, &committed_values_digest),
+                vk,
+            )
+        });
+
+        let mut proof = sp1_proof.clone();
+        for (index, bit) in proof_flips {
+            proof[index] ^= 1 << bit;
+        }
+        if !with_selector {
+            proof.drain(..4);
+        }
+        let mut public_inputs = *public_inputs;
+        for (index, bit) in input_flips {
+            public_inputs[index] ^= 1 << bit;
+        }
+        // Only the flags of the compressed points: alpha, beta and delta in G1, beta, gamma and
+        // delta in G2, and the three IC points.
+        let mut vk = vk.clone();
+        for (point, bit) in vk_flag_flips {
+            let offset = [0, 32, 64, 128, 192, 224, 292, 324, 356][point];
+            vk[offset] ^= 1 << bit;
+        }
+
+        let verified = verify_proof_raw(&proof, &public_inputs, &vk);
+        match precheck_proof(&proof, &public_inputs, &vk) {
+            Ok(_) => proptest::prop_assert!(
+                matches!(verified, Ok(()) | Err(Error::VerificationError)),
+                "the precheck passed, but verification failed with {:?}",
+                verified
+            ),
+            Err(error) => proptest::prop_assert!(
+                verified.is_err(),
+                "the precheck failed with {:?}, but the proof verifies",
+                error
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_circuit_verify_proof_with_domain() {
+    use crate::{utils::sha256, verify_proof_with_domain, CommittedValuesDigest, Error};
+
+    const DOMAIN: &[u8] = b"sp1-solana:test:v1";
+    let (sp1_vkey_hash, plain_digest) = sp1_inputs();
+    let committed_values_digest =
+        CommittedValuesDigest::from_domain_public_values(DOMAIN, SP1_PUBLIC_VALUES);
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let sp1_proof = [&sha256(&vk)[..4], &proof].concat();
+    let sp1_vkey_hash_hex = format!("0x{}", hex::encode(sp1_vkey_hash));
+
+    assert_eq!(
+        verify_proof_with_domain(
+            &sp1_proof,
+            SP1_PUBLIC_VALUES,
+            &sp1_vkey_hash_hex,
+            &vk,
+            Some(DOMAIN)
+        ),
+        Ok(())
+    );
+    // The same values, without the domain or under another one, are another statement.
+    for domain in [None, Some(&b"sp1-solana:test:v2"[..]), Some(&b""[..])] {
+        assert_eq!(
+            verify_proof_with_domain(
+                &sp1_proof,
+                SP1_PUBLIC_VALUES,
+                &sp1_vkey_hash_hex,
+                &vk,
+                domain
+            ),
+            Err(Error::VerificationError),
+            "{domain:?}"
+        );
+    }
+
+    // Without a domain, this is `verify_proof`.
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *plain_digest]);
+    assert_eq!(
+        verify_proof_with_domain(&proof, SP1_PUBLIC_VALUES, &sp1_vkey_hash_hex, &vk, None),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_circuit_verification_context() {
+    use crate::{
+        negate_proof_a, utils::sha256, Error, ProgramVkeys, SP1ProofFixture, VerificationContext,
+        VkProvider, VkSource, VkView,
+    };
+
+    /// Serves the key of the test circuit.
+    struct TestVks {
+        vk: Vec<u8>,
+        groth16_vkey_hash: [u8; 4],
+    }
+
+    impl VkProvider for TestVks {
+        fn groth16_vk(&self, groth16_vkey_hash: &[u8; 4]) -> Option<&[u8]> {
+            (*groth16_vkey_hash == self.groth16_vkey_hash).then_some(&self.vk)
+        }
+    }
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let groth16_vkey_hash: [u8; 4] = sha256(&vk)[..4].try_into().unwrap();
+    let parsed = VkView::new(&vk).unwrap().to_verification_key().unwrap();
+    let provider = TestVks {
+        vk: vk.clone(),
+        groth16_vkey_hash,
+    };
+    let sources = [
+        ("bytes", VkSource::Bytes(&vk)),
+        (
+            "bytes with hash",
+            VkSource::BytesWithHash {
+                vk: &vk,
+                groth16_vkey_hash,
+            },
+        ),
+        (
+            "parsed",
+            VkSource::Parsed {
+                vk: &parsed,
+                groth16_vkey_hash,
+            },
+        ),
+        ("provider", VkSource::Provider(&provider)),
+    ];
+    let prenegated_proof = negate_proof_a(&proof).unwrap();
+
+    for (name, source) in sources {
+        let mut contexts = vec![];
+        for strict_lengths in [false, true] {
+            for prenegated in [false, true] {
+                let context = VerificationContext::new(source, &sp1_vkey_hash)
+                    .with_strict_lengths(strict_lengths)
+                    .with_prenegated_proofs(prenegated);
+                contexts.push((strict_lengths, prenegated, context));
+                #[cfg(feature = "ark")]
+                contexts.push((
+                    strict_lengths,
+                    prenegated,
+                    context.with_subgroup_checks(true),
+                ));
+            }
+        }
+
+        for (strict_lengths, prenegated, context) in contexts {
+            let case = format!("{name}, strict: {strict_lengths}, prenegated: {prenegated}");
+            let (raw, other_form) = if prenegated {
+                (prenegated_proof, proof)
+            } else {
+                (proof, prenegated_proof)
+            };
+            let with_selector = [&groth16_vkey_hash[..], &raw].concat();
+
+            assert_eq!(
+                context.verify(&with_selector, SP1_PUBLIC_VALUES),
+                Ok(&sp1_vkey_hash),
+                "{case}"
+            );
+            // Only SP1's format carries the selector a provider looks keys up with.
+            let raw_result = context.verify(&raw, SP1_PUBLIC_VALUES);
+            if strict_lengths || name == "provider" {
+                assert_eq!(
+                    raw_result,
+                    Err(Error::InvalidProofLength {
+                        expected_one_of: &[260],
+                        got: 256
+                    }),
+                    "{case}"
+                );
+            } else {
+                assert_eq!(raw_result, Ok(&sp1_vkey_hash), "{case}");
+            }
+
+            let other_form = [&groth16_vkey_hash[..], &other_form].concat();
+            assert_eq!(
+                context.verify(&other_form, SP1_PUBLIC_VALUES),
+                Err(Error::VerificationError),
+                "{case}"
+            );
+            assert_eq!(
+                context.verify(&with_selector, b"other values"),
+                Err(Error::VerificationError),
+                "{case}"
+            );
+            let mut other_selector = with_selector.clone();
+            other_selector[0] ^= 1;
+            assert_eq!(
+                context.verify(&other_selector, SP1_PUBLIC_VALUES),
+                Err(Error::Groth16VkeyHashMismatch),
+                "{case}"
+            );
+        }
+    }
+
+    // Strict lengths also reject bytes after the key.
+    let padded_vk = [&vk[..], &[0]].concat();
+    let padded = VkSource::BytesWithHash {
+        vk: &padded_vk,
+        groth16_vkey_hash,
+    };
+    let sp1_proof = [&groth16_vkey_hash[..], &proof].concat();
+    let context = VerificationContext::new(padded, &sp1_vkey_hash);
+    assert_eq!(
+        context.verify(&sp1_proof, SP1_PUBLIC_VALUES),
+        Ok(&sp1_vkey_hash)
+    );
+    assert!(matches!(
+        context
+            .with_strict_lengths(true)
+            .verify(&sp1_proof, SP1_PUBLIC_VALUES),
+        Err(Error::TrailingBytes { .. })
+    ));
+
+    // An allowlist returns the hash the proof is valid for.
+    let mut other_vkey_hash = sha256(b"other program");
+    other_vkey_hash[0] = 0;
+    let allowed = [other_vkey_hash, sp1_vkey_hash];
+    let source = VkSource::Bytes(&vk);
+    let context = VerificationContext::with_programs(source, ProgramVkeys::AnyOf(&allowed));
+    assert_eq!(context.sp1_vkey_hashes(), &allowed);
+    assert_eq!(
+        context.verify(&sp1_proof, SP1_PUBLIC_VALUES),
+        Ok(&sp1_vkey_hash)
+    );
+    let other_program = VerificationContext::new(source, &other_vkey_hash);
+    assert_eq!(
+        other_program.verify(&sp1_proof, SP1_PUBLIC_VALUES),
+        Err(Error::VerificationError)
+    );
+    let context = VerificationContext::with_programs(source, ProgramVkeys::AnyOf(&allowed[..1]));
+    assert!(matches!(
+        context.verify(&sp1_proof, SP1_PUBLIC_VALUES),
+        Err(Error::NoVkeyHashMatched { candidates: 1 })
+    ));
+
+    // Fixtures of programs the context doesn't accept are rejected before their proof is checked.
+    let mut fixture =
+        SP1ProofFixture::from_components(&sp1_proof, SP1_PUBLIC_VALUES, &sp1_vkey_hash).unwrap();
+    let context = VerificationContext::new(source, &sp1_vkey_hash);
+    assert_eq!(context.verify_fixture(&fixture), Ok(&sp1_vkey_hash));
+    assert_eq!(
+        VerificationContext::with_programs(source, ProgramVkeys::AnyOf(&allowed))
+            .verify_fixture(&fixture),
+        Ok(&sp1_vkey_hash)
+    );
+    assert_eq!(
+        context
+            .with_prenegated_proofs(true)
+            .verify_fixture(&fixture),
+        Ok(&sp1_vkey_hash)
+    );
+    assert_eq!(
+        other_program.verify_fixture(&fixture),
+        Err(Error::InvalidProgramVkeyHash)
+    );
+    fixture.sp1_public_inputs = b"other values"[..].into();
+    assert_eq!(
+        context.verify_fixture(&fixture),
+        Err(Error::PublicValuesDigestMismatch)
+    );
+}
+
+#[test]
+fn test_circuit_verify_proof_fixture_for_program() {
+    use crate::{
+        utils::sha256, verify_proof_fixture, verify_proof_fixture_for_program, Error,
+        SP1ProofFixture,
+    };
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let mut other_vkey_hash = sha256(b"other program");
+    other_vkey_hash[0] = 0;
+
+    // A valid proof of another program, with the same key and public values.
+    let TestProof { vk, proof } = prove(&[other_vkey_hash, *committed_values_digest]);
+    let sp1_proof = [&sha256(&vk)[..4], &proof].concat();
+    let fixture =
+        SP1ProofFixture::from_components(&sp1_proof, SP1_PUBLIC_VALUES, &other_vkey_hash).unwrap();
+
+    assert_eq!(verify_proof_fixture(&fixture, &vk), Ok(()));
+    assert_eq!(
+        verify_proof_fixture_for_program(&fixture, &other_vkey_hash, &vk),
+        Ok(())
+    );
+    assert_eq!(
+        verify_proof_fixture_for_program(&fixture, &sp1_vkey_hash, &vk),
+        Err(Error::InvalidProgramVkeyHash)
+    );
+
+    // The hashes are compared in full, though the proof only binds their last 31 bytes.
+    let mut unprefixed = other_vkey_hash;
+    unprefixed[0] = 1;
+    assert_eq!(
+        verify_proof_fixture_for_program(&fixture, &unprefixed, &vk),
+        Err(Error::InvalidProgramVkeyHash)
+    );
+    let mut relabeled = fixture.clone();
+    relabeled.sp1_vkey_hash = unprefixed;
+    assert_eq!(
+        verify_proof_fixture_for_program(&relabeled, &unprefixed, &vk),
+        Err(Error::InvalidProgramVkeyHash)
+    );
+
+    // The fixture's own checks still apply.
+    let mut tampered = fixture.clone();
+    tampered.sp1_public_inputs = b"other values"[..].into();
+    assert_eq!(
+        verify_proof_fixture_for_program(&tampered, &other_vkey_hash, &vk),
+        Err(Error::PublicValuesDigestMismatch)
+    );
+}
+
+#[test]
+fn test_circuit_prepared_inputs() {
+    use ark_bn254::{Fq, G1Projective};
+    use ark_ec::CurveGroup;
+
+    use crate::{
+        groth16_public_inputs, verify, verify_proof_with_prepared_inputs, CommittedValuesDigest,
+        Error, Proof, PublicInputs, VerificationKey, VkView,
+    };
+
+    // The MSM, computed with arkworks.
+    let g1 = |bytes: &[u8; 64]| {
+        G1Affine::new(
+            Fq::from_be_bytes_mod_order(&bytes[..32]),
+            Fq::from_be_bytes_mod_order(&bytes[32..]),
+        )
+    };
+    let prepare = |vk: &VerificationKey, public_inputs: &PublicInputs<2>| {
+        let ic = vk.vk_ic.as_slice();
+        let prepared = public_inputs
+            .inputs
+            .iter()
+            .zip(&ic[1..])
+            .fold(G1Projective::from(g1(&ic[0])), |sum, (input, point)| {
+                sum + g1(point) * Fr::from_be_bytes_mod_order(input)
+            });
+        g1_uncompressed(&prepared.into_affine())
+    };
+
+    let (sp1_vkey_hash, committed_values_digest) = sp1_inputs();
+    let TestProof { vk, proof } = prove(&[sp1_vkey_hash, *committed_values_digest]);
+    let vk = VkView::new(&vk).unwrap().to_verification_key().unwrap();
+    let proof = Proof::from_bytes(&proof).unwrap();
+    let public_inputs = PublicInputs::from_bytes(&groth16_public_inputs(
+        &sp1_vkey_hash,
+        &committed_values_digest,
+    ))
+    .unwrap();
+    let prepared = prepare(&vk, &public_inputs);
+    #[cfg(feature = "solana")]
+    assert_eq!(
+        crate::prepare_public_inputs(&public_inputs, &vk),
+        Ok(prepared)
+    );
+
+    // Both paths agree, on the proof's inputs and on others.
+    assert_eq!(verify(&proof, &public_inputs, &vk), Ok(()));
+    assert_eq!(
+        verify_proof_with_prepared_inputs(&proof, &prepared, &vk),
+        Ok(())
+    );
+    let other_inputs = PublicInputs::from_bytes(&groth16_public_inputs(
+        &sp1_vkey_hash,
+        &CommittedValuesDigest::from_public_values(b"other values"),
+    ))
+    .unwrap();
+    assert_eq!(
+        verify(&proof, &other_inputs, &vk),
+        Err(Error::VerificationError)
+    );
+    assert_eq!(
+        verify_proof_with_prepared_inputs(&proof, &prepare(&vk, &other_inputs), &vk),
+        Err(Error::VerificationError)
+    );
+
+    // A tampered point is rejected, whether or not it is still on the curve.
+    for index in [0, 31, 32, 63] {
+        let mut tampered = prepared;
+        tampered[index] ^= 1;
+        assert_eq!(
+            verify_proof_with_prepared_inputs(&proof, &tampered, &vk),
+            Err(Error::VerificationError),
+            "{index}"
+        );
+    }
+    let negated = g1_uncompressed(&-g1(&prepared));
+    assert_eq!(
+        verify_proof_with_prepared_inputs(&proof, &negated, &vk),
+        Err(Error::VerificationError)
+    );
+}
+
+#[test]
+fn test_circuit_verify_and_decode_with_schema() {
+    use crate::{
+        sp1_public_values_schema, utils::sha256, verify_and_decode_with_schema,
+        CommittedValuesDigest, Error, Sp1PublicValuesDecode, TrailingBytesPolicy,
+    };
+
+    sp1_public_values_schema! {
+        #[derive(Debug, PartialEq)]
+        struct Values {
+            n: u32,
+            #[sp1(default)]
+            steps: u64,
+        }
+    }
+
+    let (sp1_vkey_hash, _) = sp1_inputs();
+    let payload = [
+        &Values::SCHEMA_HASH[..],
+        &20u32.to_le_bytes(),
+        &30u64.to_le_bytes(),
+    ]
+    .concat();
+    let TestProof { vk, proof } = prove(&[
+        sp1_vkey_hash,
+        *CommittedValuesDigest::from_public_values(&payload),
+    ]);
+    let sp1_proof = [&sha256(&vk)[..4], &proof].concat();
+    let verify = |payload: &[u8]| {
+        verify_and_decode_with_schema::<Values>(
+            &sp1_proof,
+            payload,
+            &sp1_vkey_hash,
+            &vk,
+            TrailingBytesPolicy::Strict,
+        )
+    };
+
+    assert_eq!(verify(&payload), Ok(Values { n: 20, steps: 30 }));
+    // The schema hash is checked before the proof.
+    let mut other_schema = payload.clone();
+    other_schema[0] ^= 1;
+    assert_eq!(verify(&other_schema), Err(Error::SchemaHashMismatch));
+    // Values of the schema that weren't proven fail verification.
+    let mut tampered = payload.clone();
+    tampered[32] ^= 1;
+    assert_eq!(verify(&tampered), Err(Error::VerificationError));
+}
@@ -0,0 +1,48 @@
+//! `tracing` instrumentation of the off-chain paths, with the `tracing` feature.
+//!
+//! None of it is built for Solana, where the feature has no effect. The spans, named after the
+//! functions they instrument, are:
+//!
+//! - `sp1_solana::verify_proof`, with the proof's `selector`, the `sp1_vkey_hash` and
+//!   `public_values_len`;
+//! - `sp1_solana::verify_proof_with_digest`, with the `sp1_vkey_hash` and the
+//!   `committed_values_digest`, once the proof's selector is checked;
+//! - `sp1_solana::verify_groth16`, with `parse_us` and `verify_us`, the microseconds spent loading
+//!   the verifying key and public inputs, and in the pairing check;
+//! - `sp1_solana::vk_view`, with the verifying key's `len`;
+//! - `sp1_solana::fixture_from_components`, with the `selector` and `public_values_len`;
+//! - `sp1_solana::fixture_load` and `sp1_solana::fixture_save`, with the `path`.
+//!
+//! Failures are emitted as error events in their spans. Durations aren't recorded on
+//! `wasm32-unknown-unknown`, which has no clock.
+
+/// The time a stage of verification started.
+pub(crate) struct Stage {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+}
+
+impl Stage {
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    /// Records the microseconds since the stage started as `field` of the current span.
+    pub(crate) fn record(self, field: &'static str) {
+        #[cfg(not(target_arch = "wasm32"))]
+        tracing::Span::current().record(field, self.start.elapsed().as_micros() as u64);
+        #[cfg(target_arch = "wasm32")]
+        let _ = field;
+    }
+}
+
+/// Formats the Groth16 vkey hash selector of a proof, if it has one.
+pub(crate) fn selector(proof: &[u8]) -> String {
+    proof
+        .get(..crate::SELECTOR_LEN)
+        .map(hex::encode)
+        .unwrap_or_default()
+}
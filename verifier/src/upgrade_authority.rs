@@ -0,0 +1,60 @@
+//! The upgrade authority of a program deployed with the upgradeable BPF loader.
+//!
+//! An initialization instruction that makes its signer the admin of a PDA, e.g. of a verifier
+//! registry or a verification key account, can be front-run: whoever calls it first controls the
+//! PDA, and can make the program accept proofs of their own key. Programs gate such instructions on
+//! their upgrade authority, read with [`upgrade_authority`] from the program's `ProgramData`
+//! account.
+
+use solana_program::{
+    account_info::AccountInfo, bpf_loader_upgradeable, program_error::ProgramError, pubkey::Pubkey,
+};
+
+/// The tag of `UpgradeableLoaderState::ProgramData`, a little-endian `u32`.
+const PROGRAM_DATA_TAG: [u8; 4] = 3u32.to_le_bytes();
+
+/// The offset of the upgrade authority in a `ProgramData` account: after the tag and the `u64`
+/// slot of the last deployment.
+const AUTHORITY_OFFSET: usize = 4 + 8;
+
+/// The address of the `ProgramData` account of `program_id`.
+pub fn program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+/// Returns the upgrade authority of `program_id`, or `None` if the program is immutable.
+///
+/// `program_data` must be the `ProgramData` account of `program_id`, at
+/// [`program_data_address`], or this fails with [`ProgramError::InvalidAccountData`].
+pub fn upgrade_authority(
+    program_id: &Pubkey,
+    program_data: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    if *program_data.owner != bpf_loader_upgradeable::id()
+        || *program_data.key != program_data_address(program_id)
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let data = program_data.try_borrow_data()?;
+    let (tag, authority) = data
+        .get(..AUTHORITY_OFFSET + 1 + 32)
+        .ok_or(ProgramError::InvalidAccountData)?
+        .split_at(AUTHORITY_OFFSET);
+    if tag[..4] != PROGRAM_DATA_TAG {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    match authority.split_first() {
+        Some((&0, _)) => Ok(None),
+        Some((&1, authority)) => Ok(Some(Pubkey::try_from(authority).unwrap())),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Whether `authority` signed the instruction and is the upgrade authority of `program_id`.
+pub fn is_upgrade_authority(
+    program_id: &Pubkey,
+    program_data: &AccountInfo,
+    authority: &AccountInfo,
+) -> Result<bool, ProgramError> {
+    Ok(authority.is_signer && upgrade_authority(program_id, program_data)? == Some(*authority.key))
+}
@@ -4,49 +4,465 @@
 //! for loading proofs into a form appropriate for verification. This is necessary to coerce
 //! SP1 Groth16 proofs into the form expected by the `groth16_solana` crate.
 
+#[cfg(feature = "ark")]
 use ark_bn254::{Fq, G1Affine};
+#[cfg(feature = "ark")]
 use ark_ff::PrimeField;
+#[cfg(feature = "ark")]
 use ark_serialize::CanonicalSerialize;
+use core::cell::OnceCell;
+use num_bigint::BigUint;
+#[cfg(not(target_os = "solana"))]
 use sha2::{Digest, Sha256};
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+use crate::CommittedValuesDigest;
+
+/// The underlying error of an [`Error`], such as the `std::io::Error` of a failed fixture read.
+///
+/// Sources are only kept off-chain with the `std` feature, so that on-chain builds carry no `std`
+/// error types: there, this is always `None`.
+#[cfg(all(feature = "std", not(target_os = "solana")))]
+pub type ErrorSource = Option<std::sync::Arc<dyn std::error::Error + Send + Sync>>;
+
+/// The underlying error of an [`Error`], always `None` without the `std` feature and on-chain.
+#[cfg(not(all(feature = "std", not(target_os = "solana"))))]
+pub type ErrorSource = Option<NoSource>;
+
+/// The type of the sources of errors when none are kept. It has no values.
+#[cfg(not(all(feature = "std", not(target_os = "solana"))))]
+#[derive(Debug, Clone)]
+pub enum NoSource {}
+
+#[cfg(not(all(feature = "std", not(target_os = "solana"))))]
+impl core::fmt::Display for NoSource {
+    fn fmt(&self, _: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {}
+    }
+}
+
+#[cfg(not(all(feature = "std", not(target_os = "solana"))))]
+impl std::error::Error for NoSource {}
+
+/// Keeps `error` as the source of an [`Error`].
+#[cfg(all(feature = "std", not(target_os = "solana")))]
+pub(crate) fn source(error: impl std::error::Error + Send + Sync + 'static) -> ErrorSource {
+    Some(std::sync::Arc::new(error))
+}
+
+/// Drops `error`, as sources aren't kept without the `std` feature and on-chain.
+#[cfg(not(all(feature = "std", not(target_os = "solana"))))]
+pub(crate) fn source<E>(_error: E) -> ErrorSource {
+    None
+}
+
+/// The errors of the verifier.
+///
+/// Errors compare equal when they are the same variant with the same fields, regardless of their
+/// sources; [`same_kind`](Self::same_kind) compares variants only. On-chain, each is reported by its
+/// [`code`](Self::code), which is its position in this enum.
+#[derive(Error, Debug, Clone)]
+#[repr(u32)]
 pub enum Error {
     #[error("G1 compression error")]
     G1CompressionError,
     #[error("G2 compression error")]
     G2CompressionError,
+    /// The pairing check failed: the proof's points are on the curve, but it doesn't prove these
+    /// public inputs against this verification key.
+    ///
+    /// A proof whose pi_a was pre-negated with `negate_proof_a` also fails with this error when
+    /// passed to `verify_proof_raw`; such proofs must go through `verify_proof_raw_prenegated`.
     #[error("Verification error")]
     VerificationError,
+    /// A public input isn't below the scalar field modulus, or their number doesn't match the
+    /// verification key. A committed values digest with any of its top 3 bits set is rejected
+    /// with this error too.
     #[error("Invalid public input")]
     InvalidPublicInput,
     #[error("Serialization error")]
     SerializationError,
     #[error("Deserialization error")]
-    DeserializationError,
+    DeserializationError(#[source] ErrorSource),
     #[error("Invalid instruction data")]
     InvalidInstructionData,
     #[error("Arithmetic error")]
     ArithmeticError,
     #[error("Pairing error")]
     PairingError,
+    /// An input is truncated or malformed, e.g. a proof point isn't on the curve.
     #[error("Invalid input")]
     InvalidInput,
     #[error("Borsh serialization error")]
-    BorshSerializeError,
+    BorshSerializeError(#[source] ErrorSource),
     #[error("Borsh deserialization error")]
-    BorshDeserializeError,
+    BorshDeserializeError(#[source] ErrorSource),
     #[error("IO error")]
-    IoError,
+    IoError(#[source] ErrorSource),
     #[error("Groth16 vkey hash mismatch")]
     Groth16VkeyHashMismatch,
+    /// The program vkey hash isn't `0x` followed by 64 hex digits, or its first byte isn't zero.
     #[error("Invalid program vkey hash")]
     InvalidProgramVkeyHash,
+    #[error("Public values don't match the committed values digest")]
+    PublicValuesDigestMismatch,
+    #[error("Input exceeds its maximum length")]
+    InputTooLarge,
+    #[error("The aggregation proof doesn't include the expected execution")]
+    ExecutionNotAggregated,
+    /// The crate was built without any of the `embedded-vk-*` features, so there is no verification
+    /// key to pick from a proof's selector.
+    #[error("No embedded Groth16 verification key is available")]
+    NoVkAvailable,
+    /// The proof has none of the lengths the entry point accepts. The most common cause is passing
+    /// the 260-byte output of `SP1ProofWithPublicValues::bytes()` where the 256-byte raw proof is
+    /// expected, or the reverse.
+    #[error(
+        "Invalid proof length: expected one of {expected_one_of:?} bytes, got {got}. Proofs from \
+         SP1ProofWithPublicValues::bytes() are 260 bytes: a 4-byte Groth16 vkey hash selector, \
+         then the 256-byte raw proof"
+    )]
+    InvalidProofLength {
+        expected_one_of: &'static [usize],
+        got: usize,
+    },
+    /// An input of a strict entry point, e.g. `verify_proof_raw_exact`, continues past what its
+    /// parser reads. The other entry points ignore trailing bytes after verifying keys and
+    /// borrowed fixtures.
+    #[error("Trailing bytes after the {section}: {extra} bytes")]
+    TrailingBytes { section: InputSection, extra: usize },
+    /// `verify_proof_any_of` was given no program vkey hash the proof is valid for.
+    #[error("The proof is valid for none of the {candidates} allowed program vkey hashes")]
+    NoVkeyHashMatched { candidates: usize },
+    /// The proof's selector is that of a known SP1 release whose verification key isn't compiled
+    /// in, typically because `sp1-sdk` and this crate were upgraded separately.
+    #[error(
+        "The proof is from SP1 {detected}, whose Groth16 verification key isn't compiled in; \
+         supported versions: {supported:?}"
+    )]
+    UnsupportedSp1Version {
+        detected: &'static str,
+        supported: &'static [&'static str],
+    },
+    /// The public values don't start with the schema hash of a version of the type they are
+    /// decoded as, e.g. because the program committed fields the verifier doesn't know of yet.
+    #[error("The public values' schema hash is that of no version of the expected schema")]
+    SchemaHashMismatch,
+    /// The length prefix of serialized public values exceeds the limit of their parser, by default
+    /// [`MAX_SP1_PUBLIC_VALUES_LEN`](crate::MAX_SP1_PUBLIC_VALUES_LEN). It is checked before
+    /// anything is allocated, so a forged length can't exhaust the heap.
+    #[error("The public values are {len} bytes long, above the limit of {max}")]
+    PublicValuesTooLarge { len: usize, max: usize },
+}
+
+/// The input an [`Error::TrailingBytes`] was found in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputSection {
+    Proof,
+    PublicInputs,
+    Vk,
+    Fixture,
+    PublicValues,
+}
+
+impl std::fmt::Display for InputSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Proof => "proof",
+            Self::PublicInputs => "public inputs",
+            Self::Vk => "verifying key",
+            Self::Fixture => "fixture",
+            Self::PublicValues => "public values",
+        })
+    }
+}
+
+impl Error {
+    /// The numeric code of the error, reported on-chain as `ProgramError::Custom(code)`.
+    pub fn code(&self) -> u32 {
+        // SAFETY: `Error` is `repr(u32)`, so it starts with its `u32` discriminant, which counts
+        // the variants from 0.
+        unsafe { *(self as *const Self).cast::<u32>() }
+    }
+
+    /// Whether `self` and `other` are the same variant, whatever their fields and sources.
+    pub fn same_kind(&self, other: &Self) -> bool {
+        self.code() == other.code()
+    }
+
+    /// The name of the variant, e.g. `"Groth16VkeyHashMismatch"`. Like codes, names never change.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::G1CompressionError => "G1CompressionError",
+            Self::G2CompressionError => "G2CompressionError",
+            Self::VerificationError => "VerificationError",
+            Self::InvalidPublicInput => "InvalidPublicInput",
+            Self::SerializationError => "SerializationError",
+            Self::DeserializationError(_) => "DeserializationError",
+            Self::InvalidInstructionData => "InvalidInstructionData",
+            Self::ArithmeticError => "ArithmeticError",
+            Self::PairingError => "PairingError",
+            Self::InvalidInput => "InvalidInput",
+            Self::BorshSerializeError(_) => "BorshSerializeError",
+            Self::BorshDeserializeError(_) => "BorshDeserializeError",
+            Self::IoError(_) => "IoError",
+            Self::Groth16VkeyHashMismatch => "Groth16VkeyHashMismatch",
+            Self::InvalidProgramVkeyHash => "InvalidProgramVkeyHash",
+            Self::PublicValuesDigestMismatch => "PublicValuesDigestMismatch",
+            Self::InputTooLarge => "InputTooLarge",
+            Self::ExecutionNotAggregated => "ExecutionNotAggregated",
+            Self::NoVkAvailable => "NoVkAvailable",
+            Self::InvalidProofLength { .. } => "InvalidProofLength",
+            Self::TrailingBytes { .. } => "TrailingBytes",
+            Self::NoVkeyHashMatched { .. } => "NoVkeyHashMatched",
+            Self::UnsupportedSp1Version { .. } => "UnsupportedSp1Version",
+            Self::SchemaHashMismatch => "SchemaHashMismatch",
+            Self::PublicValuesTooLarge { .. } => "PublicValuesTooLarge",
+        }
+    }
+
+    /// The error of a failed Borsh deserialization: the verifier error it carries, such as
+    /// [`Error::PublicValuesTooLarge`], or else [`Error::BorshDeserializeError`].
+    pub fn from_borsh(error: borsh::io::Error) -> Self {
+        match error
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<Self>())
+        {
+            Some(inner) => inner.clone(),
+            None => Self::BorshDeserializeError(source(error)),
+        }
+    }
+}
+
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // Sources can't be compared, so errors wrapping one compare by variant.
+            (Self::DeserializationError(_), Self::DeserializationError(_))
+            | (Self::BorshSerializeError(_), Self::BorshSerializeError(_))
+            | (Self::BorshDeserializeError(_), Self::BorshDeserializeError(_))
+            | (Self::IoError(_), Self::IoError(_)) => true,
+            (
+                Self::InvalidProofLength {
+                    expected_one_of,
+                    got,
+                },
+                Self::InvalidProofLength {
+                    expected_one_of: other_expected_one_of,
+                    got: other_got,
+                },
+            ) => expected_one_of == other_expected_one_of && got == other_got,
+            (
+                Self::TrailingBytes { section, extra },
+                Self::TrailingBytes {
+                    section: other_section,
+                    extra: other_extra,
+                },
+            ) => section == other_section && extra == other_extra,
+            (
+                Self::NoVkeyHashMatched { candidates },
+                Self::NoVkeyHashMatched {
+                    candidates: other_candidates,
+                },
+            ) => candidates == other_candidates,
+            (
+                Self::UnsupportedSp1Version {
+                    detected,
+                    supported,
+                },
+                Self::UnsupportedSp1Version {
+                    detected: other_detected,
+                    supported: other_supported,
+                },
+            ) => detected == other_detected && supported == other_supported,
+            (
+                Self::PublicValuesTooLarge { len, max },
+                Self::PublicValuesTooLarge {
+                    len: other_len,
+                    max: other_max,
+                },
+            ) => len == other_len && max == other_max,
+            // Any other pair is either of different variants or of the same fieldless one.
+            _ => self.same_kind(other),
+        }
+    }
+}
+
+impl Eq for Error {}
+
+#[cfg(all(feature = "std", not(target_os = "solana")))]
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(source(error))
+    }
+}
+
+/// Anchor programs number their own `#[error_code]`s from 6000, so verifier errors are reported
+/// from 7000 to keep the two apart.
+#[cfg(feature = "anchor")]
+pub const ANCHOR_ERROR_CODE_OFFSET: u32 = 7000;
+
+#[cfg(feature = "anchor")]
+impl From<Error> for anchor_lang::error::Error {
+    fn from(error: Error) -> Self {
+        anchor_lang::error::AnchorError {
+            error_name: error.name().to_string(),
+            error_code_number: ANCHOR_ERROR_CODE_OFFSET + error.code(),
+            error_msg: error.to_string(),
+            error_origin: None,
+            compared_values: None,
+        }
+        .into()
+    }
+}
+
+/// Verifier errors are reported as `ProgramError::Custom(error.code())`.
+#[cfg(feature = "solana")]
+impl From<Error> for solana_program::program_error::ProgramError {
+    fn from(error: Error) -> Self {
+        solana_program::program_error::ProgramError::Custom(error.code())
+    }
 }
 
 const SCALAR_LEN: usize = 32;
 const G1_LEN: usize = 64;
 const G2_LEN: usize = 128;
+const G1_COMPRESSED_LEN: usize = 32;
+const G2_COMPRESSED_LEN: usize = 64;
+
+/// The number of IC points of the verifying keys of SP1 programs: one per public input, plus one.
+/// [`VkView`] stores up to this many inline, so that loading such keys doesn't allocate.
+const MAX_VK_IC_LEN: usize = 3;
+
+/// The offset of the IC count in a gnark verifying key, after the alpha, beta, gamma and delta
+/// points, with beta and delta in both G1 and G2.
+const VK_NUM_IC_OFFSET: usize = 3 * G1_COMPRESSED_LEN + 3 * G2_COMPRESSED_LEN;
+
+/// The offset of the first IC point in a gnark verifying key.
+const VK_IC_OFFSET: usize = VK_NUM_IC_OFFSET + 4;
+
+/// The length of the Pedersen commitment key closing the verifying keys of SP1 v2: two G2 points.
+/// Keys of SP1 v3 end with a zero count of commitment keys instead.
+const VK_COMMITMENT_KEY_LEN: usize = 2 * G2_COMPRESSED_LEN;
+
+/// The length of the selector prefixing SP1 Groth16 proofs: the first bytes of the SHA-256 hash
+/// of the Groth16 verifying key, e.g. [`GROTH16_VK_3_0_0_HASH`](crate::GROTH16_VK_3_0_0_HASH).
+pub const SELECTOR_LEN: usize = 4;
+
+/// The length of a raw Groth16 proof: pi_a, pi_b and pi_c, uncompressed.
+pub const PROOF_LEN: usize = G1_LEN + G2_LEN + G1_LEN;
+
+/// The length of a proof in SP1's standard format, the output of
+/// `SP1ProofWithPublicValues::bytes()`: the selector, then the raw proof.
+pub const PROOF_WITH_SELECTOR_LEN: usize = SELECTOR_LEN + PROOF_LEN;
+
+/// The length of the Groth16 public inputs: the SP1 vkey hash without its first byte, which is
+/// always zero, then the committed values digest.
+pub const GROTH16_PUBLIC_INPUTS_LEN: usize = SCALAR_LEN - 1 + SCALAR_LEN;
+
+/// The length of the smallest verifying key the loaders accept: its points up to the IC count,
+/// the IC count, one IC point, and the (empty) count of committed wire arrays.
+pub const GROTH16_VK_MIN_LEN: usize = VK_IC_OFFSET + G1_COMPRESSED_LEN + 4;
+
+/// Whether `vk` is laid out as the loaders expect of the keys of SP1 programs: long enough, with
+/// one IC point per public input plus one, and no committed wires.
+#[cfg_attr(not(feature = "embedded-vk"), allow(dead_code))]
+const fn is_sp1_vk_layout(vk: &[u8]) -> bool {
+    if vk.len() < GROTH16_VK_MIN_LEN {
+        return false;
+    }
+    let num_ic = u32::from_be_bytes([
+        vk[VK_NUM_IC_OFFSET],
+        vk[VK_NUM_IC_OFFSET + 1],
+        vk[VK_NUM_IC_OFFSET + 2],
+        vk[VK_NUM_IC_OFFSET + 3],
+    ]) as usize;
+    let committed_offset = VK_IC_OFFSET + num_ic * G1_COMPRESSED_LEN;
+    num_ic == MAX_VK_IC_LEN
+        && vk.len() >= committed_offset + 4
+        && vk[committed_offset] == 0
+        && vk[committed_offset + 1] == 0
+        && vk[committed_offset + 2] == 0
+        && vk[committed_offset + 3] == 0
+}
+
+// The embedded verifying keys match the constants, and the public inputs fill the IC points.
+const _: () = {
+    #[cfg(feature = "embedded-vk-v3-0-0")]
+    assert!(is_sp1_vk_layout(crate::GROTH16_VK_3_0_0_BYTES));
+    #[cfg(feature = "embedded-vk-v3-0-0-rc4")]
+    assert!(is_sp1_vk_layout(crate::GROTH16_VK_3_0_0_RC4_BYTES));
+    #[cfg(feature = "embedded-vk-v2-0-0")]
+    assert!(is_sp1_vk_layout(crate::GROTH16_VK_2_0_0_BYTES));
+    assert!(GROTH16_PUBLIC_INPUTS_LEN + 1 == (MAX_VK_IC_LEN - 1) * SCALAR_LEN);
+};
+
+/// Checks that `proof` is in SP1's standard format, [`PROOF_WITH_SELECTOR_LEN`] bytes long.
+pub fn validate_proof_len(proof: &[u8]) -> Result<(), Error> {
+    if proof.len() != PROOF_WITH_SELECTOR_LEN {
+        return Err(Error::InvalidProofLength {
+            expected_one_of: &[PROOF_WITH_SELECTOR_LEN],
+            got: proof.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `proof` is a raw proof, [`PROOF_LEN`] bytes long.
+pub fn validate_raw_proof_len(proof: &[u8]) -> Result<(), Error> {
+    if proof.len() != PROOF_LEN {
+        return Err(Error::InvalidProofLength {
+            expected_one_of: &[PROOF_LEN],
+            got: proof.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Returns the raw proof of `proof`, which is either raw, [`PROOF_LEN`] bytes long, or in SP1's
+/// standard format, [`PROOF_WITH_SELECTOR_LEN`] bytes long.
+///
+/// The selector of a proof in the standard format must be `groth16_vk_hash`, which is only
+/// computed for such proofs.
+pub(crate) fn strip_selector(
+    proof: &[u8],
+    groth16_vk_hash: impl FnOnce() -> [u8; SELECTOR_LEN],
+) -> Result<&[u8], Error> {
+    let proof = crate::proof_bytes::split_proof(proof)?;
+    crate::proof_bytes::check_selector(proof, groth16_vk_hash).map(|proof| &proof[..])
+}
+
+/// Checks that `public_inputs` are [`GROTH16_PUBLIC_INPUTS_LEN`] bytes long.
+pub fn validate_public_inputs_len(public_inputs: &[u8]) -> Result<(), Error> {
+    if public_inputs.len() != GROTH16_PUBLIC_INPUTS_LEN {
+        return Err(Error::InvalidPublicInput);
+    }
+    Ok(())
+}
+
+/// Fails with [`Error::TrailingBytes`] if `section`, `len` bytes long, continues past the
+/// `consumed` bytes its parser reads.
+pub(crate) fn check_trailing_bytes(
+    section: InputSection,
+    len: usize,
+    consumed: usize,
+) -> Result<(), Error> {
+    if len > consumed {
+        return Err(Error::TrailingBytes {
+            section,
+            extra: len - consumed,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `vk` is at least [`GROTH16_VK_MIN_LEN`] bytes long.
+pub fn validate_vk_len(vk: &[u8]) -> Result<(), Error> {
+    if vk.len() < GROTH16_VK_MIN_LEN {
+        return Err(Error::InvalidInput);
+    }
+    Ok(())
+}
 
 /// Everything needed to verify a Groth16 proof.
 #[allow(dead_code)]
@@ -59,7 +475,7 @@ pub struct Verifier<'a, const N_PUBLIC: usize> {
     vk: &'a VerificationKey,
 }
 
-/// A Groth16 proof.
+/// A Groth16 proof, with pi_a as the prover wrote it.
 ///
 /// All Group elements are represented in uncompressed form.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -69,6 +485,15 @@ pub struct Proof {
     pub pi_c: [u8; 64],
 }
 
+/// A [`Proof`] whose pi_a is negated, the form the pairing check takes.
+///
+/// [`verify`](crate::verify) negates the pi_a of a [`Proof`], while
+/// [`verify_negated`](crate::verify_negated) takes a `NegatedProof` as is. Negating pi_a twice
+/// fails the pairing check, so the two forms are kept apart in the type: a `NegatedProof` is only
+/// built by negating a [`Proof`], or from bytes documented as pre-negated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegatedProof(Proof);
+
 /// A generic Groth16 verification key over BN254.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VerificationKey {
@@ -80,38 +505,111 @@ pub struct VerificationKey {
     pub vk_ic: Vec<[u8; G1_LEN]>,
 }
 
+/// The IC points of a verifying key loaded by [`VkView`]: inline for keys of up to
+/// [`MAX_VK_IC_LEN`] points, such as those of SP1 programs, and on the heap for larger ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum IcPoints {
+    Inline {
+        points: [[u8; G1_LEN]; MAX_VK_IC_LEN],
+        len: usize,
+    },
+    Heap(Vec<[u8; G1_LEN]>),
+}
+
+impl IcPoints {
+    /// An empty container for `capacity` points, which only allocates for more than
+    /// [`MAX_VK_IC_LEN`].
+    fn with_capacity(capacity: usize) -> Self {
+        if capacity <= MAX_VK_IC_LEN {
+            Self::Inline {
+                points: [[0u8; G1_LEN]; MAX_VK_IC_LEN],
+                len: 0,
+            }
+        } else {
+            Self::Heap(Vec::with_capacity(capacity))
+        }
+    }
+
+    fn push(&mut self, point: [u8; G1_LEN]) {
+        match self {
+            Self::Inline { points, len } if *len < MAX_VK_IC_LEN => {
+                points[*len] = point;
+                *len += 1;
+            }
+            Self::Inline { points, len } => {
+                let mut heap = points[..*len].to_vec();
+                heap.push(point);
+                *self = Self::Heap(heap);
+            }
+            Self::Heap(points) => points.push(point),
+        }
+    }
+
+    fn as_slice(&self) -> &[[u8; G1_LEN]] {
+        match self {
+            Self::Inline { points, len } => &points[..*len],
+            Self::Heap(points) => points,
+        }
+    }
+}
+
 /// The public inputs for a Groth16 proof.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PublicInputs<const N: usize> {
     pub inputs: [[u8; SCALAR_LEN]; N],
 }
 
+impl PublicInputs<2> {
+    /// Reads the 63-byte public inputs of an SP1 proof, as formatted by [`groth16_public_inputs`]:
+    /// the program vkey hash without its first byte, then the committed values digest.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self, Error> {
+        load_public_inputs_from_bytes(buffer)
+    }
+}
+
+/// Compile-time check that `ARRAY_SIZE` is a non-zero multiple of `CHUNK_SIZE`.
+struct ChunkSizeCheck<const CHUNK_SIZE: usize, const ARRAY_SIZE: usize>;
+
+impl<const CHUNK_SIZE: usize, const ARRAY_SIZE: usize> ChunkSizeCheck<CHUNK_SIZE, ARRAY_SIZE> {
+    const OK: () = assert!(
+        CHUNK_SIZE > 0 && ARRAY_SIZE % CHUNK_SIZE == 0,
+        "ARRAY_SIZE must be a multiple of CHUNK_SIZE"
+    );
+}
+
 /// Convert the endianness of a byte array, chunk by chunk.
 ///
-/// Taken from https://github.com/anza-xyz/agave/blob/c54d840/curves/bn254/src/compression.rs#L176-L189
-fn convert_endianness<const CHUNK_SIZE: usize, const ARRAY_SIZE: usize>(
+/// Instantiating this with an `ARRAY_SIZE` that is not a multiple of `CHUNK_SIZE` is a compile
+/// error. Being a `const fn`, it can also be used to convert constants at compile time.
+///
+/// Adapted from https://github.com/anza-xyz/agave/blob/c54d840/curves/bn254/src/compression.rs#L176-L189
+pub const fn convert_endianness<const CHUNK_SIZE: usize, const ARRAY_SIZE: usize>(
     bytes: &[u8; ARRAY_SIZE],
 ) -> [u8; ARRAY_SIZE] {
-    let reversed: [_; ARRAY_SIZE] = bytes
-        .chunks_exact(CHUNK_SIZE)
-        .flat_map(|chunk| chunk.iter().rev().copied())
-        .enumerate()
-        .fold([0u8; ARRAY_SIZE], |mut acc, (i, v)| {
-            acc[i] = v;
-            acc
-        });
+    #[allow(clippy::let_unit_value)]
+    let () = ChunkSizeCheck::<CHUNK_SIZE, ARRAY_SIZE>::OK;
+
+    let mut reversed = [0u8; ARRAY_SIZE];
+    let mut i = 0;
+    while i < ARRAY_SIZE {
+        let chunk_start = i - i % CHUNK_SIZE;
+        reversed[i] = bytes[chunk_start + CHUNK_SIZE - 1 - i % CHUNK_SIZE];
+        i += 1;
+    }
     reversed
 }
 
-fn decompress_g1(g1_bytes: &[u8; 32]) -> Result<[u8; 64], Error> {
+#[inline(never)]
+pub(crate) fn decompress_g1(g1_bytes: &[u8; 32]) -> Result<[u8; 64], Error> {
     let g1_bytes = gnark_compressed_x_to_ark_compressed_x(g1_bytes)?;
-    let g1_bytes = convert_endianness::<32, 32>(&g1_bytes.as_slice().try_into().unwrap());
+    let g1_bytes = convert_endianness::<32, 32>(&g1_bytes);
     groth16_solana::decompression::decompress_g1(&g1_bytes).map_err(|_| Error::G1CompressionError)
 }
 
-fn decompress_g2(g2_bytes: &[u8; 64]) -> Result<[u8; 128], Error> {
+#[inline(never)]
+pub(crate) fn decompress_g2(g2_bytes: &[u8; 64]) -> Result<[u8; 128], Error> {
     let g2_bytes = gnark_compressed_x_to_ark_compressed_x(g2_bytes)?;
-    let g2_bytes = convert_endianness::<64, 64>(&g2_bytes.as_slice().try_into().unwrap());
+    let g2_bytes = convert_endianness::<64, 64>(&g2_bytes);
     groth16_solana::decompression::decompress_g2(&g2_bytes).map_err(|_| Error::G2CompressionError)
 }
 
@@ -125,7 +623,11 @@ const ARK_COMPRESSED_POSTIVE: u8 = 0b00 << 6;
 const ARK_COMPRESSED_NEGATIVE: u8 = 0b10 << 6;
 const ARK_COMPRESSED_INFINITY: u8 = 0b01 << 6;
 
-fn gnark_flag_to_ark_flag(msb: u8) -> Result<u8, Error> {
+/// Converts the flags in the most significant byte of a gnark compressed point, `0b10` if y is the
+/// smaller of y and -y, `0b11` if it is the larger, and `0b01` for the point at infinity, to the
+/// flags of arkworks, where `0b00` is the smaller and `0b10` the larger. Uncompressed points,
+/// flagged `0b00`, are rejected.
+pub(crate) fn gnark_flag_to_ark_flag(msb: u8) -> Result<u8, Error> {
     let gnark_flag = msb & GNARK_MASK;
 
     let ark_flag = match gnark_flag {
@@ -140,11 +642,22 @@ fn gnark_flag_to_ark_flag(msb: u8) -> Result<u8, Error> {
     Ok(msb & !ARK_MASK | ark_flag)
 }
 
-fn gnark_compressed_x_to_ark_compressed_x(x: &[u8]) -> Result<Vec<u8>, Error> {
-    if x.len() != 32 && x.len() != 64 {
+/// Converts a big-endian gnark compressed G1 (`N = 32`) or G2 (`N = 64`) point to the
+/// little-endian arkworks encoding.
+pub(crate) fn gnark_compressed_x_to_ark_compressed_x<const N: usize>(
+    x: &[u8; N],
+) -> Result<[u8; N], Error> {
+    if N != 32 && N != 64 {
+        return Err(Error::InvalidInput);
+    }
+    // gnark encodes the point at infinity as its flag alone. arkworks ignores the other bits of an
+    // infinity, so they are rejected here rather than accepted as more encodings of the point.
+    if x[0] & GNARK_MASK == GNARK_COMPRESSED_INFINITY
+        && (x[0] & !GNARK_MASK != 0 || x[1..].iter().any(|&byte| byte != 0))
+    {
         return Err(Error::InvalidInput);
     }
-    let mut x_copy = x.to_owned();
+    let mut x_copy = *x;
 
     let msb = gnark_flag_to_ark_flag(x_copy[0])?;
     x_copy[0] = msb;
@@ -153,6 +666,140 @@ fn gnark_compressed_x_to_ark_compressed_x(x: &[u8]) -> Result<Vec<u8>, Error> {
     Ok(x_copy)
 }
 
+/// The BN254 base field modulus, big-endian.
+pub(crate) const BN254_BASE_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+/// Computes `a - b` on 32-byte big-endian integers, assuming `a >= b`.
+fn sub_be(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut result = [0u8; 32];
+    let mut borrow = 0u16;
+    for i in (0..32).rev() {
+        let diff = (a[i] as u16).wrapping_sub(b[i] as u16 + borrow);
+        result[i] = diff as u8;
+        borrow = (diff >> 15) & 1;
+    }
+    result
+}
+
+/// Reduces a 32-byte big-endian integer modulo the BN254 base field modulus.
+fn reduce_mod_p(mut a: [u8; 32]) -> [u8; 32] {
+    // Big-endian byte arrays compare lexicographically, which is numeric order. Since
+    // 2^256 < 6p, this loops at most five times.
+    while a >= BN254_BASE_FIELD_MODULUS {
+        a = sub_be(&a, &BN254_BASE_FIELD_MODULUS);
+    }
+    a
+}
+
+/// Negates an uncompressed, big-endian G1 point with plain big-integer arithmetic: `(x, p - y)`.
+///
+/// This avoids pulling arkworks into the on-chain build. Like the arkworks implementation, both
+/// coordinates are reduced modulo p first.
+#[cfg_attr(feature = "ark", allow(dead_code))]
+pub(crate) fn negate_g1_be(g1_bytes: &[u8; 64]) -> [u8; 64] {
+    let x = reduce_mod_p(g1_bytes[..32].try_into().unwrap());
+    let y = reduce_mod_p(g1_bytes[32..].try_into().unwrap());
+
+    let mut negated = [0u8; 64];
+    negated[..32].copy_from_slice(&x);
+    if y != [0u8; 32] {
+        negated[32..].copy_from_slice(&sub_be(&BN254_BASE_FIELD_MODULUS, &y));
+    }
+    negated
+}
+
+/// Rejects G1 points with a coordinate of `p` or more.
+///
+/// Negation reduces the coordinates, so without this check `(x + p, y)` would be accepted as pi_a
+/// wherever `(x, y)` is, making proofs malleable. `ark-groth16` rejects such encodings.
+fn check_canonical_g1(g1_bytes: &[u8; 64]) -> Result<(), Error> {
+    if g1_bytes[..32] >= BN254_BASE_FIELD_MODULUS[..]
+        || g1_bytes[32..] >= BN254_BASE_FIELD_MODULUS[..]
+    {
+        return Err(Error::InvalidInput);
+    }
+    Ok(())
+}
+
+/// The BN254 scalar field modulus, big-endian. Groth16 public inputs are scalars below it.
+pub(crate) const BN254_SCALAR_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Rejects public inputs of `r` or more, which aren't scalars.
+pub(crate) fn check_public_inputs<const N: usize>(
+    public_inputs: &[[u8; 32]; N],
+) -> Result<(), Error> {
+    if public_inputs
+        .iter()
+        .any(|input| input[..] >= BN254_SCALAR_FIELD_MODULUS[..])
+    {
+        return Err(Error::InvalidPublicInput);
+    }
+    Ok(())
+}
+
+/// The coefficient `b` of the twist `y^2 = x^3 + b` of G2, `3 / (9 + u)`, as `(c0, c1)`.
+const BN254_G2_COEFF_B: [&str; 2] = [
+    "19485874751759354771024239261021720505790618469301721065564631296452457478373",
+    "266929791119991161246907387137283842545076965332900288569378510910307636690",
+];
+
+/// Multiplies two elements of Fp2 = Fp[u] / (u^2 + 1), as `(c0, c1)` with both below `p`.
+fn fp2_mul(a: &(BigUint, BigUint), b: &(BigUint, BigUint), p: &BigUint) -> (BigUint, BigUint) {
+    (
+        (&a.0 * &b.0 + p * p - &a.1 * &b.1) % p,
+        (&a.0 * &b.1 + &a.1 * &b.0) % p,
+    )
+}
+
+/// Checks that an uncompressed, big-endian G1 point is on the curve `y^2 = x^3 + 3`, with
+/// coordinates below `p`, or is the point at infinity, encoded as zeros.
+///
+/// This uses `num-bigint`, and only runs once a proof has failed verification.
+pub(crate) fn is_g1_on_curve(g1_bytes: &[u8; 64]) -> bool {
+    if g1_bytes.iter().all(|&byte| byte == 0) {
+        return true;
+    }
+    let p = BigUint::from_bytes_be(&BN254_BASE_FIELD_MODULUS);
+    let [x, y] = [&g1_bytes[..32], &g1_bytes[32..]].map(BigUint::from_bytes_be);
+    if x >= p || y >= p {
+        return false;
+    }
+    (&y * &y) % &p == (&x * &x * &x + 3u32) % &p
+}
+
+/// Checks that an uncompressed, big-endian G2 point, `x.c1 || x.c0 || y.c1 || y.c0`, is on the
+/// twist `y^2 = x^3 + 3 / (9 + u)`, with coordinates below `p`, or is the point at infinity.
+///
+/// Like [`is_g1_on_curve`], this is only meant for proofs that failed verification.
+pub(crate) fn is_g2_on_curve(g2_bytes: &[u8; 128]) -> bool {
+    if g2_bytes.iter().all(|&byte| byte == 0) {
+        return true;
+    }
+    let p = BigUint::from_bytes_be(&BN254_BASE_FIELD_MODULUS);
+    let [x1, x0, y1, y0] =
+        [0, 32, 64, 96].map(|offset| BigUint::from_bytes_be(&g2_bytes[offset..offset + 32]));
+    if [&x1, &x0, &y1, &y0].iter().any(|c| **c >= p) {
+        return false;
+    }
+    let (x, y) = ((x0, x1), (y0, y1));
+    let [b0, b1] = BN254_G2_COEFF_B.map(|c| c.parse::<BigUint>().unwrap());
+    let x3 = fp2_mul(&fp2_mul(&x, &x, &p), &x, &p);
+    fp2_mul(&y, &y, &p) == ((x3.0 + b0) % &p, (x3.1 + b1) % &p)
+}
+
+#[cfg(not(feature = "ark"))]
+pub(crate) fn negate_g1(g1_bytes: &[u8; 64]) -> Result<[u8; 64], Error> {
+    check_canonical_g1(g1_bytes)?;
+    Ok(negate_g1_be(g1_bytes))
+}
+
+#[cfg(feature = "ark")]
 fn uncompressed_bytes_to_g1_point(buf: &[u8]) -> Result<G1Affine, Error> {
     if buf.len() != 64 {
         return Err(Error::InvalidInput);
@@ -166,7 +813,9 @@ fn uncompressed_bytes_to_g1_point(buf: &[u8]) -> Result<G1Affine, Error> {
     Ok(G1Affine::new_unchecked(x, y))
 }
 
-fn negate_g1(g1_bytes: &[u8; 64]) -> Result<[u8; 64], Error> {
+#[cfg(feature = "ark")]
+pub(crate) fn negate_g1(g1_bytes: &[u8; 64]) -> Result<[u8; 64], Error> {
+    check_canonical_g1(g1_bytes)?;
     let g1 = -uncompressed_bytes_to_g1_point(g1_bytes)?;
     let mut g1_bytes = [0u8; 64];
     g1.serialize_uncompressed(&mut g1_bytes[..])
@@ -176,70 +825,288 @@ fn negate_g1(g1_bytes: &[u8; 64]) -> Result<[u8; 64], Error> {
     ))
 }
 
-pub(crate) fn load_proof_from_bytes(buffer: &[u8]) -> Result<Proof, Error> {
+impl Proof {
+    /// Reads a raw 256-byte proof.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self, Error> {
+        load_raw_proof_from_bytes(buffer)
+    }
+
+    /// Returns this proof with pi_a negated, which is the form the pairing check expects.
+    pub fn with_negated_a(self) -> Result<NegatedProof, Error> {
+        Ok(NegatedProof(Proof {
+            pi_a: negate_g1(&self.pi_a)?,
+            ..self
+        }))
+    }
+
+    /// Serializes the proof back into its raw 256-byte form.
+    pub fn to_bytes(&self) -> [u8; PROOF_LEN] {
+        let mut bytes = [0u8; PROOF_LEN];
+        bytes[..64].copy_from_slice(&self.pi_a);
+        bytes[64..192].copy_from_slice(&self.pi_b);
+        bytes[192..].copy_from_slice(&self.pi_c);
+        bytes
+    }
+
+    /// Borrows the proof's elements.
+    pub fn as_proof_ref(&self) -> ProofRef<'_> {
+        ProofRef {
+            pi_a: &self.pi_a,
+            pi_b: &self.pi_b,
+            pi_c: &self.pi_c,
+        }
+    }
+}
+
+impl NegatedProof {
+    /// Reads a raw 256-byte proof whose pi_a was already negated, e.g. by
+    /// [`negate_proof_a`](crate::negate_proof_a).
+    pub fn from_prenegated_bytes(buffer: &[u8]) -> Result<Self, Error> {
+        Ok(Self(load_raw_proof_from_bytes(buffer)?))
+    }
+
+    /// The proof, with pi_a negated.
+    pub fn proof(&self) -> &Proof {
+        &self.0
+    }
+
+    /// Serializes the proof, with pi_a negated, into its raw 256-byte form.
+    pub fn to_bytes(&self) -> [u8; PROOF_LEN] {
+        self.0.to_bytes()
+    }
+
+    /// Borrows the proof's elements, with pi_a negated.
+    pub fn as_proof_ref(&self) -> ProofRef<'_> {
+        self.0.as_proof_ref()
+    }
+}
+
+/// A Groth16 proof borrowed from a larger buffer, such as account data.
+///
+/// All Group elements are represented in uncompressed form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofRef<'a> {
+    pub pi_a: &'a [u8; 64],
+    pub pi_b: &'a [u8; 128],
+    pub pi_c: &'a [u8; 64],
+}
+
+impl<'a> ProofRef<'a> {
+    /// Borrows a raw 256-byte proof from the start of `buffer`, without copying it.
+    pub fn from_bytes(buffer: &'a [u8]) -> Result<Self, Error> {
+        let proof = buffer.get(..PROOF_LEN).ok_or(Error::InvalidInput)?;
+        Ok(ProofRef {
+            pi_a: proof[..64].try_into().unwrap(),
+            pi_b: proof[64..192].try_into().unwrap(),
+            pi_c: proof[192..].try_into().unwrap(),
+        })
+    }
+}
+
+/// Loads a proof and negates pi_a, as required by the `groth16_solana` verifier.
+pub(crate) fn load_proof_from_bytes(buffer: &[u8]) -> Result<NegatedProof, Error> {
+    load_raw_proof_from_bytes(buffer)?.with_negated_a()
+}
+
+/// Loads a proof as-is, without negating pi_a.
+pub(crate) fn load_raw_proof_from_bytes(buffer: &[u8]) -> Result<Proof, Error> {
+    validate_raw_proof_len(buffer)?;
+    let proof = ProofRef::from_bytes(buffer)?;
     Ok(Proof {
-        pi_a: negate_g1(
-            &buffer[..64]
-                .try_into()
-                .map_err(|_| Error::G1CompressionError)?,
-        )?,
-        pi_b: buffer[64..192]
-            .try_into()
-            .map_err(|_| Error::G2CompressionError)?,
-        pi_c: buffer[192..256]
-            .try_into()
-            .map_err(|_| Error::G1CompressionError)?,
+        pi_a: *proof.pi_a,
+        pi_b: *proof.pi_b,
+        pi_c: *proof.pi_c,
     })
 }
-pub(crate) fn load_groth16_verifying_key_from_bytes(
-    buffer: &[u8],
-) -> Result<VerificationKey, Error> {
+
+/// A lazily decompressed view of a gnark Groth16 verifying key.
+///
+/// Constructing the view only performs a cheap structural pass over the buffer, recording where
+/// each element lives. Points are decompressed the first time they are accessed and memoized, so a
+/// verification that fails early never pays for decompression.
+///
+/// Loading the key of an SP1 program performs no heap allocations: its IC points are stored
+/// inline, and only keys with more public inputs put theirs on the heap. Each decompression lives
+/// in its own non-inlined function, so only the view itself (and not the temporaries of every
+/// decompression) sits in the caller's 4 KB BPF stack frame.
+#[derive(Debug, Clone)]
+pub struct VkView<'a> {
+    buffer: &'a [u8],
+    num_ic: usize,
+    nr_pubinputs: u32,
+    alpha_g1: OnceCell<[u8; G1_LEN]>,
+    beta_g2: OnceCell<[u8; G2_LEN]>,
+    gamma_g2: OnceCell<[u8; G2_LEN]>,
+    delta_g2: OnceCell<[u8; G2_LEN]>,
+    ic: OnceCell<IcPoints>,
+}
+
+impl<'a> VkView<'a> {
+    /// Checks the structure of the verifying key, without decompressing any points.
+    #[cfg_attr(
+        all(feature = "tracing", not(target_os = "solana")),
+        tracing::instrument(
+            name = "sp1_solana::vk_view",
+            level = "debug",
+            skip_all,
+            fields(len = buffer.len()),
+            err(Display),
+        )
+    )]
+    pub fn new(buffer: &'a [u8]) -> Result<Self, Error> {
+        validate_vk_len(buffer)?;
+        let num_ic = read_u32_be(buffer, VK_NUM_IC_OFFSET)? as usize;
+        // Every IC point must be in the buffer, which also keeps the offsets below from
+        // overflowing on 32-bit targets.
+        if num_ic == 0 || num_ic > (buffer.len() - VK_IC_OFFSET) / G1_COMPRESSED_LEN {
+            return Err(Error::InvalidInput);
+        }
+        // Keys of circuits with Pedersen commitments list the committed wires here. Their proofs
+        // carry commitments this verifier doesn't check, so such keys are rejected.
+        if read_u32_be(buffer, VK_IC_OFFSET + num_ic * G1_COMPRESSED_LEN)? != 0 {
+            return Err(Error::InvalidInput);
+        }
+        let nr_pubinputs = num_ic as u32 - 1;
+
+        Ok(Self {
+            buffer,
+            num_ic,
+            nr_pubinputs,
+            alpha_g1: OnceCell::new(),
+            beta_g2: OnceCell::new(),
+            gamma_g2: OnceCell::new(),
+            delta_g2: OnceCell::new(),
+            ic: OnceCell::new(),
+        })
+    }
+
+    /// Like [`Self::new`], but fails with [`Error::TrailingBytes`] if `buffer` continues past the
+    /// key.
+    ///
+    /// The key ends with its Pedersen commitment keys, which are never used since keys with
+    /// committed wires are rejected: a zero count of them, or the single key of SP1 v2, if any.
+    /// Bytes after the key that form neither are all trailing, e.g. a truncated count.
+    pub fn new_exact(buffer: &'a [u8]) -> Result<Self, Error> {
+        let view = Self::new(buffer)?;
+        let end = VK_IC_OFFSET + view.num_ic * G1_COMPRESSED_LEN + 4;
+        let consumed = match buffer.len() - end {
+            rest if rest >= 4 && read_u32_be(buffer, end)? == 0 => end + 4,
+            rest if rest >= VK_COMMITMENT_KEY_LEN => end + VK_COMMITMENT_KEY_LEN,
+            _ => end,
+        };
+        check_trailing_bytes(InputSection::Vk, buffer.len(), consumed)?;
+        Ok(view)
+    }
+
+    pub fn nr_pubinputs(&self) -> u32 {
+        self.nr_pubinputs
+    }
+
+    pub fn alpha_g1(&self) -> Result<&[u8; G1_LEN], Error> {
+        get_or_try_init(&self.alpha_g1, || {
+            decompress_g1(self.buffer[..32].try_into().unwrap())
+        })
+    }
+
     // Note that g1_beta and g1_delta are not used in the verification process.
-    let g1_alpha = decompress_g1(buffer[..32].try_into().unwrap())?;
-    let g2_beta = decompress_g2(buffer[64..128].try_into().unwrap())?;
-    let g2_gamma = decompress_g2(buffer[128..192].try_into().unwrap())?;
-    let g2_delta = decompress_g2(buffer[224..288].try_into().unwrap())?;
-
-    let num_k = u32::from_be_bytes([buffer[288], buffer[289], buffer[290], buffer[291]]);
-    let mut k = Vec::new();
-    let mut offset = 292;
-    for _ in 0..num_k {
-        let point = decompress_g1(&buffer[offset..offset + 32].try_into().unwrap())?;
-        k.push(point);
-        offset += 32;
-    }
-
-    let num_of_array_of_public_and_commitment_committed = u32::from_be_bytes([
-        buffer[offset],
-        buffer[offset + 1],
-        buffer[offset + 2],
-        buffer[offset + 3],
-    ]);
-    offset += 4;
-    for _ in 0..num_of_array_of_public_and_commitment_committed {
-        let num = u32::from_be_bytes([
-            buffer[offset],
-            buffer[offset + 1],
-            buffer[offset + 2],
-            buffer[offset + 3],
-        ]);
-        offset += 4;
-        for _ in 0..num {
-            offset += 4;
+    pub fn beta_g2(&self) -> Result<&[u8; G2_LEN], Error> {
+        get_or_try_init(&self.beta_g2, || {
+            decompress_g2(self.buffer[64..128].try_into().unwrap())
+        })
+    }
+
+    pub fn gamma_g2(&self) -> Result<&[u8; G2_LEN], Error> {
+        get_or_try_init(&self.gamma_g2, || {
+            decompress_g2(self.buffer[128..192].try_into().unwrap())
+        })
+    }
+
+    pub fn delta_g2(&self) -> Result<&[u8; G2_LEN], Error> {
+        get_or_try_init(&self.delta_g2, || {
+            decompress_g2(self.buffer[224..288].try_into().unwrap())
+        })
+    }
+
+    pub fn ic(&self) -> Result<&[[u8; G1_LEN]], Error> {
+        get_or_try_init(&self.ic, || load_vk_ic(self.buffer, self.num_ic)).map(IcPoints::as_slice)
+    }
+
+    /// Checks the gnark flags of every compressed point of the key, without decompressing any:
+    /// each must be flagged as compressed, and the point at infinity must be its flag alone.
+    pub(crate) fn check_compressed_flags(&self) -> Result<(), Error> {
+        let g1_offsets = [0, 32, 192]
+            .into_iter()
+            .chain((0..self.num_ic).map(|i| VK_IC_OFFSET + i * G1_COMPRESSED_LEN));
+        for offset in g1_offsets {
+            gnark_compressed_x_to_ark_compressed_x::<G1_COMPRESSED_LEN>(
+                self.buffer[offset..offset + G1_COMPRESSED_LEN]
+                    .try_into()
+                    .unwrap(),
+            )?;
         }
+        for offset in [64, 128, 224] {
+            gnark_compressed_x_to_ark_compressed_x::<G2_COMPRESSED_LEN>(
+                self.buffer[offset..offset + G2_COMPRESSED_LEN]
+                    .try_into()
+                    .unwrap(),
+            )?;
+        }
+        Ok(())
     }
 
-    Ok(VerificationKey {
-        vk_alpha_g1: g1_alpha,
-        vk_beta_g2: g2_beta,
-        vk_gamma_g2: g2_gamma,
-        vk_delta_g2: g2_delta,
-        vk_ic: k.clone(),
-        nr_pubinputs: num_of_array_of_public_and_commitment_committed,
-    })
+    /// Decompresses every element of the verifying key.
+    pub fn to_verification_key(&self) -> Result<VerificationKey, Error> {
+        Ok(VerificationKey {
+            nr_pubinputs: self.nr_pubinputs,
+            vk_alpha_g1: *self.alpha_g1()?,
+            vk_beta_g2: *self.beta_g2()?,
+            vk_gamma_g2: *self.gamma_g2()?,
+            vk_delta_g2: *self.delta_g2()?,
+            vk_ic: self.ic()?.to_vec(),
+        })
+    }
+}
+
+/// Returns the value in `cell`, initializing it with `init` if it is empty.
+fn get_or_try_init<T>(
+    cell: &OnceCell<T>,
+    init: impl FnOnce() -> Result<T, Error>,
+) -> Result<&T, Error> {
+    if let Some(value) = cell.get() {
+        return Ok(value);
+    }
+    let value = init()?;
+    Ok(cell.get_or_init(|| value))
+}
+
+fn read_u32_be(buffer: &[u8], offset: usize) -> Result<u32, Error> {
+    let bytes = buffer
+        .get(offset..offset + 4)
+        .ok_or(Error::InvalidInput)?
+        .try_into()
+        .unwrap();
+    Ok(u32::from_be_bytes(bytes))
+}
+
+/// Decompresses the IC points of the verifying key.
+#[inline(never)]
+fn load_vk_ic(buffer: &[u8], num_ic: usize) -> Result<IcPoints, Error> {
+    let mut k = IcPoints::with_capacity(num_ic);
+    let mut offset = VK_IC_OFFSET;
+    for _ in 0..num_ic {
+        let point = decompress_g1(
+            &buffer[offset..offset + G1_COMPRESSED_LEN]
+                .try_into()
+                .unwrap(),
+        )?;
+        k.push(point);
+        offset += G1_COMPRESSED_LEN;
+    }
+    Ok(k)
 }
 
 pub(crate) fn load_public_inputs_from_bytes(buffer: &[u8]) -> Result<PublicInputs<2>, Error> {
+    validate_public_inputs_len(buffer)?;
     let mut bytes = [0u8; 64];
     bytes[1..].copy_from_slice(buffer); // vkey_hash is 31 bytes
 
@@ -251,15 +1118,87 @@ pub(crate) fn load_public_inputs_from_bytes(buffer: &[u8]) -> Result<PublicInput
     })
 }
 
-/// Hashes the public inputs in the same format as the Groth16 verifier.
+/// Hashes the public inputs in the same format as the Groth16 verifier: the bytes of
+/// [`CommittedValuesDigest::from_public_values`].
 pub fn hash_public_inputs(public_inputs: &[u8]) -> [u8; 32] {
-    let mut result = Sha256::digest(public_inputs);
+    CommittedValuesDigest::from_public_values(public_inputs).to_bytes()
+}
 
-    // The Groth16 verifier operates over a 254 bit field (BN254), so we need to zero
-    // out the first 3 bits. The same logic happens in the SP1 Ethereum verifier contract.
-    result[0] = result[0] & 0x1F;
+/// Hashes the public inputs like [`hash_public_inputs`], after a domain tag: the bytes of
+/// [`CommittedValuesDigest::from_domain_public_values`].
+pub fn hash_public_inputs_domain(domain: &[u8], public_inputs: &[u8]) -> [u8; 32] {
+    CommittedValuesDigest::from_domain_public_values(domain, public_inputs).to_bytes()
+}
 
-    result.into()
+/// The Groth16 vkey hash of `vk`, its selector: the first bytes of its SHA-256 hash.
+pub(crate) fn groth16_vk_hash(vk: &[u8]) -> [u8; SELECTOR_LEN] {
+    sha256(vk)[..SELECTOR_LEN].try_into().unwrap()
+}
+
+/// Compares two byte strings, such as hashes, selectors and digests, in a time that only depends
+/// on their lengths.
+///
+/// Every hash, selector and digest comparison of the crate goes through this, so that an
+/// off-chain service doesn't leak through its timing how close a submission came to a match.
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b));
+    // Keeps the compiler from turning the fold back into an early exit.
+    core::hint::black_box(diff) == 0
+}
+
+/// Computes the SHA-256 hash of `data`.
+pub(crate) fn sha256(data: &[u8]) -> [u8; 32] {
+    sha256v(&[data])
+}
+
+/// Computes the SHA-256 hash of the concatenation of `data`.
+///
+/// On Solana this goes through the `sol_sha256` syscall, which is far cheaper than hashing in BPF.
+/// The syscall is declared here rather than through `solana-program`, so that programs built on
+/// other entrypoint crates don't pull it in.
+#[cfg(target_os = "solana")]
+pub(crate) fn sha256v(data: &[&[u8]]) -> [u8; 32] {
+    extern "C" {
+        fn sol_sha256(vals: *const u8, val_len: u64, hash_result: *mut u8) -> u64;
+    }
+
+    let mut hash = [0u8; 32];
+    // SAFETY: `data` is laid out as the `(ptr, len)` pairs the syscall expects, and `hash` is
+    // 32 bytes long.
+    unsafe {
+        sol_sha256(
+            data as *const _ as *const u8,
+            data.len() as u64,
+            hash.as_mut_ptr(),
+        );
+    }
+    hash
+}
+
+/// Computes the SHA-256 hash of the concatenation of `data`.
+#[cfg(not(target_os = "solana"))]
+pub(crate) fn sha256v(data: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for chunk in data {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+/// Formats the sp1 vkey hash and committed values digest as the 63-byte Groth16 public inputs.
+///
+/// The first byte of the vkey hash is dropped, since it is always zero.
+pub fn groth16_public_inputs(
+    sp1_vkey_hash: &[u8; 32],
+    committed_values_digest: &CommittedValuesDigest,
+) -> [u8; GROTH16_PUBLIC_INPUTS_LEN] {
+    let mut public_inputs = [0u8; GROTH16_PUBLIC_INPUTS_LEN];
+    public_inputs[..31].copy_from_slice(&sp1_vkey_hash[1..]);
+    public_inputs[31..].copy_from_slice(committed_values_digest.as_bytes());
+    public_inputs
 }
 
 /// Formats the sp1 vkey hash and public inputs for use in the Groth16 verifier.
@@ -273,7 +1212,25 @@ pub fn groth16_public_values(sp1_vkey_hash: &[u8; 32], sp1_public_inputs: &[u8])
 }
 
 /// Decodes the sp1 vkey hash from the string from bytes32.
+///
+/// The string must be `0x` followed by 64 hex digits, of a hash whose first byte is zero.
 pub fn decode_sp1_vkey_hash(sp1_vkey_hash: &str) -> Result<[u8; 32], Error> {
-    let bytes = hex::decode(&sp1_vkey_hash[2..]).map_err(|_| Error::InvalidProgramVkeyHash)?;
-    bytes.try_into().map_err(|_| Error::InvalidProgramVkeyHash)
+    let digits = sp1_vkey_hash
+        .strip_prefix("0x")
+        .ok_or(Error::InvalidProgramVkeyHash)?;
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(digits, &mut bytes).map_err(|_| Error::InvalidProgramVkeyHash)?;
+    check_sp1_vkey_hash(&bytes)?;
+    Ok(bytes)
+}
+
+/// Rejects program vkey hashes whose first byte isn't zero.
+///
+/// SP1 packs the vkey hash in 248 bits, and [`groth16_public_inputs`] drops its first byte, so a
+/// hash with a nonzero first byte would be verified as another hash.
+pub(crate) fn check_sp1_vkey_hash(sp1_vkey_hash: &[u8; 32]) -> Result<(), Error> {
+    if sp1_vkey_hash[0] != 0 {
+        return Err(Error::InvalidProgramVkeyHash);
+    }
+    Ok(())
 }
@@ -0,0 +1,76 @@
+//! Accounts holding SP1 public values too large to fit in a transaction.
+//!
+//! The account data is a [`PublicValuesHeader`] followed by the raw public values. A client creates
+//! the account owned by the verifying program with [`create_public_values_account`], then uploads
+//! the values in chunks of at most [`MAX_CHUNK_LEN`] bytes, see [`chunks`]. The program hashes the
+//! values with [`crate::hash_public_inputs`] and verifies the proof against the digest.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{instruction::Instruction, pubkey::Pubkey, system_instruction};
+
+use crate::{utils::source, Error};
+
+/// The largest chunk of public values that fits in a single transaction alongside its accounts
+/// and signature.
+pub const MAX_CHUNK_LEN: usize = 900;
+
+/// The header of a public values account.
+#[derive(Clone, Debug, Default, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct PublicValuesHeader {
+    /// The only account allowed to write the public values.
+    pub authority: Pubkey,
+    /// The length of the public values following the header.
+    pub len: u32,
+}
+
+impl PublicValuesHeader {
+    /// The length of the serialized header.
+    pub const LEN: usize = 32 + 4;
+}
+
+/// The size of a public values account holding `len` bytes of public values.
+pub const fn public_values_account_space(len: usize) -> usize {
+    PublicValuesHeader::LEN + len
+}
+
+/// Splits public values account data into its header and public values.
+pub fn read_public_values(data: &[u8]) -> Result<(PublicValuesHeader, &[u8]), Error> {
+    if data.len() < PublicValuesHeader::LEN {
+        return Err(Error::InvalidInput);
+    }
+    let (header, values) = data.split_at(PublicValuesHeader::LEN);
+    let header = PublicValuesHeader::try_from_slice(header)
+        .map_err(|error| Error::BorshDeserializeError(source(error)))?;
+    let values = values
+        .get(..header.len as usize)
+        .ok_or(Error::InvalidInput)?;
+    Ok((header, values))
+}
+
+/// Builds the instruction creating a public values account for `len` bytes of public values,
+/// owned by `program_id`.
+///
+/// `lamports` should be the rent-exempt minimum of [`public_values_account_space`]`(len)`.
+pub fn create_public_values_account(
+    payer: &Pubkey,
+    account: &Pubkey,
+    program_id: &Pubkey,
+    len: usize,
+    lamports: u64,
+) -> Instruction {
+    system_instruction::create_account(
+        payer,
+        account,
+        lamports,
+        public_values_account_space(len) as u64,
+        program_id,
+    )
+}
+
+/// Splits public values into `(offset, chunk)` pairs to upload one transaction at a time.
+pub fn chunks(values: &[u8]) -> impl Iterator<Item = (u32, &[u8])> {
+    values
+        .chunks(MAX_CHUNK_LEN)
+        .enumerate()
+        .map(|(i, chunk)| ((i * MAX_CHUNK_LEN) as u32, chunk))
+}
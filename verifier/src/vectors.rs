@@ -0,0 +1,106 @@
+//! Canonical test vectors of the verification pipeline, for implementations in other languages.
+//!
+//! [`TestVectors`] records each intermediate value between a proof's SP1 public values and what
+//! the Groth16 verifier checks: the digest of the public values before and after masking, the
+//! 63-byte public inputs, the program vkey hash as a field element, and the selector. The crate
+//! commits the vectors of the fibonacci proof of `proofs/fibonacci_proof.json` to
+//! `testdata/vectors.json`, which its tests check against these functions, so that a
+//! reimplementation tested against the file agrees with the crate.
+
+use crate::{
+    groth16_public_inputs,
+    utils::{check_sp1_vkey_hash, sha256},
+    validate_proof_len, CommittedValuesDigest, Error, PublicInputs, GROTH16_PUBLIC_INPUTS_LEN,
+    SELECTOR_LEN,
+};
+
+/// The intermediate values of the verification of one proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestVectors {
+    /// The name of the proof, e.g. `fibonacci`.
+    pub name: String,
+    /// The SP1 program vkey hash, from `vk.bytes32()`.
+    pub sp1_vkey_hash: [u8; 32],
+    /// The SP1 public values.
+    pub public_values: Vec<u8>,
+    /// The SHA-256 hash of the public values, before masking.
+    pub public_values_sha256: [u8; 32],
+    /// The committed values digest: the SHA-256 hash with its top 3 bits cleared.
+    pub committed_values_digest: [u8; 32],
+    /// The Groth16 public inputs, as formatted by [`groth16_public_inputs`].
+    pub groth16_public_inputs: [u8; GROTH16_PUBLIC_INPUTS_LEN],
+    /// The first public input: the program vkey hash as a big-endian field element, padded back
+    /// to 32 bytes from the 31 bytes of the public inputs.
+    pub vkey_hash_scalar: [u8; 32],
+    /// The Groth16 vkey hash the proof starts with.
+    pub groth16_selector: [u8; SELECTOR_LEN],
+    /// The proof in SP1's standard format, selector included.
+    pub proof: Vec<u8>,
+}
+
+impl TestVectors {
+    /// Computes the vectors of a proof in SP1's standard format, i.e. the output of
+    /// `SP1ProofWithPublicValues::bytes()`, of `public_values` by the program `sp1_vkey_hash`.
+    ///
+    /// The proof isn't verified.
+    pub fn new(
+        name: impl Into<String>,
+        sp1_vkey_hash: &[u8; 32],
+        public_values: &[u8],
+        proof: &[u8],
+    ) -> Result<Self, Error> {
+        check_sp1_vkey_hash(sp1_vkey_hash)?;
+        validate_proof_len(proof)?;
+        let committed_values_digest = CommittedValuesDigest::from_public_values(public_values);
+        let groth16_public_inputs = groth16_public_inputs(sp1_vkey_hash, &committed_values_digest);
+        let [vkey_hash_scalar, _] = PublicInputs::from_bytes(&groth16_public_inputs)?.inputs;
+        Ok(Self {
+            name: name.into(),
+            sp1_vkey_hash: *sp1_vkey_hash,
+            public_values: public_values.to_vec(),
+            public_values_sha256: sha256(public_values),
+            committed_values_digest: committed_values_digest.to_bytes(),
+            groth16_public_inputs,
+            vkey_hash_scalar,
+            groth16_selector: proof[..SELECTOR_LEN].try_into().unwrap(),
+            proof: proof.to_vec(),
+        })
+    }
+
+    /// The fields of the vectors, as `0x`-prefixed hex, in the order of the JSON file.
+    fn hex_fields(&self) -> [(&'static str, String); 8] {
+        let hex = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+        [
+            ("sp1_vkey_hash", hex(&self.sp1_vkey_hash)),
+            ("public_values", hex(&self.public_values)),
+            ("public_values_sha256", hex(&self.public_values_sha256)),
+            (
+                "committed_values_digest",
+                hex(&self.committed_values_digest),
+            ),
+            ("groth16_public_inputs", hex(&self.groth16_public_inputs)),
+            ("vkey_hash_scalar", hex(&self.vkey_hash_scalar)),
+            ("groth16_selector", hex(&self.groth16_selector)),
+            ("proof", hex(&self.proof)),
+        ]
+    }
+}
+
+/// Renders `vectors` as the JSON of `testdata/vectors.json`: an array with an object per proof,
+/// holding its name and its fields as `0x`-prefixed hex strings.
+pub fn to_json(vectors: &[TestVectors]) -> String {
+    let objects: Vec<String> = vectors
+        .iter()
+        .map(|vectors| {
+            let mut lines = vec![format!("    \"name\": \"{}\"", vectors.name)];
+            lines.extend(
+                vectors
+                    .hex_fields()
+                    .into_iter()
+                    .map(|(field, value)| format!("    \"{field}\": \"{value}\"")),
+            );
+            format!("  {{\n{}\n  }}", lines.join(",\n"))
+        })
+        .collect();
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
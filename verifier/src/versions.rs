@@ -0,0 +1,85 @@
+//! The SP1 releases whose Groth16 verification keys the crate knows of, by selector.
+//!
+//! A proof whose selector matches no key usually comes from a version of `sp1-sdk` other than the
+//! one the verifier was built for. When its selector is that of a known release whose key isn't
+//! compiled in, verification fails with [`Error::UnsupportedSp1Version`], which names both sides
+//! of the skew, instead of [`Error::Groth16VkeyHashMismatch`].
+//!
+//! Adding a release is one entry of the list at the end of this module, along with its key under
+//! `vk/` and its `embedded-vk-*` feature.
+
+use crate::{
+    utils::ct_eq, Error, GROTH16_VK_2_0_0_HASH, GROTH16_VK_3_0_0_HASH, GROTH16_VK_3_0_0_RC4_HASH,
+    SELECTOR_LEN,
+};
+
+/// An SP1 release and the selector of the proofs generated with its Groth16 verification key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KnownSp1Version {
+    /// The first 4 bytes of the SHA-256 hash of the key, prefixed to its proofs.
+    pub groth16_vkey_hash: [u8; SELECTOR_LEN],
+    /// The SP1 release, e.g. `v3.0.0`, as named by `Groth16VkVersion::name`.
+    pub version: &'static str,
+    /// Whether the key is embedded, with its `embedded-vk-*` feature.
+    pub embedded: bool,
+}
+
+macro_rules! known_sp1_versions {
+    ($($groth16_vkey_hash:expr => $version:literal, $feature:literal;)*) => {
+        /// Every SP1 release the crate knows the key of, newest first.
+        pub const KNOWN_SP1_VERSIONS: &[KnownSp1Version] = &[$(
+            KnownSp1Version {
+                groth16_vkey_hash: $groth16_vkey_hash,
+                version: $version,
+                embedded: cfg!(feature = $feature),
+            },
+        )*];
+
+        /// The SP1 releases whose key is embedded, newest first.
+        pub const SUPPORTED_SP1_VERSIONS: &[&str] = &[$(
+            #[cfg(feature = $feature)]
+            $version,
+        )*];
+    };
+}
+
+known_sp1_versions! {
+    GROTH16_VK_3_0_0_HASH => "v3.0.0", "embedded-vk-v3-0-0";
+    GROTH16_VK_3_0_0_RC4_HASH => "v3.0.0rc4", "embedded-vk-v3-0-0-rc4";
+    GROTH16_VK_2_0_0_HASH => "v2.0.0", "embedded-vk-v2-0-0";
+}
+
+/// The known release whose proofs have the selector `groth16_vkey_hash`, if any.
+pub fn known_sp1_version(groth16_vkey_hash: &[u8]) -> Option<&'static KnownSp1Version> {
+    KNOWN_SP1_VERSIONS
+        .iter()
+        .find(|version| ct_eq(&version.groth16_vkey_hash, groth16_vkey_hash))
+}
+
+/// The error of a proof whose selector, `groth16_vkey_hash`, isn't that of the key it is checked
+/// against.
+pub(crate) fn selector_mismatch(groth16_vkey_hash: &[u8]) -> Error {
+    selector_mismatch_in(
+        KNOWN_SP1_VERSIONS,
+        SUPPORTED_SP1_VERSIONS,
+        groth16_vkey_hash,
+    )
+}
+
+/// [`selector_mismatch`] against another table, for the tests to leave releases out of.
+pub(crate) fn selector_mismatch_in(
+    known: &'static [KnownSp1Version],
+    supported: &'static [&'static str],
+    groth16_vkey_hash: &[u8],
+) -> Error {
+    match known
+        .iter()
+        .find(|version| ct_eq(&version.groth16_vkey_hash, groth16_vkey_hash))
+    {
+        Some(version) if !version.embedded => Error::UnsupportedSp1Version {
+            detected: version.version,
+            supported,
+        },
+        _ => Error::Groth16VkeyHashMismatch,
+    }
+}
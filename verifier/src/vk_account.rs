@@ -0,0 +1,92 @@
+//! Accounts holding a Groth16 verification key, so that it can change without redeploying.
+//!
+//! The account data is a [`VkAccountHeader`] followed by the gnark verification key bytes, e.g.
+//! [`crate::GROTH16_VK_3_0_0_BYTES`]. The account is allocated with [`vk_account_space`], which
+//! leaves room for any key up to [`MAX_VK_LEN`] bytes, so a program can replace the key in place
+//! when SP1 upgrades its circuits.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+use crate::{
+    utils::{sha256, source},
+    verify_proof_with_digest, CommittedValuesDigest, Error, VkView,
+};
+
+/// The largest verification key a verification key account can hold.
+///
+/// The keys of the supported SP1 versions are 396 to 520 bytes long.
+pub const MAX_VK_LEN: usize = 640;
+
+/// The header of a verification key account.
+#[derive(Clone, Debug, Default, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct VkAccountHeader {
+    /// The only account allowed to replace the verification key.
+    pub authority: Pubkey,
+    /// The length of the verification key following the header.
+    pub len: u32,
+}
+
+impl VkAccountHeader {
+    /// The length of the serialized header.
+    pub const LEN: usize = 32 + 4;
+}
+
+/// The size of a verification key account.
+pub const fn vk_account_space() -> usize {
+    VkAccountHeader::LEN + MAX_VK_LEN
+}
+
+/// Splits verification key account data into its header and verification key.
+pub fn read_vk(data: &[u8]) -> Result<(VkAccountHeader, &[u8]), Error> {
+    if data.len() < VkAccountHeader::LEN {
+        return Err(Error::InvalidInput);
+    }
+    let (header, vk) = data.split_at(VkAccountHeader::LEN);
+    let header = VkAccountHeader::try_from_slice(header)
+        .map_err(|error| Error::BorshDeserializeError(source(error)))?;
+    let vk = vk.get(..header.len as usize).ok_or(Error::InvalidInput)?;
+    Ok((header, vk))
+}
+
+/// Writes `header` and `vk` to verification key account data.
+///
+/// Fails with [`Error::InvalidInput`] if the key is longer than [`MAX_VK_LEN`] or doesn't fit in
+/// the data, and with the error of [`VkView::new`] if it is malformed.
+pub fn write_vk(data: &mut [u8], authority: &Pubkey, vk: &[u8]) -> Result<(), Error> {
+    if vk.len() > MAX_VK_LEN || data.len() < VkAccountHeader::LEN + vk.len() {
+        return Err(Error::InvalidInput);
+    }
+    VkView::new(vk)?;
+
+    let header = VkAccountHeader {
+        authority: *authority,
+        len: vk.len() as u32,
+    };
+    let (header_data, vk_data) = data.split_at_mut(VkAccountHeader::LEN);
+    header
+        .serialize(&mut &mut header_data[..])
+        .map_err(|error| Error::BorshSerializeError(source(error)))?;
+    vk_data[..vk.len()].copy_from_slice(vk);
+    Ok(())
+}
+
+/// Verifies a proof in SP1's standard format against the verification key stored in
+/// `vk_account`.
+///
+/// The caller must check that `vk_account` is the account it trusts, e.g. its owner and address.
+pub fn verify_proof_with_vk_account(
+    proof: &[u8],
+    committed_values_digest: &CommittedValuesDigest,
+    sp1_vkey_hash: &[u8; 32],
+    vk_account: &AccountInfo,
+) -> Result<(), Error> {
+    let data = vk_account
+        .try_borrow_data()
+        .map_err(|_| Error::InvalidInput)?;
+    let (_, vk) = read_vk(&data)?;
+
+    // The key isn't known in advance, so its hash can't be precomputed.
+    let vk_hash: [u8; 4] = sha256(vk)[..4].try_into().unwrap();
+    verify_proof_with_digest(proof, committed_values_digest, sp1_vkey_hash, vk, &vk_hash)
+}
@@ -0,0 +1,27 @@
+//! `wasm-bindgen` bindings, so that browsers can check a proof before asking the user to sign the
+//! transaction that submits it.
+//!
+//! Build with `--no-default-features --features wasm` for `wasm32-unknown-unknown`: the `std`
+//! feature only adds file IO, which browsers don't have.
+
+use wasm_bindgen::prelude::*;
+
+/// Verifies a proof like [`verify_proof`](crate::verify_proof), rejecting with the error message
+/// if it doesn't verify.
+#[wasm_bindgen]
+pub fn verify_proof_js(
+    proof: &[u8],
+    public_inputs: &[u8],
+    vkey_hash: &str,
+    vk: &[u8],
+) -> Result<(), JsValue> {
+    crate::verify_proof(proof, public_inputs, vkey_hash, vk)
+        .map_err(|error| JsValue::from_str(&error.to_string()))
+}
+
+/// Computes the committed values digest of SP1 public values, like
+/// [`hash_public_inputs`](crate::hash_public_inputs).
+#[wasm_bindgen]
+pub fn hash_public_inputs_js(public_inputs: &[u8]) -> Vec<u8> {
+    crate::hash_public_inputs(public_inputs).to_vec()
+}
@@ -0,0 +1,181 @@
+#!/usr/bin/env python3
+"""Generates the circom multiplier fixture in snarkjs's JSON formats.
+
+The circuit is `multiplier.circom`: c <== a * b, with c public. Its R1CS and QAP follow snarkjs,
+including the extra constraint per public signal, and the setup and proof are computed from the
+toxic waste directly, which yields the same points as proving from the corresponding SRS. The
+script checks the verification equation in the exponent before writing anything.
+
+    python3 generate.py
+"""
+
+import json
+import os
+import random
+
+P = 21888242871839275222246405745257275088696311157297823662689037894645226208583
+R = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+
+# Fp2 = Fp[u] / (u^2 + 1), as pairs (c0, c1) meaning c0 + c1 * u.
+def f2_add(a, b):
+    return ((a[0] + b[0]) % P, (a[1] + b[1]) % P)
+
+def f2_sub(a, b):
+    return ((a[0] - b[0]) % P, (a[1] - b[1]) % P)
+
+def f2_mul(a, b):
+    return ((a[0] * b[0] - a[1] * b[1]) % P, (a[0] * b[1] + a[1] * b[0]) % P)
+
+def f2_inv(a):
+    norm = pow(a[0] * a[0] + a[1] * a[1], P - 2, P)
+    return (a[0] * norm % P, -a[1] * norm % P)
+
+class Field:
+    """The base field of a curve: Fp for G1, Fp2 for G2."""
+
+    def __init__(self, zero, one, add, sub, mul, inv, from_int):
+        self.zero, self.one = zero, one
+        self.add, self.sub, self.mul, self.inv, self.from_int = add, sub, mul, inv, from_int
+
+FP = Field(0, 1, lambda a, b: (a + b) % P, lambda a, b: (a - b) % P, lambda a, b: a * b % P,
+           lambda a: pow(a, P - 2, P), lambda n: n % P)
+FP2 = Field((0, 0), (1, 0), f2_add, f2_sub, f2_mul, f2_inv, lambda n: (n % P, 0))
+
+def ec_add(field, p1, p2):
+    if p1 is None:
+        return p2
+    if p2 is None:
+        return p1
+    (x1, y1), (x2, y2) = p1, p2
+    if x1 == x2:
+        if field.add(y1, y2) == field.zero:
+            return None
+        slope = field.mul(field.mul(field.from_int(3), field.mul(x1, x1)),
+                          field.inv(field.mul(field.from_int(2), y1)))
+    else:
+        slope = field.mul(field.sub(y2, y1), field.inv(field.sub(x2, x1)))
+    x3 = field.sub(field.sub(field.mul(slope, slope), x1), x2)
+    return (x3, field.sub(field.mul(slope, field.sub(x1, x3)), y1))
+
+def ec_mul(field, point, scalar):
+    result = None
+    while scalar:
+        if scalar & 1:
+            result = ec_add(field, result, point)
+        point = ec_add(field, point, point)
+        scalar >>= 1
+    return result
+
+def on_curve(field, point, b):
+    x, y = point
+    return field.sub(field.mul(y, y), field.add(field.mul(field.mul(x, x), x), b)) == field.zero
+
+G1 = (1, 2)
+G2 = (
+    (10857046999023057135944570762232829481370756359578518086990519993285655852781,
+     11559732032986387107991004021392285783925812861821192530917403151452391805634),
+    (8495653923123431417604973247489272438418190587263600148770280649306958101930,
+     4082367875863433681332203403145435568316851327593401208105741076214120093531),
+)
+B1 = 3
+# 3 / (9 + u), the coefficient of the twist.
+B2 = f2_mul((3, 0), f2_inv((9, 1)))
+
+assert on_curve(FP, G1, B1) and ec_mul(FP, G1, R) is None
+assert on_curve(FP2, G2, B2) and ec_mul(FP2, G2, R) is None
+
+def g1(scalar):
+    x, y = ec_mul(FP, G1, scalar % R)
+    return [str(x), str(y), "1"]
+
+def g2(scalar):
+    (x0, x1), (y0, y1) = ec_mul(FP2, G2, scalar % R)
+    return [[str(x0), str(x1)], [str(y0), str(y1)], ["1", "0"]]
+
+def inv(a):
+    return pow(a, R - 2, R)
+
+# Signals, in circom's order: the constant 1, the public output c, then the private inputs a, b.
+a, b = 3, 11
+witness = [1, a * b, a, b]
+n_public = 1
+
+# One row per constraint, then one per public signal (and the constant), as snarkjs adds them so
+# that the IC points are independent. Each row maps signal indices to coefficients.
+rows_a = [{2: 1}] + [{i: 1} for i in range(n_public + 1)]
+rows_b = [{3: 1}] + [{} for _ in range(n_public + 1)]
+rows_c = [{1: 1}] + [{} for _ in range(n_public + 1)]
+
+# The evaluation domain: the 4th roots of unity in Fr, 5 being a generator of Fr*.
+domain_size = 4
+omega = pow(5, (R - 1) // domain_size, R)
+domain = [pow(omega, k, R) for k in range(domain_size)]
+
+rng = random.Random(0x5eed)
+tau, alpha, beta, gamma, delta, r, s = (rng.randrange(1, R) for _ in range(7))
+
+def lagrange(k, x):
+    value = 1
+    for j, point in enumerate(domain):
+        if j != k:
+            value = value * (x - point) * inv(domain[k] - point) % R
+    return value
+
+def evaluate(rows, signal):
+    return sum(row.get(signal, 0) * lagrange(k, tau) for k, row in enumerate(rows)) % R
+
+u = [evaluate(rows_a, i) for i in range(len(witness))]
+v = [evaluate(rows_b, i) for i in range(len(witness))]
+w = [evaluate(rows_c, i) for i in range(len(witness))]
+
+# The witness satisfies every row, so A(x) B(x) - C(x) is divisible by t(x) = x^4 - 1.
+for row_a, row_b, row_c in zip(rows_a, rows_b, rows_c):
+    dot = lambda row: sum(coefficient * witness[i] for i, coefficient in row.items())
+    assert dot(row_a) * dot(row_b) == dot(row_c)
+
+def lc(i):
+    return (beta * u[i] + alpha * v[i] + w[i]) % R
+
+ic = [lc(i) * inv(gamma) % R for i in range(n_public + 1)]
+
+a_sum = sum(x * y for x, y in zip(witness, u)) % R
+b_sum = sum(x * y for x, y in zip(witness, v)) % R
+c_sum = sum(x * y for x, y in zip(witness, w)) % R
+h_t = (a_sum * b_sum - c_sum) % R
+proof_a = (alpha + a_sum + r * delta) % R
+proof_b = (beta + b_sum + s * delta) % R
+private = sum(witness[i] * lc(i) for i in range(n_public + 1, len(witness)))
+proof_c = ((private + h_t) * inv(delta) + proof_a * s + proof_b * r - r * s * delta) % R
+
+# The verification equation, in the exponent: e(A, B) = e(alpha, beta) e(L, gamma) e(C, delta).
+l = sum(witness[i] * ic[i] for i in range(n_public + 1)) % R
+assert proof_a * proof_b % R == (alpha * beta + l * gamma + proof_c * delta) % R
+
+verification_key = {
+    "protocol": "groth16",
+    "curve": "bn128",
+    "nPublic": n_public,
+    "vk_alpha_1": g1(alpha),
+    "vk_beta_2": g2(beta),
+    "vk_gamma_2": g2(gamma),
+    "vk_delta_2": g2(delta),
+    "IC": [g1(point) for point in ic],
+}
+proof = {
+    "pi_a": g1(proof_a),
+    "pi_b": g2(proof_b),
+    "pi_c": g1(proof_c),
+    "protocol": "groth16",
+    "curve": "bn128",
+}
+public = [str(x) for x in witness[1:n_public + 1]]
+
+directory = os.path.dirname(os.path.abspath(__file__))
+for name, value in [
+    ("verification_key.json", verification_key),
+    ("proof.json", proof),
+    ("public.json", public),
+]:
+    with open(os.path.join(directory, name), "w") as file:
+        json.dump(value, file, indent=1)
+        file.write("\n")
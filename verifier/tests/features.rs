@@ -0,0 +1,65 @@
+//! Checks the library under feature combinations that the other tests don't build, pinning the
+//! message of each `compile_error!`.
+//!
+//! Each case runs `cargo check` in a separate target directory, so these tests are ignored by
+//! default. Run them with `cargo test --test features -- --ignored`.
+
+use std::{path::Path, process::Command};
+
+/// Runs `cargo check` on the library with only `features`, returning its diagnostics if it fails.
+fn check(features: &str) -> Result<(), String> {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // A separate target directory, as this test's own build holds the lock of the default one.
+    let output = Command::new(env!("CARGO"))
+        .args([
+            "check",
+            "--lib",
+            "--no-default-features",
+            "--features",
+            features,
+        ])
+        .arg("--target-dir")
+        .arg(manifest_dir.join("../target/features-test"))
+        .current_dir(manifest_dir)
+        .output()
+        .unwrap();
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+}
+
+#[test]
+#[ignore = "runs cargo check"]
+fn test_embedded_vk_without_version() {
+    let stderr = check("embedded-vk").unwrap_err();
+    assert!(
+        stderr.contains(
+            "error: the embedded-vk feature embeds no verification key on its own: enable one of \
+             embedded-vk-v3-0-0, embedded-vk-v3-0-0-rc4 or embedded-vk-v2-0-0, or pass your own \
+             key to verify_proof"
+        ),
+        "{stderr}"
+    );
+}
+
+#[test]
+#[ignore = "runs cargo check"]
+fn test_mock_verifier_on_mainnet() {
+    let stderr = check("mock-verifier,mainnet").unwrap_err();
+    assert!(
+        stderr.contains(
+            "error: the mock-verifier feature accepts any proof, and can't be enabled with mainnet"
+        ),
+        "{stderr}"
+    );
+}
+
+#[test]
+#[ignore = "runs cargo check"]
+fn test_vk_features() {
+    // No embedded key at all, and a single one.
+    check("").unwrap();
+    check("embedded-vk-v2-0-0").unwrap();
+}
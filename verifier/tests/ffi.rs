@@ -0,0 +1,68 @@
+//! Builds the library as a `staticlib` and runs `tests/ffi/verify.c` against it, on the fibonacci
+//! proof. Needs a C compiler, `cc` unless the `CC` environment variable is set.
+
+#![cfg(all(feature = "ffi", target_os = "linux"))]
+
+use sp1_sdk::SP1ProofWithPublicValues;
+use sp1_solana::{decode_sp1_vkey_hash, hash_public_inputs, GROTH16_VK_3_0_0_BYTES};
+use std::{path::Path, process::Command};
+
+#[test]
+fn test_ffi() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // A separate target directory, as this test's own build holds the lock of the default one.
+    let target_dir = manifest_dir.join("../target/ffi-test");
+    let status = Command::new(env!("CARGO"))
+        .args(["rustc", "--features", "ffi", "--crate-type", "staticlib"])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .current_dir(manifest_dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "building the staticlib failed");
+
+    let work_dir = std::env::temp_dir().join(format!("sp1_solana_ffi_{}", std::process::id()));
+    std::fs::create_dir_all(&work_dir).unwrap();
+    let binary = work_dir.join("verify");
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(manifest_dir.join("tests/ffi/verify.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(target_dir.join("debug/libsp1_solana.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&binary)
+        .status()
+        .unwrap();
+    assert!(status.success(), "compiling verify.c failed");
+
+    let sp1_proof_with_public_values =
+        SP1ProofWithPublicValues::load("../proofs/fibonacci_proof.bin").unwrap();
+    let public_values = sp1_proof_with_public_values.public_values.to_vec();
+    let vkey_hash =
+        decode_sp1_vkey_hash("0x007a04fa063e8b4a76f65e95923df3319e13e2187c0543368aeb372609555f83")
+            .unwrap();
+    let files = [
+        ("proof.bin", sp1_proof_with_public_values.bytes()),
+        ("public_values.bin", public_values.clone()),
+        ("vkey_hash.bin", vkey_hash.to_vec()),
+        ("vk.bin", GROTH16_VK_3_0_0_BYTES.to_vec()),
+    ];
+    for (name, bytes) in &files {
+        std::fs::write(work_dir.join(name), bytes).unwrap();
+    }
+
+    let output = Command::new(&binary)
+        .args(files.iter().map(|(name, _)| work_dir.join(name)))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim(),
+        hex::encode(hash_public_inputs(&public_values))
+    );
+    std::fs::remove_dir_all(&work_dir).unwrap();
+}
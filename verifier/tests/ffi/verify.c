@@ -0,0 +1,75 @@
+/*
+ * Verifies a proof through the C interface. Run by tests/ffi.rs with the paths of the proof,
+ * the public values, the 32-byte program vkey hash and the Groth16 verification key, and prints
+ * the committed values digest in hex.
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "sp1_solana.h"
+
+static uint8_t *read_file(const char *path, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    if (file == NULL) {
+        return NULL;
+    }
+    fseek(file, 0, SEEK_END);
+    *len = (size_t)ftell(file);
+    fseek(file, 0, SEEK_SET);
+    uint8_t *bytes = malloc(*len > 0 ? *len : 1);
+    if (bytes != NULL && fread(bytes, 1, *len, file) != *len) {
+        free(bytes);
+        bytes = NULL;
+    }
+    fclose(file);
+    return bytes;
+}
+
+#define CHECK(condition)                                                                  \
+    if (!(condition)) {                                                                   \
+        fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition);     \
+        return 1;                                                                         \
+    }
+
+int main(int argc, char **argv) {
+    CHECK(argc == 5);
+    size_t proof_len, public_values_len, vkey_hash_len, vk_len;
+    uint8_t *proof = read_file(argv[1], &proof_len);
+    uint8_t *public_values = read_file(argv[2], &public_values_len);
+    uint8_t *vkey_hash = read_file(argv[3], &vkey_hash_len);
+    uint8_t *vk = read_file(argv[4], &vk_len);
+    CHECK(proof != NULL && public_values != NULL && vkey_hash != NULL && vk != NULL);
+    CHECK(vkey_hash_len == 32);
+
+    CHECK(sp1_solana_verify(proof, proof_len, public_values, public_values_len, vkey_hash, vk,
+                            vk_len) == SP1_SOLANA_OK);
+
+    /* Tampered public values are reported with the verifier's error code. */
+    public_values[0] ^= 1;
+    int32_t result =
+        sp1_solana_verify(proof, proof_len, public_values, public_values_len, vkey_hash, vk, vk_len);
+    CHECK(result >= SP1_SOLANA_ERROR_CODE_OFFSET);
+    public_values[0] ^= 1;
+
+    CHECK(sp1_solana_verify(NULL, proof_len, public_values, public_values_len, vkey_hash, vk,
+                            vk_len) == SP1_SOLANA_ERROR_NULL_POINTER);
+    CHECK(sp1_solana_verify(proof, proof_len, public_values, public_values_len, NULL, vk,
+                            vk_len) == SP1_SOLANA_ERROR_NULL_POINTER);
+
+    uint8_t digest[32];
+    CHECK(sp1_solana_hash_public_inputs(public_values, public_values_len, digest) ==
+          SP1_SOLANA_OK);
+    CHECK(sp1_solana_hash_public_inputs(public_values, public_values_len, NULL) ==
+          SP1_SOLANA_ERROR_NULL_POINTER);
+    for (size_t i = 0; i < sizeof(digest); i++) {
+        printf("%02x", digest[i]);
+    }
+    printf("\n");
+
+    free(proof);
+    free(public_values);
+    free(vkey_hash);
+    free(vk);
+    return 0;
+}
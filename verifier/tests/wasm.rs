@@ -0,0 +1,48 @@
+//! Verifies the fibonacci proof through the `wasm-bindgen` bindings, in Node:
+//!
+//! ```sh
+//! wasm-pack test --node -- --no-default-features --features wasm,embedded-vk-v3-0-0,embedded-vk-v2-0-0
+//! ```
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use sp1_solana::{
+    hash_public_inputs_js, verify_proof_js, GROTH16_VK_2_0_0_BYTES, GROTH16_VK_3_0_0_BYTES,
+};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Returns the proof, public values and vkey hash of the fibonacci proof.
+fn fibonacci_proof() -> (Vec<u8>, Vec<u8>, String) {
+    let json: serde_json::Value =
+        serde_json::from_str(include_str!("../../proofs/fibonacci_proof.json")).unwrap();
+    let decode =
+        |key: &str| hex::decode(json[key].as_str().unwrap().trim_start_matches("0x")).unwrap();
+    (
+        decode("proof"),
+        decode("public_values"),
+        json["vkey_hash"].as_str().unwrap().to_string(),
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_verify_proof_js() {
+    let (proof, public_values, vkey_hash) = fibonacci_proof();
+    verify_proof_js(&proof, &public_values, &vkey_hash, GROTH16_VK_3_0_0_BYTES).unwrap();
+
+    let error =
+        verify_proof_js(&proof, &public_values, &vkey_hash, GROTH16_VK_2_0_0_BYTES).unwrap_err();
+    assert_eq!(error.as_string().unwrap(), "Groth16 vkey hash mismatch");
+
+    let mut tampered = public_values.clone();
+    tampered[0] ^= 1;
+    assert!(verify_proof_js(&proof, &tampered, &vkey_hash, GROTH16_VK_3_0_0_BYTES).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_hash_public_inputs_js() {
+    let (_, public_values, _) = fibonacci_proof();
+    assert_eq!(
+        hash_public_inputs_js(&public_values),
+        sp1_solana::hash_public_inputs(&public_values)
+    );
+}